@@ -0,0 +1,35 @@
+use proptest::array::{uniform11, uniform7};
+use proptest::prelude::*;
+use proptest::sample::select;
+use {Cube, Face, Move, Turn};
+
+impl Arbitrary for Move {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Move>;
+
+  /// Any of the 18 moves, shrinking towards `U`.
+  fn arbitrary_with(_: ()) -> BoxedStrategy<Move> {
+    (select(&Face::ALL[..]), select(&Turn::ALL[..]))
+      .prop_map(|(face, turn)| Move(face, turn))
+      .boxed()
+  }
+}
+
+impl Arbitrary for Cube {
+  type Parameters = ();
+  type Strategy = BoxedStrategy<Cube>;
+
+  /// Any solvable state, with every state equally likely, shrinking towards
+  /// the solved cube. The parts of the state are chosen like `Cube::random`.
+  fn arbitrary_with(_: ()) -> BoxedStrategy<Cube> {
+    let solved = Cube::solved();
+    (
+      Just(solved.cp).prop_shuffle(),
+      uniform7(0u8..3),
+      Just(solved.ep).prop_shuffle(),
+      uniform11(0u8..2),
+    )
+      .prop_map(|(cp, co, ep, eo)| Cube::from_random_parts(cp, co, ep, eo))
+      .boxed()
+  }
+}
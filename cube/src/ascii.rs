@@ -0,0 +1,92 @@
+use std::fmt;
+use {Color, ColorScheme, Cube, Face, FaceletCube};
+
+// The first facelet of each face.
+pub(crate) const U: usize = 0;
+pub(crate) const R: usize = 9;
+pub(crate) const F: usize = 18;
+pub(crate) const D: usize = 27;
+pub(crate) const L: usize = 36;
+pub(crate) const B: usize = 45;
+
+// The ANSI escape code that sets the background to `color`.
+fn ansi_color(color: Color) -> &'static str {
+  match color {
+    Color::White => "\x1b[107m",
+    Color::Red => "\x1b[41m",
+    Color::Green => "\x1b[42m",
+    Color::Yellow => "\x1b[103m",
+    Color::Orange => "\x1b[48;5;208m",
+    Color::Blue => "\x1b[44m",
+  }
+}
+
+impl FaceletCube {
+  // Render the net, with `sticker` rendering each facelet as two characters.
+  fn net<S: Fn(Face) -> String>(&self, sticker: S) -> String {
+    let row = |face: usize, r: usize| -> String {
+      self.0[face + 3 * r..face + 3 * r + 3]
+        .iter()
+        .map(|&f| sticker(f))
+        .collect()
+    };
+    let mut lines = vec![];
+    for r in 0..3 {
+      lines.push(format!("      {}", row(U, r)));
+    }
+    for r in 0..3 {
+      lines.push([L, F, R, B].iter().map(|&face| row(face, r)).collect());
+    }
+    for r in 0..3 {
+      lines.push(format!("      {}", row(D, r)));
+    }
+    let mut net = lines.join("\n");
+    net.push('\n');
+    net
+  }
+
+  /// Render an unfolded net of the cube, with U above and D below L, F, R
+  /// and B, and each facelet shown as the letter of its color.
+  pub fn to_ascii(&self) -> String {
+    let net = self.net(|f| format!("{} ", f));
+    net.lines().map(|l| format!("{}\n", l.trim_end())).collect()
+  }
+
+  /// Render the net of `to_ascii` with ANSI colors for terminals, using the
+  /// WCA color scheme.
+  pub fn to_ansi(&self) -> String {
+    self.to_ansi_with(&ColorScheme::default())
+  }
+
+  /// Render the net of `to_ascii` with ANSI colors for terminals, using the
+  /// colors of `scheme`.
+  pub fn to_ansi_with(&self, scheme: &ColorScheme) -> String {
+    self.net(|f| format!("{}  \x1b[0m", ansi_color(scheme.color(f))))
+  }
+}
+
+impl Cube {
+  /// Render an unfolded net of the `Cube`, see `FaceletCube::to_ascii`.
+  pub fn to_ascii(&self) -> String {
+    FaceletCube::from(self).to_ascii()
+  }
+
+  /// Render an unfolded net of the `Cube` with ANSI colors, see
+  /// `FaceletCube::to_ansi`.
+  pub fn to_ansi(&self) -> String {
+    FaceletCube::from(self).to_ansi()
+  }
+
+  /// Render an unfolded net of the `Cube` with the ANSI colors of `scheme`,
+  /// see `FaceletCube::to_ansi_with`.
+  pub fn to_ansi_with(&self, scheme: &ColorScheme) -> String {
+    FaceletCube::from(self).to_ansi_with(scheme)
+  }
+}
+
+impl fmt::Display for Cube {
+  /// Show the `Cube` as the net of `to_ascii`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.to_ascii())
+  }
+}
@@ -0,0 +1,176 @@
+use {Corner, Cube, Edge, NUM_CORNERS, NUM_EDGES};
+
+/// The Speffz letters of the facelets of each corner, starting with the U or
+/// D facelet and going clockwise around the corner, like the orientation of
+/// a corner.
+const CORNER_LETTERS: [[char; 3]; NUM_CORNERS] = [
+  ['C', 'M', 'J'],
+  ['D', 'I', 'F'],
+  ['A', 'E', 'R'],
+  ['B', 'Q', 'N'],
+  ['V', 'K', 'P'],
+  ['U', 'G', 'L'],
+  ['X', 'S', 'H'],
+  ['W', 'O', 'T'],
+];
+
+/// The Speffz letters of the facelets of each edge, starting with the U or D
+/// facelet, or the F or B facelet for the E-slice edges.
+const EDGE_LETTERS: [[char; 2]; NUM_EDGES] = [
+  ['B', 'M'],
+  ['C', 'I'],
+  ['D', 'E'],
+  ['A', 'Q'],
+  ['V', 'O'],
+  ['U', 'K'],
+  ['X', 'G'],
+  ['W', 'S'],
+  ['J', 'P'],
+  ['L', 'F'],
+  ['R', 'H'],
+  ['T', 'N'],
+];
+
+/// The memo of the corners or the edges of a cube for a blindfolded solve.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Memo<P> {
+  /// The Speffz letters of the targets of the buffer, in order. A new cycle
+  /// starts with a letter of its first piece and ends with a letter of the
+  /// same piece.
+  pub letters: Vec<char>,
+  /// The pieces other than the buffer that are at their own position but
+  /// twisted or flipped, with their orientation.
+  pub misoriented: Vec<(P, u8)>,
+}
+
+impl<P> Memo<P> {
+  /// Whether there is an odd number of targets, which means the pieces need
+  /// a parity algorithm. The corners and edges of a solvable cube always
+  /// have the same parity.
+  pub fn has_parity(&self) -> bool {
+    self.letters.len() % 2 == 1
+  }
+
+  /// The letters in pairs, as they are usually memorized, with a single
+  /// letter at the end if there is parity.
+  pub fn letter_pairs(&self) -> Vec<String> {
+    self.letters.chunks(2).map(|c| c.iter().collect()).collect()
+  }
+}
+
+// Trace the targets of the piece at `buffer`, for `n` pieces that can each
+// be in `k` orientations. `piece_at` gives the piece at a position and its
+// orientation there, and `letter` the Speffz letter of a facelet of a piece.
+fn trace(
+  n: usize,
+  k: usize,
+  buffer: usize,
+  piece_at: impl Fn(usize) -> (usize, usize),
+  letter: impl Fn(usize, usize) -> char,
+) -> (Vec<char>, Vec<(usize, u8)>) {
+  let mut letters = vec![];
+  let mut misoriented = vec![];
+  // The pieces that are at their own position need no targets, though the
+  // ones other than the buffer may need to be twisted or flipped.
+  let mut done = vec![false; n];
+  for (p, done) in done.iter_mut().enumerate() {
+    let (piece, orientation) = piece_at(p);
+    if piece == p {
+      *done = true;
+      if p != buffer && orientation != 0 {
+        misoriented.push((p, orientation as u8));
+      }
+    }
+  }
+  done[buffer] = true;
+
+  let mut start = buffer;
+  loop {
+    // Follow the facelets from the first facelet of `start`, adding the
+    // letter of where each one belongs, until `start` comes back.
+    let (mut position, mut facelet) = (start, 0);
+    loop {
+      let (piece, orientation) = piece_at(position);
+      facelet = (facelet + orientation) % k;
+      if piece == start {
+        break;
+      }
+      letters.push(letter(piece, facelet));
+      done[piece] = true;
+      position = piece;
+    }
+    if start != buffer {
+      // Close the cycle at whichever facelet of `start` came back.
+      letters.push(letter(start, facelet));
+    }
+
+    // Break into the next cycle by shooting to the first facelet of its
+    // first piece.
+    match (0..n).find(|&p| !done[p]) {
+      Some(p) => {
+        start = p;
+        done[p] = true;
+        letters.push(letter(p, 0));
+      }
+      None => return (letters, misoriented),
+    }
+  }
+}
+
+impl Cube {
+  /// The memo of the corners, with `buffer` as the buffer, which is usually
+  /// `Corner::URF`.
+  ///
+  /// The facelet of a corner that is followed first is its U or D facelet,
+  /// both for the buffer and when breaking into a new cycle.
+  pub fn corner_memo(&self, buffer: Corner) -> Memo<Corner> {
+    let (letters, misoriented) = trace(
+      NUM_CORNERS,
+      3,
+      buffer as usize,
+      |p| (self.cp[p] as usize, self.co[p] as usize),
+      |piece, facelet| CORNER_LETTERS[piece][facelet],
+    );
+    Memo {
+      letters,
+      misoriented: misoriented
+        .into_iter()
+        .map(|(p, o)| (Corner::from(p), o))
+        .collect(),
+    }
+  }
+
+  /// The memo of the edges, with `buffer` as the buffer, which is usually
+  /// `Edge::UF`.
+  ///
+  /// The facelet of an edge that is followed first is its U or D facelet,
+  /// or its F or B facelet for the E-slice edges.
+  pub fn edge_memo(&self, buffer: Edge) -> Memo<Edge> {
+    let (letters, misoriented) = trace(
+      NUM_EDGES,
+      2,
+      buffer as usize,
+      |p| (self.ep[p] as usize, self.eo[p] as usize),
+      |piece, facelet| EDGE_LETTERS[piece][facelet],
+    );
+    Memo {
+      letters,
+      misoriented: misoriented
+        .into_iter()
+        .map(|(p, o)| (Edge::from(p), o))
+        .collect(),
+    }
+  }
+}
+
+/// The Speffz letter of a facelet of `corner`, where facelet 0 is its U or D
+/// facelet and the others follow clockwise.
+pub fn corner_letter(corner: Corner, facelet: usize) -> char {
+  CORNER_LETTERS[corner as usize][facelet]
+}
+
+/// The Speffz letter of a facelet of `edge`, where facelet 0 is its U or D
+/// facelet, or its F or B facelet for the E-slice edges.
+pub fn edge_letter(edge: Edge, facelet: usize) -> char {
+  EDGE_LETTERS[edge as usize][facelet]
+}
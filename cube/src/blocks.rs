@@ -0,0 +1,45 @@
+use {Corner, Cube, Edge};
+
+/// The two 1x2x3 blocks of the Roux method, each the bottom two layers of
+/// the L or R face.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum RouxBlock {
+  /// The block on L, which is solved first.
+  First,
+  /// The block on R.
+  Second,
+}
+
+impl RouxBlock {
+  /// Both blocks, in the order they are solved.
+  pub const ALL: [RouxBlock; 2] = [RouxBlock::First, RouxBlock::Second];
+
+  /// The corners of the block.
+  pub fn corners(self) -> [Corner; 2] {
+    match self {
+      RouxBlock::First => [Corner::DLF, Corner::DBL],
+      RouxBlock::Second => [Corner::DFR, Corner::DRB],
+    }
+  }
+
+  /// The edges of the block, starting with the D edge.
+  pub fn edges(self) -> [Edge; 3] {
+    match self {
+      RouxBlock::First => [Edge::DL, Edge::FL, Edge::BL],
+      RouxBlock::Second => [Edge::DR, Edge::FR, Edge::BR],
+    }
+  }
+}
+
+impl Cube {
+  /// Whether every piece of `block` is solved.
+  ///
+  /// Like the rest of the `Cube`, this is relative to the centers, so a turn
+  /// of the M slice, which moves the U, F, D and B centers, takes both blocks
+  /// out of place.
+  pub fn is_roux_block_solved(&self, block: RouxBlock) -> bool {
+    block.corners().iter().all(|&c| self.is_corner_solved(c))
+      && block.edges().iter().all(|&e| self.is_edge_solved(e))
+  }
+}
@@ -0,0 +1,56 @@
+use {Corner, Cube, CubeStateErr, Edge};
+
+/// Builds a `Cube` one piece at a time, starting from the solved cube, and
+/// only checks that it is solvable once it is built.
+#[derive(Clone, Copy, Debug)]
+pub struct CubeBuilder(Cube);
+
+impl CubeBuilder {
+  /// Start building from the solved cube.
+  pub fn new() -> CubeBuilder {
+    CubeBuilder(Cube::solved())
+  }
+
+  /// Put `corner` at `position` with `orientation`.
+  pub fn corner(
+    mut self,
+    position: Corner,
+    corner: Corner,
+    orientation: u8,
+  ) -> CubeBuilder {
+    self.0.cp[position as usize] = corner;
+    self.0.co[position as usize] = orientation;
+    self
+  }
+
+  /// Put `edge` at `position` with `orientation`.
+  pub fn edge(
+    mut self,
+    position: Edge,
+    edge: Edge,
+    orientation: u8,
+  ) -> CubeBuilder {
+    self.0.ep[position as usize] = edge;
+    self.0.eo[position as usize] = orientation;
+    self
+  }
+
+  /// Build the `Cube`, checking that it is in a solvable state.
+  pub fn build(self) -> Result<Cube, CubeStateErr> {
+    self.0.verify()?;
+    Ok(self.0)
+  }
+}
+
+impl Default for CubeBuilder {
+  fn default() -> CubeBuilder {
+    CubeBuilder::new()
+  }
+}
+
+impl From<Cube> for CubeBuilder {
+  /// Start building from `cube`.
+  fn from(val: Cube) -> CubeBuilder {
+    CubeBuilder(val)
+  }
+}
@@ -0,0 +1,174 @@
+use std::convert::TryFrom;
+use std::fmt;
+use {Cube, Face, FaceletCube, FaceletErr, NUM_FACELETS};
+
+/// The colors of the stickers of a cube.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Color {
+  White,
+  Yellow,
+  Red,
+  Orange,
+  Green,
+  Blue,
+}
+
+impl Color {
+  /// All the colors.
+  pub const ALL: [Color; 6] = [
+    Color::White,
+    Color::Yellow,
+    Color::Red,
+    Color::Orange,
+    Color::Green,
+    Color::Blue,
+  ];
+
+  /// The first letter of the color's name, which is how it is written in
+  /// color strings.
+  pub fn letter(self) -> char {
+    match self {
+      Color::White => 'W',
+      Color::Yellow => 'Y',
+      Color::Red => 'R',
+      Color::Orange => 'O',
+      Color::Green => 'G',
+      Color::Blue => 'B',
+    }
+  }
+
+  /// The `Color` written as `letter`, or `None` if it is not the letter of a
+  /// color.
+  pub fn from_letter(letter: char) -> Option<Color> {
+    Color::ALL.iter().cloned().find(|c| c.letter() == letter)
+  }
+}
+
+impl fmt::Display for Color {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.letter())
+  }
+}
+
+/// The color of each face of a cube, to convert between the colors that a
+/// scanner sees or a renderer shows and the `Face`s of a `FaceletCube`.
+///
+/// Every face has a different color, so each color is also the color of
+/// exactly one face. The default is the WCA scheme.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ColorScheme([Color; 6]);
+
+impl ColorScheme {
+  /// The color scheme of most cubes, with white U, red R, green F, yellow D,
+  /// blue B and orange L.
+  pub const WCA: ColorScheme = ColorScheme([
+    Color::White,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Orange,
+  ]);
+
+  /// The Japanese color scheme, which is the WCA scheme with blue and yellow
+  /// swapped, so white is opposite blue.
+  pub const JAPANESE: ColorScheme = ColorScheme([
+    Color::White,
+    Color::Red,
+    Color::Green,
+    Color::Blue,
+    Color::Yellow,
+    Color::Orange,
+  ]);
+
+  /// The `ColorScheme` with the color of each face in `colors`, in the order
+  /// of `Face::ALL`, or `None` if two faces have the same color.
+  pub fn new(colors: [Color; 6]) -> Option<ColorScheme> {
+    let distinct = Color::ALL.iter().all(|c| colors.contains(c));
+    if distinct {
+      Some(ColorScheme(colors))
+    } else {
+      None
+    }
+  }
+
+  /// The color of `face`.
+  pub fn color(&self, face: Face) -> Color {
+    self.0[face.index()]
+  }
+
+  /// The face whose color is `color`.
+  pub fn face(&self, color: Color) -> Face {
+    Face::ALL[self.0.iter().position(|&c| c == color).unwrap()]
+  }
+}
+
+impl Default for ColorScheme {
+  fn default() -> ColorScheme {
+    ColorScheme::WCA
+  }
+}
+
+impl FaceletCube {
+  /// The `FaceletCube` whose facelets have `colors` in the colors of
+  /// `scheme`, in the order of the facelets.
+  pub fn from_colors(
+    colors: &[Color; NUM_FACELETS],
+    scheme: &ColorScheme,
+  ) -> FaceletCube {
+    let mut facelets = [Face::U; NUM_FACELETS];
+    for (facelet, &color) in facelets.iter_mut().zip(colors.iter()) {
+      *facelet = scheme.face(color);
+    }
+    FaceletCube(facelets)
+  }
+
+  /// The color of each facelet in the colors of `scheme`.
+  pub fn colors(&self, scheme: &ColorScheme) -> [Color; NUM_FACELETS] {
+    let mut colors = [Color::White; NUM_FACELETS];
+    for (color, &face) in colors.iter_mut().zip(self.0.iter()) {
+      *color = scheme.color(face);
+    }
+    colors
+  }
+
+  /// Parse a string of 54 color letters, such as `W` for white, in the order
+  /// of the facelets, with the colors of `scheme`.
+  pub fn from_color_str(
+    s: &str,
+    scheme: &ColorScheme,
+  ) -> Result<FaceletCube, FaceletErr> {
+    let len = s.chars().count();
+    if len != NUM_FACELETS {
+      return Err(FaceletErr::ErrLength(len));
+    }
+    let mut colors = [Color::White; NUM_FACELETS];
+    for (i, c) in s.chars().enumerate() {
+      colors[i] = Color::from_letter(c).ok_or(FaceletErr::ErrFacelet(i))?;
+    }
+    Ok(FaceletCube::from_colors(&colors, scheme))
+  }
+
+  /// The string of color letters read by `FaceletCube::from_color_str`.
+  pub fn to_color_str(&self, scheme: &ColorScheme) -> String {
+    self.colors(scheme).iter().map(|c| c.letter()).collect()
+  }
+}
+
+impl Cube {
+  /// Creates a new `Cube` from a string of color letters, as read by
+  /// `FaceletCube::from_color_str`.
+  pub fn from_color_str(
+    s: &str,
+    scheme: &ColorScheme,
+  ) -> Result<Cube, FaceletErr> {
+    Cube::try_from(FaceletCube::from_color_str(s, scheme)?)
+  }
+
+  /// The color string of the `Cube`, as read by `Cube::from_color_str`.
+  pub fn to_color_str(&self, scheme: &ColorScheme) -> String {
+    FaceletCube::from(self).to_color_str(scheme)
+  }
+}
@@ -0,0 +1,156 @@
+use std::sync::OnceLock;
+use {Corner, CornerState, Cube, CubeStateErr, Face, Move, Turn, NUM_CORNERS};
+
+/// Models a 2x2x2 cube, which only has corners.
+///
+/// The moves turn the corners like the face turns of a 3x3x3 cube. A 2x2x2
+/// cube has no centers to tell which way it is held, so it is solved when
+/// its corners are solved for any way of holding it, and turning a face is
+/// the same as turning the opposite face the same way and turning the whole
+/// cube back.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Cube2 {
+  pub cp: [Corner; NUM_CORNERS],
+  pub co: [u8; NUM_CORNERS],
+}
+
+impl Cube2 {
+  /// Creates a new `Cube2` with the specified permutation and orientation.
+  ///
+  /// Panics if the `Cube2` is not in a solvable state.
+  pub fn new(cp: [Corner; NUM_CORNERS], co: [u8; NUM_CORNERS]) -> Cube2 {
+    Cube2::try_new(cp, co).unwrap()
+  }
+
+  /// Creates a new `Cube2` with the specified permutation and orientation,
+  /// or the error from `verify` if it is not in a solvable state.
+  pub fn try_new(
+    cp: [Corner; NUM_CORNERS],
+    co: [u8; NUM_CORNERS],
+  ) -> Result<Cube2, CubeStateErr> {
+    let cube = Cube2 { cp, co };
+    cube.verify()?;
+    Ok(cube)
+  }
+
+  /// Creates a new `Cube2` in the solved state.
+  pub fn solved() -> Cube2 {
+    Cube2::from(CornerState::solved())
+  }
+
+  /// The `Cube2` that `moves` produce from the solved `Cube2`.
+  pub fn from_algorithm(moves: &[Move]) -> Cube2 {
+    Cube2::solved().apply_algorithm(moves)
+  }
+
+  /// The corners of the `Cube2`.
+  pub fn corners(&self) -> CornerState {
+    CornerState {
+      cp: self.cp,
+      co: self.co,
+    }
+  }
+
+  /// Return a new `Cube2` after applying `Move`.
+  pub fn apply_move(&self, m: Move) -> Cube2 {
+    Cube2::from(self.corners().apply_move(m))
+  }
+
+  /// Return a new `Cube2` after applying each of `moves`.
+  pub fn apply_algorithm(&self, moves: &[Move]) -> Cube2 {
+    moves.iter().fold(*self, |acc, &m| acc.apply_move(m))
+  }
+
+  /// Return a new `Cube2` after applying `other` as a permutation, like
+  /// `Cube::compose`.
+  pub fn compose(&self, other: &Cube2) -> Cube2 {
+    let mut cube = *other;
+    for i in 0..NUM_CORNERS {
+      let j = other.cp[i] as usize;
+      cube.cp[i] = self.cp[j];
+      cube.co[i] = (self.co[j] + other.co[i]) % 3;
+    }
+    cube
+  }
+
+  /// The `Cube2` that undoes the current `Cube2`.
+  pub fn inverse(&self) -> Cube2 {
+    let mut cube = *self;
+    for i in 0..NUM_CORNERS {
+      let j = self.cp[i] as usize;
+      cube.cp[j] = Corner::from(i);
+      cube.co[j] = (3 - self.co[i]) % 3;
+    }
+    cube
+  }
+
+  /// Verify that a `Cube2` is in a solvable state. Unlike a `Cube`, any
+  /// permutation of the corners can be solved.
+  pub fn verify(&self) -> Result<(), CubeStateErr> {
+    self.corners().verify()
+  }
+
+  /// The same `Cube2` turned as a whole so that the DBL corner is solved.
+  ///
+  /// Every state of a 2x2x2 cube has exactly one way of being held with DBL
+  /// solved, and it can then be solved with only U, R and F, so this is how
+  /// states are compared regardless of how the cube is held.
+  pub fn normalized(&self) -> Cube2 {
+    let dbl = Corner::DBL as usize;
+    rotations()
+      .iter()
+      .map(|rotation| self.compose(rotation))
+      .find(|cube| cube.cp[dbl] == Corner::DBL && cube.co[dbl] == 0)
+      .expect("a rotation brings DBL to its position with orientation 0")
+  }
+
+  /// Whether the `Cube2` is solved, however it is held.
+  pub fn is_solved(&self) -> bool {
+    self.normalized() == Cube2::solved()
+  }
+}
+
+// The 24 ways of turning a whole 2x2x2 cube, as the turns of two opposite
+// faces, with the identity first.
+fn rotations() -> &'static [Cube2] {
+  static ROTATIONS: OnceLock<Vec<Cube2>> = OnceLock::new();
+  ROTATIONS.get_or_init(|| {
+    let x = Cube2::from_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::L, Turn::Counter),
+    ]);
+    let y = Cube2::from_algorithm(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::D, Turn::Counter),
+    ]);
+    let mut rotations = vec![Cube2::solved()];
+    let mut i = 0;
+    while i < rotations.len() {
+      for generator in &[x, y] {
+        let rotation = rotations[i].compose(generator);
+        if !rotations.contains(&rotation) {
+          rotations.push(rotation);
+        }
+      }
+      i += 1;
+    }
+    debug_assert_eq!(24, rotations.len());
+    rotations
+  })
+}
+
+impl From<CornerState> for Cube2 {
+  fn from(corners: CornerState) -> Cube2 {
+    Cube2 {
+      cp: corners.cp,
+      co: corners.co,
+    }
+  }
+}
+
+impl From<Cube> for Cube2 {
+  /// The corners of a 3x3x3 cube, which are a 2x2x2 cube.
+  fn from(cube: Cube) -> Cube2 {
+    Cube2::from(cube.corners())
+  }
+}
@@ -0,0 +1,181 @@
+use std::fmt;
+use {Corner, Cube, Edge, Move, NUM_CORNERS, NUM_EDGES};
+
+/// A cycle of the pieces of a `Cube`, where the piece at each position moves
+/// to the next position, and the piece at the last position moves to the
+/// first.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PieceCycle<P> {
+  /// The positions of the cycle, starting with the first one in the order of
+  /// `Corner` or `Edge`.
+  pub positions: Vec<P>,
+  /// The orientation that a piece of the cycle gains by going once around
+  /// it, which is how much the pieces are twisted or flipped when the cycle
+  /// only has one position.
+  pub orientation: u8,
+}
+
+impl<P> PieceCycle<P> {
+  /// The number of positions of the cycle.
+  pub fn len(&self) -> usize {
+    self.positions.len()
+  }
+
+  // How many times the cycle has to be applied to bring its pieces back to
+  // their positions with their orientations, for pieces with `k`
+  // orientations.
+  fn order(&self, k: usize) -> usize {
+    let orientation = self.orientation as usize;
+    self.len() * (k / gcd(orientation, k))
+  }
+
+  /// Check if the cycle has no positions, which is never the case.
+  pub fn is_empty(&self) -> bool {
+    self.positions.is_empty()
+  }
+}
+
+impl<P: fmt::Debug> fmt::Display for PieceCycle<P> {
+  /// The positions in brackets, followed by `+` if the pieces gain an
+  /// orientation of 1 and `-` if they gain an orientation of 2.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "(")?;
+    for (i, p) in self.positions.iter().enumerate() {
+      if i > 0 {
+        write!(f, " ")?;
+      }
+      write!(f, "{:?}", p)?;
+    }
+    write!(f, ")")?;
+    match self.orientation {
+      0 => Ok(()),
+      1 => write!(f, "+"),
+      _ => write!(f, "-"),
+    }
+  }
+}
+
+/// The effect of an algorithm on the pieces of the cube, as the cycles of
+/// the corners and edges that it moves or reorients.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CycleDescription {
+  /// The cycles of the corners, including corners twisted in place.
+  pub corners: Vec<PieceCycle<Corner>>,
+  /// The cycles of the edges, including edges flipped in place.
+  pub edges: Vec<PieceCycle<Edge>>,
+}
+
+impl CycleDescription {
+  /// Check if no piece is moved or reoriented.
+  pub fn is_identity(&self) -> bool {
+    self.corners.is_empty() && self.edges.is_empty()
+  }
+
+  /// How many times the cycles have to be applied to bring every piece back
+  /// to where it started, which is the least common multiple of the orders
+  /// of the cycles.
+  pub fn order(&self) -> usize {
+    let corners = self.corners.iter().map(|c| c.order(3));
+    let edges = self.edges.iter().map(|c| c.order(2));
+    corners.chain(edges).fold(1, |a, b| a / gcd(a, b) * b)
+  }
+}
+
+// The greatest common divisor of `a` and `b`, where the divisor of 0 and `b`
+// is `b`.
+fn gcd(a: usize, b: usize) -> usize {
+  if a == 0 {
+    b
+  } else {
+    gcd(b % a, a)
+  }
+}
+
+impl fmt::Display for CycleDescription {
+  /// The corner cycles and then the edge cycles, separated by spaces.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let corners = self.corners.iter().map(|c| c.to_string());
+    let edges = self.edges.iter().map(|c| c.to_string());
+    let cycles: Vec<String> = corners.chain(edges).collect();
+    write!(f, "{}", cycles.join(" "))
+  }
+}
+
+// The cycles of `n` pieces with `k` orientations, leaving out the pieces
+// that stay in place unchanged. `piece_at` gives the piece at a position and
+// its orientation there.
+fn decompose(
+  n: usize,
+  k: usize,
+  piece_at: impl Fn(usize) -> (usize, usize),
+) -> Vec<(Vec<usize>, u8)> {
+  // The position that the piece from each position moved to.
+  let mut moved_to = vec![0; n];
+  for p in 0..n {
+    moved_to[piece_at(p).0] = p;
+  }
+  let mut done = vec![false; n];
+  let mut cycles = vec![];
+  for start in 0..n {
+    if done[start] {
+      continue;
+    }
+    let (mut positions, mut orientation) = (vec![], 0);
+    let mut p = start;
+    while !done[p] {
+      done[p] = true;
+      positions.push(p);
+      orientation = (orientation + piece_at(p).1) % k;
+      p = moved_to[p];
+    }
+    if positions.len() > 1 || orientation != 0 {
+      cycles.push((positions, orientation as u8));
+    }
+  }
+  cycles
+}
+
+impl Cube {
+  /// The cycles of the pieces, for the moves that reach this `Cube` from the
+  /// solved cube.
+  pub fn cycle_structure(&self) -> CycleDescription {
+    let corners = decompose(NUM_CORNERS, 3, |p| {
+      (self.cp[p] as usize, self.co[p] as usize)
+    });
+    let edges =
+      decompose(NUM_EDGES, 2, |p| (self.ep[p] as usize, self.eo[p] as usize));
+    CycleDescription {
+      corners: corners
+        .into_iter()
+        .map(|(positions, orientation)| PieceCycle {
+          positions: positions.into_iter().map(Corner::from).collect(),
+          orientation,
+        })
+        .collect(),
+      edges: edges
+        .into_iter()
+        .map(|(positions, orientation)| PieceCycle {
+          positions: positions.into_iter().map(Edge::from).collect(),
+          orientation,
+        })
+        .collect(),
+    }
+  }
+
+  /// How many times the moves that reach this `Cube` from the solved cube
+  /// have to be repeated to return to solved.
+  pub fn order(&self) -> usize {
+    self.cycle_structure().order()
+  }
+}
+
+/// Check if `alg` solves `cube`, such as an algorithm entered for the case of
+/// a trainer.
+pub fn solves(cube: &Cube, alg: &[Move]) -> bool {
+  cube.apply_algorithm(alg) == Cube::solved()
+}
+
+/// The cycles that `alg` performs on the pieces of a solved cube.
+pub fn effect_of(alg: &[Move]) -> CycleDescription {
+  Cube::from_algorithm(alg).cycle_structure()
+}
@@ -0,0 +1,100 @@
+use {Corner, Cube, Edge, Face};
+
+/// The four slots of the first two layers, each a D corner and the E-slice
+/// edge above it, named after the edge.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum F2LSlot {
+  FR,
+  FL,
+  BL,
+  BR,
+}
+
+impl F2LSlot {
+  /// All the slots, in the order of their edges.
+  pub const ALL: [F2LSlot; 4] =
+    [F2LSlot::FR, F2LSlot::FL, F2LSlot::BL, F2LSlot::BR];
+
+  /// The corner of the slot.
+  pub fn corner(self) -> Corner {
+    match self {
+      F2LSlot::FR => Corner::DFR,
+      F2LSlot::FL => Corner::DLF,
+      F2LSlot::BL => Corner::DBL,
+      F2LSlot::BR => Corner::DRB,
+    }
+  }
+
+  /// The edge of the slot.
+  pub fn edge(self) -> Edge {
+    match self {
+      F2LSlot::FR => Edge::FR,
+      F2LSlot::FL => Edge::FL,
+      F2LSlot::BL => Edge::BL,
+      F2LSlot::BR => Edge::BR,
+    }
+  }
+}
+
+/// The edges of the cross on D.
+const CROSS_EDGES: [Edge; 4] = Face::D.adjacent_edges();
+
+/// The corners of the last layer on U.
+const LAST_LAYER_CORNERS: [Corner; 4] = Face::U.adjacent_corners();
+
+/// The edges of the last layer on U.
+const LAST_LAYER_EDGES: [Edge; 4] = Face::U.adjacent_edges();
+
+impl Cube {
+  /// Whether the corner `c` is at its own position with orientation 0.
+  pub fn is_corner_solved(&self, c: Corner) -> bool {
+    self.corner_at(c) == c && self.corner_orientation_at(c) == 0
+  }
+
+  /// Whether the edge `e` is at its own position with orientation 0.
+  pub fn is_edge_solved(&self, e: Edge) -> bool {
+    self.edge_at(e) == e && self.edge_orientation_at(e) == 0
+  }
+
+  /// Whether the four D edges are solved.
+  pub fn is_cross_solved(&self) -> bool {
+    CROSS_EDGES.iter().all(|&e| self.is_edge_solved(e))
+  }
+
+  /// Whether the corner and edge of `slot` are solved. This does not check
+  /// the cross.
+  pub fn is_f2l_slot_solved(&self, slot: F2LSlot) -> bool {
+    self.is_corner_solved(slot.corner()) && self.is_edge_solved(slot.edge())
+  }
+
+  /// The slots whose corner and edge are solved.
+  pub fn solved_f2l_slots(&self) -> Vec<F2LSlot> {
+    F2LSlot::ALL
+      .iter()
+      .cloned()
+      .filter(|&slot| self.is_f2l_slot_solved(slot))
+      .collect()
+  }
+
+  /// Whether the cross and every slot are solved, so only the last layer is
+  /// left.
+  pub fn is_f2l_solved(&self) -> bool {
+    self.is_cross_solved()
+      && F2LSlot::ALL
+        .iter()
+        .all(|&slot| self.is_f2l_slot_solved(slot))
+  }
+
+  /// Whether the first two layers are solved and every last layer piece has
+  /// orientation 0, so the U face is a single color.
+  pub fn is_last_layer_oriented(&self) -> bool {
+    self.is_f2l_solved()
+      && LAST_LAYER_CORNERS
+        .iter()
+        .all(|&c| self.corner_orientation_at(c) == 0)
+      && LAST_LAYER_EDGES
+        .iter()
+        .all(|&e| self.edge_orientation_at(e) == 0)
+  }
+}
@@ -0,0 +1,242 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use {Corner, Cube, CubeStateErr, Edge, Face, NUM_CORNERS, NUM_EDGES};
+
+/// Number of facelets (stickers) on a 3x3x3 cube.
+pub const NUM_FACELETS: usize = 54;
+
+/// Models a 3x3x3 cube as the color of each of its 54 facelets, where each
+/// color is named after the face whose center has that color.
+///
+/// The facelets are ordered by face, U, R, F, D, L and then B, and each face
+/// is read row by row as it is seen in an unfolded net of the cube:
+///
+/// ```text
+///              |************|
+///              |*U1**U2**U3*|
+///              |************|
+///              |*U4**U5**U6*|
+///              |************|
+///              |*U7**U8**U9*|
+/// |************|************|************|************|
+/// |*L1**L2**L3*|*F1**F2**F3*|*R1**R2**R3*|*B1**B2**B3*|
+/// |************|************|************|************|
+/// |*L4**L5**L6*|*F4**F5**F6*|*R4**R5**R6*|*B4**B5**B6*|
+/// |************|************|************|************|
+/// |*L7**L8**L9*|*F7**F8**F9*|*R7**R8**R9*|*B7**B8**B9*|
+/// |************|************|************|************|
+///              |*D1**D2**D3*|
+///              |************|
+///              |*D4**D5**D6*|
+///              |************|
+///              |*D7**D8**D9*|
+///              |************|
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FaceletCube(pub [Face; NUM_FACELETS]);
+
+/// The faces in the order of their facelets.
+const FACELET_FACES: [Face; 6] =
+  [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+/// The facelets of each corner, in the order of `Corner::faces`.
+pub(crate) const CORNER_FACELETS: [[usize; 3]; NUM_CORNERS] = [
+  [8, 9, 20],
+  [6, 18, 38],
+  [0, 36, 47],
+  [2, 45, 11],
+  [29, 26, 15],
+  [27, 44, 24],
+  [33, 53, 42],
+  [35, 17, 51],
+];
+
+/// The facelets of each edge, in the order of `Edge::faces`.
+pub(crate) const EDGE_FACELETS: [[usize; 2]; NUM_EDGES] = [
+  [5, 10],
+  [7, 19],
+  [3, 37],
+  [1, 46],
+  [32, 16],
+  [28, 25],
+  [30, 43],
+  [34, 52],
+  [23, 12],
+  [21, 41],
+  [50, 39],
+  [48, 14],
+];
+
+/// An error for the different invalid facelet configurations.
+#[derive(Debug, PartialEq)]
+pub enum FaceletErr {
+  /// The facelet string does not have 54 facelets.
+  ErrLength(usize),
+  /// The facelet at this index is not one of `U`, `R`, `F`, `D`, `L` or `B`.
+  ErrFacelet(usize),
+  /// The center of this face has the wrong color.
+  ErrCenter(Face),
+  /// The colors at this corner position are not the colors of any corner.
+  ErrCorner(Corner),
+  /// The colors at this edge position are not the colors of any edge.
+  ErrEdge(Edge),
+  /// The pieces are valid, but do not form a solvable cube.
+  ErrState(CubeStateErr),
+}
+
+impl fmt::Display for FaceletErr {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      FaceletErr::ErrLength(len) => {
+        write!(f, "expected {} facelets, found {}", NUM_FACELETS, len)
+      }
+      FaceletErr::ErrFacelet(i) => write!(f, "invalid facelet at {}", i),
+      FaceletErr::ErrCenter(face) => write!(f, "invalid {} center", face),
+      FaceletErr::ErrCorner(c) => write!(f, "invalid corner at {:?}", c),
+      FaceletErr::ErrEdge(e) => write!(f, "invalid edge at {:?}", e),
+      FaceletErr::ErrState(err) => write!(f, "{}", err),
+    }
+  }
+}
+
+impl Error for FaceletErr {
+  fn source(&self) -> Option<&(dyn Error + 'static)> {
+    match self {
+      FaceletErr::ErrState(err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl FaceletCube {
+  /// Creates a new `FaceletCube` in the solved state.
+  pub fn solved() -> FaceletCube {
+    let mut facelets = [Face::U; NUM_FACELETS];
+    for (i, f) in facelets.iter_mut().enumerate() {
+      *f = FACELET_FACES[i / 9];
+    }
+    FaceletCube(facelets)
+  }
+}
+
+impl FromStr for FaceletCube {
+  type Err = FaceletErr;
+
+  /// Parse the 54 character facelet string used by Kociemba's reference
+  /// implementation, which has the color of each facelet in order. The solved
+  /// cube is `UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB`.
+  fn from_str(s: &str) -> Result<FaceletCube, FaceletErr> {
+    let len = s.chars().count();
+    if len != NUM_FACELETS {
+      return Err(FaceletErr::ErrLength(len));
+    }
+    let mut facelets = [Face::U; NUM_FACELETS];
+    for (i, c) in s.chars().enumerate() {
+      facelets[i] = match c {
+        'U' => Face::U,
+        'R' => Face::R,
+        'F' => Face::F,
+        'D' => Face::D,
+        'L' => Face::L,
+        'B' => Face::B,
+        _ => return Err(FaceletErr::ErrFacelet(i)),
+      };
+    }
+    Ok(FaceletCube(facelets))
+  }
+}
+
+impl fmt::Display for FaceletCube {
+  /// Format the facelets as the string read by `FaceletCube::from_str`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for face in self.0.iter() {
+      write!(f, "{}", face)?;
+    }
+    Ok(())
+  }
+}
+
+impl Cube {
+  /// Creates a new `Cube` from a facelet string, as read by
+  /// `FaceletCube::from_str`.
+  pub fn from_facelet_str(s: &str) -> Result<Cube, FaceletErr> {
+    Cube::try_from(s.parse::<FaceletCube>()?)
+  }
+
+  /// The facelet string of the `Cube`, as read by `Cube::from_facelet_str`.
+  pub fn to_facelet_str(&self) -> String {
+    FaceletCube::from(self).to_string()
+  }
+}
+
+impl<'a> From<&'a Cube> for FaceletCube {
+  fn from(cube: &'a Cube) -> FaceletCube {
+    let mut facelets = FaceletCube::solved().0;
+    for (i, facelet) in CORNER_FACELETS.iter().enumerate() {
+      let colors = cube.cp[i].faces();
+      // `co` counts twists in the opposite direction to the order of
+      // `CORNER_FACELETS`.
+      let twist = (3 - cube.co[i] as usize) % 3;
+      for (n, &color) in colors.iter().enumerate() {
+        facelets[facelet[(n + twist) % 3]] = color;
+      }
+    }
+    for (i, facelet) in EDGE_FACELETS.iter().enumerate() {
+      let colors = cube.ep[i].faces();
+      let flip = cube.eo[i] as usize;
+      for (n, &color) in colors.iter().enumerate() {
+        facelets[facelet[(n + flip) % 2]] = color;
+      }
+    }
+    FaceletCube(facelets)
+  }
+}
+
+impl TryFrom<FaceletCube> for Cube {
+  type Error = FaceletErr;
+
+  fn try_from(facelets: FaceletCube) -> Result<Cube, FaceletErr> {
+    let f = &facelets.0;
+    for (i, &face) in FACELET_FACES.iter().enumerate() {
+      if f[i * 9 + 4] != face {
+        return Err(FaceletErr::ErrCenter(face));
+      }
+    }
+
+    let mut cube = Cube::solved();
+    for (i, facelet) in CORNER_FACELETS.iter().enumerate() {
+      let twist = (0..3)
+        .find(|&n| f[facelet[n]] == Face::U || f[facelet[n]] == Face::D)
+        .ok_or(FaceletErr::ErrCorner(Corner::from(i)))?;
+      let colors = [
+        f[facelet[twist]],
+        f[facelet[(twist + 1) % 3]],
+        f[facelet[(twist + 2) % 3]],
+      ];
+      cube.cp[i] = *Corner::ALL
+        .iter()
+        .find(|c| c.faces() == colors)
+        .ok_or(FaceletErr::ErrCorner(Corner::from(i)))?;
+      cube.co[i] = ((3 - twist) % 3) as u8;
+    }
+    for (i, facelet) in EDGE_FACELETS.iter().enumerate() {
+      let colors = [f[facelet[0]], f[facelet[1]]];
+      let flipped = [colors[1], colors[0]];
+      let (edge, flip) = Edge::ALL
+        .iter()
+        .find_map(|&e| match e.faces() {
+          c if c == colors => Some((e, 0)),
+          c if c == flipped => Some((e, 1)),
+          _ => None,
+        })
+        .ok_or(FaceletErr::ErrEdge(Edge::from(i)))?;
+      cube.ep[i] = edge;
+      cube.eo[i] = flip;
+    }
+
+    cube.verify().map_err(FaceletErr::ErrState)?;
+    Ok(cube)
+  }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use {invert_algorithm, simplify_algorithm};
+use {Corner, Cube, Edge, Face, Move, Turn, NUM_CORNERS, NUM_EDGES};
+
+/// An algorithm inserted into a sequence of moves.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Insertion {
+  /// The number of moves of the sequence before the algorithm, counting the
+  /// algorithms inserted before this one but not any moves they cancel.
+  pub position: usize,
+  /// The algorithm that is inserted.
+  pub algorithm: Vec<Move>,
+}
+
+/// A skeleton completed by inserting algorithms into it.
+#[derive(Clone, Debug, PartialEq)]
+pub struct InsertionSolution {
+  /// The insertions, in the order they were made.
+  pub insertions: Vec<Insertion>,
+  /// The whole solution, with the moves that cancel removed.
+  pub moves: Vec<Move>,
+}
+
+// The number of corners and of edges that are not solved.
+fn unsolved_pieces(cube: &Cube) -> (usize, usize) {
+  let corners = (0..NUM_CORNERS)
+    .filter(|&i| !cube.is_corner_solved(Corner::from(i)))
+    .count();
+  let edges = (0..NUM_EDGES)
+    .filter(|&i| !cube.is_edge_solved(Edge::from(i)))
+    .count();
+  (corners, edges)
+}
+
+// Every sequence of 1 to `max_len` moves where the same face is not turned
+// twice in a row, and a face is not turned again straight after its
+// opposite face.
+fn sequences(max_len: usize) -> Vec<Vec<Move>> {
+  let mut sequences = vec![];
+  let mut last: Vec<Vec<Move>> = vec![vec![]];
+  for _ in 0..max_len {
+    let mut next = vec![];
+    for sequence in &last {
+      for &face in &Face::ALL {
+        let skip = match sequence[..] {
+          [.., Move(last, _)] if last == face => true,
+          [.., Move(f1, _), Move(f2, _)] => f1 == face && f2.is_opposite(face),
+          _ => false,
+        };
+        if skip {
+          continue;
+        }
+        for &turn in &Turn::ALL {
+          let mut sequence = sequence.clone();
+          sequence.push(Move(face, turn));
+          next.push(sequence);
+        }
+      }
+    }
+    sequences.extend(next.iter().cloned());
+    last = next;
+  }
+  sequences
+}
+
+// Add `algorithm` to `algorithms` if it only moves three or four corners or
+// three or four edges, and it is the shortest algorithm for its cube so far.
+fn add_algorithm(
+  algorithms: &mut HashMap<Cube, Vec<Move>>,
+  algorithm: Vec<Move>,
+) {
+  let cube = Cube::from_algorithm(&algorithm);
+  let (corners, edges) = unsolved_pieces(&cube);
+  let cycle = |n| n == 3 || n == 4;
+  if !((cycle(corners) && edges == 0) || (corners == 0 && cycle(edges))) {
+    return;
+  }
+  let shortest = algorithms.entry(cube).or_insert_with(|| algorithm.clone());
+  if algorithm.len() < shortest.len() {
+    *shortest = algorithm;
+  }
+}
+
+/// Generate algorithms to insert into a skeleton: the shortest algorithm
+/// found for each way of moving only three or four corners or only three or
+/// four edges, such as 3-cycles and double swaps.
+///
+/// The algorithms are the commutators `A B A' B'`, where `A` has at most
+/// `a_len` moves and `B` at most `b_len`, and those commutators conjugated by
+/// up to `setup_len` moves. The number of algorithms grows quickly with the
+/// lengths: 3, 1 and 1 gives most of the corner 3-cycles, and 4, 2 and 2
+/// gives nearly all the 3-cycles, but takes several seconds.
+pub fn generate_insertion_algorithms(
+  a_len: usize,
+  b_len: usize,
+  setup_len: usize,
+) -> Vec<Vec<Move>> {
+  let mut algorithms: HashMap<Cube, Vec<Move>> = HashMap::new();
+  let bs = sequences(b_len);
+  for a in &sequences(a_len) {
+    for b in &bs {
+      let mut commutator = a.clone();
+      commutator.extend(b);
+      commutator.extend(invert_algorithm(a));
+      commutator.extend(invert_algorithm(b));
+      add_algorithm(&mut algorithms, simplify_algorithm(&commutator));
+    }
+  }
+  let commutators: Vec<Vec<Move>> = algorithms.values().cloned().collect();
+  for setup in &sequences(setup_len) {
+    for commutator in &commutators {
+      let mut conjugate = setup.clone();
+      conjugate.extend(commutator);
+      conjugate.extend(invert_algorithm(setup));
+      add_algorithm(&mut algorithms, simplify_algorithm(&conjugate));
+    }
+  }
+
+  let mut algorithms: Vec<Vec<Move>> = algorithms.into_values().collect();
+  algorithms.sort_by_key(|algorithm| algorithm.len());
+  algorithms
+}
+
+/// Find the shortest way to finish `skeleton`, a sequence of moves that
+/// almost solves `scramble`, by inserting up to `max_insertions` of
+/// `algorithms` into it.
+///
+/// Each insertion has to solve more pieces than it breaks, and the
+/// algorithms are inserted at every point of the skeleton, including the
+/// start and the end, so the best solution is the one where the most moves
+/// cancel. `None` is returned if the skeleton cannot be finished.
+pub fn find_insertions(
+  scramble: &Cube,
+  skeleton: &[Move],
+  algorithms: &[Vec<Move>],
+  max_insertions: usize,
+) -> Option<InsertionSolution> {
+  let algorithms: Vec<(Cube, &Vec<Move>)> = algorithms
+    .iter()
+    .map(|algorithm| (Cube::from_algorithm(algorithm), algorithm))
+    .collect();
+  let mut best = None;
+  insert(
+    scramble,
+    &mut skeleton.to_vec(),
+    &algorithms,
+    max_insertions,
+    &mut vec![],
+    &mut best,
+  );
+  best
+}
+
+// Try each insertion into `moves`, after `insertions`, keeping the shortest
+// solution in `best`.
+fn insert(
+  scramble: &Cube,
+  moves: &mut Vec<Move>,
+  algorithms: &[(Cube, &Vec<Move>)],
+  max_insertions: usize,
+  insertions: &mut Vec<Insertion>,
+  best: &mut Option<InsertionSolution>,
+) {
+  let (corners, edges) = unsolved_pieces(&scramble.apply_algorithm(moves));
+  let unsolved = corners + edges;
+  if unsolved == 0 {
+    let solution = simplify_algorithm(moves);
+    if best
+      .as_ref()
+      .is_none_or(|best| solution.len() < best.moves.len())
+    {
+      *best = Some(InsertionSolution {
+        insertions: insertions.clone(),
+        moves: solution,
+      });
+    }
+    return;
+  }
+  if insertions.len() == max_insertions {
+    return;
+  }
+
+  for position in 0..=moves.len() {
+    // Inserting `A` between `P` and `S` leaves the cube as `scramble P A S`,
+    // which has the same number of unsolved pieces as its conjugate
+    // `S scramble P A`, so only the algorithm needs to be applied for each
+    // insertion.
+    let (before, after) = moves.split_at(position);
+    let rotated =
+      Cube::from_algorithm(after).compose(&scramble.apply_algorithm(before));
+    for &(cube, algorithm) in algorithms {
+      let (corners, edges) = unsolved_pieces(&rotated.compose(&cube));
+      if corners + edges >= unsolved {
+        continue;
+      }
+      let tail = moves.split_off(position);
+      moves.extend(algorithm);
+      moves.extend(tail);
+      insertions.push(Insertion {
+        position,
+        algorithm: algorithm.clone(),
+      });
+      insert(
+        scramble,
+        moves,
+        algorithms,
+        max_insertions,
+        insertions,
+        best,
+      );
+      insertions.pop();
+      let tail = moves.split_off(position + algorithm.len());
+      moves.truncate(position);
+      moves.extend(tail);
+    }
+  }
+}
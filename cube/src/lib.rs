@@ -1,4 +1,132 @@
+#[cfg(feature = "proptest")]
+extern crate proptest;
+extern crate rand;
+extern crate rand_chacha;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+use std::cmp::Ordering;
+use std::ops::Mul;
+
+#[cfg(feature = "proptest")]
+mod arbitrary;
+mod ascii;
+mod bld;
+mod blocks;
+mod builder;
+mod color;
+mod cube2;
+mod cycles;
+mod f2l;
+mod facelet;
+mod insertions;
+mod metric;
+mod notation;
+mod oriented;
+mod pack;
+mod pieces;
+mod puzzle;
+mod pyraminx;
+mod rotation;
+mod scramble;
+#[cfg(feature = "serde")]
+mod serialize;
+mod simplify;
+mod smartcube;
+mod svg;
+mod symmetry;
+mod transform;
+mod verify;
+
+pub use bld::corner_letter;
+pub use bld::edge_letter;
+pub use bld::Memo;
+
+pub use blocks::RouxBlock;
+
+pub use builder::CubeBuilder;
+
+pub use color::Color;
+pub use color::ColorScheme;
+
+pub use cube2::Cube2;
+
+pub use cycles::effect_of;
+pub use cycles::solves;
+pub use cycles::CycleDescription;
+pub use cycles::PieceCycle;
+
+pub use f2l::F2LSlot;
+
+pub use facelet::FaceletCube;
+pub use facelet::FaceletErr;
+pub use facelet::NUM_FACELETS;
+
+pub use insertions::find_insertions;
+pub use insertions::generate_insertion_algorithms;
+pub use insertions::Insertion;
+pub use insertions::InsertionSolution;
+
+pub use metric::algorithm_length;
+pub use metric::extended_algorithm_length;
+pub use metric::Metric;
+
+pub use notation::format_algorithm;
+pub use notation::format_extended_algorithm;
+pub use notation::parse_algorithm;
+pub use notation::parse_extended_algorithm;
+pub use notation::ParseError;
+
+pub use oriented::OrientedCube;
+
+pub use pieces::CornerState;
+pub use pieces::EdgeState;
+
+pub use puzzle::PermutationPuzzle;
+
+pub use pyraminx::parse_pyraminx_algorithm;
+pub use pyraminx::Pyraminx;
+pub use pyraminx::PyraminxMove;
+pub use pyraminx::Vertex;
+pub use pyraminx::NUM_PYRAMINX_EDGES;
+
+pub use rotation::expand_algorithm;
+pub use rotation::Axis;
+pub use rotation::ExtendedMove;
+pub use rotation::Orientation;
+
+pub use scramble::generate_random_move_scramble;
+pub use scramble::seeded_rng;
+pub use scramble::SeededRng;
+
+pub use simplify::simplify_algorithm;
+
+pub use smartcube::GanDecoder;
+pub use smartcube::GiikerDecoder;
+pub use smartcube::MoveDecoder;
+pub use smartcube::PacketError;
+pub use smartcube::SmartCube;
+
+pub use svg::SvgRenderer;
+pub use svg::SvgView;
+
+pub use symmetry::Symmetry;
+pub use symmetry::NUM_SYMMETRIES;
+pub use symmetry::NUM_UD_SYMMETRIES;
+
+pub use transform::mirror_algorithm;
+pub use transform::mirror_extended_algorithm;
+pub use transform::remove_rotations;
+pub use transform::rotate_algorithm;
+pub use transform::rotate_extended_algorithm;
+
+pub use verify::verify_move_tables;
+
+#[cfg(feature = "serde")]
+pub use serialize::UncheckedCube;
+
 /// The faces on a 3x3x3 cube.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[derive(Clone, Copy, Debug, PartialEq)]
 pub enum Face {
   U,
@@ -9,14 +137,41 @@ pub enum Face {
   L,
 }
 
-#[derive(PartialEq)]
-enum Slice {
+/// The middle layers of a 3x3x3 cube.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Slice {
+  /// The layer between U and D, which turns in the direction of D.
   E,
+  /// The layer between R and L, which turns in the direction of L.
   M,
+  /// The layer between F and B, which turns in the direction of F.
   S,
 }
 
 impl Face {
+  /// All the faces, in the order of their `index`.
+  pub const ALL: [Face; 6] =
+    [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+
+  /// The index of the face, from 0 to 5, which is its position in
+  /// `Face::ALL`. Tables of faces are indexed by it, so it never changes.
+  pub fn index(self) -> usize {
+    match self {
+      Face::U => 0,
+      Face::R => 1,
+      Face::F => 2,
+      Face::D => 3,
+      Face::B => 4,
+      Face::L => 5,
+    }
+  }
+
+  /// The `Face` with `index`, or `None` if it is not from 0 to 5.
+  pub fn from_index(index: usize) -> Option<Face> {
+    Face::ALL.get(index).copied()
+  }
+
   fn slice(&self) -> Slice {
     match self {
       Face::U => Slice::E,
@@ -33,87 +188,219 @@ impl Face {
   pub fn is_opposite(&self, face: Face) -> bool {
     self.slice() == face.slice()
   }
+
+  /// The `Face` opposite this face.
+  pub fn opposite(&self) -> Face {
+    match self {
+      Face::U => Face::D,
+      Face::R => Face::L,
+      Face::F => Face::B,
+      Face::D => Face::U,
+      Face::B => Face::F,
+      Face::L => Face::R,
+    }
+  }
+
+  /// The corners on this face, in the order of `Corner::ALL`.
+  pub const fn adjacent_corners(self) -> [Corner; 4] {
+    match self {
+      Face::U => [Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR],
+      Face::R => [Corner::URF, Corner::UBR, Corner::DFR, Corner::DRB],
+      Face::F => [Corner::URF, Corner::UFL, Corner::DFR, Corner::DLF],
+      Face::D => [Corner::DFR, Corner::DLF, Corner::DBL, Corner::DRB],
+      Face::B => [Corner::ULB, Corner::UBR, Corner::DBL, Corner::DRB],
+      Face::L => [Corner::UFL, Corner::ULB, Corner::DLF, Corner::DBL],
+    }
+  }
+
+  /// The edges on this face, in the order of `Edge::ALL`.
+  pub const fn adjacent_edges(self) -> [Edge; 4] {
+    match self {
+      Face::U => [Edge::UR, Edge::UF, Edge::UL, Edge::UB],
+      Face::R => [Edge::UR, Edge::DR, Edge::FR, Edge::BR],
+      Face::F => [Edge::UF, Edge::DF, Edge::FR, Edge::FL],
+      Face::D => [Edge::DR, Edge::DF, Edge::DL, Edge::DB],
+      Face::B => [Edge::UB, Edge::DB, Edge::BL, Edge::BR],
+      Face::L => [Edge::UL, Edge::DL, Edge::FL, Edge::BL],
+    }
+  }
 }
 
 impl From<Face> for usize {
   fn from(val: Face) -> usize {
-    match val {
-      Face::U => 0,
-      Face::R => 1,
-      Face::F => 2,
-      Face::D => 3,
-      Face::B => 4,
-      Face::L => 5,
+    val.index()
+  }
+}
+
+/// How far a `Move` turns its face.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Turn {
+  /// A quarter turn clockwise.
+  Clockwise,
+  /// A half turn.
+  Half,
+  /// A quarter turn counter-clockwise.
+  Counter,
+}
+
+impl Turn {
+  /// All the turns, in order of their number of clockwise quarter turns.
+  pub const ALL: [Turn; 3] = [Turn::Clockwise, Turn::Half, Turn::Counter];
+
+  /// The `Turn` of `quarter_turns` clockwise quarter turns, modulo 4, or
+  /// `None` if that does not turn the face at all.
+  pub fn from_quarter_turns(quarter_turns: u8) -> Option<Turn> {
+    match quarter_turns % 4 {
+      1 => Some(Turn::Clockwise),
+      2 => Some(Turn::Half),
+      3 => Some(Turn::Counter),
+      _ => None,
+    }
+  }
+
+  /// The number of clockwise quarter turns, from 1 to 3.
+  pub fn quarter_turns(self) -> u8 {
+    match self {
+      Turn::Clockwise => 1,
+      Turn::Half => 2,
+      Turn::Counter => 3,
+    }
+  }
+
+  /// The `Turn` that undoes this turn.
+  pub fn inverse(self) -> Turn {
+    match self {
+      Turn::Clockwise => Turn::Counter,
+      Turn::Half => Turn::Half,
+      Turn::Counter => Turn::Clockwise,
     }
   }
 }
 
+impl From<Turn> for u8 {
+  fn from(val: Turn) -> u8 {
+    val.quarter_turns()
+  }
+}
+
 /// A move on a 3x3x3 cube.
-#[derive(Clone, Copy, Debug)]
-pub struct Move(pub Face, pub u8);
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Move(pub Face, pub Turn);
+
+impl Move {
+  /// The `Move` of `quarter_turns` clockwise quarter turns of `face`, modulo
+  /// 4, or `None` if that does not turn the face at all.
+  pub fn new(face: Face, quarter_turns: u8) -> Option<Move> {
+    Turn::from_quarter_turns(quarter_turns).map(|turn| Move(face, turn))
+  }
+
+  /// The `Move` that undoes this move.
+  pub fn inverse(&self) -> Move {
+    let Move(face, turn) = *self;
+    Move(face, turn.inverse())
+  }
+}
+
+/// The algorithm that undoes `moves`, which is the inverse of each move in
+/// reverse order.
+pub fn invert_algorithm(moves: &[Move]) -> Vec<Move> {
+  moves.iter().rev().map(|m| m.inverse()).collect()
+}
 
 /// The permutations and orientations representing a move.
+#[derive(Clone, Copy)]
 struct MovePerm {
-  cp: &'static [usize; NUM_CORNERS],
-  co: &'static [u8; NUM_CORNERS],
-  ep: &'static [usize; NUM_EDGES],
-  eo: &'static [u8; NUM_EDGES],
+  cp: [usize; NUM_CORNERS],
+  co: [u8; NUM_CORNERS],
+  ep: [usize; NUM_EDGES],
+  eo: [u8; NUM_EDGES],
+}
+
+impl MovePerm {
+  /// The `MovePerm` of applying `self` and then `other`.
+  const fn then(&self, other: &MovePerm) -> MovePerm {
+    let mut perm = *other;
+    let mut i = 0;
+    while i < NUM_CORNERS {
+      let j = self.cp[other.cp[i]];
+      perm.cp[i] = j;
+      perm.co[j] = (self.co[j] + other.co[other.cp[i]]) % 3;
+      i += 1;
+    }
+    let mut i = 0;
+    while i < NUM_EDGES {
+      perm.ep[i] = self.ep[other.ep[i]];
+      perm.eo[i] = self.eo[other.ep[i]] ^ other.eo[i];
+      i += 1;
+    }
+    perm
+  }
+
+  /// The quarter, half and prime turns of the move.
+  const fn turns(&self) -> [MovePerm; 3] {
+    let half = self.then(self);
+    let prime = half.then(self);
+    [*self, half, prime]
+  }
 }
 
-/// An array containing the 6 basic moves on a 3x3x3.
-const MOVE_PERMS: [MovePerm; 6] = [
-  MOVE_PERM_U,
-  MOVE_PERM_R,
-  MOVE_PERM_F,
-  MOVE_PERM_D,
-  MOVE_PERM_B,
-  MOVE_PERM_L,
+/// The quarter, half and prime turns of the 6 basic moves on a 3x3x3.
+const MOVE_PERMS: [[MovePerm; 3]; 6] = [
+  MOVE_PERM_U.turns(),
+  MOVE_PERM_R.turns(),
+  MOVE_PERM_F.turns(),
+  MOVE_PERM_D.turns(),
+  MOVE_PERM_B.turns(),
+  MOVE_PERM_L.turns(),
 ];
 
 const MOVE_PERM_U: MovePerm = MovePerm {
-  cp: &[3, 0, 1, 2, 4, 5, 6, 7],
-  co: &[0; 8],
-  ep: &[3, 0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11],
-  eo: &[0; 12],
+  cp: [3, 0, 1, 2, 4, 5, 6, 7],
+  co: [0; 8],
+  ep: [3, 0, 1, 2, 4, 5, 6, 7, 8, 9, 10, 11],
+  eo: [0; 12],
 };
 
 const MOVE_PERM_R: MovePerm = MovePerm {
-  cp: &[4, 1, 2, 0, 7, 5, 6, 3],
-  co: &[2, 0, 0, 1, 1, 0, 0, 2],
-  ep: &[8, 1, 2, 3, 11, 5, 6, 7, 4, 9, 10, 0],
-  eo: &[0; 12],
+  cp: [4, 1, 2, 0, 7, 5, 6, 3],
+  co: [2, 0, 0, 1, 1, 0, 0, 2],
+  ep: [8, 1, 2, 3, 11, 5, 6, 7, 4, 9, 10, 0],
+  eo: [0; 12],
 };
 
 const MOVE_PERM_F: MovePerm = MovePerm {
-  cp: &[1, 5, 2, 3, 0, 4, 6, 7],
-  co: &[1, 2, 0, 0, 2, 1, 0, 0],
-  ep: &[0, 9, 2, 3, 4, 8, 6, 7, 1, 5, 10, 11],
-  eo: &[0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
+  cp: [1, 5, 2, 3, 0, 4, 6, 7],
+  co: [1, 2, 0, 0, 2, 1, 0, 0],
+  ep: [0, 9, 2, 3, 4, 8, 6, 7, 1, 5, 10, 11],
+  eo: [0, 1, 0, 0, 0, 1, 0, 0, 1, 1, 0, 0],
 };
 
 const MOVE_PERM_D: MovePerm = MovePerm {
-  cp: &[0, 1, 2, 3, 5, 6, 7, 4],
-  co: &[0; 8],
-  ep: &[0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11],
-  eo: &[0; 12],
+  cp: [0, 1, 2, 3, 5, 6, 7, 4],
+  co: [0; 8],
+  ep: [0, 1, 2, 3, 5, 6, 7, 4, 8, 9, 10, 11],
+  eo: [0; 12],
 };
 
 const MOVE_PERM_B: MovePerm = MovePerm {
-  cp: &[0, 1, 3, 7, 4, 5, 2, 6],
-  co: &[0, 0, 1, 2, 0, 0, 2, 1],
-  ep: &[0, 1, 2, 11, 4, 5, 6, 10, 8, 9, 3, 7],
-  eo: &[0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
+  cp: [0, 1, 3, 7, 4, 5, 2, 6],
+  co: [0, 0, 1, 2, 0, 0, 2, 1],
+  ep: [0, 1, 2, 11, 4, 5, 6, 10, 8, 9, 3, 7],
+  eo: [0, 0, 0, 1, 0, 0, 0, 1, 0, 0, 1, 1],
 };
 
 const MOVE_PERM_L: MovePerm = MovePerm {
-  cp: &[0, 2, 6, 3, 4, 1, 5, 7],
-  co: &[0, 1, 2, 0, 0, 2, 1, 0],
-  ep: &[0, 1, 10, 3, 4, 5, 9, 7, 8, 2, 6, 11],
-  eo: &[0; 12],
+  cp: [0, 2, 6, 3, 4, 1, 5, 7],
+  co: [0, 1, 2, 0, 0, 2, 1, 0],
+  ep: [0, 1, 10, 3, 4, 5, 9, 7, 8, 2, 6, 11],
+  eo: [0; 12],
 };
 
 /// The corners on a 3x3x3 cube.
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Corner {
   URF,
   UFL,
@@ -125,6 +412,36 @@ pub enum Corner {
   DRB,
 }
 
+impl Corner {
+  /// All the corners, in the order of their positions in `Cube::cp`.
+  pub const ALL: [Corner; 8] = [
+    Corner::URF,
+    Corner::UFL,
+    Corner::ULB,
+    Corner::UBR,
+    Corner::DFR,
+    Corner::DLF,
+    Corner::DBL,
+    Corner::DRB,
+  ];
+
+  /// The faces of the corner's facelets, starting with U or D and going
+  /// clockwise around the corner. These are the colors of the corner, in the
+  /// order that `Cube::co` twists them.
+  pub const fn faces(self) -> [Face; 3] {
+    match self {
+      Corner::URF => [Face::U, Face::R, Face::F],
+      Corner::UFL => [Face::U, Face::F, Face::L],
+      Corner::ULB => [Face::U, Face::L, Face::B],
+      Corner::UBR => [Face::U, Face::B, Face::R],
+      Corner::DFR => [Face::D, Face::F, Face::R],
+      Corner::DLF => [Face::D, Face::L, Face::F],
+      Corner::DBL => [Face::D, Face::B, Face::L],
+      Corner::DRB => [Face::D, Face::R, Face::B],
+    }
+  }
+}
+
 impl From<usize> for Corner {
   fn from(val: usize) -> Corner {
     match val {
@@ -142,7 +459,8 @@ impl From<usize> for Corner {
 }
 
 /// The edges on a 3x3x3 cube.
-#[derive(Clone, Copy, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub enum Edge {
   UR,
   UF,
@@ -158,6 +476,44 @@ pub enum Edge {
   BR,
 }
 
+impl Edge {
+  /// All the edges, in the order of their positions in `Cube::ep`.
+  pub const ALL: [Edge; 12] = [
+    Edge::UR,
+    Edge::UF,
+    Edge::UL,
+    Edge::UB,
+    Edge::DR,
+    Edge::DF,
+    Edge::DL,
+    Edge::DB,
+    Edge::FR,
+    Edge::FL,
+    Edge::BL,
+    Edge::BR,
+  ];
+
+  /// The faces of the edge's facelets, starting with U or D, or with F or B
+  /// for the E-slice edges. An edge with an `eo` of 1 has its first face on
+  /// the other facelet.
+  pub const fn faces(self) -> [Face; 2] {
+    match self {
+      Edge::UR => [Face::U, Face::R],
+      Edge::UF => [Face::U, Face::F],
+      Edge::UL => [Face::U, Face::L],
+      Edge::UB => [Face::U, Face::B],
+      Edge::DR => [Face::D, Face::R],
+      Edge::DF => [Face::D, Face::F],
+      Edge::DL => [Face::D, Face::L],
+      Edge::DB => [Face::D, Face::B],
+      Edge::FR => [Face::F, Face::R],
+      Edge::FL => [Face::F, Face::L],
+      Edge::BL => [Face::B, Face::L],
+      Edge::BR => [Face::B, Face::R],
+    }
+  }
+}
+
 impl From<usize> for Edge {
   fn from(val: usize) -> Edge {
     match val {
@@ -188,13 +544,38 @@ pub enum CubeStateErr {
   ErrParity,
 }
 
+impl std::fmt::Display for CubeStateErr {
+  fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+    let msg = match self {
+      CubeStateErr::ErrEO => "invalid edge orientation",
+      CubeStateErr::ErrCO => "invalid corner orientation",
+      CubeStateErr::ErrEP => "invalid edge permutation",
+      CubeStateErr::ErrCP => "invalid corner permutation",
+      CubeStateErr::ErrParity => "corner and edge parity do not match",
+    };
+    write!(f, "{}", msg)
+  }
+}
+
+impl std::error::Error for CubeStateErr {}
+
 /// Number of corners on a 3x3x3 cube.
 pub const NUM_CORNERS: usize = 8;
 /// Number of edges on a 3x3x3 cube.
 pub const NUM_EDGES: usize = 12;
 
 /// Models a 3x3x3 cube, separating permutation and orientation.
-#[derive(Clone, Copy, Debug, PartialEq)]
+///
+/// Cubes are ordered by the coordinates of their corner permutation, corner
+/// orientation, edge permutation and then edge orientation, which are the
+/// coordinates of the two-phase algorithm, with the edge permutation over all
+/// 12 edges. The solved cube is the smallest cube.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+  feature = "serde",
+  serde(try_from = "serialize::RawCube", into = "serialize::RawCube")
+)]
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
 pub struct Cube {
   pub cp: [Corner; NUM_CORNERS],
   pub co: [u8; NUM_CORNERS],
@@ -204,15 +585,29 @@ pub struct Cube {
 
 impl Cube {
   /// Creates a new `Cube` with the specified permutations and orientations.
+  ///
+  /// Panics if the `Cube` is not in a solvable state. Use `Cube::try_new` for
+  /// permutations and orientations that come from user input.
   pub fn new(
     cp: [Corner; NUM_CORNERS],
     co: [u8; NUM_CORNERS],
     ep: [Edge; NUM_EDGES],
     eo: [u8; NUM_EDGES],
   ) -> Cube {
+    Cube::try_new(cp, co, ep, eo).unwrap()
+  }
+
+  /// Creates a new `Cube` with the specified permutations and orientations,
+  /// or the error from `verify` if it is not in a solvable state.
+  pub fn try_new(
+    cp: [Corner; NUM_CORNERS],
+    co: [u8; NUM_CORNERS],
+    ep: [Edge; NUM_EDGES],
+    eo: [u8; NUM_EDGES],
+  ) -> Result<Cube, CubeStateErr> {
     let cube = Cube { cp, co, ep, eo };
-    cube.verify().unwrap();
-    cube
+    cube.verify()?;
+    Ok(cube)
   }
 
   /// Creates a new `Cube` with the specified permutations and orientations.
@@ -254,79 +649,111 @@ impl Cube {
       Edge::BR,
     ];
     let eo = [0; NUM_EDGES];
-    Cube::new(cp, co, ep, eo)
+    Cube { cp, co, ep, eo }
+  }
+
+  /// The corner at `position`.
+  pub fn corner_at(&self, position: Corner) -> Corner {
+    self.cp[position as usize]
+  }
+
+  /// The orientation of the corner at `position`, from 0 to 2.
+  pub fn corner_orientation_at(&self, position: Corner) -> u8 {
+    self.co[position as usize]
+  }
+
+  /// The edge at `position`.
+  pub fn edge_at(&self, position: Edge) -> Edge {
+    self.ep[position as usize]
+  }
+
+  /// The orientation of the edge at `position`, 0 or 1.
+  pub fn edge_orientation_at(&self, position: Edge) -> u8 {
+    self.eo[position as usize]
+  }
+
+  /// The `Cube` that `moves` produce from the solved `Cube`.
+  ///
+  /// Composing with the result applies all of `moves` at once, which is
+  /// faster than applying them one by one when the same algorithm is applied
+  /// many times.
+  pub fn from_algorithm(moves: &[Move]) -> Cube {
+    Cube::solved().apply_algorithm(moves)
   }
 
   /// Return a new `Cube` after applying `Move` to the current `Cube`.
   pub fn apply_move(&self, move_: Move) -> Cube {
-    assert!(move_.1 > 0 && move_.1 < 4);
-    let mp = &MOVE_PERMS[usize::from(move_.0)];
-    let new = (0..move_.1).fold(*self, |acc, _| acc.apply_move_perm(mp));
+    let new = self.turn(move_);
     debug_assert!(new.verify().is_ok());
     new
   }
 
-  /// Return a new `Cube` after applying `MovePerm` to the current `Cube`.
-  fn apply_move_perm(&self, move_perm: &MovePerm) -> Cube {
-    let mut cp = [Corner::URF; NUM_CORNERS];
-    let mut co = [0; NUM_CORNERS];
-    let mut ep = [Edge::UR; NUM_EDGES];
-    let mut eo = [0; NUM_EDGES];
-
-    for (i, &j) in move_perm.cp.iter().enumerate() {
-      cp[i] = self.cp[j];
-      co[i] = (self.co[j] + move_perm.co[j]) % 3;
-    }
-
-    for (i, &j) in move_perm.ep.iter().enumerate() {
-      ep[i] = self.ep[j];
-      eo[i] = self.eo[j] ^ move_perm.eo[i];
-    }
+  /// Return a new `Cube` after applying each of `moves` to the current `Cube`.
+  /// The result is only verified once, after all the moves.
+  pub fn apply_algorithm(&self, moves: &[Move]) -> Cube {
+    let new = moves.iter().fold(*self, |acc, &m| acc.turn(m));
+    debug_assert!(new.verify().is_ok());
+    new
+  }
 
-    Cube { cp, co, ep, eo }
+  /// Apply each of `moves` to the current `Cube` in place.
+  pub fn apply_algorithm_mut(&mut self, moves: &[Move]) {
+    *self = self.apply_algorithm(moves);
   }
 
-  /// Verify that a `Cube` is in a solvable state.
-  pub fn verify(&self) -> Result<(), CubeStateErr> {
-    // Check that each edge is used only once.
-    let mut edges = 0u16;
-    for i in &self.ep {
-      edges |= 1 << (*i as u16);
+  /// Return a new `Cube` after applying `other` as a permutation to the
+  /// current `Cube`, which is the same as applying the moves that produce
+  /// `other`. This is also available as `self * other`.
+  pub fn compose(&self, other: &Cube) -> Cube {
+    let mut cube = *other;
+    for i in 0..NUM_CORNERS {
+      let j = other.cp[i] as usize;
+      cube.cp[i] = self.cp[j];
+      cube.co[i] = (self.co[j] + other.co[i]) % 3;
     }
-    if edges != 0b111111111111 {
-      return Err(CubeStateErr::ErrEP);
+    for i in 0..NUM_EDGES {
+      let j = other.ep[i] as usize;
+      cube.ep[i] = self.ep[j];
+      cube.eo[i] = self.eo[j] ^ other.eo[i];
     }
+    cube
+  }
 
-    // Check that each edge orientation is 0 or 1.
-    if !self.eo.iter().all(|&eo| eo <= 1) {
-      return Err(CubeStateErr::ErrEO);
+  /// The `Cube` that undoes the current `Cube`, so that composing them in
+  /// either order gives the solved `Cube`.
+  pub fn inverse(&self) -> Cube {
+    let mut cube = *self;
+    for i in 0..NUM_CORNERS {
+      let j = self.cp[i] as usize;
+      cube.cp[j] = Corner::from(i);
+      cube.co[j] = (3 - self.co[i]) % 3;
     }
-
-    // Check that the total edge orientation is a multiple of 2.
-    let eo: u8 = self.eo.iter().sum();
-    if eo % 2 != 0 {
-      return Err(CubeStateErr::ErrEO);
+    for i in 0..NUM_EDGES {
+      let j = self.ep[i] as usize;
+      cube.ep[j] = Edge::from(i);
+      cube.eo[j] = self.eo[i];
     }
+    cube
+  }
 
-    // Check that each corner is used only once.
-    let mut corners = 0u8;
-    for i in &self.cp {
-      corners |= 1 << (*i as u8);
-    }
-    if corners != 0b11111111 {
-      return Err(CubeStateErr::ErrCP);
-    }
+  /// Return a new `Cube` after applying `Move`, without verifying it.
+  fn turn(&self, move_: Move) -> Cube {
+    let Move(face, turn) = move_;
+    let n = turn.quarter_turns() as usize;
+    self.apply_move_perm(&MOVE_PERMS[face.index()][n - 1])
+  }
 
-    // Check that each edge orientation is 0, 1 or 2.
-    if !self.co.iter().all(|&co| co <= 2) {
-      return Err(CubeStateErr::ErrCO);
-    }
+  /// Return a new `Cube` after applying `MovePerm` to the current `Cube`.
+  fn apply_move_perm(&self, move_perm: &MovePerm) -> Cube {
+    let corners = self.corners().apply_move_perm(move_perm);
+    let edges = self.edges().apply_move_perm(move_perm);
+    Cube::new_unchecked(corners.cp, corners.co, edges.ep, edges.eo)
+  }
 
-    // Check that the total corner orientation is a multiple of 3.
-    let co: u8 = self.co.iter().sum();
-    if co % 3 != 0 {
-      return Err(CubeStateErr::ErrCO);
-    }
+  /// Verify that a `Cube` is in a solvable state.
+  pub fn verify(&self) -> Result<(), CubeStateErr> {
+    self.edges().verify()?;
+    self.corners().verify()?;
 
     // Check that corner parity and edge parity are equal.
     if !self.has_valid_parity() {
@@ -335,17 +762,9 @@ impl Cube {
     Ok(())
   }
 
-  fn corner_parity(&self) -> bool {
-    num_inversions(&self.cp) % 2 != 0
-  }
-
-  fn edge_parity(&self) -> bool {
-    num_inversions(&self.ep) % 2 != 0
-  }
-
   /// Check if a `Cube` has valid parity.
   pub fn has_valid_parity(&self) -> bool {
-    self.edge_parity() == self.corner_parity()
+    self.edges().parity() == self.corners().parity()
   }
 }
 
@@ -362,3 +781,57 @@ fn num_inversions<P: PartialOrd>(perm: &[P]) -> usize {
   }
   num
 }
+
+/// The coordinate of a permutation, which is the sum of `i! * n` for each
+/// position `i`, where `n` is the number of elements before it that are
+/// greater than the element at `i`, so the identity is 0 and the reversed
+/// permutation is `perm.len()! - 1`.
+pub fn perm_coord<P: PartialOrd>(perm: &[P]) -> usize {
+  // Horner's method, from the last position, which has the largest weight.
+  (0..perm.len()).rev().fold(0, |acc, i| {
+    let greater = perm[..i].iter().filter(|p| **p > perm[i]).count();
+    acc * (i + 1) + greater
+  })
+}
+
+impl Cube {
+  // The coordinates that cubes are ordered by. The last orientation of each
+  // kind follows from the others in a solvable cube, so it is left out. Cubes
+  // that are not solvable can have the same coordinates, so `cmp` compares
+  // their pieces to break ties.
+  fn coords(&self) -> (usize, usize, usize, usize) {
+    let co = self.co[..7].iter().fold(0, |acc, &o| acc * 3 + o as usize);
+    let eo = self.eo[..11].iter().fold(0, |acc, &o| acc * 2 + o as usize);
+    (perm_coord(&self.cp), co, perm_coord(&self.ep), eo)
+  }
+}
+
+impl Ord for Cube {
+  fn cmp(&self, other: &Cube) -> Ordering {
+    self.coords().cmp(&other.coords()).then_with(|| {
+      (self.cp, self.co, self.ep, self.eo)
+        .cmp(&(other.cp, other.co, other.ep, other.eo))
+    })
+  }
+}
+
+impl PartialOrd for Cube {
+  fn partial_cmp(&self, other: &Cube) -> Option<Ordering> {
+    Some(self.cmp(other))
+  }
+}
+
+impl<'a> From<&'a [Move]> for Cube {
+  fn from(val: &'a [Move]) -> Cube {
+    Cube::from_algorithm(val)
+  }
+}
+
+impl Mul for Cube {
+  type Output = Cube;
+
+  /// Compose the cubes, see `Cube::compose`.
+  fn mul(self, rhs: Cube) -> Cube {
+    self.compose(&rhs)
+  }
+}
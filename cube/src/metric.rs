@@ -0,0 +1,77 @@
+use {ExtendedMove, Move, Turn};
+
+/// A way of counting the moves of an algorithm.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Metric {
+  /// The quarter turn metric (QTM), where a half turn counts as two moves.
+  Quarter,
+  /// The half turn metric (HTM), where any turn of a face counts as one move.
+  #[default]
+  Half,
+  /// The slice turn metric (STM), where any turn of a face or of a middle
+  /// layer counts as one move.
+  Slice,
+  /// The execution turn metric (ETM), where every move counts as one,
+  /// including rotations of the whole cube.
+  Execution,
+}
+
+// The number of quarter turns of `turn`, in either direction.
+fn quarters(turn: Turn) -> usize {
+  match turn {
+    Turn::Half => 2,
+    _ => 1,
+  }
+}
+
+impl Metric {
+  /// How many moves `m` counts as.
+  pub fn move_cost(self, m: Move) -> usize {
+    match self {
+      Metric::Quarter => quarters(m.1),
+      _ => 1,
+    }
+  }
+
+  /// How many moves `m` counts as. A turn of a middle layer counts as a turn
+  /// of each of the faces next to it in the quarter and half turn metrics,
+  /// and rotations only count in the execution turn metric.
+  pub fn extended_move_cost(self, m: ExtendedMove) -> usize {
+    match (self, m) {
+      (_, ExtendedMove::Face(m)) => self.move_cost(m),
+      (Metric::Quarter, ExtendedMove::Wide(_, turn)) => quarters(turn),
+      (_, ExtendedMove::Wide(..)) => 1,
+      (Metric::Quarter, ExtendedMove::Slice(_, turn)) => 2 * quarters(turn),
+      (Metric::Half, ExtendedMove::Slice(..)) => 2,
+      (_, ExtendedMove::Slice(..)) => 1,
+      (Metric::Execution, ExtendedMove::Rotation(..)) => 1,
+      (_, ExtendedMove::Rotation(..)) => 0,
+    }
+  }
+
+  /// The most that any face move counts as.
+  pub fn max_move_cost(self) -> usize {
+    match self {
+      Metric::Quarter => 2,
+      _ => 1,
+    }
+  }
+}
+
+/// The number of moves of `moves`, counted in `metric`.
+///
+/// The face moves of a `Move` never turn a middle layer or rotate the cube,
+/// so the half, slice and execution turn metrics all give the number of
+/// moves.
+pub fn algorithm_length(moves: &[Move], metric: Metric) -> usize {
+  moves.iter().map(|&m| metric.move_cost(m)).sum()
+}
+
+/// The number of moves of `moves`, counted in `metric`.
+pub fn extended_algorithm_length(
+  moves: &[ExtendedMove],
+  metric: Metric,
+) -> usize {
+  moves.iter().map(|&m| metric.extended_move_cost(m)).sum()
+}
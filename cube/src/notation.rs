@@ -0,0 +1,249 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+use {Axis, ExtendedMove, Face, Move, Slice, Turn};
+
+/// An error from parsing moves in Singmaster notation.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ParseError {
+  /// There was no move to parse.
+  Empty,
+  /// The move `token`, starting at byte `position`, does not start with one
+  /// of the faces `U`, `R`, `F`, `D`, `B` or `L`, or, for an `ExtendedMove`,
+  /// one of the layers `M`, `E` and `S`, the axes `x`, `y` and `z` or a wide
+  /// turn.
+  InvalidFace { token: String, position: usize },
+  /// The move `token`, starting at byte `position`, does not end with
+  /// nothing, `2` or `'`.
+  InvalidTurn { token: String, position: usize },
+}
+
+impl fmt::Display for ParseError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      ParseError::Empty => write!(f, "no move"),
+      ParseError::InvalidFace { token, position } => {
+        write!(f, "invalid face in move '{}' at {}", token, position)
+      }
+      ParseError::InvalidTurn { token, position } => {
+        write!(f, "invalid turn in move '{}' at {}", token, position)
+      }
+    }
+  }
+}
+
+impl Error for ParseError {}
+
+// Parse the end of `token`, after its face, as a `Turn`.
+fn parse_turn(
+  token: &str,
+  rest: &str,
+  position: usize,
+) -> Result<Turn, ParseError> {
+  // A half turn is the same in both directions, so `2'` is accepted too.
+  match rest {
+    "" => Ok(Turn::Clockwise),
+    "2" | "2'" | "2’" => Ok(Turn::Half),
+    "'" | "’" => Ok(Turn::Counter),
+    _ => Err(ParseError::InvalidTurn {
+      token: token.to_string(),
+      position,
+    }),
+  }
+}
+
+fn parse_face(c: char) -> Option<Face> {
+  match c {
+    'U' => Some(Face::U),
+    'R' => Some(Face::R),
+    'F' => Some(Face::F),
+    'D' => Some(Face::D),
+    'B' => Some(Face::B),
+    'L' => Some(Face::L),
+    _ => None,
+  }
+}
+
+// Parse a single move, `position` being where it starts in the algorithm.
+fn parse_move(token: &str, position: usize) -> Result<Move, ParseError> {
+  let mut chars = token.chars();
+  let face = match chars.next() {
+    None => return Err(ParseError::Empty),
+    Some(c) => parse_face(c).ok_or_else(|| ParseError::InvalidFace {
+      token: token.to_string(),
+      position,
+    })?,
+  };
+  let turn = parse_turn(token, chars.as_str(), position)?;
+  Ok(Move(face, turn))
+}
+
+// Parse a single move that can also be a middle layer turn or a rotation.
+fn parse_extended_move(
+  token: &str,
+  position: usize,
+) -> Result<ExtendedMove, ParseError> {
+  let mut chars = token.chars();
+  let c = chars.next().ok_or(ParseError::Empty)?;
+  // A wide turn is written as either `Rw` or `r`.
+  if let Some(face) = parse_face(c.to_ascii_uppercase()) {
+    let wide = match chars.as_str().strip_prefix('w') {
+      Some(rest) if c.is_ascii_uppercase() => Some(rest),
+      _ if c.is_ascii_lowercase() => Some(chars.as_str()),
+      _ => None,
+    };
+    if let Some(rest) = wide {
+      let turn = parse_turn(token, rest, position)?;
+      return Ok(ExtendedMove::Wide(face, turn));
+    }
+  }
+  let turn = || parse_turn(token, chars.as_str(), position);
+  let m = match c {
+    'M' => ExtendedMove::Slice(Slice::M, turn()?),
+    'E' => ExtendedMove::Slice(Slice::E, turn()?),
+    'S' => ExtendedMove::Slice(Slice::S, turn()?),
+    'x' => ExtendedMove::Rotation(Axis::X, turn()?),
+    'y' => ExtendedMove::Rotation(Axis::Y, turn()?),
+    'z' => ExtendedMove::Rotation(Axis::Z, turn()?),
+    _ => ExtendedMove::Face(parse_move(token, position)?),
+  };
+  Ok(m)
+}
+
+impl FromStr for Move {
+  type Err = ParseError;
+
+  /// Parse a move such as `R`, `U2` or `F'`.
+  fn from_str(s: &str) -> Result<Move, ParseError> {
+    parse_move(s, 0)
+  }
+}
+
+impl FromStr for ExtendedMove {
+  type Err = ParseError;
+
+  /// Parse a move such as `R`, `M2`, `x'` or `Rw`.
+  fn from_str(s: &str) -> Result<ExtendedMove, ParseError> {
+    parse_extended_move(s, 0)
+  }
+}
+
+// The end of a move that turns by `turn`.
+fn turn_suffix(turn: Turn) -> &'static str {
+  match turn {
+    Turn::Clockwise => "",
+    Turn::Half => "2",
+    Turn::Counter => "'",
+  }
+}
+
+impl fmt::Display for Face {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let c = match self {
+      Face::U => 'U',
+      Face::R => 'R',
+      Face::F => 'F',
+      Face::D => 'D',
+      Face::B => 'B',
+      Face::L => 'L',
+    };
+    write!(f, "{}", c)
+  }
+}
+
+impl fmt::Display for Move {
+  /// Format the move in Singmaster notation, such as `R`, `U2` or `F'`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let Move(face, turn) = *self;
+    write!(f, "{}{}", face, turn_suffix(turn))
+  }
+}
+
+impl fmt::Display for Slice {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let c = match self {
+      Slice::E => 'E',
+      Slice::M => 'M',
+      Slice::S => 'S',
+    };
+    write!(f, "{}", c)
+  }
+}
+
+impl fmt::Display for Axis {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let c = match self {
+      Axis::X => 'x',
+      Axis::Y => 'y',
+      Axis::Z => 'z',
+    };
+    write!(f, "{}", c)
+  }
+}
+
+impl fmt::Display for ExtendedMove {
+  /// Format the move such as `R`, `M2`, `x'` or `Rw`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      ExtendedMove::Face(m) => write!(f, "{}", m),
+      ExtendedMove::Slice(slice, turn) => {
+        write!(f, "{}{}", slice, turn_suffix(turn))
+      }
+      ExtendedMove::Rotation(axis, turn) => {
+        write!(f, "{}{}", axis, turn_suffix(turn))
+      }
+      ExtendedMove::Wide(face, turn) => {
+        write!(f, "{}w{}", face, turn_suffix(turn))
+      }
+    }
+  }
+}
+
+/// Format `moves` as an algorithm in Singmaster notation, with the moves
+/// separated by spaces. This is the format that `parse_algorithm` reads.
+pub fn format_algorithm(moves: &[Move]) -> String {
+  moves
+    .iter()
+    .map(|m| m.to_string())
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+/// Format `moves` like `format_algorithm`. This is the format that
+/// `parse_extended_algorithm` reads.
+pub fn format_extended_algorithm(moves: &[ExtendedMove]) -> String {
+  moves
+    .iter()
+    .map(|m| m.to_string())
+    .collect::<Vec<_>>()
+    .join(" ")
+}
+
+// Parse each whitespace separated token of `s` with `parse`.
+pub(crate) fn parse_tokens<T>(
+  s: &str,
+  parse: fn(&str, usize) -> Result<T, ParseError>,
+) -> Result<Vec<T>, ParseError> {
+  s.split_whitespace()
+    .map(|token| {
+      // `token` is a subslice of `s`, so its offset is its position.
+      let position = token.as_ptr() as usize - s.as_ptr() as usize;
+      parse(token, position)
+    })
+    .collect()
+}
+
+/// Parse an algorithm of whitespace separated moves, such as `R U R' U2 F2`.
+pub fn parse_algorithm(s: &str) -> Result<Vec<Move>, ParseError> {
+  parse_tokens(s, parse_move)
+}
+
+/// Parse an algorithm like `parse_algorithm`, which can also turn the middle
+/// layers `M`, `E` and `S`, rotate the cube with `x`, `y` and `z`, and do
+/// wide turns such as `Rw` or `r`, such as `M2 U M' U2 M U M2` or
+/// `x R' U R' D2 R U' R' D2 R2 x'`.
+pub fn parse_extended_algorithm(
+  s: &str,
+) -> Result<Vec<ExtendedMove>, ParseError> {
+  parse_tokens(s, parse_extended_move)
+}
@@ -0,0 +1,68 @@
+use {expand_algorithm, Cube, ExtendedMove, Move, Orientation};
+
+/// A `Cube` together with the `Orientation` of its centers, so that
+/// rotations, wide turns and middle layer turns can be applied to it.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OrientedCube {
+  cube: Cube,
+  orientation: Orientation,
+}
+
+impl OrientedCube {
+  /// Create an `OrientedCube` from `cube`, which is relative to the centers,
+  /// held in `orientation`.
+  pub fn new(cube: Cube, orientation: Orientation) -> OrientedCube {
+    OrientedCube { cube, orientation }
+  }
+
+  /// A solved `OrientedCube` with every center at its own position.
+  pub fn solved() -> OrientedCube {
+    OrientedCube::from(Cube::solved())
+  }
+
+  /// The `Cube`, relative to the centers, without the orientation.
+  pub fn cube(&self) -> Cube {
+    self.cube
+  }
+
+  /// Where the centers are.
+  pub fn orientation(&self) -> Orientation {
+    self.orientation
+  }
+
+  /// Return a new `OrientedCube` after applying `move_`.
+  pub fn apply_move(&self, move_: ExtendedMove) -> OrientedCube {
+    self.apply_algorithm(&[move_])
+  }
+
+  /// Return a new `OrientedCube` after applying each of `moves`.
+  pub fn apply_algorithm(&self, moves: &[ExtendedMove]) -> OrientedCube {
+    let (face_moves, orientation) = expand_algorithm(moves, self.orientation);
+    OrientedCube {
+      cube: self.cube.apply_algorithm(&face_moves),
+      orientation,
+    }
+  }
+
+  /// Rewrite `moves`, which turn the faces of `cube`, as turns of the
+  /// positions of those faces, so that a solution of `cube` can be followed
+  /// without rotating the cube back.
+  pub fn position_moves(&self, moves: &[Move]) -> Vec<Move> {
+    moves
+      .iter()
+      .map(|&m| self.orientation.position_move(m))
+      .collect()
+  }
+}
+
+impl From<Cube> for OrientedCube {
+  fn from(val: Cube) -> OrientedCube {
+    OrientedCube::new(val, Orientation::identity())
+  }
+}
+
+impl From<OrientedCube> for Cube {
+  fn from(val: OrientedCube) -> Cube {
+    val.cube
+  }
+}
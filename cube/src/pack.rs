@@ -0,0 +1,74 @@
+use {Corner, Cube, CubeStateErr, Edge, NUM_CORNERS, NUM_EDGES};
+
+// The number of bits used for each piece and orientation.
+const CP_BITS: u32 = 3;
+const CO_BITS: u32 = 2;
+const EP_BITS: u32 = 4;
+const EO_BITS: u32 = 1;
+
+// Reads fields from the low bits of a packed cube.
+struct Unpacker(u128);
+
+impl Unpacker {
+  fn next(&mut self, bits: u32) -> usize {
+    let val = self.0 & ((1 << bits) - 1);
+    self.0 >>= bits;
+    val as usize
+  }
+}
+
+impl Cube {
+  /// Pack the `Cube` into the low 100 bits of a `u128`.
+  ///
+  /// Starting from the lowest bits, each corner takes 3 bits, each corner
+  /// orientation 2 bits, each edge 4 bits and each edge orientation 1 bit,
+  /// in the order of their positions. Equal cubes pack to equal values, so
+  /// the packed value is a cheap key for hashing and storing many states.
+  pub fn pack(&self) -> u128 {
+    let mut packed = 0;
+    let mut shift = 0;
+    let mut push = |val: u8, bits: u32| {
+      packed |= (val as u128) << shift;
+      shift += bits;
+    };
+    for &c in &self.cp {
+      push(c as u8, CP_BITS);
+    }
+    for &co in &self.co {
+      push(co, CO_BITS);
+    }
+    for &e in &self.ep {
+      push(e as u8, EP_BITS);
+    }
+    for &eo in &self.eo {
+      push(eo, EO_BITS);
+    }
+    packed
+  }
+
+  /// Unpack a `Cube` packed by `Cube::pack`, checking that it is in a
+  /// solvable state. Only the low 100 bits are read, so the high bits can be
+  /// used to store something else.
+  pub fn unpack(packed: u128) -> Result<Cube, CubeStateErr> {
+    let mut unpacker = Unpacker(packed);
+    let mut cube = Cube::solved();
+    for i in 0..NUM_CORNERS {
+      cube.cp[i] = Corner::from(unpacker.next(CP_BITS));
+    }
+    for i in 0..NUM_CORNERS {
+      cube.co[i] = unpacker.next(CO_BITS) as u8;
+    }
+    for i in 0..NUM_EDGES {
+      let e = unpacker.next(EP_BITS);
+      if e >= NUM_EDGES {
+        return Err(CubeStateErr::ErrEP);
+      }
+      cube.ep[i] = Edge::from(e);
+    }
+    for i in 0..NUM_EDGES {
+      cube.eo[i] = unpacker.next(EO_BITS) as u8;
+    }
+    cube.verify()?;
+    Ok(cube)
+  }
+}
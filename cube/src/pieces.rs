@@ -0,0 +1,173 @@
+use {
+  num_inversions, Corner, Cube, CubeStateErr, Edge, Move, MovePerm, MOVE_PERMS,
+  NUM_CORNERS, NUM_EDGES,
+};
+
+/// The corners of a `Cube`, without its edges.
+///
+/// This is the state space of corners-first methods and corner pattern
+/// databases, which only has 8! * 3^7 states. Every corner state that passes
+/// `verify` is the corners of a solvable `Cube`, with either parity of edges.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct CornerState {
+  pub cp: [Corner; NUM_CORNERS],
+  pub co: [u8; NUM_CORNERS],
+}
+
+/// The edges of a `Cube`, without its corners.
+///
+/// This is the state space of edges-first methods and edge pattern databases,
+/// which has 12! * 2^11 states. Every edge state that passes `verify` is the
+/// edges of a solvable `Cube`, with either parity of corners.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct EdgeState {
+  pub ep: [Edge; NUM_EDGES],
+  pub eo: [u8; NUM_EDGES],
+}
+
+// The `MovePerm` of `m`.
+fn move_perm(m: Move) -> &'static MovePerm {
+  let Move(face, turn) = m;
+  &MOVE_PERMS[face.index()][turn.quarter_turns() as usize - 1]
+}
+
+impl CornerState {
+  /// The corners of the solved `Cube`.
+  pub fn solved() -> CornerState {
+    Cube::solved().corners()
+  }
+
+  /// Return a new `CornerState` after applying `Move`.
+  pub fn apply_move(&self, m: Move) -> CornerState {
+    self.apply_move_perm(move_perm(m))
+  }
+
+  /// Return a new `CornerState` after applying each of `moves`.
+  pub fn apply_algorithm(&self, moves: &[Move]) -> CornerState {
+    moves.iter().fold(*self, |acc, &m| acc.apply_move(m))
+  }
+
+  /// Return a new `CornerState` after applying `MovePerm`.
+  pub(crate) fn apply_move_perm(&self, move_perm: &MovePerm) -> CornerState {
+    let mut cp = [Corner::URF; NUM_CORNERS];
+    let mut co = [0; NUM_CORNERS];
+    for (i, &j) in move_perm.cp.iter().enumerate() {
+      cp[i] = self.cp[j];
+      co[i] = (self.co[j] + move_perm.co[j]) % 3;
+    }
+    CornerState { cp, co }
+  }
+
+  /// Verify that the corners are the corners of a solvable `Cube`.
+  pub fn verify(&self) -> Result<(), CubeStateErr> {
+    // Check that each corner is used only once.
+    let mut corners = 0u8;
+    for i in &self.cp {
+      corners |= 1 << (*i as u8);
+    }
+    if corners != 0b11111111 {
+      return Err(CubeStateErr::ErrCP);
+    }
+
+    // Check that each corner orientation is 0, 1 or 2.
+    if !self.co.iter().all(|&co| co <= 2) {
+      return Err(CubeStateErr::ErrCO);
+    }
+
+    // Check that the total corner orientation is a multiple of 3.
+    let co: u8 = self.co.iter().sum();
+    if !co.is_multiple_of(3) {
+      return Err(CubeStateErr::ErrCO);
+    }
+    Ok(())
+  }
+
+  /// Whether the corner permutation is odd.
+  pub fn parity(&self) -> bool {
+    !num_inversions(&self.cp).is_multiple_of(2)
+  }
+}
+
+impl EdgeState {
+  /// The edges of the solved `Cube`.
+  pub fn solved() -> EdgeState {
+    Cube::solved().edges()
+  }
+
+  /// Return a new `EdgeState` after applying `Move`.
+  pub fn apply_move(&self, m: Move) -> EdgeState {
+    self.apply_move_perm(move_perm(m))
+  }
+
+  /// Return a new `EdgeState` after applying each of `moves`.
+  pub fn apply_algorithm(&self, moves: &[Move]) -> EdgeState {
+    moves.iter().fold(*self, |acc, &m| acc.apply_move(m))
+  }
+
+  /// Return a new `EdgeState` after applying `MovePerm`.
+  pub(crate) fn apply_move_perm(&self, move_perm: &MovePerm) -> EdgeState {
+    let mut ep = [Edge::UR; NUM_EDGES];
+    let mut eo = [0; NUM_EDGES];
+    for (i, &j) in move_perm.ep.iter().enumerate() {
+      ep[i] = self.ep[j];
+      eo[i] = self.eo[j] ^ move_perm.eo[i];
+    }
+    EdgeState { ep, eo }
+  }
+
+  /// Verify that the edges are the edges of a solvable `Cube`.
+  pub fn verify(&self) -> Result<(), CubeStateErr> {
+    // Check that each edge is used only once.
+    let mut edges = 0u16;
+    for i in &self.ep {
+      edges |= 1 << (*i as u16);
+    }
+    if edges != 0b111111111111 {
+      return Err(CubeStateErr::ErrEP);
+    }
+
+    // Check that each edge orientation is 0 or 1.
+    if !self.eo.iter().all(|&eo| eo <= 1) {
+      return Err(CubeStateErr::ErrEO);
+    }
+
+    // Check that the total edge orientation is a multiple of 2.
+    let eo: u8 = self.eo.iter().sum();
+    if !eo.is_multiple_of(2) {
+      return Err(CubeStateErr::ErrEO);
+    }
+    Ok(())
+  }
+
+  /// Whether the edge permutation is odd.
+  pub fn parity(&self) -> bool {
+    !num_inversions(&self.ep).is_multiple_of(2)
+  }
+}
+
+impl Cube {
+  /// The corners of the `Cube`.
+  pub fn corners(&self) -> CornerState {
+    CornerState {
+      cp: self.cp,
+      co: self.co,
+    }
+  }
+
+  /// The edges of the `Cube`.
+  pub fn edges(&self) -> EdgeState {
+    EdgeState {
+      ep: self.ep,
+      eo: self.eo,
+    }
+  }
+
+  /// Creates a new `Cube` from its corners and edges, or the error from
+  /// `verify` if it is not in a solvable state.
+  pub fn from_pieces(
+    corners: CornerState,
+    edges: EdgeState,
+  ) -> Result<Cube, CubeStateErr> {
+    Cube::try_new(corners.cp, corners.co, edges.ep, edges.eo)
+  }
+}
@@ -0,0 +1,83 @@
+use std::fmt::Debug;
+use std::hash::Hash;
+use {Cube, Cube2, Face, Move, Turn};
+
+/// A twisty puzzle whose states are the permutations and orientations of its
+/// pieces, with a finite set of moves.
+///
+/// This is what a search needs to know about a puzzle, so the same breadth
+/// first searches and tables can be built for each puzzle that implements
+/// it.
+pub trait PermutationPuzzle: Copy + Debug + Eq + Hash {
+  /// A move of the puzzle.
+  type Move: Copy + Debug + PartialEq;
+
+  /// The solved state.
+  fn solved() -> Self;
+
+  /// Every move of the puzzle, which are the moves a search tries at each
+  /// step.
+  fn moves() -> Vec<Self::Move>;
+
+  /// The state after applying `m`.
+  fn apply_move(&self, m: Self::Move) -> Self;
+
+  /// The state after applying each of `moves`.
+  fn apply_algorithm(&self, moves: &[Self::Move]) -> Self {
+    moves.iter().fold(*self, |acc, &m| acc.apply_move(m))
+  }
+
+  /// Whether the state is solved.
+  fn is_solved(&self) -> bool {
+    *self == Self::solved()
+  }
+}
+
+// The 18 face turns.
+fn face_turns() -> Vec<Move> {
+  Face::ALL
+    .iter()
+    .flat_map(|&f| Turn::ALL.iter().map(move |&t| Move(f, t)))
+    .collect()
+}
+
+impl PermutationPuzzle for Cube {
+  type Move = Move;
+
+  fn solved() -> Cube {
+    Cube::solved()
+  }
+
+  fn moves() -> Vec<Move> {
+    face_turns()
+  }
+
+  fn apply_move(&self, m: Move) -> Cube {
+    Cube::apply_move(self, m)
+  }
+
+  fn apply_algorithm(&self, moves: &[Move]) -> Cube {
+    Cube::apply_algorithm(self, moves)
+  }
+}
+
+impl PermutationPuzzle for Cube2 {
+  type Move = Move;
+
+  fn solved() -> Cube2 {
+    Cube2::solved()
+  }
+
+  fn moves() -> Vec<Move> {
+    face_turns()
+  }
+
+  fn apply_move(&self, m: Move) -> Cube2 {
+    Cube2::apply_move(self, m)
+  }
+
+  /// Whether the `Cube2` is solved, however it is held.
+  fn is_solved(&self) -> bool {
+    Cube2::is_solved(self)
+  }
+}
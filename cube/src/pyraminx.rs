@@ -0,0 +1,254 @@
+use notation::parse_tokens;
+use std::fmt;
+use std::str::FromStr;
+use {num_inversions, CubeStateErr, ParseError, PermutationPuzzle};
+
+/// The vertices of a Pyraminx, held with a face in front: `U` at the top,
+/// `L` and `R` at the front and `B` at the back.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Vertex {
+  U,
+  L,
+  R,
+  B,
+}
+
+impl Vertex {
+  /// All the vertices.
+  pub const ALL: [Vertex; 4] = [Vertex::U, Vertex::L, Vertex::R, Vertex::B];
+}
+
+/// A move of a Pyraminx: a third of a turn of the layer of `vertex`, or of
+/// only its tip, clockwise when looking at the vertex.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct PyraminxMove {
+  pub vertex: Vertex,
+  pub clockwise: bool,
+  /// Whether only the tip is turned, which is written in lower case.
+  pub tip: bool,
+}
+
+impl PyraminxMove {
+  /// The `PyraminxMove` that undoes this move.
+  pub fn inverse(&self) -> PyraminxMove {
+    PyraminxMove {
+      clockwise: !self.clockwise,
+      ..*self
+    }
+  }
+}
+
+/// The number of edges of a Pyraminx.
+pub const NUM_PYRAMINX_EDGES: usize = 6;
+
+/// The position each edge comes from for a clockwise turn of each vertex,
+/// with the edges in the order UL, UR, UB, LR, LB, RB.
+const EDGE_SOURCES: [[usize; NUM_PYRAMINX_EDGES]; 4] = [
+  [1, 2, 0, 3, 4, 5],
+  [4, 1, 2, 0, 3, 5],
+  [0, 3, 2, 5, 4, 1],
+  [0, 1, 5, 3, 2, 4],
+];
+
+/// Whether the orientation of the edge that ends at each position changes,
+/// for a clockwise turn of each vertex.
+///
+/// An edge lies on the two faces opposite the vertices it does not touch.
+/// It has orientation 0 when its facelet that belongs on the face opposite
+/// the first of those vertices, in the order of `Vertex::ALL`, is on the
+/// face opposite the first of the vertices its position does not touch.
+const EDGE_FLIPS: [[u8; NUM_PYRAMINX_EDGES]; 4] = [
+  [1, 1, 0, 0, 0, 0],
+  [0, 0, 0, 1, 1, 0],
+  [0, 1, 0, 1, 0, 0],
+  [0, 0, 0, 0, 1, 1],
+];
+
+/// Models a Pyraminx, with its edges, the centers at each vertex and the
+/// tips.
+///
+/// The centers and the tips never leave their vertex, so only their
+/// orientation is kept, from 0 to 2 clockwise turns from solved.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Pyraminx {
+  /// The edge at each position, in the order UL, UR, UB, LR, LB, RB.
+  pub ep: [u8; NUM_PYRAMINX_EDGES],
+  /// The orientation of the edge at each position, 0 or 1.
+  pub eo: [u8; NUM_PYRAMINX_EDGES],
+  /// The orientation of the center of each vertex, in the order of
+  /// `Vertex::ALL`.
+  pub centers: [u8; 4],
+  /// The orientation of the tip of each vertex.
+  pub tips: [u8; 4],
+}
+
+impl Pyraminx {
+  /// Creates a new `Pyraminx` in the solved state.
+  pub fn solved() -> Pyraminx {
+    Pyraminx {
+      ep: [0, 1, 2, 3, 4, 5],
+      eo: [0; NUM_PYRAMINX_EDGES],
+      centers: [0; 4],
+      tips: [0; 4],
+    }
+  }
+
+  /// The `Pyraminx` that `moves` produce from the solved `Pyraminx`.
+  pub fn from_algorithm(moves: &[PyraminxMove]) -> Pyraminx {
+    Pyraminx::solved().apply_algorithm(moves)
+  }
+
+  /// Return a new `Pyraminx` after applying `PyraminxMove`.
+  pub fn apply_move(&self, m: PyraminxMove) -> Pyraminx {
+    let v = m.vertex as usize;
+    let turns = if m.clockwise { 1 } else { 2 };
+    let mut new = *self;
+    new.tips[v] = (new.tips[v] + turns) % 3;
+    if m.tip {
+      return new;
+    }
+    new.centers[v] = (new.centers[v] + turns) % 3;
+    for _ in 0..turns {
+      let old = new;
+      for i in 0..NUM_PYRAMINX_EDGES {
+        let j = EDGE_SOURCES[v][i];
+        new.ep[i] = old.ep[j];
+        new.eo[i] = old.eo[j] ^ EDGE_FLIPS[v][i];
+      }
+    }
+    new
+  }
+
+  /// Return a new `Pyraminx` after applying each of `moves`.
+  pub fn apply_algorithm(&self, moves: &[PyraminxMove]) -> Pyraminx {
+    moves.iter().fold(*self, |acc, &m| acc.apply_move(m))
+  }
+
+  /// The same `Pyraminx` with every tip solved. The tips can each be
+  /// solved with one move at any time, so they are usually left out of
+  /// searches.
+  pub fn without_tips(&self) -> Pyraminx {
+    Pyraminx {
+      tips: [0; 4],
+      ..*self
+    }
+  }
+
+  /// Verify that a `Pyraminx` is in a solvable state: each edge is used
+  /// once, in an even permutation, and an even number of edges are flipped.
+  pub fn verify(&self) -> Result<(), CubeStateErr> {
+    let mut edges = 0u8;
+    for &e in &self.ep {
+      edges |= 1 << e;
+    }
+    if edges != 0b111111 || !num_inversions(&self.ep).is_multiple_of(2) {
+      return Err(CubeStateErr::ErrEP);
+    }
+    let eo: u8 = self.eo.iter().sum();
+    if !self.eo.iter().all(|&eo| eo <= 1) || !eo.is_multiple_of(2) {
+      return Err(CubeStateErr::ErrEO);
+    }
+    if !self.centers.iter().chain(&self.tips).all(|&o| o <= 2) {
+      return Err(CubeStateErr::ErrCO);
+    }
+    Ok(())
+  }
+}
+
+impl PermutationPuzzle for Pyraminx {
+  type Move = PyraminxMove;
+
+  fn solved() -> Pyraminx {
+    Pyraminx::solved()
+  }
+
+  /// The turns of each layer and each tip.
+  fn moves() -> Vec<PyraminxMove> {
+    let mut moves = vec![];
+    for &tip in &[false, true] {
+      for &vertex in &Vertex::ALL {
+        for &clockwise in &[true, false] {
+          moves.push(PyraminxMove {
+            vertex,
+            clockwise,
+            tip,
+          });
+        }
+      }
+    }
+    moves
+  }
+
+  fn apply_move(&self, m: PyraminxMove) -> Pyraminx {
+    Pyraminx::apply_move(self, m)
+  }
+}
+
+impl fmt::Display for PyraminxMove {
+  /// Format the move such as `U`, `R'` or `b`.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    let c = match self.vertex {
+      Vertex::U => 'U',
+      Vertex::L => 'L',
+      Vertex::R => 'R',
+      Vertex::B => 'B',
+    };
+    let c = if self.tip { c.to_ascii_lowercase() } else { c };
+    write!(f, "{}{}", c, if self.clockwise { "" } else { "'" })
+  }
+}
+
+// Parse a single Pyraminx move, `position` being where it starts in the
+// algorithm.
+fn parse_pyraminx_move(
+  token: &str,
+  position: usize,
+) -> Result<PyraminxMove, ParseError> {
+  let mut chars = token.chars();
+  let c = chars.next().ok_or(ParseError::Empty)?;
+  let vertex = match c.to_ascii_uppercase() {
+    'U' => Vertex::U,
+    'L' => Vertex::L,
+    'R' => Vertex::R,
+    'B' => Vertex::B,
+    _ => {
+      return Err(ParseError::InvalidFace {
+        token: token.to_string(),
+        position,
+      })
+    }
+  };
+  let clockwise = match chars.as_str() {
+    "" => true,
+    "'" | "’" => false,
+    _ => {
+      return Err(ParseError::InvalidTurn {
+        token: token.to_string(),
+        position,
+      })
+    }
+  };
+  Ok(PyraminxMove {
+    vertex,
+    clockwise,
+    tip: c.is_ascii_lowercase(),
+  })
+}
+
+impl FromStr for PyraminxMove {
+  type Err = ParseError;
+
+  /// Parse a move such as `U`, `R'` or `b`.
+  fn from_str(s: &str) -> Result<PyraminxMove, ParseError> {
+    parse_pyraminx_move(s, 0)
+  }
+}
+
+/// Parse a Pyraminx algorithm of whitespace separated moves, such as
+/// `R U' L' U R' u b'`, where the upper case moves turn a layer and the
+/// lower case ones only turn a tip.
+pub fn parse_pyraminx_algorithm(
+  s: &str,
+) -> Result<Vec<PyraminxMove>, ParseError> {
+  parse_tokens(s, parse_pyraminx_move)
+}
@@ -0,0 +1,229 @@
+use {Cube, Face, Move, Slice, Turn};
+
+/// The axes that the whole cube can be rotated around.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Axis {
+  /// The R-L axis, rotated in the direction of R.
+  X,
+  /// The U-D axis, rotated in the direction of U.
+  Y,
+  /// The F-B axis, rotated in the direction of F.
+  Z,
+}
+
+/// A move in the notation used by most algorithm sources, which can also
+/// turn a middle layer or rotate the whole cube.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ExtendedMove {
+  /// A turn of the face at a position, such as R.
+  Face(Move),
+  /// A turn of a middle layer, such as M.
+  Slice(Slice, Turn),
+  /// A rotation of the whole cube, such as x.
+  Rotation(Axis, Turn),
+  /// A turn of a face together with the middle layer next to it, such as
+  /// Rw.
+  Wide(Face, Turn),
+}
+
+impl From<Move> for ExtendedMove {
+  fn from(val: Move) -> ExtendedMove {
+    ExtendedMove::Face(val)
+  }
+}
+
+impl ExtendedMove {
+  /// The `ExtendedMove` that undoes this move.
+  pub fn inverse(&self) -> ExtendedMove {
+    match *self {
+      ExtendedMove::Face(m) => ExtendedMove::Face(m.inverse()),
+      ExtendedMove::Slice(slice, turn) => {
+        ExtendedMove::Slice(slice, turn.inverse())
+      }
+      ExtendedMove::Rotation(axis, turn) => {
+        ExtendedMove::Rotation(axis, turn.inverse())
+      }
+      ExtendedMove::Wide(face, turn) => {
+        ExtendedMove::Wide(face, turn.inverse())
+      }
+    }
+  }
+}
+
+// The positions around U, R and F, in the order that rotating the cube like
+// the face moves the centers.
+const Y_CYCLE: [Face; 4] = [Face::F, Face::L, Face::B, Face::R];
+const X_CYCLE: [Face; 4] = [Face::F, Face::U, Face::B, Face::D];
+const Z_CYCLE: [Face; 4] = [Face::U, Face::R, Face::D, Face::L];
+
+/// Which center is at each position after rotating the whole cube.
+///
+/// A `Cube` is always relative to its centers, so after a rotation a turn of
+/// the face at a position is a turn of the `Face` whose center is there.
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Orientation([Face; 6]);
+
+impl Orientation {
+  /// The `Orientation` where every center is at its own position.
+  pub fn identity() -> Orientation {
+    Orientation(Face::ALL)
+  }
+
+  /// The `Orientation` with the center `up` at U and `front` at F, or `None`
+  /// if they are the same or opposite faces.
+  pub fn new(up: Face, front: Face) -> Option<Orientation> {
+    // Bring each center to U, then turn each side to F.
+    let identity = Orientation::identity();
+    let ups = [
+      identity,
+      identity.rotate(Axis::X, Turn::Clockwise),
+      identity.rotate(Axis::X, Turn::Half),
+      identity.rotate(Axis::X, Turn::Counter),
+      identity.rotate(Axis::Z, Turn::Clockwise),
+      identity.rotate(Axis::Z, Turn::Counter),
+    ];
+    ups
+      .iter()
+      .flat_map(|&o| {
+        let turns = Turn::ALL.iter().map(move |&turn| o.rotate(Axis::Y, turn));
+        std::iter::once(o).chain(turns)
+      })
+      .find(|o| o.up() == up && o.front() == front)
+  }
+
+  /// The center at `position`.
+  pub fn center(&self, position: Face) -> Face {
+    self.0[position.index()]
+  }
+
+  /// The center at U.
+  pub fn up(&self) -> Face {
+    self.center(Face::U)
+  }
+
+  /// The center at F.
+  pub fn front(&self) -> Face {
+    self.center(Face::F)
+  }
+
+  /// The position of the center `face`.
+  pub fn position(&self, face: Face) -> Face {
+    *Face::ALL
+      .iter()
+      .find(|&&position| self.center(position) == face)
+      .unwrap()
+  }
+
+  /// The `Orientation` after rotating the whole cube around `axis`.
+  pub fn rotate(&self, axis: Axis, turn: Turn) -> Orientation {
+    let cycle = match axis {
+      Axis::X => X_CYCLE,
+      Axis::Y => Y_CYCLE,
+      Axis::Z => Z_CYCLE,
+    };
+    let n = turn.quarter_turns() as usize;
+    let mut centers = self.0;
+    for i in 0..4 {
+      let to = cycle[(i + n) % 4].index();
+      centers[to] = self.center(cycle[i]);
+    }
+    Orientation(centers)
+  }
+
+  /// The turn of the `Face` whose center is at the position that `m` turns.
+  pub fn face_move(&self, m: Move) -> Move {
+    Move(self.center(m.0), m.1)
+  }
+
+  /// The turn of the position of the center that `m` turns, which undoes
+  /// `face_move`.
+  pub fn position_move(&self, m: Move) -> Move {
+    Move(self.position(m.0), m.1)
+  }
+}
+
+impl Default for Orientation {
+  fn default() -> Orientation {
+    Orientation::identity()
+  }
+}
+
+// The turns of the outer layers, relative to the positions, and the rotation
+// that together turn `slice`.
+fn slice_moves(slice: Slice, turn: Turn) -> ([Move; 2], Axis, Turn) {
+  let inverse = turn.inverse();
+  match slice {
+    Slice::E => (
+      [Move(Face::U, turn), Move(Face::D, inverse)],
+      Axis::Y,
+      inverse,
+    ),
+    Slice::M => (
+      [Move(Face::R, turn), Move(Face::L, inverse)],
+      Axis::X,
+      inverse,
+    ),
+    Slice::S => ([Move(Face::F, inverse), Move(Face::B, turn)], Axis::Z, turn),
+  }
+}
+
+// The turn of the outer layer, relative to the positions, and the rotation
+// that together turn `face` and the middle layer next to it.
+fn wide_moves(face: Face, turn: Turn) -> (Move, Axis, Turn) {
+  let (axis, rotation) = match face {
+    Face::R => (Axis::X, turn),
+    Face::L => (Axis::X, turn.inverse()),
+    Face::U => (Axis::Y, turn),
+    Face::D => (Axis::Y, turn.inverse()),
+    Face::F => (Axis::Z, turn),
+    Face::B => (Axis::Z, turn.inverse()),
+  };
+  (Move(face.opposite(), turn), axis, rotation)
+}
+
+/// Expand `moves` into turns of the faces, relative to the centers, starting
+/// from `orientation`.
+///
+/// The moves of the outer layers are relative to the positions. A middle
+/// layer turn is the two outer layers turned the other way and a rotation,
+/// a wide turn is the opposite face and a rotation, and a rotation only
+/// changes the orientation. Returns the face turns and the orientation that
+/// the moves end with.
+pub fn expand_algorithm(
+  moves: &[ExtendedMove],
+  orientation: Orientation,
+) -> (Vec<Move>, Orientation) {
+  let mut expanded = Vec::with_capacity(moves.len());
+  let mut orientation = orientation;
+  for &m in moves {
+    match m {
+      ExtendedMove::Face(m) => expanded.push(orientation.face_move(m)),
+      ExtendedMove::Slice(slice, turn) => {
+        let (outer, axis, rotation) = slice_moves(slice, turn);
+        expanded.extend(outer.iter().map(|&m| orientation.face_move(m)));
+        orientation = orientation.rotate(axis, rotation);
+      }
+      ExtendedMove::Rotation(axis, turn) => {
+        orientation = orientation.rotate(axis, turn);
+      }
+      ExtendedMove::Wide(face, turn) => {
+        let (outer, axis, rotation) = wide_moves(face, turn);
+        expanded.push(orientation.face_move(outer));
+        orientation = orientation.rotate(axis, rotation);
+      }
+    }
+  }
+  (expanded, orientation)
+}
+
+impl Cube {
+  /// Apply each of `moves`, starting with every center at its own position,
+  /// which gives the `Cube` relative to wherever the centers end up.
+  pub fn apply_extended_algorithm(&self, moves: &[ExtendedMove]) -> Cube {
+    let (moves, _) = expand_algorithm(moves, Orientation::identity());
+    self.apply_algorithm(&moves)
+  }
+}
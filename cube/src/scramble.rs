@@ -0,0 +1,96 @@
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use {Corner, Cube, Edge, Face, Move, Turn, NUM_CORNERS, NUM_EDGES};
+
+/// The random number generator returned by `seeded_rng`.
+pub type SeededRng = ChaCha8Rng;
+
+/// A random number generator for the randomized functions, such as
+/// `generate_random_move_scramble` and `Cube::random`, that always produces
+/// the same numbers for the same `seed`.
+///
+/// The numbers are the same on every platform and will not change between
+/// versions of this crate, so a seed is enough to reproduce a benchmark or to
+/// audit a set of scrambles.
+pub fn seeded_rng(seed: u64) -> SeededRng {
+  SeededRng::seed_from_u64(seed)
+}
+
+/// Generate a scramble of `len` random moves.
+///
+/// The same face is never turned twice in a row, and a face is never turned
+/// again straight after its opposite face, so no moves cancel or merge.
+/// This is quicker than generating a random state, but the scrambles are not
+/// uniformly distributed over all states.
+pub fn generate_random_move_scramble(
+  len: usize,
+  rng: &mut impl Rng,
+) -> Vec<Move> {
+  let mut scramble: Vec<Move> = Vec::with_capacity(len);
+  while scramble.len() < len {
+    let face = Face::ALL[rng.random_range(0..Face::ALL.len())];
+    let skip = match scramble[..] {
+      [.., Move(last, _)] if last == face => true,
+      [.., Move(f1, _), Move(f2, _)] => f1 == face && f2.is_opposite(face),
+      _ => false,
+    };
+    if !skip {
+      let turn = Turn::ALL[rng.random_range(0..Turn::ALL.len())];
+      scramble.push(Move(face, turn));
+    }
+  }
+  scramble
+}
+
+// Shuffle `items` with the Fisher-Yates shuffle.
+fn shuffle<T>(items: &mut [T], rng: &mut impl Rng) {
+  for i in (1..items.len()).rev() {
+    items.swap(i, rng.random_range(0..i + 1));
+  }
+}
+
+impl Cube {
+  /// A random solvable state, with every state equally likely, from
+  /// `Cube::from_random_parts`.
+  pub fn random(rng: &mut impl Rng) -> Cube {
+    let mut cp = Cube::solved().cp;
+    let mut ep = Cube::solved().ep;
+    shuffle(&mut cp, rng);
+    shuffle(&mut ep, rng);
+    let mut co = [0; NUM_CORNERS - 1];
+    for o in co.iter_mut() {
+      *o = rng.random_range(0..3);
+    }
+    let mut eo = [0; NUM_EDGES - 1];
+    for o in eo.iter_mut() {
+      *o = rng.random_range(0..2);
+    }
+    Cube::from_random_parts(cp, co, ep, eo)
+  }
+
+  // The solvable state with the permutations `cp` and `ep`, and the first 7
+  // corner and 11 edge orientations `co` and `eo`, which are chosen
+  // independently and uniformly to pick a random state.
+  //
+  // The last orientations are the ones that make the cube solvable, and two
+  // edges are swapped if the parities of the permutations do not match, so
+  // each state is reached in exactly two ways.
+  pub(crate) fn from_random_parts(
+    cp: [Corner; NUM_CORNERS],
+    co: [u8; NUM_CORNERS - 1],
+    ep: [Edge; NUM_EDGES],
+    eo: [u8; NUM_EDGES - 1],
+  ) -> Cube {
+    let mut cube =
+      Cube::new_unchecked(cp, [0; NUM_CORNERS], ep, [0; NUM_EDGES]);
+    cube.co[..NUM_CORNERS - 1].copy_from_slice(&co);
+    cube.co[NUM_CORNERS - 1] = (3 - co.iter().sum::<u8>() % 3) % 3;
+    cube.eo[..NUM_EDGES - 1].copy_from_slice(&eo);
+    cube.eo[NUM_EDGES - 1] = eo.iter().sum::<u8>() % 2;
+    if !cube.has_valid_parity() {
+      cube.ep.swap(NUM_EDGES - 2, NUM_EDGES - 1);
+    }
+    debug_assert!(cube.verify().is_ok());
+    cube
+  }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use {Corner, Cube, CubeStateErr, Edge, NUM_CORNERS, NUM_EDGES};
+
+/// The serialized form of a `Cube`, which is deserialized without any checks.
+#[derive(Clone, Copy, Serialize, Deserialize)]
+pub(crate) struct RawCube {
+  cp: [Corner; NUM_CORNERS],
+  co: [u8; NUM_CORNERS],
+  ep: [Edge; NUM_EDGES],
+  eo: [u8; NUM_EDGES],
+}
+
+impl From<Cube> for RawCube {
+  fn from(cube: Cube) -> RawCube {
+    RawCube {
+      cp: cube.cp,
+      co: cube.co,
+      ep: cube.ep,
+      eo: cube.eo,
+    }
+  }
+}
+
+impl TryFrom<RawCube> for Cube {
+  type Error = CubeStateErr;
+
+  fn try_from(raw: RawCube) -> Result<Cube, CubeStateErr> {
+    let cube = Cube::new_unchecked(raw.cp, raw.co, raw.ep, raw.eo);
+    cube.verify()?;
+    Ok(cube)
+  }
+}
+
+/// A `Cube` that is deserialized without checking that it is in a solvable
+/// state, like `Cube::new_unchecked`. A `Cube` is only deserialized if it
+/// is solvable.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "RawCube", into = "RawCube")]
+pub struct UncheckedCube(pub Cube);
+
+impl From<RawCube> for UncheckedCube {
+  fn from(raw: RawCube) -> UncheckedCube {
+    UncheckedCube(Cube::new_unchecked(raw.cp, raw.co, raw.ep, raw.eo))
+  }
+}
+
+impl From<UncheckedCube> for RawCube {
+  fn from(cube: UncheckedCube) -> RawCube {
+    RawCube::from(cube.0)
+  }
+}
@@ -0,0 +1,43 @@
+use {Move, Turn};
+
+/// Simplify an algorithm without changing what it does to the cube.
+///
+/// Turns of the same face are merged, including across a turn of the
+/// opposite face since opposite faces commute, and turns that merge to
+/// nothing are removed. Each pair of turns of opposite faces is put in the
+/// order U before D, R before L and F before B.
+pub fn simplify_algorithm(moves: &[Move]) -> Vec<Move> {
+  let mut simplified: Vec<Move> = Vec::with_capacity(moves.len());
+  for &Move(face, turn) in moves {
+    let len = simplified.len();
+    // The index of a move that `face` can be merged with.
+    let merge = match simplified[..] {
+      [.., Move(last, _)] if last == face => Some(len - 1),
+      [.., Move(f1, _), Move(f2, _)] if f1 == face && f2.is_opposite(face) => {
+        Some(len - 2)
+      }
+      _ => None,
+    };
+    match merge {
+      Some(i) => {
+        let n = simplified[i].1.quarter_turns() + turn.quarter_turns();
+        match Turn::from_quarter_turns(n) {
+          Some(turn) => simplified[i].1 = turn,
+          None => {
+            simplified.remove(i);
+          }
+        }
+      }
+      None => simplified.push(Move(face, turn)),
+    }
+  }
+
+  // Merging leaves at most two turns of the same axis in a row.
+  for i in 1..simplified.len() {
+    let (Move(f1, _), Move(f2, _)) = (simplified[i - 1], simplified[i]);
+    if f1.is_opposite(f2) && f1.index() > f2.index() {
+      simplified.swap(i - 1, i);
+    }
+  }
+  simplified
+}
@@ -0,0 +1,262 @@
+use std::error::Error;
+use std::fmt;
+use {Cube, Face, Move, Turn};
+
+/// An error from decoding a packet of a smart cube.
+#[derive(Clone, Debug, PartialEq)]
+pub enum PacketError {
+  /// The packet has `found` bytes instead of at least `expected`.
+  Length { expected: usize, found: usize },
+  /// The packet turns a face with this number, which is not a face.
+  InvalidFace(u8),
+  /// The packet turns a face by this number, which is not a turn.
+  InvalidTurn(u8),
+  /// This many moves were made since the previous packet, which is more than
+  /// the packet holds, so the moves in between were lost.
+  MissedMoves(usize),
+}
+
+impl fmt::Display for PacketError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      PacketError::Length { expected, found } => {
+        write!(f, "expected {} bytes, found {}", expected, found)
+      }
+      PacketError::InvalidFace(face) => write!(f, "invalid face {}", face),
+      PacketError::InvalidTurn(turn) => write!(f, "invalid turn {}", turn),
+      PacketError::MissedMoves(moves) => {
+        write!(f, "missed moves: {} moves since the last packet", moves)
+      }
+    }
+  }
+}
+
+impl Error for PacketError {}
+
+/// Decodes the packets that a smart cube sends over Bluetooth into moves.
+///
+/// Decoders only see the bytes of each packet, so the caller is left to
+/// connect to the cube and subscribe to its notifications with any Bluetooth
+/// library.
+pub trait MoveDecoder {
+  /// The moves made since the previous packet, oldest first. Packets that are
+  /// not about moves, such as battery levels, give no moves.
+  fn decode(&mut self, packet: &[u8]) -> Result<Vec<Move>, PacketError>;
+}
+
+// Check that `packet` has at least `expected` bytes.
+fn check_length(packet: &[u8], expected: usize) -> Result<(), PacketError> {
+  if packet.len() < expected {
+    return Err(PacketError::Length {
+      expected,
+      found: packet.len(),
+    });
+  }
+  Ok(())
+}
+
+/// The length of the state packets of a Giiker cube.
+const GIIKER_PACKET_LEN: usize = 20;
+
+/// The value of byte 18 of the state packets that newer Giiker cubes
+/// obfuscate with `GIIKER_KEY`.
+const GIIKER_OBFUSCATED: u8 = 0xa7;
+
+/// The bytes that obfuscated Giiker packets are offset by.
+const GIIKER_KEY: [u8; 36] = [
+  176, 81, 104, 224, 86, 137, 237, 119, 38, 26, 193, 161, 210, 126, 150, 81,
+  93, 13, 236, 249, 89, 235, 88, 24, 113, 81, 214, 131, 130, 199, 2, 169, 39,
+  165, 171, 41,
+];
+
+/// The faces of Giiker cubes, in the order of their numbers from 1.
+const GIIKER_FACES: [Face; 6] =
+  [Face::B, Face::D, Face::L, Face::U, Face::R, Face::F];
+
+/// Decodes the state packets of Giiker cubes, which are sent after every
+/// move.
+///
+/// Each packet has the state of the cube in its first 16 bytes and the last
+/// 4 moves in the next 4, a byte each with the face in the upper 4 bits.
+/// Only the last move is new, since the cube sends a packet for each move.
+#[derive(Clone, Debug, Default)]
+pub struct GiikerDecoder;
+
+impl GiikerDecoder {
+  /// Create a decoder of Giiker packets.
+  pub fn new() -> GiikerDecoder {
+    GiikerDecoder
+  }
+}
+
+// Undo the obfuscation of `packet`, where byte 19 selects the two offsets
+// into `GIIKER_KEY` that each byte was offset by.
+fn deobfuscate(packet: &[u8]) -> Vec<u8> {
+  if packet[18] != GIIKER_OBFUSCATED {
+    return packet.to_vec();
+  }
+  let (k1, k2) = ((packet[19] >> 4) as usize, (packet[19] & 0xf) as usize);
+  packet[..18]
+    .iter()
+    .enumerate()
+    .map(|(i, b)| {
+      b.wrapping_add(GIIKER_KEY[i + k1])
+        .wrapping_add(GIIKER_KEY[i + k2])
+    })
+    .collect()
+}
+
+impl MoveDecoder for GiikerDecoder {
+  fn decode(&mut self, packet: &[u8]) -> Result<Vec<Move>, PacketError> {
+    check_length(packet, GIIKER_PACKET_LEN)?;
+    let last = deobfuscate(packet)[16];
+    let (face, turn) = (last >> 4, last & 0xf);
+    let face = match face {
+      1..=6 => GIIKER_FACES[face as usize - 1],
+      _ => return Err(PacketError::InvalidFace(face)),
+    };
+    // Some cubes send 9 for half turns.
+    let turn = match turn {
+      1 => Turn::Clockwise,
+      2 | 9 => Turn::Half,
+      3 => Turn::Counter,
+      _ => return Err(PacketError::InvalidTurn(turn)),
+    };
+    Ok(vec![Move(face, turn)])
+  }
+}
+
+/// The length of the packets of a GAN cube.
+const GAN_PACKET_LEN: usize = 20;
+
+/// The type of the GAN packets that have moves.
+const GAN_MOVE_PACKET: u32 = 2;
+
+/// The number of moves in each GAN move packet.
+const GAN_MOVES: usize = 7;
+
+/// The faces of GAN cubes, in the order of their numbers.
+const GAN_FACES: [Face; 6] =
+  [Face::U, Face::R, Face::F, Face::D, Face::L, Face::B];
+
+/// Decodes the packets of GAN cubes with the second version of their
+/// protocol, such as the GAN 356 i3 and the GAN 11 M Pro.
+///
+/// The cubes encrypt their packets with AES, using a key derived from the
+/// address of the cube, so they have to be decrypted by the caller first.
+/// Move packets start with 4 bits of type, an 8 bit count of the moves, and
+/// then the last 7 quarter turns, 5 bits each, newest first. The count is
+/// used to recover the moves of packets that were lost. If more than 7 moves
+/// were lost, decoding fails with `PacketError::MissedMoves`, and the state
+/// of the cube has to be set again before the next packet.
+#[derive(Clone, Debug, Default)]
+pub struct GanDecoder {
+  count: Option<u8>,
+}
+
+impl GanDecoder {
+  /// Create a decoder of GAN packets.
+  pub fn new() -> GanDecoder {
+    GanDecoder { count: None }
+  }
+}
+
+// The `len` bits of `packet` from bit `start`, where bit 0 is the highest bit
+// of byte 0.
+fn bits(packet: &[u8], start: usize, len: usize) -> u32 {
+  (start..start + len).fold(0, |acc, i| {
+    (acc << 1) | ((packet[i / 8] >> (7 - i % 8)) & 1) as u32
+  })
+}
+
+impl MoveDecoder for GanDecoder {
+  fn decode(&mut self, packet: &[u8]) -> Result<Vec<Move>, PacketError> {
+    check_length(packet, GAN_PACKET_LEN)?;
+    if bits(packet, 0, 4) != GAN_MOVE_PACKET {
+      return Ok(vec![]);
+    }
+    let count = bits(packet, 4, 8) as u8;
+    // Without an earlier count, only the newest move is known to be new.
+    let new = self.count.map_or(1, |c| count.wrapping_sub(c) as usize);
+    if new > GAN_MOVES {
+      // Resync, so the next packet is decoded from this count.
+      self.count = Some(count);
+      return Err(PacketError::MissedMoves(new));
+    }
+    let mut moves = (0..new)
+      .map(|i| {
+        let m = bits(packet, 12 + 5 * i, 5) as u8;
+        let face = *GAN_FACES
+          .get((m >> 1) as usize)
+          .ok_or(PacketError::InvalidFace(m >> 1))?;
+        let turn = if m & 1 == 0 {
+          Turn::Clockwise
+        } else {
+          Turn::Counter
+        };
+        Ok(Move(face, turn))
+      })
+      .collect::<Result<Vec<Move>, PacketError>>()?;
+    moves.reverse();
+    self.count = Some(count);
+    Ok(moves)
+  }
+}
+
+/// The live state of a smart cube, which applies the moves that a
+/// `MoveDecoder` decodes from its packets.
+///
+/// The state starts out solved, so the cube has to be solved when it
+/// connects, or be given its state with `with_cube`.
+pub struct SmartCube<D> {
+  decoder: D,
+  cube: Cube,
+  moves: Vec<Move>,
+}
+
+impl<D: MoveDecoder> SmartCube<D> {
+  /// Create a solved cube that is turned by the packets of `decoder`.
+  pub fn new(decoder: D) -> SmartCube<D> {
+    SmartCube {
+      decoder,
+      cube: Cube::solved(),
+      moves: vec![],
+    }
+  }
+
+  /// Start from `cube` instead.
+  pub fn with_cube(self, cube: Cube) -> SmartCube<D> {
+    SmartCube { cube, ..self }
+  }
+
+  /// Decode `packet` and apply its moves, returning them.
+  pub fn receive(&mut self, packet: &[u8]) -> Result<&[Move], PacketError> {
+    let moves = self.decoder.decode(packet)?;
+    self.cube.apply_algorithm_mut(&moves);
+    let start = self.moves.len();
+    self.moves.extend(moves);
+    Ok(&self.moves[start..])
+  }
+
+  /// The current state of the cube.
+  pub fn cube(&self) -> &Cube {
+    &self.cube
+  }
+
+  /// The moves made since the cube was created or reset, oldest first.
+  pub fn moves(&self) -> &[Move] {
+    &self.moves
+  }
+
+  /// Check if the cube is solved, such as to stop a timer.
+  pub fn is_solved(&self) -> bool {
+    self.cube == Cube::solved()
+  }
+
+  /// Mark the cube as solved and forget its moves, for when the state has
+  /// drifted from the physical cube.
+  pub fn reset(&mut self) {
+    self.cube = Cube::solved();
+    self.moves.clear();
+  }
+}
@@ -0,0 +1,258 @@
+use ascii::{B, D, F, L, R, U};
+use facelet::{CORNER_FACELETS, EDGE_FACELETS};
+use std::fmt::Write;
+use {Color, ColorScheme, Corner, Cube, Edge, FaceletCube};
+
+// A sticker, as the facelet it shows and the corners of its outline, in
+// units of the width of a sticker.
+type Sticker = (usize, [(f64, f64); 4]);
+
+// The fill of each color, in the shades of the usual stickers.
+fn fill(color: Color) -> &'static str {
+  match color {
+    Color::White => "#ffffff",
+    Color::Yellow => "#ffd500",
+    Color::Red => "#c41e3a",
+    Color::Orange => "#ff5800",
+    Color::Green => "#009e60",
+    Color::Blue => "#0051ba",
+  }
+}
+
+/// The fill of the stickers of pieces that are not highlighted.
+const GRAY: &str = "#808080";
+
+/// How thick the side stickers of `SvgView::LastLayer` are, compared to the
+/// stickers of U.
+const SIDE_WIDTH: f64 = 0.4;
+
+/// The views of a cube that an `SvgRenderer` draws.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SvgView {
+  /// An unfolded net of the whole cube, laid out like `Cube::to_ascii`.
+  Net,
+  /// The U, F and R faces, seen from above the corner between them.
+  Isometric,
+  /// The U face from above, with the top row of each side around it, as in
+  /// the diagrams of OLL and PLL cases.
+  LastLayer,
+}
+
+/// Renders cubes as SVG images, such as to show states or cases on a website.
+///
+/// ```
+/// # use cube::{Corner, Cube, SvgRenderer, SvgView};
+/// let svg = SvgRenderer::new(SvgView::LastLayer)
+///   .with_highlighted_corners(&[Corner::URF, Corner::UFL])
+///   .render(&Cube::solved());
+/// assert!(svg.starts_with("<svg"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct SvgRenderer {
+  view: SvgView,
+  scheme: ColorScheme,
+  sticker_size: f64,
+  // The facelets of the highlighted pieces, or `None` to show every piece.
+  highlighted: Option<Vec<usize>>,
+}
+
+impl SvgRenderer {
+  /// Create a renderer of `view`, with the WCA color scheme and stickers 30
+  /// pixels wide.
+  pub fn new(view: SvgView) -> SvgRenderer {
+    SvgRenderer {
+      view,
+      scheme: ColorScheme::default(),
+      sticker_size: 30.0,
+      highlighted: None,
+    }
+  }
+
+  /// Color the stickers with `scheme` instead.
+  pub fn with_scheme(self, scheme: ColorScheme) -> SvgRenderer {
+    SvgRenderer { scheme, ..self }
+  }
+
+  /// Draw stickers `size` pixels wide instead.
+  pub fn with_sticker_size(self, size: f64) -> SvgRenderer {
+    SvgRenderer {
+      sticker_size: size,
+      ..self
+    }
+  }
+
+  /// Highlight the pieces at the positions `corners`.
+  ///
+  /// Once any piece is highlighted, the stickers of the other pieces are
+  /// gray, except for the centers.
+  pub fn with_highlighted_corners(self, corners: &[Corner]) -> SvgRenderer {
+    let facelets = corners.iter().flat_map(|&c| CORNER_FACELETS[c as usize]);
+    self.highlight(facelets)
+  }
+
+  /// Highlight the pieces at the positions `edges`, like
+  /// `with_highlighted_corners`.
+  pub fn with_highlighted_edges(self, edges: &[Edge]) -> SvgRenderer {
+    let facelets = edges.iter().flat_map(|&e| EDGE_FACELETS[e as usize]);
+    self.highlight(facelets)
+  }
+
+  fn highlight(self, facelets: impl Iterator<Item = usize>) -> SvgRenderer {
+    let mut highlighted = self.highlighted.unwrap_or_default();
+    highlighted.extend(facelets);
+    SvgRenderer {
+      highlighted: Some(highlighted),
+      ..self
+    }
+  }
+
+  /// Render `cube` as an SVG document.
+  pub fn render(&self, cube: &Cube) -> String {
+    self.render_facelets(&FaceletCube::from(cube))
+  }
+
+  /// Render `facelets` as an SVG document, which does not have to be a
+  /// solvable cube.
+  pub fn render_facelets(&self, facelets: &FaceletCube) -> String {
+    let (stickers, (width, height)) = match self.view {
+      SvgView::Net => net(),
+      SvgView::Isometric => isometric(),
+      SvgView::LastLayer => last_layer(),
+    };
+    let colors = facelets.colors(&self.scheme);
+    let size = self.sticker_size;
+    // Leave room for the outlines of the stickers at the edges.
+    let stroke = size / 15.0;
+    let (width, height) = (width * size + stroke, height * size + stroke);
+    let mut svg = format!(
+      "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.2}\" \
+       height=\"{:.2}\" viewBox=\"0 0 {:.2} {:.2}\">\n",
+      width, height, width, height
+    );
+    for (facelet, corners) in stickers {
+      let shown = match self.highlighted {
+        Some(ref highlighted) => {
+          facelet % 9 == 4 || highlighted.contains(&facelet)
+        }
+        None => true,
+      };
+      let fill = if shown { fill(colors[facelet]) } else { GRAY };
+      let points: Vec<String> = corners
+        .iter()
+        .map(|&(x, y)| {
+          format!(
+            "{:.2},{:.2}",
+            x * size + stroke / 2.0,
+            y * size + stroke / 2.0
+          )
+        })
+        .collect();
+      writeln!(
+        svg,
+        "  <polygon points=\"{}\" fill=\"{}\" stroke=\"#000000\" \
+         stroke-width=\"{:.2}\"/>",
+        points.join(" "),
+        fill,
+        stroke
+      )
+      .unwrap();
+    }
+    svg.push_str("</svg>\n");
+    svg
+  }
+}
+
+impl Default for SvgRenderer {
+  fn default() -> SvgRenderer {
+    SvgRenderer::new(SvgView::Net)
+  }
+}
+
+// A square sticker for `facelet`, with its top left corner at `(x, y)`.
+fn square(facelet: usize, x: f64, y: f64) -> Sticker {
+  (
+    facelet,
+    [(x, y), (x + 1.0, y), (x + 1.0, y + 1.0), (x, y + 1.0)],
+  )
+}
+
+// The stickers of the net, with the size of the net.
+fn net() -> (Vec<Sticker>, (f64, f64)) {
+  // The first facelet of each face and its column and row in the net.
+  let faces = [
+    (U, 3.0, 0.0),
+    (L, 0.0, 3.0),
+    (F, 3.0, 3.0),
+    (R, 6.0, 3.0),
+    (B, 9.0, 3.0),
+    (D, 3.0, 6.0),
+  ];
+  let stickers = faces
+    .iter()
+    .flat_map(|&(face, x, y)| {
+      (0..9)
+        .map(move |i| square(face + i, x + (i % 3) as f64, y + (i / 3) as f64))
+    })
+    .collect();
+  (stickers, (12.0, 9.0))
+}
+
+// The stickers of U and the top rows of the sides seen from above, with the
+// size of the view.
+fn last_layer() -> (Vec<Sticker>, (f64, f64)) {
+  let w = SIDE_WIDTH;
+  let mut stickers: Vec<Sticker> = (0..9)
+    .map(|i| square(U + i, w + (i % 3) as f64, w + (i / 3) as f64))
+    .collect();
+  // Each side from the left or the top of the view, which is the reverse of
+  // the order of the facelets for B and R.
+  for k in 0..3 {
+    let (a, b) = (w + k as f64, w + k as f64 + 1.0);
+    let (near, far) = (w + 3.0, 2.0 * w + 3.0);
+    stickers.push((B + 2 - k, [(a, 0.0), (b, 0.0), (b, w), (a, w)]));
+    stickers.push((F + k, [(a, near), (b, near), (b, far), (a, far)]));
+    stickers.push((L + k, [(0.0, a), (w, a), (w, b), (0.0, b)]));
+    stickers.push((R + 2 - k, [(near, a), (far, a), (far, b), (near, b)]));
+  }
+  (stickers, (3.0 + 2.0 * w, 3.0 + 2.0 * w))
+}
+
+// The stickers of U, F and R in an isometric projection, with the size of
+// the view.
+fn isometric() -> (Vec<Sticker>, (f64, f64)) {
+  // The point `(x, y, z)` of the cube, from the left, front and bottom of
+  // the cube, seen from above the URF corner. The top of the view is at the
+  // ULB corner, `(0, 3, 3)`.
+  let project = |(x, y, z): (f64, f64, f64)| -> (f64, f64) {
+    let cos = 30f64.to_radians().cos();
+    ((x + y) * cos, 4.5 - z + (x - y) / 2.0)
+  };
+  let face = |first: usize, corner: &dyn Fn(f64, f64) -> (f64, f64, f64)| {
+    (0..9)
+      .map(|i| {
+        let (c, r) = ((i % 3) as f64, (i / 3) as f64);
+        let corners = [
+          project(corner(c, r)),
+          project(corner(c + 1.0, r)),
+          project(corner(c + 1.0, r + 1.0)),
+          project(corner(c, r + 1.0)),
+        ];
+        (first + i, corners)
+      })
+      .collect::<Vec<Sticker>>()
+  };
+  // Each face is read from its top left corner as seen from outside.
+  let mut stickers = face(U, &|c, r| (c, 3.0 - r, 3.0));
+  stickers.extend(face(F, &|c, r| (c, 0.0, 3.0 - r)));
+  stickers.extend(face(R, &|c, r| (3.0, c, 3.0 - r)));
+  let width = 6.0 * 30f64.to_radians().cos();
+  (stickers, (width, 6.0))
+}
+
+impl Cube {
+  /// Render an unfolded net of the `Cube` as an SVG document, with the WCA
+  /// color scheme. See `SvgRenderer` for other views.
+  pub fn to_svg(&self) -> String {
+    SvgRenderer::default().render(self)
+  }
+}
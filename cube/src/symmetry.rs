@@ -0,0 +1,273 @@
+use {Corner, Cube, Edge, Face, Move, Turn, NUM_CORNERS, NUM_EDGES};
+
+/// Number of symmetries of the cube that keep the U and D faces on the UD
+/// axis.
+pub const NUM_UD_SYMMETRIES: usize = 16;
+
+/// Number of symmetries of the cube, including the reflections.
+pub const NUM_SYMMETRIES: usize = 48;
+
+/// One of the 48 symmetries of the cube. Each symmetry is made of a number
+/// of 120 degree rotations around the URF-DBL diagonal (URF3), a 180 degree
+/// rotation around the FB axis (F2), a number of 90 degree rotations around
+/// the UD axis (U4) and a reflection in the plane between the L and R faces
+/// (LR2), in that order. The first 16 are the symmetries without URF3, which
+/// keep the U and D faces on the UD axis.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Symmetry(u8);
+
+/// A cube with the corner orientation used by the symmetries, where the
+/// corners of reflected cubes have orientations 3 to 5.
+#[derive(Clone, Copy)]
+struct SymCube {
+  cp: [usize; NUM_CORNERS],
+  co: [u8; NUM_CORNERS],
+  ep: [usize; NUM_EDGES],
+  eo: [u8; NUM_EDGES],
+}
+
+const SYM_IDENTITY: SymCube = SymCube {
+  cp: [0, 1, 2, 3, 4, 5, 6, 7],
+  co: [0; 8],
+  ep: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11],
+  eo: [0; 12],
+};
+
+const SYM_URF3: SymCube = SymCube {
+  cp: [0, 4, 5, 1, 3, 7, 6, 2],
+  co: [1, 2, 1, 2, 2, 1, 2, 1],
+  ep: [1, 8, 5, 9, 3, 11, 7, 10, 0, 4, 6, 2],
+  eo: [1, 0, 1, 0, 1, 0, 1, 0, 1, 1, 1, 1],
+};
+
+const SYM_F2: SymCube = SymCube {
+  cp: [5, 4, 7, 6, 1, 0, 3, 2],
+  co: [0; 8],
+  ep: [6, 5, 4, 7, 2, 1, 0, 3, 9, 8, 11, 10],
+  eo: [0; 12],
+};
+
+const SYM_U4: SymCube = SymCube {
+  cp: [3, 0, 1, 2, 7, 4, 5, 6],
+  co: [0; 8],
+  ep: [3, 0, 1, 2, 7, 4, 5, 6, 11, 8, 9, 10],
+  eo: [0, 0, 0, 0, 0, 0, 0, 0, 1, 1, 1, 1],
+};
+
+const SYM_LR2: SymCube = SymCube {
+  cp: [1, 0, 3, 2, 5, 4, 7, 6],
+  co: [3; 8],
+  ep: [2, 1, 0, 3, 6, 5, 4, 7, 9, 8, 11, 10],
+  eo: [0; 12],
+};
+
+impl SymCube {
+  /// The `SymCube` of applying `self` and then `other`.
+  const fn then(&self, other: &SymCube) -> SymCube {
+    let mut cube = SYM_IDENTITY;
+    let mut i = 0;
+    while i < NUM_CORNERS {
+      let j = other.cp[i];
+      cube.cp[i] = self.cp[j];
+      let (a, b) = (self.co[j], other.co[i]);
+      // A reflection reverses the direction that the other cube twists its
+      // corners.
+      cube.co[i] = match (a < 3, b < 3) {
+        (true, true) => (a + b) % 3,
+        (true, false) => 3 + (a + b) % 3,
+        (false, true) => 3 + (a + 3 - b) % 3,
+        (false, false) => (a + 3 - b) % 3,
+      };
+      i += 1;
+    }
+    let mut i = 0;
+    while i < NUM_EDGES {
+      let j = other.ep[i];
+      cube.ep[i] = self.ep[j];
+      cube.eo[i] = self.eo[j] ^ other.eo[i];
+      i += 1;
+    }
+    cube
+  }
+
+  /// Raise the `SymCube` to the `n`th power.
+  const fn pow(&self, n: usize) -> SymCube {
+    let mut cube = SYM_IDENTITY;
+    let mut i = 0;
+    while i < n {
+      cube = cube.then(self);
+      i += 1;
+    }
+    cube
+  }
+
+  /// Check if the `SymCube` is the identity.
+  const fn is_identity(&self) -> bool {
+    let mut i = 0;
+    while i < NUM_CORNERS {
+      if self.cp[i] != i || self.co[i] != 0 {
+        return false;
+      }
+      i += 1;
+    }
+    let mut i = 0;
+    while i < NUM_EDGES {
+      if self.ep[i] != i || self.eo[i] != 0 {
+        return false;
+      }
+      i += 1;
+    }
+    true
+  }
+}
+
+/// The `SymCube` of each symmetry, URF3^urf3 F2^f2 U4^u4 LR2^lr2, at index
+/// 16 * urf3 + 8 * f2 + 2 * u4 + lr2.
+const SYM_CUBES: [SymCube; NUM_SYMMETRIES] = {
+  let mut cubes = [SYM_IDENTITY; NUM_SYMMETRIES];
+  let mut i = 0;
+  while i < NUM_SYMMETRIES {
+    cubes[i] = SYM_URF3
+      .pow(i >> 4)
+      .then(&SYM_F2.pow((i >> 3) & 1))
+      .then(&SYM_U4.pow((i >> 1) & 3))
+      .then(&SYM_LR2.pow(i & 1));
+    i += 1;
+  }
+  cubes
+};
+
+/// The index of the inverse of each symmetry.
+const SYM_INVERSES: [u8; NUM_SYMMETRIES] = {
+  let mut inverses = [0; NUM_SYMMETRIES];
+  let mut i = 0;
+  while i < NUM_SYMMETRIES {
+    let mut j = 0;
+    while !SYM_CUBES[i].then(&SYM_CUBES[j]).is_identity() {
+      j += 1;
+    }
+    inverses[i] = j as u8;
+    i += 1;
+  }
+  inverses
+};
+
+// `Cube` twists its corners in the opposite direction to `SymCube`.
+impl From<Cube> for SymCube {
+  fn from(cube: Cube) -> SymCube {
+    let mut sym_cube = SYM_IDENTITY;
+    for i in 0..NUM_CORNERS {
+      sym_cube.cp[i] = cube.cp[i] as usize;
+      sym_cube.co[i] = (3 - cube.co[i]) % 3;
+    }
+    for i in 0..NUM_EDGES {
+      sym_cube.ep[i] = cube.ep[i] as usize;
+      sym_cube.eo[i] = cube.eo[i];
+    }
+    sym_cube
+  }
+}
+
+impl From<SymCube> for Cube {
+  fn from(sym_cube: SymCube) -> Cube {
+    let mut cube = Cube::solved();
+    for i in 0..NUM_CORNERS {
+      assert!(sym_cube.co[i] < 3, "a reflected cube is not a valid cube");
+      cube.cp[i] = Corner::from(sym_cube.cp[i]);
+      cube.co[i] = (3 - sym_cube.co[i]) % 3;
+    }
+    for i in 0..NUM_EDGES {
+      cube.ep[i] = Edge::from(sym_cube.ep[i]);
+      cube.eo[i] = sym_cube.eo[i];
+    }
+    cube
+  }
+}
+
+impl Symmetry {
+  /// All the symmetries that keep the U and D faces on the UD axis,
+  /// starting with the identity.
+  pub fn all() -> impl Iterator<Item = Symmetry> {
+    (0..NUM_UD_SYMMETRIES as u8).map(Symmetry)
+  }
+
+  /// All 48 symmetries of the cube, starting with those of `Symmetry::all`.
+  pub fn all_48() -> impl Iterator<Item = Symmetry> {
+    (0..NUM_SYMMETRIES as u8).map(Symmetry)
+  }
+
+  /// The index of the symmetry in `Symmetry::all_48`, which is also its
+  /// index in `Symmetry::all` if it keeps the UD axis.
+  pub fn index(&self) -> usize {
+    self.0 as usize
+  }
+
+  /// Check if the symmetry keeps the U and D faces on the UD axis.
+  pub fn keeps_ud_axis(&self) -> bool {
+    self.index() < NUM_UD_SYMMETRIES
+  }
+
+  /// Check if the symmetry includes a reflection.
+  pub fn is_reflection(&self) -> bool {
+    self.0 & 1 == 1
+  }
+
+  /// The `Symmetry` that undoes this symmetry.
+  pub fn inverse(&self) -> Symmetry {
+    Symmetry(SYM_INVERSES[self.index()])
+  }
+
+  /// The move that does the same as `move_` on a cube conjugated by the
+  /// symmetry, so that `cube.apply_move(move_).conjugate(sym)` is
+  /// `cube.conjugate(sym).apply_move(sym.conjugate_move(move_))`.
+  pub fn conjugate_move(&self, move_: Move) -> Move {
+    let turned = Cube::solved().apply_move(move_).conjugate(*self);
+    Face::ALL
+      .iter()
+      .flat_map(|&f| Turn::ALL.iter().map(move |&turn| Move(f, turn)))
+      .find(|&m| Cube::solved().apply_move(m) == turned)
+      .expect("a symmetry maps every face turn to a face turn")
+  }
+
+  fn sym_cube(&self) -> &'static SymCube {
+    &SYM_CUBES[self.index()]
+  }
+}
+
+impl Cube {
+  /// Conjugate the `Cube` by `sym`, which gives the same cube seen after
+  /// the symmetry is applied to the whole cube.
+  pub fn conjugate(&self, sym: Symmetry) -> Cube {
+    let s = sym.sym_cube();
+    let s_inv = sym.inverse().sym_cube();
+    let cube = s.then(&SymCube::from(*self)).then(s_inv);
+    let cube = Cube::from(cube);
+    debug_assert!(cube.verify().is_ok());
+    cube
+  }
+
+  /// The smallest `Cube` that conjugating by any of the 48 symmetries gives,
+  /// in the order of `Cube`, and the first symmetry that gives it.
+  ///
+  /// Cubes that are the same up to symmetry have the same canonical cube,
+  /// so it can be used to store one cube of each symmetry class.
+  pub fn canonicalize(&self) -> (Cube, Symmetry) {
+    self.canonicalize_by(Symmetry::all_48())
+  }
+
+  /// Canonicalize the `Cube` like `canonicalize`, with only the 16
+  /// symmetries that keep the UD axis, as the two-phase tables do.
+  pub fn canonicalize_ud(&self) -> (Cube, Symmetry) {
+    self.canonicalize_by(Symmetry::all())
+  }
+
+  fn canonicalize_by(
+    &self,
+    syms: impl Iterator<Item = Symmetry>,
+  ) -> (Cube, Symmetry) {
+    syms
+      .map(|sym| (self.conjugate(sym), sym))
+      .min_by_key(|&(cube, _)| cube)
+      .expect("there is always the identity")
+  }
+}
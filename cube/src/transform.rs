@@ -0,0 +1,155 @@
+use {Axis, ExtendedMove, Face, Move, Orientation, Slice, Turn};
+
+// The face that `slice` turns in the direction of.
+fn slice_face(slice: Slice) -> Face {
+  match slice {
+    Slice::E => Face::D,
+    Slice::M => Face::L,
+    Slice::S => Face::F,
+  }
+}
+
+// The face that a rotation around `axis` turns in the direction of.
+fn axis_face(axis: Axis) -> Face {
+  match axis {
+    Axis::X => Face::R,
+    Axis::Y => Face::U,
+    Axis::Z => Face::F,
+  }
+}
+
+// The middle layer next to `face`, turned in the direction of `turn` of
+// `face`.
+fn face_slice(face: Face, turn: Turn) -> (Slice, Turn) {
+  match face {
+    Face::D => (Slice::E, turn),
+    Face::U => (Slice::E, turn.inverse()),
+    Face::L => (Slice::M, turn),
+    Face::R => (Slice::M, turn.inverse()),
+    Face::F => (Slice::S, turn),
+    Face::B => (Slice::S, turn.inverse()),
+  }
+}
+
+// The rotation around the axis of `face`, in the direction of `turn` of
+// `face`.
+fn face_axis(face: Face, turn: Turn) -> (Axis, Turn) {
+  match face {
+    Face::R => (Axis::X, turn),
+    Face::L => (Axis::X, turn.inverse()),
+    Face::U => (Axis::Y, turn),
+    Face::D => (Axis::Y, turn.inverse()),
+    Face::F => (Axis::Z, turn),
+    Face::B => (Axis::Z, turn.inverse()),
+  }
+}
+
+// Map `m` with `map`, which maps a turn in the direction of a face to
+// another. Middle layers and rotations are mapped by the face that they turn
+// in the direction of.
+fn map_move(
+  m: ExtendedMove,
+  map: impl Fn(Face, Turn) -> (Face, Turn),
+) -> ExtendedMove {
+  match m {
+    ExtendedMove::Face(Move(face, turn)) => {
+      let (face, turn) = map(face, turn);
+      ExtendedMove::Face(Move(face, turn))
+    }
+    ExtendedMove::Slice(slice, turn) => {
+      let (face, turn) = map(slice_face(slice), turn);
+      let (slice, turn) = face_slice(face, turn);
+      ExtendedMove::Slice(slice, turn)
+    }
+    ExtendedMove::Rotation(axis, turn) => {
+      let (face, turn) = map(axis_face(axis), turn);
+      let (axis, turn) = face_axis(face, turn);
+      ExtendedMove::Rotation(axis, turn)
+    }
+    ExtendedMove::Wide(face, turn) => {
+      let (face, turn) = map(face, turn);
+      ExtendedMove::Wide(face, turn)
+    }
+  }
+}
+
+// The face that a mirror across the plane of the middle layer of `axis` maps
+// `face` to, where the faces on `axis` swap and the others stay.
+fn mirror_face(face: Face, axis: Axis) -> Face {
+  let on_axis = axis_face(axis);
+  if face == on_axis || face == on_axis.opposite() {
+    face.opposite()
+  } else {
+    face
+  }
+}
+
+/// Mirror `moves` across the plane of the middle layer of `axis`, such as
+/// across M for `Axis::X`, which swaps R and L, so that a right-handed
+/// algorithm becomes its left-handed version. Every turn changes direction.
+pub fn mirror_algorithm(moves: &[Move], axis: Axis) -> Vec<Move> {
+  moves
+    .iter()
+    .map(|&Move(face, turn)| Move(mirror_face(face, axis), turn.inverse()))
+    .collect()
+}
+
+/// Mirror `moves` like `mirror_algorithm`, including the middle layers, wide
+/// turns and rotations.
+pub fn mirror_extended_algorithm(
+  moves: &[ExtendedMove],
+  axis: Axis,
+) -> Vec<ExtendedMove> {
+  moves
+    .iter()
+    .map(|&m| {
+      map_move(m, |face, turn| (mirror_face(face, axis), turn.inverse()))
+    })
+    .collect()
+}
+
+/// The moves that do the same as `moves` when the cube is rotated around
+/// `axis` first, so that `moves` are done from another side, like removing
+/// the rotations from `y R U R' y'`.
+pub fn rotate_algorithm(moves: &[Move], axis: Axis, turn: Turn) -> Vec<Move> {
+  let orientation = Orientation::identity().rotate(axis, turn);
+  moves.iter().map(|&m| orientation.face_move(m)).collect()
+}
+
+/// Rotate `moves` like `rotate_algorithm`, including the middle layers, wide
+/// turns and rotations.
+pub fn rotate_extended_algorithm(
+  moves: &[ExtendedMove],
+  axis: Axis,
+  turn: Turn,
+) -> Vec<ExtendedMove> {
+  let orientation = Orientation::identity().rotate(axis, turn);
+  moves
+    .iter()
+    .map(|&m| map_move(m, |face, turn| (orientation.center(face), turn)))
+    .collect()
+}
+
+/// Remove the rotations from `moves`, by changing the layers of the moves
+/// after each rotation to the layers that the rotation brought to their
+/// positions.
+///
+/// Unlike `expand_algorithm`, the middle layers and wide turns are kept.
+/// Returns the moves and the `Orientation` of the rotations, which is how
+/// the cube ends up held differently from `moves`.
+pub fn remove_rotations(
+  moves: &[ExtendedMove],
+) -> (Vec<ExtendedMove>, Orientation) {
+  let mut rotationless = Vec::with_capacity(moves.len());
+  let mut orientation = Orientation::identity();
+  for &m in moves {
+    match m {
+      ExtendedMove::Rotation(axis, turn) => {
+        orientation = orientation.rotate(axis, turn);
+      }
+      _ => rotationless
+        .push(map_move(m, |face, turn| (orientation.center(face), turn))),
+    }
+  }
+  (rotationless, orientation)
+}
@@ -0,0 +1,78 @@
+use {Cube, Face, FaceletCube, Move, Turn, NUM_CORNERS, NUM_EDGES};
+
+// The number of corners and edges that are not in their solved position and
+// orientation.
+fn moved_pieces(cube: &Cube) -> (usize, usize) {
+  let solved = Cube::solved();
+  let corners = (0..NUM_CORNERS)
+    .filter(|&i| cube.cp[i] != solved.cp[i] || cube.co[i] != 0)
+    .count();
+  let edges = (0..NUM_EDGES)
+    .filter(|&i| cube.ep[i] != solved.ep[i] || cube.eo[i] != 0)
+    .count();
+  (corners, edges)
+}
+
+// Check that the facelets of `face` and its opposite face are still their
+// own colors, as a turn of `face` only moves facelets within those faces.
+fn keeps_faces(cube: &Cube, face: Face) -> bool {
+  let solved = FaceletCube::solved().0;
+  let facelets = FaceletCube::from(cube).0;
+  solved
+    .iter()
+    .zip(&facelets)
+    .filter(|&(&f, _)| f == face || f == face.opposite())
+    .all(|(f, g)| f == g)
+}
+
+/// Check the built-in move tables against each other and against `Cube`'s
+/// own permutation composition, returning the first move whose entry is
+/// inconsistent.
+///
+/// A quarter turn must be a solvable state that moves exactly 4 corners and
+/// 4 edges, keeps the colors of its face and the opposite face on those
+/// faces, and gives the solved cube after 4 turns but not after 2. The half
+/// and prime turns must be the quarter turn composed with itself 2 and 3
+/// times, and applying any move to a cube must be the same as composing the
+/// cube with the move.
+///
+/// This is a diagnostic for changes to the tables. The tables of a released
+/// version always pass it.
+pub fn verify_move_tables() -> Result<(), Move> {
+  // A scrambled cube, so that wrong orientations in the tables are not
+  // hidden by only applying them to solved pieces.
+  let base = Cube::from_algorithm(&[
+    Move(Face::R, Turn::Clockwise),
+    Move(Face::U, Turn::Clockwise),
+    Move(Face::F, Turn::Counter),
+    Move(Face::D, Turn::Half),
+    Move(Face::L, Turn::Clockwise),
+    Move(Face::B, Turn::Counter),
+  ]);
+  for &face in &Face::ALL {
+    let quarter_move = Move(face, Turn::Clockwise);
+    let quarter = Cube::solved().turn(quarter_move);
+    if quarter.verify().is_err()
+      || moved_pieces(&quarter) != (4, 4)
+      || !keeps_faces(&quarter, face)
+    {
+      return Err(quarter_move);
+    }
+    let half = quarter.compose(&quarter);
+    if half == Cube::solved() || half.compose(&half) != Cube::solved() {
+      return Err(quarter_move);
+    }
+
+    let mut composed = Cube::solved();
+    for &turn in &Turn::ALL {
+      let m = Move(face, turn);
+      composed = composed.compose(&quarter);
+      if Cube::solved().turn(m) != composed
+        || base.turn(m) != base.compose(&composed)
+      {
+        return Err(m);
+      }
+    }
+  }
+  Ok(())
+}
@@ -1,8 +1,12 @@
 extern crate cube;
+extern crate rand;
 
 use cube::Corner::*;
 use cube::Edge::*;
 use cube::*;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::convert::TryFrom;
 
 #[test]
 fn solved_cube() {
@@ -89,6 +93,25 @@ fn invalid_cube() {
   );
 }
 
+#[test]
+fn try_new() {
+  let solved = Cube::try_new(
+    [URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB],
+    [0; NUM_CORNERS],
+    [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
+    [0; NUM_EDGES],
+  );
+  assert_eq!(Ok(Cube::solved()), solved);
+
+  let invalid_corner_parity = Cube::try_new(
+    [UFL, URF, ULB, UBR, DFR, DLF, DBL, DRB],
+    [0; NUM_CORNERS],
+    [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
+    [0; NUM_EDGES],
+  );
+  assert_eq!(Err(CubeStateErr::ErrParity), invalid_corner_parity);
+}
+
 #[test]
 fn test_has_valid_parity() {
   let cube = Cube::new_unchecked(
@@ -104,7 +127,7 @@ fn test_has_valid_parity() {
 #[test]
 fn move_u() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::U, 1));
+  let cube = cube.apply_move(Move(Face::U, Turn::Clockwise));
   let move_u = Cube::new(
     [UBR, URF, UFL, ULB, DFR, DLF, DBL, DRB],
     [0; NUM_CORNERS],
@@ -117,7 +140,7 @@ fn move_u() {
 #[test]
 fn move_r() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::R, 1));
+  let cube = cube.apply_move(Move(Face::R, Turn::Clockwise));
   let move_r = Cube::new(
     [DFR, UFL, ULB, URF, DRB, DLF, DBL, UBR],
     [1, 0, 0, 2, 2, 0, 0, 1],
@@ -130,7 +153,7 @@ fn move_r() {
 #[test]
 fn move_f() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::F, 1));
+  let cube = cube.apply_move(Move(Face::F, Turn::Clockwise));
   let move_f = Cube::new(
     [UFL, DLF, ULB, UBR, URF, DFR, DBL, DRB],
     [2, 1, 0, 0, 1, 2, 0, 0],
@@ -143,7 +166,7 @@ fn move_f() {
 #[test]
 fn move_d() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::D, 1));
+  let cube = cube.apply_move(Move(Face::D, Turn::Clockwise));
   let move_d = Cube::new(
     [URF, UFL, ULB, UBR, DLF, DBL, DRB, DFR],
     [0; NUM_CORNERS],
@@ -156,7 +179,7 @@ fn move_d() {
 #[test]
 fn move_b() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::B, 1));
+  let cube = cube.apply_move(Move(Face::B, Turn::Clockwise));
   let move_b = Cube::new(
     [URF, UFL, UBR, DRB, DFR, DLF, ULB, DBL],
     [0, 0, 2, 1, 0, 0, 1, 2],
@@ -169,7 +192,7 @@ fn move_b() {
 #[test]
 fn move_l() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::L, 1));
+  let cube = cube.apply_move(Move(Face::L, Turn::Clockwise));
   let move_l = Cube::new(
     [URF, ULB, DBL, UBR, DFR, UFL, DLF, DRB],
     [0, 2, 1, 0, 0, 1, 2, 0],
@@ -182,7 +205,7 @@ fn move_l() {
 #[test]
 fn move_u2() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::U, 2));
+  let cube = cube.apply_move(Move(Face::U, Turn::Half));
   let move_u2 = Cube::new(
     [ULB, UBR, URF, UFL, DFR, DLF, DBL, DRB],
     [0; NUM_CORNERS],
@@ -195,7 +218,7 @@ fn move_u2() {
 #[test]
 fn move_u_prime() {
   let cube = Cube::solved();
-  let cube = cube.apply_move(Move(Face::U, 3));
+  let cube = cube.apply_move(Move(Face::U, Turn::Counter));
   let move_u_prime = Cube::new(
     [UFL, ULB, UBR, URF, DFR, DLF, DBL, DRB],
     [0; NUM_CORNERS],
@@ -209,8 +232,8 @@ fn move_u_prime() {
 fn alg_u2r2() {
   let mut cube = Cube::solved();
   for _ in 0..3 {
-    cube = cube.apply_move(Move(Face::U, 2));
-    cube = cube.apply_move(Move(Face::R, 2));
+    cube = cube.apply_move(Move(Face::U, Turn::Half));
+    cube = cube.apply_move(Move(Face::R, Turn::Half));
   }
   let cube_u2r2 = Cube::new(
     [URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB],
@@ -224,25 +247,22 @@ fn alg_u2r2() {
 #[test]
 fn alg_tperm() {
   let tperm = [
-    Move(Face::R, 1),
-    Move(Face::U, 1),
-    Move(Face::R, 3),
-    Move(Face::U, 3),
-    Move(Face::R, 3),
-    Move(Face::F, 1),
-    Move(Face::R, 2),
-    Move(Face::U, 3),
-    Move(Face::R, 3),
-    Move(Face::U, 3),
-    Move(Face::R, 1),
-    Move(Face::U, 1),
-    Move(Face::R, 3),
-    Move(Face::F, 3),
+    Move(Face::R, Turn::Clockwise),
+    Move(Face::U, Turn::Clockwise),
+    Move(Face::R, Turn::Counter),
+    Move(Face::U, Turn::Counter),
+    Move(Face::R, Turn::Counter),
+    Move(Face::F, Turn::Clockwise),
+    Move(Face::R, Turn::Half),
+    Move(Face::U, Turn::Counter),
+    Move(Face::R, Turn::Counter),
+    Move(Face::U, Turn::Counter),
+    Move(Face::R, Turn::Clockwise),
+    Move(Face::U, Turn::Clockwise),
+    Move(Face::R, Turn::Counter),
+    Move(Face::F, Turn::Counter),
   ];
-  let mut cube = Cube::solved();
-  for m in &tperm {
-    cube = cube.apply_move(*m);
-  }
+  let cube = Cube::solved().apply_algorithm(&tperm);
   let cube_tperm = Cube::new(
     [UBR, UFL, ULB, URF, DFR, DLF, DBL, DRB],
     [0; NUM_CORNERS],
@@ -266,3 +286,1494 @@ fn opposite_face() {
   assert!(r.is_opposite(Face::L));
   assert!(!r.is_opposite(Face::F));
 }
+
+#[test]
+fn face_index() {
+  for (i, &face) in Face::ALL.iter().enumerate() {
+    assert_eq!(i, face.index());
+    assert_eq!(i, usize::from(face));
+    assert_eq!(Some(face), Face::from_index(i));
+    assert_eq!(face, face.opposite().opposite());
+    assert!(face.is_opposite(face.opposite()));
+  }
+  assert_eq!(None, Face::from_index(6));
+}
+
+#[test]
+fn piece_faces() {
+  for (i, &corner) in Corner::ALL.iter().enumerate() {
+    assert_eq!(corner, Corner::from(i));
+    let faces = corner.faces();
+    assert!(faces[0] == Face::U || faces[0] == Face::D);
+    assert_eq!(
+      format!("{:?}", corner),
+      format!("{:?}{:?}{:?}", faces[0], faces[1], faces[2])
+    );
+  }
+  for (i, &edge) in Edge::ALL.iter().enumerate() {
+    assert_eq!(edge, Edge::from(i));
+    let [f1, f2] = edge.faces();
+    assert_eq!(format!("{:?}", edge), format!("{:?}{:?}", f1, f2));
+  }
+
+  // Each face has the pieces that have a facelet on it, and is next to every
+  // face but its opposite.
+  for &face in &Face::ALL {
+    let corners: Vec<Corner> = Corner::ALL
+      .iter()
+      .cloned()
+      .filter(|c| c.faces().contains(&face))
+      .collect();
+    assert_eq!(corners, face.adjacent_corners());
+    let edges: Vec<Edge> = Edge::ALL
+      .iter()
+      .cloned()
+      .filter(|e| e.faces().contains(&face))
+      .collect();
+    assert_eq!(edges, face.adjacent_edges());
+    let neighbours: Vec<Face> = edges
+      .iter()
+      .map(|e| e.faces().iter().cloned().find(|&f| f != face).unwrap())
+      .collect();
+    assert!(!neighbours.contains(&face.opposite()));
+  }
+}
+
+#[test]
+fn parse_move() {
+  assert_eq!(Ok(Move(Face::R, Turn::Clockwise)), "R".parse());
+  assert_eq!(Ok(Move(Face::U, Turn::Half)), "U2".parse());
+  assert_eq!(Ok(Move(Face::F, Turn::Counter)), "F'".parse());
+  assert_eq!(Ok(Move(Face::B, Turn::Half)), "B2'".parse());
+  assert_eq!(Err(ParseError::Empty), "".parse::<Move>());
+  assert_eq!(
+    Err(ParseError::InvalidFace {
+      token: "x".to_string(),
+      position: 0
+    }),
+    "x".parse::<Move>()
+  );
+  assert_eq!(
+    Err(ParseError::InvalidTurn {
+      token: "R3".to_string(),
+      position: 0
+    }),
+    "R3".parse::<Move>()
+  );
+}
+
+#[test]
+fn parse_alg() {
+  assert_eq!(
+    Ok(vec![
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Half),
+      Move(Face::F, Turn::Half),
+    ]),
+    parse_algorithm("R U R' U2 F2")
+  );
+  assert_eq!(
+    Ok(vec![
+      Move(Face::D, Turn::Clockwise),
+      Move(Face::L, Turn::Counter)
+    ]),
+    parse_algorithm("  D\tL'\n")
+  );
+  assert_eq!(Ok(vec![]), parse_algorithm(""));
+  assert_eq!(
+    Err(ParseError::InvalidTurn {
+      token: "U''".to_string(),
+      position: 5
+    }),
+    parse_algorithm("R  F U'' D")
+  );
+}
+
+#[test]
+fn format_alg() {
+  assert_eq!("R", Move(Face::R, Turn::Clockwise).to_string());
+  assert_eq!("U2", Move(Face::U, Turn::Half).to_string());
+  assert_eq!("F'", Move(Face::F, Turn::Counter).to_string());
+  assert_eq!("", format_algorithm(&[]));
+
+  let alg = "R U R' U' R' F R2 U' R' U' R U R' F'";
+  assert_eq!(alg, format_algorithm(&parse_algorithm(alg).unwrap()));
+}
+
+#[test]
+fn half_and_prime_turns() {
+  let faces = [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+  // Start from a cube with every piece moved and twisted, so the turns are
+  // checked for every piece.
+  let start = parse_algorithm("R U F' D2 L B' R2 U'")
+    .unwrap()
+    .iter()
+    .fold(Cube::solved(), |acc, &m| acc.apply_move(m));
+  for &f in &faces {
+    let mut quarters = start;
+    for &turn in &Turn::ALL {
+      quarters = quarters.apply_move(Move(f, Turn::Clockwise));
+      assert_eq!(quarters, start.apply_move(Move(f, turn)));
+    }
+  }
+}
+
+#[test]
+fn move_new() {
+  assert_eq!(Some(Move(Face::U, Turn::Clockwise)), Move::new(Face::U, 1));
+  assert_eq!(Some(Move(Face::R, Turn::Half)), Move::new(Face::R, 6));
+  assert_eq!(Some(Move(Face::F, Turn::Counter)), Move::new(Face::F, 3));
+  assert_eq!(None, Move::new(Face::D, 0));
+  assert_eq!(None, Move::new(Face::B, 4));
+  for &turn in &Turn::ALL {
+    assert_eq!(Some(turn), Turn::from_quarter_turns(u8::from(turn)));
+    let n = turn.quarter_turns() + turn.inverse().quarter_turns();
+    assert_eq!(None, Turn::from_quarter_turns(n));
+  }
+}
+
+#[test]
+fn inverse() {
+  assert_eq!(
+    Move(Face::R, Turn::Counter),
+    Move(Face::R, Turn::Clockwise).inverse()
+  );
+  assert_eq!(
+    Move(Face::U, Turn::Half),
+    Move(Face::U, Turn::Half).inverse()
+  );
+  assert_eq!(
+    Move(Face::F, Turn::Clockwise),
+    Move(Face::F, Turn::Counter).inverse()
+  );
+
+  let alg = parse_algorithm("R U R' U' F2 D L' B").unwrap();
+  let inverse = invert_algorithm(&alg);
+  assert_eq!(parse_algorithm("B' L D' F2 U R U' R'").unwrap(), inverse);
+  let scrambled = Cube::solved().apply_algorithm(&alg);
+  assert_ne!(Cube::solved(), scrambled);
+  assert_eq!(Cube::solved(), scrambled.apply_algorithm(&inverse));
+  assert_eq!(alg, invert_algorithm(&inverse));
+}
+
+#[test]
+fn apply_algorithm() {
+  let alg = parse_algorithm("R U R' U'").unwrap();
+  let mut cube = Cube::solved();
+  for _ in 0..6 {
+    let next = cube.apply_algorithm(&alg);
+    assert_eq!(alg.iter().fold(cube, |acc, &m| acc.apply_move(m)), next);
+    cube.apply_algorithm_mut(&alg);
+    assert_eq!(next, cube);
+  }
+  // The sexy move has order 6.
+  assert_eq!(Cube::solved(), cube);
+  assert_eq!(cube, cube.apply_algorithm(&[]));
+}
+
+#[test]
+fn facelets() {
+  let solved = FaceletCube::solved();
+  assert_eq!(solved, FaceletCube::from(&Cube::solved()));
+  assert_eq!(Ok(Cube::solved()), Cube::try_from(solved));
+
+  // U moves the top row of R to F.
+  let u = FaceletCube::from(
+    &Cube::solved().apply_move(Move(Face::U, Turn::Clockwise)),
+  );
+  assert_eq!([Face::R; 3], u.0[18..21]);
+  // R moves the right column of F to U.
+  let r = FaceletCube::from(
+    &Cube::solved().apply_move(Move(Face::R, Turn::Clockwise)),
+  );
+  assert_eq!([Face::F; 3], [r.0[2], r.0[5], r.0[8]]);
+
+  let alg = parse_algorithm("R U R' U' F2 D L' B").unwrap();
+  let cube = Cube::solved().apply_algorithm(&alg);
+  assert_eq!(Ok(cube), Cube::try_from(FaceletCube::from(&cube)));
+}
+
+#[test]
+fn invalid_facelets() {
+  let solved = FaceletCube::solved();
+
+  let mut center = solved;
+  center.0.swap(4, 13);
+  assert_eq!(Err(FaceletErr::ErrCenter(Face::U)), Cube::try_from(center));
+
+  // The URF corner with two U stickers.
+  let mut corner = solved;
+  corner.0[9] = Face::U;
+  assert_eq!(Err(FaceletErr::ErrCorner(URF)), Cube::try_from(corner));
+
+  // The UR edge with two U stickers.
+  let mut edge = solved;
+  edge.0[10] = Face::U;
+  assert_eq!(Err(FaceletErr::ErrEdge(UR)), Cube::try_from(edge));
+
+  // A twisted URF corner.
+  let mut twist = solved;
+  twist.0[8] = Face::F;
+  twist.0[9] = Face::U;
+  twist.0[20] = Face::R;
+  assert_eq!(
+    Err(FaceletErr::ErrState(CubeStateErr::ErrCO)),
+    Cube::try_from(twist)
+  );
+
+  // A flipped UR edge.
+  let mut flip = solved;
+  flip.0.swap(5, 10);
+  assert_eq!(
+    Err(FaceletErr::ErrState(CubeStateErr::ErrEO)),
+    Cube::try_from(flip)
+  );
+
+  // Two swapped edges.
+  let mut swap = solved;
+  swap.0.swap(10, 19);
+  assert_eq!(
+    Err(FaceletErr::ErrState(CubeStateErr::ErrParity)),
+    Cube::try_from(swap)
+  );
+}
+
+#[test]
+fn facelet_str() {
+  let solved = "UUUUUUUUURRRRRRRRRFFFFFFFFFDDDDDDDDDLLLLLLLLLBBBBBBBBB";
+  assert_eq!(Ok(Cube::solved()), Cube::from_facelet_str(solved));
+  assert_eq!(solved, Cube::solved().to_facelet_str());
+
+  // An example from Kociemba's reference implementation.
+  let cube = Cube::from_facelet_str(
+    "DRLUUBFBRBLURRLRUBLRDDFDLFUFUFFDBRDUBRUFLLFDDBFLUBLRBD",
+  )
+  .unwrap();
+  let solution =
+    parse_algorithm("D2 R' D' F2 B D R2 D2 R' F2 D' F2 U' B2 L2 U2 D R2 U")
+      .unwrap();
+  assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+
+  let cube = Cube::solved().apply_algorithm(&invert_algorithm(&solution));
+  assert_eq!(
+    "DRLUUBFBRBLURRLRUBLRDDFDLFUFUFFDBRDUBRUFLLFDDBFLUBLRBD",
+    cube.to_facelet_str()
+  );
+
+  assert_eq!(Err(FaceletErr::ErrLength(3)), Cube::from_facelet_str("UUU"));
+  assert_eq!(
+    Err(FaceletErr::ErrFacelet(1)),
+    Cube::from_facelet_str(&format!("Ux{}", &solved[2..]))
+  );
+}
+
+#[test]
+fn ascii() {
+  let net = "      U U U
+      U U U
+      U U U
+L L L F F F R R R B B B
+L L L F F F R R R B B B
+L L L F F F R R R B B B
+      D D D
+      D D D
+      D D D
+";
+  assert_eq!(net, Cube::solved().to_ascii());
+  assert_eq!(net, Cube::solved().to_string());
+
+  let net = "      U U U
+      U U U
+      L L L
+L L D F F F U R R B B B
+L L D F F F U R R B B B
+L L D F F F U R R B B B
+      R R R
+      D D D
+      D D D
+";
+  assert_eq!(
+    net,
+    Cube::solved()
+      .apply_move(Move(Face::F, Turn::Clockwise))
+      .to_ascii()
+  );
+
+  let ansi = Cube::solved().to_ansi();
+  assert_eq!(9, ansi.lines().count());
+  assert_eq!(54, ansi.matches("\x1b[0m").count());
+}
+
+#[test]
+fn svg() {
+  let cube =
+    Cube::from_algorithm(&parse_algorithm("R U R' U R U2 R'").unwrap());
+  let net = cube.to_svg();
+  assert!(net.starts_with("<svg xmlns=\"http://www.w3.org/2000/svg\""));
+  assert!(net.ends_with("</svg>\n"));
+  assert_eq!(54, net.matches("<polygon").count());
+  assert_eq!(net, SvgRenderer::default().render(&cube));
+  assert_eq!(9, net.matches("#ffd500").count());
+
+  let isometric = SvgRenderer::new(SvgView::Isometric).render(&cube);
+  assert_eq!(27, isometric.matches("<polygon").count());
+  let last_layer = SvgRenderer::new(SvgView::LastLayer).render(&cube);
+  assert_eq!(21, last_layer.matches("<polygon").count());
+
+  // Only the U corners and the centers keep their colors.
+  let corners = SvgRenderer::new(SvgView::Net)
+    .with_highlighted_corners(&Face::U.adjacent_corners())
+    .render(&cube);
+  assert_eq!(54 - 12 - 6, corners.matches("#808080").count());
+  let pieces = SvgRenderer::new(SvgView::LastLayer)
+    .with_highlighted_corners(&Face::U.adjacent_corners())
+    .with_highlighted_edges(&Face::U.adjacent_edges())
+    .render(&cube);
+  assert_eq!(0, pieces.matches("#808080").count());
+
+  let japanese = SvgRenderer::new(SvgView::Net)
+    .with_scheme(ColorScheme::JAPANESE)
+    .with_sticker_size(10.0)
+    .render_facelets(&FaceletCube::solved());
+  assert_eq!(9, japanese.matches("#0051ba").count());
+  assert!(japanese.contains("width=\"120.67\" height=\"90.67\""));
+}
+
+#[test]
+fn color_schemes() {
+  let wca = ColorScheme::default();
+  assert_eq!(ColorScheme::WCA, wca);
+  assert_eq!(Color::Green, wca.color(Face::F));
+  assert_eq!(Face::D, wca.face(Color::Yellow));
+  assert_eq!(Face::D, ColorScheme::JAPANESE.face(Color::Blue));
+  for &color in &Color::ALL {
+    assert_eq!(Some(color), Color::from_letter(color.letter()));
+  }
+  for &face in &Face::ALL {
+    assert_eq!(face, wca.face(wca.color(face)));
+  }
+  assert_eq!(None, ColorScheme::new([Color::White; 6]));
+
+  let solved = "WWWWWWWWWRRRRRRRRRGGGGGGGGGYYYYYYYYYOOOOOOOOOBBBBBBBBB";
+  assert_eq!(solved, Cube::solved().to_color_str(&wca));
+  assert_eq!(Ok(Cube::solved()), Cube::from_color_str(solved, &wca));
+
+  // The same cube is read from the colors of any scheme.
+  let cube = Cube::from_algorithm(&parse_algorithm("R U F' L2 D B'").unwrap());
+  let custom = ColorScheme::new([
+    Color::Red,
+    Color::White,
+    Color::Blue,
+    Color::Orange,
+    Color::Green,
+    Color::Yellow,
+  ])
+  .unwrap();
+  for scheme in &[wca, ColorScheme::JAPANESE, custom] {
+    let colors = cube.to_color_str(scheme);
+    assert_eq!(Ok(cube), Cube::from_color_str(&colors, scheme));
+    let facelets = FaceletCube::from(&cube);
+    let from_colors =
+      FaceletCube::from_colors(&facelets.colors(scheme), scheme);
+    assert_eq!(facelets, from_colors);
+  }
+  assert_ne!(
+    cube.to_color_str(&wca),
+    cube.to_color_str(&ColorScheme::JAPANESE)
+  );
+  assert_eq!(cube.to_ansi(), cube.to_ansi_with(&wca));
+  assert_ne!(cube.to_ansi(), cube.to_ansi_with(&ColorScheme::JAPANESE));
+
+  assert_eq!(
+    Err(FaceletErr::ErrFacelet(0)),
+    Cube::from_color_str(&solved.replace('W', "U"), &wca)
+  );
+  assert_eq!(
+    Err(FaceletErr::ErrLength(3)),
+    Cube::from_color_str("WWW", &wca)
+  );
+}
+
+#[test]
+fn random_move_scramble() {
+  let mut rng = StdRng::seed_from_u64(0);
+  assert!(generate_random_move_scramble(0, &mut rng).is_empty());
+  for _ in 0..100 {
+    let scramble = generate_random_move_scramble(25, &mut rng);
+    assert_eq!(25, scramble.len());
+    for w in scramble.windows(2) {
+      assert_ne!(w[0].0, w[1].0);
+    }
+    for w in scramble.windows(3) {
+      assert!(!(w[0].0 == w[2].0 && w[0].0.is_opposite(w[1].0)));
+    }
+  }
+}
+
+#[test]
+fn random_state() {
+  let mut rng = StdRng::seed_from_u64(1573);
+  let cubes: Vec<Cube> = (0..100).map(|_| Cube::random(&mut rng)).collect();
+  for cube in &cubes {
+    assert!(cube.verify().is_ok());
+  }
+  assert_ne!(cubes[0], cubes[1]);
+}
+
+#[test]
+fn seeded_rng_is_reproducible() {
+  // These must never change, as scrambles are reproduced from their seeds.
+  let scramble = generate_random_move_scramble(25, &mut seeded_rng(7));
+  assert_eq!(
+    "U R B U2 B2 R L2 F2 L2 R' F2 D' R2 F D' R' U' R F B2 U F D2 R' B",
+    format_algorithm(&scramble)
+  );
+  let cube = Cube::random(&mut seeded_rng(7));
+  assert_eq!(
+    "LUDDURRFBRBFBRFRLLDDULFDDLUBBFFDFRLFBRFRLDDULLBURBUUUB",
+    cube.to_facelet_str()
+  );
+
+  let mut rng = seeded_rng(1574);
+  let a: Vec<u32> = (0..4).map(|_| rng.random()).collect();
+  let mut rng = seeded_rng(1574);
+  let b: Vec<u32> = (0..4).map(|_| rng.random()).collect();
+  assert_eq!(a, b);
+}
+
+#[test]
+fn symmetries() {
+  let syms: Vec<Symmetry> = Symmetry::all().collect();
+  assert_eq!(NUM_UD_SYMMETRIES, syms.len());
+  let identity = syms[0];
+  assert_eq!(identity, identity.inverse());
+
+  let mut rng = StdRng::seed_from_u64(0);
+  let scramble = generate_random_move_scramble(25, &mut rng);
+  let cube = Cube::solved().apply_algorithm(&scramble);
+  assert_eq!(cube, cube.conjugate(identity));
+
+  let faces = [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+  for &sym in &syms {
+    assert_eq!(sym, sym.inverse().inverse());
+    assert_eq!(Cube::solved(), Cube::solved().conjugate(sym));
+    assert_eq!(cube, cube.conjugate(sym).conjugate(sym.inverse()));
+
+    // Conjugating a whole algorithm is the same as conjugating each move.
+    let moves: Vec<Move> =
+      scramble.iter().map(|&m| sym.conjugate_move(m)).collect();
+    assert_eq!(cube.conjugate(sym), Cube::solved().apply_algorithm(&moves));
+
+    for &f in &faces {
+      let Move(face, turn) = sym.conjugate_move(Move(f, Turn::Clockwise));
+      // The UD axis is kept, and reflections turn the other way.
+      assert_eq!(
+        f == Face::U || f == Face::D,
+        face == Face::U || face == Face::D
+      );
+      let expected = if sym.is_reflection() {
+        Turn::Counter
+      } else {
+        Turn::Clockwise
+      };
+      assert_eq!(expected, turn);
+    }
+  }
+
+  // The scramble has no symmetry, so every conjugate is different.
+  for (i, &s1) in syms.iter().enumerate() {
+    for &s2 in &syms[i + 1..] {
+      assert_ne!(cube.conjugate(s1), cube.conjugate(s2));
+    }
+  }
+}
+
+#[test]
+fn all_symmetries() {
+  let syms: Vec<Symmetry> = Symmetry::all_48().collect();
+  assert_eq!(NUM_SYMMETRIES, syms.len());
+  assert_eq!(
+    Symmetry::all().collect::<Vec<_>>(),
+    syms[..NUM_UD_SYMMETRIES]
+  );
+  assert_eq!(
+    NUM_UD_SYMMETRIES,
+    syms.iter().filter(|s| s.keeps_ud_axis()).count()
+  );
+  assert_eq!(24, syms.iter().filter(|s| s.is_reflection()).count());
+
+  let mut rng = seeded_rng(1587);
+  let scramble = generate_random_move_scramble(25, &mut rng);
+  let cube = Cube::solved().apply_algorithm(&scramble);
+  for &sym in &syms {
+    assert_eq!(sym, sym.inverse().inverse());
+    let conjugated = cube.conjugate(sym);
+    assert!(conjugated.verify().is_ok());
+    assert_eq!(cube, conjugated.conjugate(sym.inverse()));
+    let moves: Vec<Move> =
+      scramble.iter().map(|&m| sym.conjugate_move(m)).collect();
+    assert_eq!(conjugated, Cube::solved().apply_algorithm(&moves));
+  }
+  let mut conjugates: Vec<Cube> =
+    syms.iter().map(|&sym| cube.conjugate(sym)).collect();
+  conjugates.sort();
+  conjugates.dedup();
+  assert_eq!(NUM_SYMMETRIES, conjugates.len());
+
+  // Every conjugate has the same canonical cube, which is the smallest.
+  let (canonical, sym) = cube.canonicalize();
+  assert_eq!(conjugates[0], canonical);
+  assert_eq!(canonical, cube.conjugate(sym));
+  for &s in &syms {
+    assert_eq!(canonical, cube.conjugate(s).canonicalize().0);
+  }
+  let (canonical_ud, sym) = cube.canonicalize_ud();
+  assert!(sym.keeps_ud_axis());
+  assert_eq!(canonical_ud, cube.conjugate(sym));
+  assert!(canonical <= canonical_ud);
+  assert_eq!((Cube::solved(), syms[0]), Cube::solved().canonicalize());
+}
+
+#[test]
+fn simplify_alg() {
+  let simplify = |s: &str| {
+    format_algorithm(&simplify_algorithm(&parse_algorithm(s).unwrap()))
+  };
+  assert_eq!("", simplify(""));
+  assert_eq!("U2", simplify("U U"));
+  assert_eq!("", simplify("R U U' R'"));
+  assert_eq!("U D", simplify("D U"));
+  assert_eq!("U2 D'", simplify("U D U2 D2 U'"));
+  assert_eq!("R' F", simplify("R U D2 D2 U' R2 F"));
+  assert_eq!("R L U", simplify("L R U"));
+  assert_eq!("U R U'", simplify("U R U'"));
+
+  // Simplifying never changes what the moves do, and leaves nothing to
+  // simplify.
+  let mut rng = StdRng::seed_from_u64(4);
+  let faces = [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+  for _ in 0..100 {
+    let moves: Vec<Move> = (0..20)
+      .map(|_| {
+        let face = faces[rng.random_range(0..6)];
+        Move(face, Turn::ALL[rng.random_range(0..3)])
+      })
+      .collect();
+    let simplified = simplify_algorithm(&moves);
+    assert_eq!(
+      Cube::solved().apply_algorithm(&moves),
+      Cube::solved().apply_algorithm(&simplified)
+    );
+    assert_eq!(simplified, simplify_algorithm(&simplified));
+    for w in simplified.windows(2) {
+      assert_ne!(w[0].0, w[1].0);
+    }
+  }
+}
+
+#[test]
+fn metrics() {
+  let moves = parse_algorithm("R U2 R' D2").unwrap();
+  assert_eq!(4, algorithm_length(&moves, Metric::Half));
+  assert_eq!(6, algorithm_length(&moves, Metric::Quarter));
+  assert_eq!(4, algorithm_length(&moves, Metric::Slice));
+  assert_eq!(4, algorithm_length(&moves, Metric::Execution));
+  assert_eq!(0, algorithm_length(&[], Metric::Quarter));
+  assert_eq!(Metric::Half, Metric::default());
+
+  let moves = parse_extended_algorithm("x R M' E2 y2 Rw2").unwrap();
+  assert_eq!(6, extended_algorithm_length(&moves, Metric::Half));
+  assert_eq!(9, extended_algorithm_length(&moves, Metric::Quarter));
+  assert_eq!(4, extended_algorithm_length(&moves, Metric::Slice));
+  assert_eq!(6, extended_algorithm_length(&moves, Metric::Execution));
+}
+
+#[test]
+fn parse_format_extended_alg() {
+  assert_eq!(
+    Ok(vec![
+      ExtendedMove::Face(Move(Face::R, Turn::Clockwise)),
+      ExtendedMove::Slice(Slice::M, Turn::Counter),
+      ExtendedMove::Slice(Slice::E, Turn::Half),
+      ExtendedMove::Slice(Slice::S, Turn::Clockwise),
+      ExtendedMove::Rotation(Axis::X, Turn::Clockwise),
+      ExtendedMove::Rotation(Axis::Y, Turn::Half),
+      ExtendedMove::Rotation(Axis::Z, Turn::Counter),
+    ]),
+    parse_extended_algorithm("R M' E2 S x y2 z'")
+  );
+  assert_eq!(
+    Err(ParseError::InvalidFace {
+      token: "X".to_string(),
+      position: 2
+    }),
+    parse_extended_algorithm("R X")
+  );
+  assert_eq!(
+    Err(ParseError::InvalidTurn {
+      token: "M3".to_string(),
+      position: 0
+    }),
+    parse_extended_algorithm("M3")
+  );
+  // Only the face turns are accepted by `parse_algorithm`.
+  assert!(parse_algorithm("M").is_err());
+
+  let alg = "x' R U' R D2 R' U R D2 R2 x M2 U M U2 M' U M2";
+  let moves = parse_extended_algorithm(alg).unwrap();
+  assert_eq!(alg, format_extended_algorithm(&moves));
+}
+
+#[test]
+fn rotations_and_slices() {
+  let expand = |s: &str| {
+    let moves = parse_extended_algorithm(s).unwrap();
+    let (moves, orientation) =
+      expand_algorithm(&moves, Orientation::identity());
+    (Cube::solved().apply_algorithm(&moves), orientation)
+  };
+  let rotated = |axis, turn| Orientation::identity().rotate(axis, turn);
+
+  // x, y and z move the centers like R, U and F.
+  let x = rotated(Axis::X, Turn::Clockwise);
+  assert_eq!(Face::F, x.center(Face::U));
+  assert_eq!(Face::R, x.center(Face::R));
+  let y = rotated(Axis::Y, Turn::Clockwise);
+  assert_eq!(Face::R, y.center(Face::F));
+  let z = rotated(Axis::Z, Turn::Clockwise);
+  assert_eq!(Face::L, z.center(Face::U));
+  for &axis in &[Axis::X, Axis::Y, Axis::Z] {
+    let half = rotated(axis, Turn::Half);
+    assert_eq!(Orientation::identity(), half.rotate(axis, Turn::Half));
+    assert_eq!(
+      Orientation::identity(),
+      rotated(axis, Turn::Clockwise).rotate(axis, Turn::Counter)
+    );
+  }
+
+  // A rotation only changes which face is turned.
+  assert_eq!(expand("F"), expand("x U x'"));
+  assert_eq!(expand("B"), expand("y R y'"));
+  assert_eq!(expand("L"), expand("z U z'"));
+  assert_eq!(expand("R U"), expand("x2 R D x2"));
+  assert_eq!((Cube::solved(), x), expand("R M' L'"));
+
+  // The middle layers turn like the faces next to them.
+  let pons = Cube::solved()
+    .apply_algorithm(&parse_algorithm("R2 L2 U2 D2 F2 B2").unwrap());
+  assert_eq!((pons, Orientation::identity()), expand("M2 E2 S2"));
+  assert_eq!(expand("R' L x"), expand("M'"));
+  assert_eq!(expand("U D' y'"), expand("E"));
+  assert_eq!(expand("F' B z"), expand("S"));
+
+  // H permutation, which swaps opposite edges of the U layer.
+  let (h, orientation) = expand("M2 U M2 U2 M2 U M2");
+  assert_eq!(Orientation::identity(), orientation);
+  let mut expected = Cube::solved();
+  expected.ep.swap(UR as usize, UL as usize);
+  expected.ep.swap(UF as usize, UB as usize);
+  assert_eq!(expected, h);
+
+  // Every move is undone by its inverse.
+  let mut moves = parse_extended_algorithm("R M' E2 S x y2 z' U").unwrap();
+  let inverse: Vec<ExtendedMove> =
+    moves.iter().rev().map(|m| m.inverse()).collect();
+  moves.extend(inverse);
+  let (moves, orientation) = expand_algorithm(&moves, Orientation::identity());
+  assert!(simplify_algorithm(&moves).is_empty());
+  assert_eq!(Orientation::identity(), orientation);
+}
+
+#[test]
+fn transforms() {
+  let parse = |s: &str| parse_extended_algorithm(s).unwrap();
+  let alg = |s: &str| parse_algorithm(s).unwrap();
+  let cube =
+    |moves: &[ExtendedMove]| Cube::solved().apply_extended_algorithm(moves);
+
+  // Mirroring across M turns a right-handed algorithm into a left-handed
+  // one, and mirroring twice gives the algorithm back.
+  let sune = alg("R U R' U R U2 R'");
+  assert_eq!(alg("L' U' L U' L' U2 L"), mirror_algorithm(&sune, Axis::X));
+  assert_eq!(alg("R' U' R U' R' U2 R"), mirror_algorithm(&sune, Axis::Z));
+  assert_eq!(alg("R' D' R D' R' D2 R"), mirror_algorithm(&sune, Axis::Y));
+  for &axis in &[Axis::X, Axis::Y, Axis::Z] {
+    assert_eq!(sune, mirror_algorithm(&mirror_algorithm(&sune, axis), axis));
+  }
+  // The mirror across M is the reflection between the L and R faces.
+  let lr2 = Symmetry::all().nth(1).unwrap();
+  let mut rng = seeded_rng(1586);
+  let scramble = generate_random_move_scramble(25, &mut rng);
+  let reflected: Vec<Move> =
+    scramble.iter().map(|&m| lr2.conjugate_move(m)).collect();
+  assert_eq!(reflected, mirror_algorithm(&scramble, Axis::X));
+
+  let extended = parse("Rw U R' M' E S2 x y' z2 F");
+  assert_eq!(
+    parse("Lw' U' L M' E' S2 x y z2 F'"),
+    mirror_extended_algorithm(&extended, Axis::X)
+  );
+  let expected = mirror_algorithm(
+    &expand_algorithm(&extended, Orientation::identity()).0,
+    Axis::X,
+  );
+  let (mirrored, _) = expand_algorithm(
+    &mirror_extended_algorithm(&extended, Axis::X),
+    Orientation::identity(),
+  );
+  assert_eq!(
+    Cube::solved().apply_algorithm(&expected),
+    Cube::solved().apply_algorithm(&mirrored)
+  );
+
+  // Rotating an algorithm removes the rotations around it.
+  assert_eq!(
+    alg("B U B'"),
+    rotate_algorithm(&alg("R U R'"), Axis::Y, Turn::Clockwise)
+  );
+  for &axis in &[Axis::X, Axis::Y, Axis::Z] {
+    for &turn in &Turn::ALL {
+      let mut conjugated = vec![ExtendedMove::Rotation(axis, turn)];
+      conjugated.extend(extended.iter().cloned());
+      conjugated.push(ExtendedMove::Rotation(axis, turn.inverse()));
+      let rotated = rotate_extended_algorithm(&extended, axis, turn);
+      assert_eq!(cube(&conjugated), cube(&rotated));
+
+      // The face turns are rotated the same way by both.
+      let faces: Vec<ExtendedMove> =
+        scramble.iter().map(|&m| m.into()).collect();
+      let rotated: Vec<ExtendedMove> = rotate_algorithm(&scramble, axis, turn)
+        .iter()
+        .map(|&m| m.into())
+        .collect();
+      assert_eq!(rotate_extended_algorithm(&faces, axis, turn), rotated);
+    }
+  }
+
+  // Removing the rotations keeps the middle layers and wide turns.
+  let (moves, orientation) = remove_rotations(&parse("y R U R' y' x M' U Rw"));
+  assert_eq!(parse("B U B' M' F Rw"), moves);
+  assert_eq!(
+    Orientation::identity().rotate(Axis::X, Turn::Clockwise),
+    orientation
+  );
+  assert_eq!(cube(&parse("y R U R' y' x M' U Rw")), cube(&moves));
+  let (moves, _) = remove_rotations(&extended);
+  assert!(moves
+    .iter()
+    .all(|m| !matches!(m, ExtendedMove::Rotation(..))));
+  assert_eq!(cube(&extended), cube(&moves));
+}
+
+#[test]
+fn wide_moves() {
+  assert_eq!(
+    Ok(vec![
+      ExtendedMove::Wide(Face::R, Turn::Clockwise),
+      ExtendedMove::Wide(Face::U, Turn::Half),
+      ExtendedMove::Wide(Face::F, Turn::Counter),
+      ExtendedMove::Wide(Face::L, Turn::Counter),
+      ExtendedMove::Wide(Face::D, Turn::Half),
+      ExtendedMove::Face(Move(Face::B, Turn::Clockwise)),
+    ]),
+    parse_extended_algorithm("Rw Uw2 Fw' l' d2 B")
+  );
+  assert_eq!(
+    Err(ParseError::InvalidTurn {
+      token: "Rw3".to_string(),
+      position: 0
+    }),
+    parse_extended_algorithm("Rw3")
+  );
+  let alg = "Rw U Rw' U' Lw2 Dw Bw' Fw";
+  let moves = parse_extended_algorithm(alg).unwrap();
+  assert_eq!(alg, format_extended_algorithm(&moves));
+
+  let expand = |s: &str| {
+    let moves = parse_extended_algorithm(s).unwrap();
+    expand_algorithm(&moves, Orientation::identity())
+  };
+  // A wide turn is the opposite face and a rotation.
+  assert_eq!(expand("L x"), expand("Rw"));
+  assert_eq!(expand("R x'"), expand("Lw"));
+  assert_eq!(expand("D y"), expand("Uw"));
+  assert_eq!(expand("U y'"), expand("Dw"));
+  assert_eq!(expand("B z"), expand("Fw"));
+  assert_eq!(expand("F z'"), expand("Bw"));
+  let (rm, orientation) = expand("R M'");
+  let (rw, wide_orientation) = expand("Rw2 Rw'");
+  assert_eq!(simplify_algorithm(&rm), simplify_algorithm(&rw));
+  assert_eq!(orientation, wide_orientation);
+
+  // The face turns after a wide turn are relative to the rotated centers.
+  let moves = parse_extended_algorithm("Rw U Rw' U' Rw' F Rw F'").unwrap();
+  let cube = Cube::solved().apply_extended_algorithm(&moves);
+  let faces = parse_algorithm("L F L' U' L' U L F'").unwrap();
+  assert_eq!(Cube::solved().apply_algorithm(&faces), cube);
+  let inverse: Vec<ExtendedMove> =
+    moves.iter().rev().map(|m| m.inverse()).collect();
+  assert_eq!(Cube::solved(), cube.apply_extended_algorithm(&inverse));
+}
+
+#[test]
+fn oriented_cube() {
+  let faces = [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+  let mut orientations = vec![];
+  for &up in &faces {
+    for &front in &faces {
+      match Orientation::new(up, front) {
+        Some(o) => {
+          assert_eq!((up, front), (o.up(), o.front()));
+          for &f in &faces {
+            assert_eq!(f, o.position(o.center(f)));
+          }
+          orientations.push(o);
+        }
+        None => assert!(up.is_opposite(front)),
+      }
+    }
+  }
+  assert_eq!(24, orientations.len());
+  assert_eq!(
+    Some(Orientation::identity()),
+    Orientation::new(Face::U, Face::F)
+  );
+
+  let x = OrientedCube::solved()
+    .apply_move(ExtendedMove::Rotation(Axis::X, Turn::Clockwise));
+  assert_eq!(Cube::solved(), x.cube());
+  assert_eq!(
+    (Face::F, Face::D),
+    (x.orientation().up(), x.orientation().front())
+  );
+
+  // Solve a scramble relative to the centers, and follow the solution
+  // without rotating the cube back.
+  let scramble = parse_extended_algorithm("R U x' F Rw2 M' D y L2 S").unwrap();
+  let scrambled = OrientedCube::solved().apply_algorithm(&scramble);
+  let (moves, _) = expand_algorithm(&scramble, Orientation::identity());
+  assert_eq!(Cube::solved().apply_algorithm(&moves), scrambled.cube());
+  let solution: Vec<ExtendedMove> = scrambled
+    .position_moves(&invert_algorithm(&moves))
+    .into_iter()
+    .map(ExtendedMove::from)
+    .collect();
+  let solved = scrambled.apply_algorithm(&solution);
+  assert_eq!(Cube::solved(), Cube::from(solved));
+  assert_eq!(scrambled.orientation(), solved.orientation());
+
+  let cube = Cube::solved().apply_algorithm(&moves);
+  assert_eq!(cube, Cube::from(OrientedCube::from(cube)));
+  assert_eq!(
+    Orientation::identity(),
+    OrientedCube::from(cube).orientation()
+  );
+}
+
+#[test]
+fn compose_inverse() {
+  let a = Cube::solved().apply_algorithm(&parse_algorithm("R U F'").unwrap());
+  let b = Cube::solved().apply_algorithm(&parse_algorithm("L2 D B").unwrap());
+  let ab = a.apply_algorithm(&parse_algorithm("L2 D B").unwrap());
+  assert_eq!(ab, a.compose(&b));
+  assert_eq!(ab, a * b);
+  assert_ne!(ab, b * a);
+  assert_eq!(a, Cube::solved() * a);
+  assert_eq!(a, a * Cube::solved());
+  assert_eq!((a * b) * ab, a * (b * ab));
+
+  assert_eq!(Cube::solved(), Cube::solved().inverse());
+  assert_eq!(Cube::solved(), a * a.inverse());
+  assert_eq!(Cube::solved(), a.inverse() * a);
+  assert_eq!(b.inverse() * a.inverse(), ab.inverse());
+  let inverse = invert_algorithm(&parse_algorithm("R U F'").unwrap());
+  assert_eq!(Cube::solved().apply_algorithm(&inverse), a.inverse());
+  assert!(ab.inverse().verify().is_ok());
+}
+
+#[test]
+fn from_algorithm() {
+  let sune = parse_algorithm("R U R' U R U2 R'").unwrap();
+  let cube = Cube::from_algorithm(&sune);
+  assert_eq!(Cube::solved().apply_algorithm(&sune), cube);
+  assert_eq!(cube, Cube::from(&sune[..]));
+  assert_eq!(Cube::solved(), Cube::from_algorithm(&[]));
+
+  // Applying the permutation is the same as applying the moves.
+  let start = Cube::from_algorithm(&parse_algorithm("F D2 L'").unwrap());
+  let mut moves = start;
+  let mut composed = start;
+  for i in 1..=6 {
+    moves = moves.apply_algorithm(&sune);
+    composed = composed * cube;
+    assert_eq!(moves, composed);
+    assert_eq!(i == 6, composed == start);
+  }
+}
+
+#[test]
+fn hash_ord() {
+  use std::collections::{BTreeSet, HashSet};
+  let moves = parse_algorithm("R U R' U' F2 D L'").unwrap();
+  let cubes: Vec<Cube> = (0..=moves.len())
+    .map(|i| Cube::from_algorithm(&moves[..i]))
+    .collect();
+  let hashed: HashSet<Cube> = cubes.iter().cloned().collect();
+  let ordered: BTreeSet<Cube> = cubes.iter().cloned().collect();
+  assert_eq!(cubes.len(), hashed.len());
+  assert_eq!(cubes.len(), ordered.len());
+  assert!(hashed.contains(&Cube::from_algorithm(&moves[..3])));
+
+  // The solved cube has the smallest coordinates.
+  assert_eq!(Some(&Cube::solved()), ordered.iter().next());
+  // Swapping the first two corners has corner permutation coordinate 1, and
+  // swapping the next two has 2, even though the first corner of the second
+  // is smaller.
+  let mut first = Cube::solved();
+  first.cp.swap(0, 1);
+  first.ep.swap(0, 1);
+  let mut second = Cube::solved();
+  second.cp.swap(1, 2);
+  second.ep.swap(0, 1);
+  assert!(first < second);
+  assert!(second.cp < first.cp);
+
+  // Cubes that are not solvable are only equal if their pieces are.
+  let mut twisted = Cube::solved();
+  twisted.co[7] = 1;
+  assert!(Cube::solved() < twisted);
+  let mut repeated = Cube::solved();
+  repeated.cp[1] = URF;
+  assert_eq!(0, perm_coord(&repeated.cp));
+  assert!(repeated < Cube::solved());
+}
+
+#[test]
+fn pack_unpack() {
+  assert_eq!(Ok(Cube::solved()), Cube::unpack(Cube::solved().pack()));
+  let mut rng = StdRng::seed_from_u64(1);
+  let mut packed = std::collections::HashSet::new();
+  for _ in 0..100 {
+    let scramble = generate_random_move_scramble(25, &mut rng);
+    let cube = Cube::from_algorithm(&scramble);
+    let p = cube.pack();
+    assert!(p < 1 << 100);
+    assert_eq!(Ok(cube), Cube::unpack(p));
+    assert_eq!(Ok(cube), Cube::unpack(p | 1 << 127));
+    packed.insert(p);
+  }
+  assert_eq!(100, packed.len());
+
+  // Corners are the lowest bits, and edges start after the orientations.
+  let solved = Cube::solved().pack();
+  assert_eq!(Err(CubeStateErr::ErrCP), Cube::unpack(solved ^ 1));
+  assert_eq!(Err(CubeStateErr::ErrCO), Cube::unpack(solved | 1 << 24));
+  assert_eq!(Err(CubeStateErr::ErrEP), Cube::unpack(solved | 0xf << 40));
+  assert_eq!(Err(CubeStateErr::ErrEO), Cube::unpack(solved | 1 << 88));
+}
+
+#[test]
+fn accessors_builder() {
+  let cube = Cube::from_algorithm(&parse_algorithm("R U2 F'").unwrap());
+  for (i, &c) in cube.cp.iter().enumerate() {
+    assert_eq!(c, cube.corner_at(Corner::from(i)));
+    assert_eq!(cube.co[i], cube.corner_orientation_at(Corner::from(i)));
+  }
+  for (i, &e) in cube.ep.iter().enumerate() {
+    assert_eq!(e, cube.edge_at(Edge::from(i)));
+    assert_eq!(cube.eo[i], cube.edge_orientation_at(Edge::from(i)));
+  }
+
+  assert_eq!(Ok(Cube::solved()), CubeBuilder::new().build());
+  assert_eq!(Ok(cube), CubeBuilder::from(cube).build());
+  let twisted = CubeBuilder::new()
+    .corner(URF, URF, 1)
+    .corner(UFL, UFL, 2)
+    .build()
+    .unwrap();
+  assert_eq!(1, twisted.corner_orientation_at(URF));
+  assert_eq!(2, twisted.corner_orientation_at(UFL));
+  let swapped = CubeBuilder::new()
+    .corner(URF, UFL, 0)
+    .corner(UFL, URF, 0)
+    .edge(UR, UF, 0)
+    .edge(UF, UR, 0)
+    .build()
+    .unwrap();
+  assert_eq!(UFL, swapped.corner_at(URF));
+  assert_eq!(UR, swapped.edge_at(UF));
+
+  // The cube is only checked once it is built.
+  let builder = CubeBuilder::new().edge(UF, UF, 1);
+  assert_eq!(Err(CubeStateErr::ErrEO), builder.build());
+  assert_eq!(Ok(Cube::solved()), builder.edge(UF, UF, 0).build());
+  let swapped_corners =
+    CubeBuilder::new().corner(URF, UFL, 0).corner(UFL, URF, 0);
+  assert_eq!(Err(CubeStateErr::ErrParity), swapped_corners.build());
+  let duplicated = CubeBuilder::new().edge(UR, UF, 0);
+  assert_eq!(Err(CubeStateErr::ErrEP), duplicated.build());
+}
+
+#[test]
+fn group_laws() {
+  let faces = [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+  let moves: Vec<Move> = faces
+    .iter()
+    .flat_map(|&f| Turn::ALL.iter().map(move |&turn| Move(f, turn)))
+    .collect();
+
+  // Every face turned 4 times is the identity.
+  for &f in &faces {
+    let quarter = Move(f, Turn::Clockwise);
+    assert_eq!(Cube::solved(), Cube::from_algorithm(&[quarter; 4]));
+  }
+
+  // (A B)' is B' A' for every pair of moves.
+  for &a in &moves {
+    for &b in &moves {
+      assert_eq!(
+        Cube::from_algorithm(&[a, b]).inverse(),
+        Cube::from_algorithm(&[b.inverse(), a.inverse()])
+      );
+    }
+  }
+
+  // The sexy move, A B A' B', has order 6 for every pair of adjacent faces.
+  for &a in &faces {
+    for &b in faces.iter().filter(|&&b| b != a && !b.is_opposite(a)) {
+      let sexy = Cube::from_algorithm(&[
+        Move(a, Turn::Clockwise),
+        Move(b, Turn::Clockwise),
+        Move(a, Turn::Counter),
+        Move(b, Turn::Counter),
+      ]);
+      let mut cube = Cube::solved();
+      for _ in 0..5 {
+        cube = cube.compose(&sexy);
+        assert_ne!(Cube::solved(), cube);
+      }
+      assert_eq!(Cube::solved(), cube.compose(&sexy));
+    }
+  }
+
+  assert_eq!(Ok(()), verify_move_tables());
+}
+
+#[test]
+fn corner_and_edge_states() {
+  let alg = parse_algorithm("R U F' D2 L B' R2 U'").unwrap();
+  let cube = Cube::from_algorithm(&alg);
+  assert_eq!(cube.corners(), CornerState::solved().apply_algorithm(&alg));
+  assert_eq!(cube.edges(), EdgeState::solved().apply_algorithm(&alg));
+  for &m in &alg {
+    assert_eq!(cube.apply_move(m).corners(), cube.corners().apply_move(m));
+    assert_eq!(cube.apply_move(m).edges(), cube.edges().apply_move(m));
+  }
+  assert_eq!(Ok(cube), Cube::from_pieces(cube.corners(), cube.edges()));
+
+  // The corners and edges are each valid, but do not form a solvable cube
+  // when only one of them has odd parity.
+  let quarter = Cube::from_algorithm(&[Move(Face::U, Turn::Clockwise)]);
+  assert!(quarter.corners().parity());
+  assert!(quarter.edges().parity());
+  assert_eq!(Ok(()), quarter.corners().verify());
+  assert_eq!(Ok(()), EdgeState::solved().verify());
+  assert_eq!(
+    Err(CubeStateErr::ErrParity),
+    Cube::from_pieces(quarter.corners(), EdgeState::solved())
+  );
+
+  let twisted = CornerState {
+    co: [1, 0, 0, 0, 0, 0, 0, 0],
+    ..CornerState::solved()
+  };
+  assert_eq!(Err(CubeStateErr::ErrCO), twisted.verify());
+  let duplicated = EdgeState {
+    ep: [UR; NUM_EDGES],
+    ..EdgeState::solved()
+  };
+  assert_eq!(Err(CubeStateErr::ErrEP), duplicated.verify());
+}
+
+#[test]
+fn f2l_slots() {
+  let cube = Cube::solved();
+  assert!(cube.is_cross_solved());
+  assert!(cube.is_f2l_solved());
+  assert!(cube.is_last_layer_oriented());
+  assert_eq!(F2LSlot::ALL.to_vec(), cube.solved_f2l_slots());
+
+  // R U R' takes out the FR pair, and puts the rest of the R layer back.
+  let cube = Cube::from_algorithm(&parse_algorithm("R U R'").unwrap());
+  assert!(cube.is_cross_solved());
+  let slots = vec![F2LSlot::FL, F2LSlot::BL, F2LSlot::BR];
+  assert_eq!(slots, cube.solved_f2l_slots());
+  assert!(!cube.is_f2l_solved());
+  assert!(cube.is_corner_solved(DLF) && !cube.is_corner_solved(DFR));
+  assert!(cube.is_edge_solved(FL) && !cube.is_edge_solved(FR));
+
+  // A sune only twists the last layer.
+  let sune = parse_algorithm("R U R' U R U2 R'").unwrap();
+  let cube = Cube::from_algorithm(&sune);
+  assert!(cube.is_f2l_solved());
+  assert!(!cube.is_last_layer_oriented());
+
+  let cube = Cube::from_algorithm(&parse_algorithm("F2").unwrap());
+  assert!(!cube.is_cross_solved());
+  assert_eq!(vec![F2LSlot::BL, F2LSlot::BR], cube.solved_f2l_slots());
+  assert_eq!(DLF, F2LSlot::FL.corner());
+  assert_eq!(BR, F2LSlot::BR.edge());
+}
+
+#[test]
+fn roux_blocks() {
+  let cube = Cube::solved();
+  assert!(RouxBlock::ALL.iter().all(|&b| cube.is_roux_block_solved(b)));
+
+  // U and R leave the first block alone.
+  let cube = Cube::from_algorithm(&parse_algorithm("R U R' U2 R").unwrap());
+  assert!(cube.is_roux_block_solved(RouxBlock::First));
+  assert!(!cube.is_roux_block_solved(RouxBlock::Second));
+
+  // An M slice turn moves the centers, so neither block is solved relative
+  // to them.
+  let m = parse_extended_algorithm("M").unwrap();
+  let cube = Cube::solved().apply_extended_algorithm(&m);
+  assert!(!cube.is_roux_block_solved(RouxBlock::First));
+  assert!(!cube.is_roux_block_solved(RouxBlock::Second));
+  assert_eq!([DLF, DBL], RouxBlock::First.corners());
+  assert_eq!([DR, FR, BR], RouxBlock::Second.edges());
+}
+
+#[test]
+fn bld_memo() {
+  let cube = Cube::solved();
+  assert_eq!(Vec::<char>::new(), cube.corner_memo(URF).letters);
+  assert_eq!(Vec::<char>::new(), cube.edge_memo(UF).letters);
+
+  let cube = Cube::from_algorithm(&parse_algorithm("U").unwrap());
+  let corners = cube.corner_memo(URF);
+  let edges = cube.edge_memo(UF);
+  assert_eq!(vec!['B', 'A', 'D'], corners.letters);
+  assert_eq!(vec!['B', 'A', 'D'], edges.letters);
+  assert!(corners.has_parity() && edges.has_parity());
+  assert_eq!(vec!["BA", "D"], corners.letter_pairs());
+
+  let cube = Cube::from_algorithm(&parse_algorithm("R").unwrap());
+  assert_eq!(vec!['K', 'W', 'Q'], cube.corner_memo(URF).letters);
+  // The buffer is solved, so the edges start by breaking into a new cycle.
+  let edges = cube.edge_memo(UF);
+  assert_eq!(vec!['B', 'J', 'V', 'T', 'B'], edges.letters);
+  assert_eq!(vec!["BJ", "VT", "B"], edges.letter_pairs());
+
+  // Pieces twisted or flipped in place are listed, except for the buffer.
+  let cube = Cube::new(
+    [URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB],
+    [1, 2, 0, 0, 0, 0, 0, 0],
+    [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
+    [0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0],
+  );
+  let corners = cube.corner_memo(URF);
+  assert!(corners.letters.is_empty());
+  assert_eq!(vec![(UFL, 2)], corners.misoriented);
+  let edges = cube.edge_memo(UR);
+  assert!(edges.letters.is_empty());
+  assert_eq!(vec![(UF, 1), (FR, 1)], edges.misoriented);
+  assert_eq!('A', corner_letter(ULB, 0));
+  assert_eq!('J', edge_letter(FR, 0));
+
+  let mut rng = StdRng::seed_from_u64(1566);
+  for _ in 0..100 {
+    let cube =
+      Cube::from_algorithm(&generate_random_move_scramble(25, &mut rng));
+    assert_eq!(
+      cube.corner_memo(URF).has_parity(),
+      cube.edge_memo(UF).has_parity()
+    );
+  }
+}
+
+#[test]
+fn cycles() {
+  assert!(effect_of(&[]).is_identity());
+  assert_eq!("", effect_of(&[]).to_string());
+
+  let r = effect_of(&parse_algorithm("R").unwrap());
+  assert_eq!(
+    vec![PieceCycle {
+      positions: vec![URF, UBR, DRB, DFR],
+      orientation: 0,
+    }],
+    r.corners
+  );
+  assert_eq!(vec![UR, BR, DR, FR], r.edges[0].positions);
+  assert_eq!("(URF UBR DRB DFR) (UR BR DR FR)", r.to_string());
+
+  let t_perm = parse_algorithm("R U R' U' R' F R2 U' R' U' R U R' F'").unwrap();
+  assert_eq!("(URF UBR) (UR UL)", effect_of(&t_perm).to_string());
+
+  // Pieces twisted or flipped in place are cycles of one position.
+  let cube = Cube::new(
+    [URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB],
+    [1, 2, 0, 0, 0, 0, 0, 0],
+    [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
+    [0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0],
+  );
+  assert_eq!(
+    "(URF)+ (UFL)- (UF)+ (FR)+",
+    cube.cycle_structure().to_string()
+  );
+  assert_eq!(1, cube.cycle_structure().corners[0].len());
+
+  let solution = invert_algorithm(&t_perm);
+  assert!(solves(&Cube::from_algorithm(&t_perm), &solution));
+  assert!(solves(&Cube::from_algorithm(&t_perm), &t_perm));
+  assert!(!solves(&Cube::from_algorithm(&t_perm), &solution[1..]));
+}
+
+#[test]
+fn order() {
+  let order =
+    |alg| Cube::from_algorithm(&parse_algorithm(alg).unwrap()).order();
+  assert_eq!(1, Cube::solved().order());
+  assert_eq!(4, order("R"));
+  assert_eq!(2, order("R2"));
+  assert_eq!(6, order("R U R' U'"));
+  assert_eq!(2, order("R U R' U' R' F R2 U' R' U' R U R' F'"));
+  assert_eq!(6, order("R U R' U R U2 R'"));
+  assert_eq!(105, order("R U"));
+  assert_eq!(1260, order("R U2 D' B D'"));
+
+  // A corner twisted in place takes three turns, and a flipped edge two.
+  let cube = Cube::new(
+    [URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB],
+    [1, 2, 0, 0, 0, 0, 0, 0],
+    [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
+    [0, 1, 0, 0, 0, 0, 0, 0, 1, 0, 0, 0],
+  );
+  assert_eq!(6, cube.order());
+  let r_u = Cube::from_algorithm(&parse_algorithm("R U").unwrap());
+  let mut repeated = r_u;
+  for _ in 1..r_u.order() {
+    repeated = repeated.compose(&r_u);
+  }
+  assert_eq!(Cube::solved(), repeated);
+}
+
+#[test]
+fn insertions() {
+  let algorithms = generate_insertion_algorithms(3, 1, 1);
+  let a9 =
+    Cube::from_algorithm(&parse_algorithm("R U R' D R U' R' D'").unwrap());
+  assert!(algorithms.iter().any(|a| Cube::from_algorithm(a) == a9));
+  let mut corners = None;
+  let mut edges = None;
+  for algorithm in &algorithms {
+    assert!(algorithm.len() <= 10);
+    let cube = Cube::from_algorithm(algorithm);
+    let unsolved = |pieces: &[bool]| pieces.iter().filter(|&&s| !s).count();
+    let c: Vec<bool> = (0..NUM_CORNERS)
+      .map(|i| cube.is_corner_solved(Corner::from(i)))
+      .collect();
+    let e: Vec<bool> = (0..NUM_EDGES)
+      .map(|i| cube.is_edge_solved(Edge::from(i)))
+      .collect();
+    match (unsolved(&c), unsolved(&e)) {
+      (3, 0) => corners = corners.or(Some(algorithm)),
+      (0, 3) => edges = edges.or(Some(algorithm)),
+      (4, 0) | (0, 4) => {}
+      pieces => panic!("{:?} moves {:?} pieces", algorithm, pieces),
+    }
+  }
+  let (corners, edges) = (corners.unwrap(), edges.unwrap());
+
+  // A skeleton that leaves a corner 3-cycle.
+  let scramble = Cube::from_algorithm(
+    &parse_algorithm("R U R' D R U' R' D' F2 L B'").unwrap(),
+  );
+  let skeleton = parse_algorithm("B L' F2").unwrap();
+  let solution = find_insertions(&scramble, &skeleton, &algorithms, 1).unwrap();
+  assert_eq!(1, solution.insertions.len());
+  assert!(solution.moves.len() <= skeleton.len() + 8);
+  assert_eq!(Cube::solved(), scramble.apply_algorithm(&solution.moves));
+  assert_eq!(None, find_insertions(&scramble, &skeleton, &algorithms, 0));
+
+  // A finished skeleton needs no insertions.
+  let scramble = Cube::from_algorithm(&invert_algorithm(&skeleton));
+  let solution = find_insertions(&scramble, &skeleton, &[], 0).unwrap();
+  assert!(solution.insertions.is_empty());
+  assert_eq!(skeleton, solution.moves);
+
+  // A skeleton that leaves a corner and an edge 3-cycle needs both.
+  let skeleton = parse_algorithm("R U F").unwrap();
+  let mut moves = skeleton.clone();
+  moves.extend(corners);
+  moves.extend(edges);
+  let scramble = Cube::from_algorithm(&moves).inverse();
+  assert_eq!(None, find_insertions(&scramble, &skeleton, &algorithms, 1));
+  let solution = find_insertions(&scramble, &skeleton, &algorithms, 2).unwrap();
+  assert_eq!(2, solution.insertions.len());
+  assert!(solution.moves.len() <= moves.len());
+  assert_eq!(Cube::solved(), scramble.apply_algorithm(&solution.moves));
+}
+
+#[test]
+fn cube2() {
+  let cube = Cube2::solved();
+  assert!(cube.is_solved());
+  assert_eq!(Cube2::from(Cube::solved()), cube);
+
+  // The corners move like the corners of a 3x3x3 cube.
+  let alg = parse_algorithm("R U R' F2 D' L B2").unwrap();
+  let cube = Cube2::from_algorithm(&alg);
+  assert_eq!(Cube2::from(Cube::from_algorithm(&alg)), cube);
+  assert!(cube.verify().is_ok());
+  assert!(!cube.is_solved());
+  assert_eq!(Cube2::solved(), cube.compose(&cube.inverse()));
+
+  // Turning opposite faces the same way turns the whole cube.
+  let cube = Cube2::from_algorithm(&parse_algorithm("R L'").unwrap());
+  assert!(cube.is_solved());
+  assert_ne!(Cube2::solved(), cube);
+  let cube = Cube2::from_algorithm(&parse_algorithm("D").unwrap());
+  assert_eq!(
+    Cube2::from_algorithm(&parse_algorithm("U").unwrap()),
+    cube.normalized()
+  );
+
+  // Any permutation of the corners can be solved, unlike on a 3x3x3 cube.
+  let swap =
+    Cube2::try_new([UFL, URF, ULB, UBR, DFR, DLF, DBL, DRB], [0; NUM_CORNERS]);
+  assert!(swap.is_ok());
+  assert_eq!(
+    Err(CubeStateErr::ErrCO),
+    Cube2::try_new(
+      [URF, UFL, ULB, UBR, DFR, DLF, DBL, DRB],
+      [1, 0, 0, 0, 0, 0, 0, 0]
+    )
+  );
+}
+
+#[test]
+fn pyraminx() {
+  let alg = parse_pyraminx_algorithm("R U' L' U R' B l b'").unwrap();
+  let formatted: Vec<String> = alg.iter().map(|m| m.to_string()).collect();
+  assert_eq!("R U' L' U R' B l b'", formatted.join(" "));
+  assert!(parse_pyraminx_algorithm("U2").is_err());
+  assert!(parse_pyraminx_algorithm("F").is_err());
+
+  let pyraminx = Pyraminx::from_algorithm(&alg);
+  assert!(pyraminx.verify().is_ok());
+  assert!(!PermutationPuzzle::is_solved(&pyraminx));
+  let inverse: Vec<PyraminxMove> =
+    alg.iter().rev().map(|m| m.inverse()).collect();
+  assert_eq!(Pyraminx::solved(), pyraminx.apply_algorithm(&inverse));
+  assert_eq!([0; 4], pyraminx.without_tips().tips);
+
+  // A layer turns its tip, its center and the three edges next to it.
+  let u = Pyraminx::from_algorithm(&parse_pyraminx_algorithm("U").unwrap());
+  assert_eq!([1, 2, 0, 3, 4, 5], u.ep);
+  assert_eq!([1, 0, 0, 0], u.centers);
+  assert_eq!([1, 0, 0, 0], u.tips);
+  let u3 = parse_pyraminx_algorithm("U U U").unwrap();
+  assert_eq!(Pyraminx::solved(), Pyraminx::from_algorithm(&u3));
+
+  let mut swapped = Pyraminx::solved();
+  swapped.ep.swap(0, 1);
+  assert_eq!(Err(CubeStateErr::ErrEP), swapped.verify());
+
+  // Every puzzle can be used through `PermutationPuzzle`.
+  fn num_moves<P: PermutationPuzzle>() -> usize {
+    P::moves().len()
+  }
+  assert_eq!(18, num_moves::<Cube>());
+  assert_eq!(18, num_moves::<Cube2>());
+  assert_eq!(16, num_moves::<Pyraminx>());
+  let rotated = Cube2::from_algorithm(&parse_algorithm("R L'").unwrap());
+  assert!(PermutationPuzzle::is_solved(&rotated));
+}
+
+// A GAN move packet with `count` and the face and direction numbers of
+// `moves`, newest first.
+fn gan_packet(count: u8, moves: &[u8]) -> Vec<u8> {
+  let mut fields = vec![(2, 4), (u32::from(count), 8)];
+  fields.extend(moves.iter().map(|&m| (u32::from(m), 5)));
+  let mut packet = vec![0; 20];
+  let mut bit = 0;
+  for (value, len) in fields {
+    for i in (0..len).rev() {
+      packet[bit / 8] |= (((value >> i) & 1) as u8) << (7 - bit % 8);
+      bit += 1;
+    }
+  }
+  packet
+}
+
+#[test]
+fn smart_cubes() {
+  // Byte 16 has the last move, here R.
+  let mut packet = [0u8; 20];
+  packet[16] = 0x51;
+  let mut giiker = SmartCube::new(GiikerDecoder::new());
+  assert_eq!(
+    parse_algorithm("R").unwrap(),
+    giiker.receive(&packet).unwrap()
+  );
+  packet[16] = 0x53;
+  assert_eq!(
+    parse_algorithm("R'").unwrap(),
+    giiker.receive(&packet).unwrap()
+  );
+  assert!(giiker.is_solved());
+  assert_eq!(parse_algorithm("R R'").unwrap(), giiker.moves());
+  packet[16] = 0x72;
+  assert_eq!(Err(PacketError::InvalidFace(7)), giiker.receive(&packet));
+  assert_eq!(
+    Err(PacketError::Length {
+      expected: 20,
+      found: 3
+    }),
+    giiker.receive(&[0; 3])
+  );
+
+  // The same U2, obfuscated with both offsets 0, which adds twice the key.
+  let mut obfuscated = [0u8; 20];
+  obfuscated[16] = 0x42u8.wrapping_sub(2 * 93);
+  obfuscated[18] = 0xa7;
+  giiker.receive(&obfuscated).unwrap();
+  assert_eq!(
+    Cube::from_algorithm(&parse_algorithm("U2").unwrap()),
+    *giiker.cube()
+  );
+
+  // GAN faces are numbered in the order U R F D L B, with the lowest bit for
+  // counter-clockwise turns.
+  let mut gan = SmartCube::new(GanDecoder::new());
+  let moves = parse_algorithm("U R'").unwrap();
+  assert_eq!(moves[1..], *gan.receive(&gan_packet(10, &[3, 0])).unwrap());
+  // A repeated count has no new moves, and lost packets are recovered. Half
+  // turns are sent as two quarter turns.
+  assert!(gan.receive(&gan_packet(10, &[3, 0])).unwrap().is_empty());
+  let moves = parse_algorithm("F D D").unwrap();
+  assert_eq!(moves, gan.receive(&gan_packet(13, &[6, 6, 4, 3])).unwrap());
+  assert_eq!(parse_algorithm("R' F D D").unwrap(), gan.moves());
+  let moves = parse_algorithm("L B'").unwrap();
+  assert_eq!(moves, gan.receive(&gan_packet(15, &[11, 8])).unwrap());
+  // Other packets, such as the gyroscope, are ignored.
+  let mut gyro = gan_packet(0, &[]);
+  gyro[0] = 0x10;
+  assert!(gan.receive(&gyro).unwrap().is_empty());
+  // A packet only holds 7 moves, so a gap of 8 loses moves. The decoder
+  // resyncs to the count of that packet.
+  assert_eq!(
+    Err(PacketError::MissedMoves(8)),
+    gan.receive(&gan_packet(23, &[0; 7]))
+  );
+  let moves = parse_algorithm("U").unwrap();
+  assert_eq!(moves, gan.receive(&gan_packet(24, &[0; 7])).unwrap());
+
+  gan.reset();
+  assert!(gan.is_solved() && gan.moves().is_empty());
+}
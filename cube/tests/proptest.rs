@@ -0,0 +1,50 @@
+#![cfg(feature = "proptest")]
+
+extern crate cube;
+extern crate proptest;
+
+use cube::*;
+use proptest::prelude::*;
+
+proptest! {
+  #[test]
+  fn arbitrary_cubes_are_solvable(cube: Cube) {
+    prop_assert!(cube.verify().is_ok());
+  }
+
+  #[test]
+  fn apply_inverse(cube: Cube, moves: Vec<Move>) {
+    let turned = cube.apply_algorithm(&moves);
+    prop_assert_eq!(cube, turned.apply_algorithm(&invert_algorithm(&moves)));
+  }
+
+  #[test]
+  fn compose_inverse(cube: Cube) {
+    prop_assert_eq!(Cube::solved(), cube.compose(&cube.inverse()));
+    prop_assert_eq!(Cube::solved(), cube.inverse().compose(&cube));
+  }
+
+  #[test]
+  fn algorithm_is_composition(cube: Cube, moves: Vec<Move>) {
+    prop_assert_eq!(
+      cube.apply_algorithm(&moves),
+      cube.compose(&Cube::from_algorithm(&moves))
+    );
+  }
+
+  #[test]
+  fn facelets_round_trip(cube: Cube) {
+    prop_assert_eq!(Ok(cube), Cube::from_facelet_str(&cube.to_facelet_str()));
+  }
+
+  #[test]
+  fn pack_round_trip(cube: Cube) {
+    prop_assert_eq!(Ok(cube), Cube::unpack(cube.pack()));
+  }
+
+  #[test]
+  fn notation_round_trip(moves: Vec<Move>) {
+    let parsed = parse_algorithm(&format_algorithm(&moves));
+    prop_assert_eq!(Ok(moves), parsed);
+  }
+}
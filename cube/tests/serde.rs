@@ -0,0 +1,30 @@
+#![cfg(feature = "serde")]
+
+extern crate cube;
+extern crate serde_json;
+
+use cube::*;
+
+#[test]
+fn round_trip() {
+  let alg = parse_algorithm("R U R' U' F2 D L' B").unwrap();
+  let cube = Cube::solved().apply_algorithm(&alg);
+  let json = serde_json::to_string(&cube).unwrap();
+  assert_eq!(cube, serde_json::from_str::<Cube>(&json).unwrap());
+
+  let json = serde_json::to_string(&alg).unwrap();
+  assert_eq!(alg, serde_json::from_str::<Vec<Move>>(&json).unwrap());
+}
+
+#[test]
+fn unsolvable() {
+  // A cube with a single twisted corner.
+  let mut twisted = Cube::solved();
+  twisted.co[0] = 1;
+  let json = serde_json::to_string(&UncheckedCube(twisted)).unwrap();
+  assert!(serde_json::from_str::<Cube>(&json).is_err());
+  assert_eq!(
+    UncheckedCube(twisted),
+    serde_json::from_str::<UncheckedCube>(&json).unwrap()
+  );
+}
@@ -0,0 +1,30 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate cube;
+
+use cube::{
+  format_algorithm, format_extended_algorithm, parse_algorithm,
+  parse_extended_algorithm, Cube,
+};
+use std::str;
+
+// Parse any string as an algorithm, and check that the moves of anything
+// that parses are read back from their formatted algorithm.
+fuzz_target!(|data: &[u8]| {
+  let s = match str::from_utf8(data) {
+    Ok(s) => s,
+    Err(_) => return,
+  };
+  if let Ok(moves) = parse_algorithm(s) {
+    assert_eq!(Ok(moves.clone()), parse_algorithm(&format_algorithm(&moves)));
+    // Every face turn is also an extended move.
+    assert!(parse_extended_algorithm(s).is_ok());
+  }
+  if let Ok(moves) = parse_extended_algorithm(s) {
+    let formatted = format_extended_algorithm(&moves);
+    assert_eq!(Ok(moves.clone()), parse_extended_algorithm(&formatted));
+    let cube = Cube::solved().apply_extended_algorithm(&moves);
+    assert!(cube.verify().is_ok());
+  }
+});
@@ -0,0 +1,22 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate cube;
+extern crate kociemba;
+
+use cube::Cube;
+use std::str;
+
+// Parse any string as facelets, and check that anything that parses is
+// formatted back the same and can be solved.
+fuzz_target!(|data: &[u8]| {
+  let s = match str::from_utf8(data) {
+    Ok(s) => s,
+    Err(_) => return,
+  };
+  if let Ok(cube) = Cube::from_facelet_str(s) {
+    assert_eq!(s, cube.to_facelet_str());
+    let solution = kociemba::solve(&cube);
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+  }
+});
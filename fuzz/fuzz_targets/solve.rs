@@ -0,0 +1,16 @@
+#![no_main]
+#[macro_use]
+extern crate libfuzzer_sys;
+extern crate cube;
+extern crate kociemba;
+
+use cube::{seeded_rng, Cube};
+
+// Solve the random state of `seed` with the default `SolverConfig`, which
+// always finds a solution of at most 30 moves.
+fuzz_target!(|seed: u64| {
+  let cube = Cube::random(&mut seeded_rng(seed));
+  let solution = kociemba::solve(&cube);
+  assert!(solution.len() <= 30);
+  assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+});
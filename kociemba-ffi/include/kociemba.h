@@ -0,0 +1,63 @@
+#ifndef KOCIEMBA_H
+#define KOCIEMBA_H
+
+/* Generated by cbindgen from src/lib.rs, do not edit. */
+
+#include <stddef.h>
+
+// The function succeeded.
+#define KOCIEMBA_OK 0
+
+// A pointer was null or a string was not valid UTF-8.
+#define KOCIEMBA_ERR_ARGUMENT -1
+
+// The facelets are not a valid cube.
+#define KOCIEMBA_ERR_FACELETS -2
+
+// The solution does not fit in the output buffer.
+#define KOCIEMBA_ERR_BUFFER -3
+
+// The tables could not be loaded.
+#define KOCIEMBA_ERR_TABLES -4
+
+// The solver panicked, which is a bug.
+#define KOCIEMBA_ERR_PANIC -5
+
+// A buffer of this many bytes fits any solution and its terminating NUL:
+// 30 moves of at most 2 characters, each followed by a space or the NUL.
+#define KOCIEMBA_MAX_SOLUTION_LEN 90
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Build the tables, which takes a few seconds. Otherwise the first call to
+// `kociemba_solve` builds them.
+int kociemba_init(void);
+
+// Load the tables from the file at `path`, saved by `save_tables`, instead
+// of building them.
+//
+// # Safety
+//
+// `path` must be null or a NUL-terminated string.
+int kociemba_init_from_file(const char *path);
+
+// Free the tables. A later `kociemba_solve` builds them again.
+void kociemba_cleanup(void);
+
+// Solve the cube given by `facelets`, as read by `Cube::from_facelet_str`,
+// writing the solution in the standard notation to `out` as a NUL-terminated
+// string of at most `out_len` bytes.
+//
+// # Safety
+//
+// `facelets` must be null or a NUL-terminated string, and `out` must be
+// null or point to at least `out_len` writable bytes.
+int kociemba_solve(const char *facelets, char *out, size_t out_len);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* KOCIEMBA_H */
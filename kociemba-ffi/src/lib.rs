@@ -0,0 +1,187 @@
+//! The C interface of the solver, declared in `include/kociemba.h`.
+//!
+//! Every function catches panics, so that they never unwind into C, and
+//! reports failures with the negative `KOCIEMBA_ERR_*` codes.
+
+extern crate cube;
+extern crate kociemba;
+
+use cube::{format_algorithm, Cube};
+use kociemba::{load_tables, two_phase_with_config, SolverConfig, Tables};
+use std::ffi::CStr;
+use std::os::raw::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::sync::{Arc, Mutex};
+
+/// The function succeeded.
+pub const KOCIEMBA_OK: c_int = 0;
+/// A pointer was null or a string was not valid UTF-8.
+pub const KOCIEMBA_ERR_ARGUMENT: c_int = -1;
+/// The facelets are not a valid cube.
+pub const KOCIEMBA_ERR_FACELETS: c_int = -2;
+/// The solution does not fit in the output buffer.
+pub const KOCIEMBA_ERR_BUFFER: c_int = -3;
+/// The tables could not be loaded.
+pub const KOCIEMBA_ERR_TABLES: c_int = -4;
+/// The solver panicked, which is a bug.
+pub const KOCIEMBA_ERR_PANIC: c_int = -5;
+
+/// A buffer of this many bytes fits any solution and its terminating NUL:
+/// 30 moves of at most 2 characters, each followed by a space or the NUL.
+pub const KOCIEMBA_MAX_SOLUTION_LEN: usize = 90;
+
+// The tables that the functions solve with, which `kociemba_cleanup` frees.
+// Solves in progress keep their own reference to them.
+static TABLES: Mutex<Option<Arc<Tables>>> = Mutex::new(None);
+
+// Replace the tables, ignoring a poisoned lock, since the tables are only
+// ever replaced whole.
+fn set_tables(tables: Option<Arc<Tables>>) {
+  *TABLES.lock().unwrap_or_else(|e| e.into_inner()) = tables;
+}
+
+// The tables, which are built if there are none.
+fn tables() -> Arc<Tables> {
+  let mut tables = TABLES.lock().unwrap_or_else(|e| e.into_inner());
+  tables
+    .get_or_insert_with(|| Arc::new(Tables::new()))
+    .clone()
+}
+
+// Run `f`, returning `KOCIEMBA_ERR_PANIC` if it panics.
+fn catch_panic(f: impl FnOnce() -> c_int) -> c_int {
+  panic::catch_unwind(AssertUnwindSafe(f)).unwrap_or(KOCIEMBA_ERR_PANIC)
+}
+
+// The UTF-8 string at `s`, or `None` if it is null or not UTF-8.
+unsafe fn to_str<'a>(s: *const c_char) -> Option<&'a str> {
+  if s.is_null() {
+    return None;
+  }
+  CStr::from_ptr(s).to_str().ok()
+}
+
+/// Build the tables, which takes a few seconds. Otherwise the first call to
+/// `kociemba_solve` builds them.
+#[no_mangle]
+pub extern "C" fn kociemba_init() -> c_int {
+  catch_panic(|| {
+    set_tables(Some(Arc::new(Tables::new())));
+    KOCIEMBA_OK
+  })
+}
+
+/// Load the tables from the file at `path`, saved by `save_tables`, instead
+/// of building them.
+///
+/// # Safety
+///
+/// `path` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn kociemba_init_from_file(path: *const c_char) -> c_int {
+  catch_panic(|| {
+    let path = match to_str(path) {
+      Some(path) => path,
+      None => return KOCIEMBA_ERR_ARGUMENT,
+    };
+    match load_tables(path) {
+      Ok(tables) => {
+        set_tables(Some(Arc::new(tables)));
+        KOCIEMBA_OK
+      }
+      Err(_) => KOCIEMBA_ERR_TABLES,
+    }
+  })
+}
+
+/// Free the tables. A later `kociemba_solve` builds them again.
+#[no_mangle]
+pub extern "C" fn kociemba_cleanup() {
+  catch_panic(|| {
+    set_tables(None);
+    KOCIEMBA_OK
+  });
+}
+
+/// Solve the cube given by `facelets`, as read by `Cube::from_facelet_str`,
+/// writing the solution in the standard notation to `out` as a NUL-terminated
+/// string of at most `out_len` bytes.
+///
+/// # Safety
+///
+/// `facelets` must be null or a NUL-terminated string, and `out` must be
+/// null or point to at least `out_len` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn kociemba_solve(
+  facelets: *const c_char,
+  out: *mut c_char,
+  out_len: usize,
+) -> c_int {
+  catch_panic(|| {
+    let facelets = match to_str(facelets) {
+      Some(facelets) if !out.is_null() => facelets,
+      _ => return KOCIEMBA_ERR_ARGUMENT,
+    };
+    let cube = match Cube::from_facelet_str(facelets) {
+      Ok(cube) => cube,
+      Err(_) => return KOCIEMBA_ERR_FACELETS,
+    };
+    let tables = tables();
+    let (tables0, tables1) = (tables.phase0(), tables.phase1());
+    let solution = two_phase_with_config(
+      &cube,
+      &tables0,
+      &tables1,
+      &SolverConfig::default(),
+    )
+    .expect("the two-phase search is exhaustive");
+    let solution = format_algorithm(&solution);
+    if solution.len() >= out_len {
+      return KOCIEMBA_ERR_BUFFER;
+    }
+    ptr::copy_nonoverlapping(solution.as_ptr(), out as *mut u8, solution.len());
+    *out.add(solution.len()) = 0;
+    KOCIEMBA_OK
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, Face, Move, Turn};
+  use std::ffi::CString;
+
+  #[test]
+  fn solve() {
+    set_tables(Some(Tables::shared()));
+    let cube = Cube::from_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Half),
+      Move(Face::F, Turn::Counter),
+    ]);
+    let facelets = CString::new(cube.to_facelet_str()).unwrap();
+    let mut out = [0 as c_char; KOCIEMBA_MAX_SOLUTION_LEN];
+    let result =
+      unsafe { kociemba_solve(facelets.as_ptr(), out.as_mut_ptr(), out.len()) };
+    assert_eq!(KOCIEMBA_OK, result);
+    let solution = unsafe { CStr::from_ptr(out.as_ptr()) }.to_str().unwrap();
+    let solution = parse_algorithm(solution).unwrap();
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+
+    let result =
+      unsafe { kociemba_solve(facelets.as_ptr(), out.as_mut_ptr(), 3) };
+    assert_eq!(KOCIEMBA_ERR_BUFFER, result);
+    let invalid = CString::new("UUU").unwrap();
+    let result =
+      unsafe { kociemba_solve(invalid.as_ptr(), out.as_mut_ptr(), out.len()) };
+    assert_eq!(KOCIEMBA_ERR_FACELETS, result);
+    let result = unsafe { kociemba_solve(ptr::null(), out.as_mut_ptr(), 0) };
+    assert_eq!(KOCIEMBA_ERR_ARGUMENT, result);
+
+    let missing = CString::new("/nonexistent/kociemba.tables").unwrap();
+    let result = unsafe { kociemba_init_from_file(missing.as_ptr()) };
+    assert_eq!(KOCIEMBA_ERR_TABLES, result);
+    kociemba_cleanup();
+  }
+}
@@ -0,0 +1,88 @@
+#[macro_use]
+extern crate criterion;
+extern crate cube;
+extern crate kociemba;
+
+use criterion::{black_box, Criterion};
+use cube::{parse_algorithm, Cube};
+use kociemba::*;
+
+// A fixed corpus of random 25 move scrambles, so that every run solves the
+// same states.
+const SCRAMBLES: &str = include_str!("scrambles.txt");
+
+fn corpus() -> Vec<Cube> {
+  SCRAMBLES
+    .lines()
+    .map(|line| Cube::from_algorithm(&parse_algorithm(line).unwrap()))
+    .collect()
+}
+
+fn transition_tables(c: &mut Criterion) {
+  let mut group = c.benchmark_group("transition_tables");
+  group.bench_function("eo", |b| b.iter(get_eo_transition_table));
+  group.bench_function("co", |b| b.iter(get_co_transition_table));
+  group.bench_function("ud1", |b| b.iter(get_ud1_transition_table));
+  group.bench_function("ep", |b| b.iter(get_ep_transition_table));
+  group.bench_function("cp", |b| b.iter(get_cp_transition_table));
+  group.bench_function("ud2", |b| b.iter(get_ud2_transition_table));
+  group.finish();
+}
+
+fn pruning_tables(c: &mut Criterion) {
+  let eo_t = get_eo_transition_table();
+  let co_t = get_co_transition_table();
+  let ud1_t = get_ud1_transition_table();
+  let ep_t = get_ep_transition_table();
+  let cp_t = get_cp_transition_table();
+  let ud2_t = get_ud2_transition_table();
+
+  let mut group = c.benchmark_group("pruning_tables");
+  group.sample_size(10);
+  group.bench_function("co", |b| b.iter(|| get_co_prune_table(&co_t)));
+  group.bench_function("ep", |b| b.iter(|| get_ep_prune_table(&ep_t)));
+  group.bench_function("eo_ud1_sym", |b| {
+    b.iter(|| get_eo_ud1_sym_prune_table(&eo_t, &ud1_t))
+  });
+  group.bench_function("cp_ud2_sym", |b| {
+    b.iter(|| get_cp_ud2_sym_prune_table(&cp_t, &ud2_t))
+  });
+  group.finish();
+}
+
+fn solves(c: &mut Criterion) {
+  let tables = Tables::get();
+  let (tables0, tables1) = (tables.phase0(), tables.phase1());
+  let cubes = corpus();
+
+  let mut group = c.benchmark_group("solve");
+  group.bench_function("single", |b| {
+    let mut i = 0;
+    b.iter(|| {
+      i = (i + 1) % cubes.len();
+      two_phase(black_box(&cubes[i]), &tables0, &tables1, 30)
+    })
+  });
+  group.bench_function("single_ordered", |b| {
+    let tables0 = tables0.with_move_ordering(true);
+    let tables1 = tables1.with_move_ordering(true);
+    let mut i = 0;
+    b.iter(|| {
+      i = (i + 1) % cubes.len();
+      two_phase(black_box(&cubes[i]), &tables0, &tables1, 30)
+    })
+  });
+  group.sample_size(10);
+  group.bench_function("batch", |b| {
+    b.iter(|| {
+      cubes
+        .iter()
+        .map(|cube| two_phase(black_box(cube), &tables0, &tables1, 30))
+        .collect::<Vec<_>>()
+    })
+  });
+  group.finish();
+}
+
+criterion_group!(benches, transition_tables, pruning_tables, solves);
+criterion_main!(benches);
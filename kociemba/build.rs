@@ -0,0 +1,53 @@
+// Generate the phase 0 tables at build time with the `embedded-tables`
+// feature, so that they can be embedded in the library instead of being built
+// at run time.
+//
+// The table modules are shared with the library, and the tables are written
+// in the same format as `save_tables`, so that `Tables::new` reads them with
+// the same code that loads saved tables.
+
+// Only the code that builds and writes the tables is used here.
+#![allow(dead_code)]
+
+extern crate cube;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+
+#[path = "src/pruning_table.rs"]
+mod pruning_table;
+#[path = "src/symmetry.rs"]
+mod symmetry;
+#[path = "src/transition_table.rs"]
+mod transition_table;
+
+#[cfg(feature = "embedded-tables")]
+fn write_phase0_tables() -> std::io::Result<()> {
+  use pruning_table::{get_co_prune_table, get_eo_ud1_sym_prune_table};
+  use std::env;
+  use std::fs::File;
+  use std::io::{BufWriter, Write};
+  use std::path::Path;
+  use transition_table::*;
+
+  let out_dir = env::var_os("OUT_DIR").unwrap();
+  let path = Path::new(&out_dir).join("phase0.tables");
+  let mut w = BufWriter::new(File::create(path)?);
+  let eo_t = get_eo_transition_table();
+  let co_t = get_co_transition_table();
+  let ud1_t = get_ud1_transition_table();
+  eo_t.write_to(&mut w)?;
+  co_t.write_to(&mut w)?;
+  ud1_t.write_to(&mut w)?;
+  get_co_prune_table(&co_t).write_to(&mut w)?;
+  get_eo_ud1_sym_prune_table(&eo_t, &ud1_t).write_to(&mut w)?;
+  w.flush()
+}
+
+fn main() {
+  println!("cargo:rerun-if-changed=build.rs");
+  println!("cargo:rerun-if-changed=src/pruning_table.rs");
+  println!("cargo:rerun-if-changed=src/symmetry.rs");
+  println!("cargo:rerun-if-changed=src/transition_table.rs");
+  #[cfg(feature = "embedded-tables")]
+  write_phase0_tables().expect("failed to write the phase 0 tables");
+}
@@ -0,0 +1,112 @@
+extern crate cube;
+extern crate kociemba;
+extern crate rand;
+
+use cube::{
+  format_algorithm, generate_random_move_scramble, parse_extended_algorithm,
+  seeded_rng, Cube, NUM_FACELETS,
+};
+use kociemba::Tables;
+use rand::Rng;
+use std::env;
+use std::process;
+
+const USAGE: &str = "usage:
+  kociemba-cli solve <facelets|algorithm>
+  kociemba-cli scramble [--count N] [--seed N]
+  kociemba-cli apply <algorithm>
+  kociemba-cli fingerprints
+  kociemba-cli dump <table>";
+
+/// The number of moves of each scramble.
+const SCRAMBLE_LENGTH: usize = 25;
+
+// The cube given by a facelet string, or by an algorithm applied to the solved
+// cube.
+fn parse_cube(s: &str) -> Result<Cube, String> {
+  let is_facelets = s.chars().count() == NUM_FACELETS
+    && s.chars().all(|c| "URFDLB".contains(c));
+  if is_facelets {
+    return Cube::from_facelet_str(s)
+      .map_err(|e| kociemba::Error::from(e).to_string());
+  }
+  let moves = parse_extended_algorithm(s)
+    .map_err(|e| kociemba::Error::from(e).to_string())?;
+  Ok(Cube::solved().apply_extended_algorithm(&moves))
+}
+
+// `count` formatted random move scrambles.
+fn scrambles(count: usize, rng: &mut impl Rng) -> Vec<String> {
+  (0..count)
+    .map(|_| {
+      format_algorithm(&generate_random_move_scramble(SCRAMBLE_LENGTH, rng))
+    })
+    .collect()
+}
+
+// Run the subcommand in `args`, returning what to print.
+fn run(args: &[String]) -> Result<String, String> {
+  let (command, rest) = match args.split_first() {
+    Some((command, rest)) => (command.as_str(), rest.join(" ")),
+    None => return Err(USAGE.to_string()),
+  };
+  match command {
+    "solve" if !rest.is_empty() => {
+      let cube = parse_cube(&rest)?;
+      Ok(format_algorithm(&kociemba::solve(&cube)))
+    }
+    "scramble" => {
+      let mut count = 1;
+      let mut seed = None;
+      for option in args[1..].chunks(2) {
+        match option {
+          [flag, n] if flag == "--count" => {
+            count = n.parse().map_err(|_| format!("invalid count: {}", n))?;
+          }
+          [flag, n] if flag == "--seed" => {
+            seed = Some(n.parse().map_err(|_| format!("invalid seed: {}", n))?);
+          }
+          _ => return Err(USAGE.to_string()),
+        }
+      }
+      let scrambles = match seed {
+        Some(seed) => scrambles(count, &mut seeded_rng(seed)),
+        None => scrambles(count, &mut rand::rng()),
+      };
+      Ok(scrambles.join("\n"))
+    }
+    "apply" => {
+      let moves = parse_extended_algorithm(&rest).map_err(|e| e.to_string())?;
+      let cube = Cube::solved().apply_extended_algorithm(&moves);
+      Ok(cube.to_facelet_str())
+    }
+    "fingerprints" => {
+      let fingerprints: Vec<String> = Tables::get()
+        .fingerprints()
+        .iter()
+        .map(|(table, fingerprint)| format!("{:016x}  {}", fingerprint, table))
+        .collect();
+      Ok(fingerprints.join("\n"))
+    }
+    "dump" if !rest.is_empty() => {
+      let mut text = vec![];
+      Tables::get()
+        .write_table_text(&rest, &mut text)
+        .map_err(|e| e.to_string())?;
+      let text = String::from_utf8(text).unwrap();
+      Ok(text.trim_end().to_string())
+    }
+    _ => Err(USAGE.to_string()),
+  }
+}
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  match run(&args) {
+    Ok(output) => println!("{}", output),
+    Err(e) => {
+      eprintln!("{}", e);
+      process::exit(1);
+    }
+  }
+}
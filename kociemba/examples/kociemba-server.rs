@@ -0,0 +1,227 @@
+extern crate cube;
+extern crate kociemba;
+extern crate serde;
+extern crate serde_json;
+extern crate tiny_http;
+
+use cube::{algorithm_length, format_algorithm, Cube};
+use kociemba::{load_tables, SolverHandle, Tables};
+use serde::{Deserialize, Serialize};
+use std::env;
+use std::io::Read;
+use std::process;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+use tiny_http::{Header, Method, Request, Response, Server};
+
+const USAGE: &str = "usage:
+  kociemba-server [--addr ADDR] [--threads N] [--tables PATH]";
+
+/// The address that the server listens on by default.
+const DEFAULT_ADDR: &str = "127.0.0.1:8080";
+
+/// How long each request is searched for by default, so that a short
+/// `max_length` can not keep a worker busy.
+const DEFAULT_TIME_LIMIT: Duration = Duration::from_secs(1);
+
+/// The longest time limit that a request can ask for, which is shared by all
+/// the cubes of a batch.
+const MAX_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// The most cubes that one request can solve.
+const MAX_BATCH: usize = 1000;
+
+/// The largest request body that is read.
+const MAX_BODY: u64 = 1 << 20;
+
+/// A cube, or a batch of cubes, given as facelet strings.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Facelets {
+  One(String),
+  Batch(Vec<String>),
+}
+
+/// The body of `POST /solve`.
+#[derive(Deserialize)]
+struct SolveRequest {
+  facelets: Facelets,
+  /// The maximum length of the solutions.
+  max_length: Option<usize>,
+  /// How long to search for all the solutions, in milliseconds.
+  time_limit_ms: Option<u64>,
+}
+
+/// The solution of one cube, or why there is none.
+#[derive(Serialize)]
+#[serde(untagged)]
+enum SolveResult {
+  Solved { solution: String, length: usize },
+  Failed { error: String },
+}
+
+// Solve the cube given by `facelets` with `solver`, searching until
+// `deadline`.
+fn solve(
+  solver: &SolverHandle,
+  facelets: &str,
+  deadline: Instant,
+) -> SolveResult {
+  let remaining = deadline.saturating_duration_since(Instant::now());
+  if remaining == Duration::ZERO {
+    return SolveResult::Failed {
+      error: "the time limit of the request was reached".to_string(),
+    };
+  }
+  let mut config = solver.config().clone();
+  config.time_limit = Some(remaining);
+  let solver = solver.clone().with_config(config);
+  let solution = Cube::from_facelet_str(facelets)
+    .map_err(kociemba::Error::from)
+    .and_then(|cube| {
+      cube.verify()?;
+      solver.solve(&cube).ok_or(kociemba::Error::NoSolution {
+        max_length: solver.config().max_length,
+      })
+    });
+  match solution {
+    Ok(moves) => SolveResult::Solved {
+      solution: format_algorithm(&moves),
+      length: algorithm_length(&moves, solver.config().metric),
+    },
+    Err(e) => SolveResult::Failed {
+      error: e.to_string(),
+    },
+  }
+}
+
+// The status and JSON body of the response to `body`, a request to solve
+// one or more cubes.
+fn solve_request(solver: &SolverHandle, body: &str) -> (u16, String) {
+  let request: SolveRequest = match serde_json::from_str(body) {
+    Ok(request) => request,
+    Err(e) => return error(400, &format!("invalid request: {}", e)),
+  };
+  let mut config = solver.config().clone();
+  if let Some(max_length) = request.max_length {
+    config.max_length = max_length;
+  }
+  let solver = solver.clone().with_config(config);
+  // The cubes of a batch are solved in turn until the same deadline, and the
+  // ones that are not reached by then fail.
+  let time_limit = request
+    .time_limit_ms
+    .map_or(DEFAULT_TIME_LIMIT, Duration::from_millis);
+  let deadline = Instant::now() + time_limit.min(MAX_TIME_LIMIT);
+  match request.facelets {
+    Facelets::One(facelets) => {
+      let result = solve(&solver, &facelets, deadline);
+      let status = match result {
+        SolveResult::Solved { .. } => 200,
+        SolveResult::Failed { .. } => 422,
+      };
+      (status, serde_json::to_string(&result).unwrap())
+    }
+    Facelets::Batch(ref batch) if batch.len() > MAX_BATCH => {
+      error(400, &format!("at most {} cubes per request", MAX_BATCH))
+    }
+    Facelets::Batch(batch) => {
+      let solutions: Vec<SolveResult> = batch
+        .iter()
+        .map(|facelets| solve(&solver, facelets, deadline))
+        .collect();
+      let body = serde_json::json!({ "solutions": solutions });
+      (200, body.to_string())
+    }
+  }
+}
+
+// An error response with `message`.
+fn error(status: u16, message: &str) -> (u16, String) {
+  (status, serde_json::json!({ "error": message }).to_string())
+}
+
+// Respond to `request`, which only `POST /solve` succeeds for.
+fn handle(solver: &SolverHandle, mut request: Request) {
+  let (status, body) = match (request.method(), request.url()) {
+    (Method::Post, "/solve") => {
+      let mut body = String::new();
+      let read = request.as_reader().take(MAX_BODY).read_to_string(&mut body);
+      match read {
+        Ok(_) => solve_request(solver, &body),
+        Err(e) => error(400, &format!("invalid body: {}", e)),
+      }
+    }
+    (_, "/solve") => error(405, "only POST is supported"),
+    _ => error(404, "not found"),
+  };
+  let content_type =
+    Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+  let response = Response::from_string(body)
+    .with_status_code(status)
+    .with_header(content_type);
+  if let Err(e) = request.respond(response) {
+    eprintln!("failed to respond: {}", e);
+  }
+}
+
+// Parse the options in `args`, returning the address, the number of worker
+// threads and the tables.
+fn parse_args(args: &[String]) -> Result<(String, usize, Arc<Tables>), String> {
+  let mut addr = DEFAULT_ADDR.to_string();
+  let mut threads = thread::available_parallelism().map_or(1, |n| n.get());
+  let mut tables = None;
+  for option in args.chunks(2) {
+    match option {
+      [flag, value] if flag == "--addr" => addr = value.clone(),
+      [flag, n] if flag == "--threads" => {
+        threads = n
+          .parse()
+          .ok()
+          .filter(|&n| n > 0)
+          .ok_or_else(|| format!("invalid thread count: {}", n))?;
+      }
+      [flag, path] if flag == "--tables" => {
+        let loaded = load_tables(path)
+          .map_err(|e| kociemba::Error::from(e).to_string())?;
+        tables = Some(Arc::new(loaded));
+      }
+      _ => return Err(USAGE.to_string()),
+    }
+  }
+  // Build the tables now, rather than in the first request.
+  let tables = tables.unwrap_or_else(Tables::shared);
+  Ok((addr, threads, tables))
+}
+
+fn main() {
+  let args: Vec<String> = env::args().skip(1).collect();
+  let (addr, threads, tables) = parse_args(&args).unwrap_or_else(|e| {
+    eprintln!("{}", e);
+    process::exit(1);
+  });
+  let server = match Server::http(&addr) {
+    Ok(server) => Arc::new(server),
+    Err(e) => {
+      eprintln!("failed to listen on {}: {}", addr, e);
+      process::exit(1);
+    }
+  };
+  eprintln!("listening on {} with {} threads", addr, threads);
+  // Every worker solves with the same tables.
+  let solver = SolverHandle::new(tables);
+  let workers: Vec<_> = (0..threads)
+    .map(|_| {
+      let (server, solver) = (server.clone(), solver.clone());
+      thread::spawn(move || {
+        for request in server.incoming_requests() {
+          handle(&solver, request);
+        }
+      })
+    })
+    .collect();
+  for worker in workers {
+    worker.join().unwrap();
+  }
+}
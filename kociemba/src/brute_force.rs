@@ -0,0 +1,89 @@
+use canonical::{skip_face, SEARCH_FACES};
+use cube::{invert_algorithm, Cube, Move, Turn};
+use std::collections::HashMap;
+
+// All states reachable from `cube` in at most `depth` moves, with the shortest
+// moves that reach them.
+fn breadth_first(cube: &Cube, depth: usize) -> HashMap<Cube, Vec<Move>> {
+  let mut seen = HashMap::new();
+  seen.insert(*cube, vec![]);
+  let mut level = vec![(*cube, vec![])];
+  for _ in 0..depth {
+    let mut next = vec![];
+    for (state, moves) in level {
+      for &f in &SEARCH_FACES {
+        if skip_face(&moves, f) {
+          continue;
+        }
+        for &turn in &Turn::ALL {
+          let state = state.apply_move(Move(f, turn));
+          if seen.contains_key(&state) {
+            continue;
+          }
+          let mut moves = moves.clone();
+          moves.push(Move(f, turn));
+          seen.insert(state, moves.clone());
+          next.push((state, moves));
+        }
+      }
+    }
+    level = next;
+  }
+  seen
+}
+
+/// Find a shortest solution of at most `max_length` moves by breadth-first
+/// search, without any of the coordinates or tables used by `two_phase`.
+///
+/// This is slow, and only meant as a reference to test faster solvers
+/// against. It searches from both `cube` and the solved cube, so lengths up to
+/// 8 are practical.
+pub fn brute_force(cube: &Cube, max_length: usize) -> Option<Vec<Move>> {
+  let backward = breadth_first(&Cube::solved(), max_length / 2);
+  let forward = breadth_first(cube, max_length - max_length / 2);
+
+  // A state reached by `moves` from `cube`, and by `back` from the solved
+  // cube, is solved by `moves` followed by the inverse of `back`.
+  forward
+    .iter()
+    .filter_map(|(state, moves)| backward.get(state).map(|back| (moves, back)))
+    .min_by_key(|(moves, back)| moves.len() + back.len())
+    .map(|(moves, back)| {
+      let mut moves = moves.clone();
+      moves.extend(invert_algorithm(back));
+      moves
+    })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::Face;
+
+  #[test]
+  fn solve() {
+    assert_eq!(Some(vec![]), brute_force(&Cube::solved(), 0));
+
+    let scramble = [
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Counter),
+      Move(Face::F, Turn::Half),
+    ];
+    let cube = Cube::solved().apply_algorithm(&scramble);
+    assert_eq!(None, brute_force(&cube, 4));
+    let solution = brute_force(&cube, 6).unwrap();
+    assert_eq!(5, solution.len());
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+
+    // U D and D U are the same state, so either order is a shortest solution.
+    let cube = Cube::solved().apply_algorithm(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::D, Turn::Half),
+    ]);
+    let solution = brute_force(&cube, 3).unwrap();
+    assert_eq!(2, solution.len());
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+  }
+}
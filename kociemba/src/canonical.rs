@@ -0,0 +1,167 @@
+use cube::{Face, Move, Turn};
+
+/// The faces in the order that they are searched.
+pub(crate) const SEARCH_FACES: [Face; 6] =
+  [Face::U, Face::D, Face::F, Face::B, Face::R, Face::L];
+
+// Check if a face should be skipped.
+pub(crate) fn skip_face(solution: &[Move], face: Face) -> bool {
+  let len = solution.len();
+  if len > 0 {
+    // Check for A A.
+    match solution[len - 1] {
+      Move(previous_face, _) if previous_face == face => return true,
+      _ => (),
+    }
+
+    if len > 1 {
+      // Check for A B A where A and B are opposite faces.
+      match &solution[len - 2..] {
+        &[Move(f1, _), Move(f2, _)] if f1.is_opposite(f2) && f1 == face => {
+          return true;
+        }
+        _ => (),
+      }
+    }
+  }
+  false
+}
+
+/// Check if a sequence of moves is canonical, which means it never turns the
+/// same face twice in a row, and never turns a face, its opposite face and
+/// then the same face again.
+pub fn is_canonical(moves: &[Move]) -> bool {
+  (0..moves.len()).all(|i| !skip_face(&moves[..i], moves[i].0))
+}
+
+/// An `Iterator` over all canonical move sequences of a given length.
+pub struct CanonicalSequences {
+  len: usize,
+  moves: Vec<Move>,
+  // The index of each move of the current sequence into `moves`.
+  indices: Vec<usize>,
+  sequence: Vec<Move>,
+  started: bool,
+}
+
+/// Iterate over all canonical move sequences of `len` moves, in the order
+/// that the searches try them.
+pub fn canonical_sequences(len: usize) -> CanonicalSequences {
+  let moves = SEARCH_FACES
+    .iter()
+    .flat_map(|&f| Turn::ALL.iter().map(move |&turn| Move(f, turn)))
+    .collect();
+  let mut sequences = CanonicalSequences {
+    len,
+    moves,
+    indices: Vec::with_capacity(len),
+    sequence: Vec::with_capacity(len),
+    started: false,
+  };
+  sequences.fill(0);
+  sequences
+}
+
+impl CanonicalSequences {
+  // Push the move at `index`, or the first valid move after it, onto the
+  // current sequence, returning false if there is no valid move.
+  fn push_from(&mut self, index: usize) -> bool {
+    let next = (index..self.moves.len())
+      .find(|&i| !skip_face(&self.sequence, self.moves[i].0));
+    match next {
+      Some(i) => {
+        self.indices.push(i);
+        self.sequence.push(self.moves[i]);
+        true
+      }
+      None => false,
+    }
+  }
+
+  // Extend the current sequence to `len` moves with the first valid moves.
+  // There is always a valid move, as only two faces can be skipped.
+  fn fill(&mut self, index: usize) {
+    let mut index = index;
+    while self.sequence.len() < self.len {
+      let pushed = self.push_from(index);
+      assert!(pushed);
+      index = 0;
+    }
+  }
+}
+
+impl Iterator for CanonicalSequences {
+  type Item = Vec<Move>;
+  fn next(&mut self) -> Option<Vec<Move>> {
+    if !self.started {
+      self.started = true;
+      return Some(self.sequence.clone());
+    }
+
+    // Replace the last move that can be replaced by a later move, and fill the
+    // rest of the sequence with the first valid moves again.
+    while let Some(last) = self.indices.pop() {
+      self.sequence.pop();
+      if self.push_from(last + 1) {
+        self.fill(0);
+        return Some(self.sequence.clone());
+      }
+    }
+    None
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn canonical() {
+    assert!(is_canonical(&[]));
+    assert!(is_canonical(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::D, Turn::Clockwise)
+    ]));
+    assert!(!is_canonical(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise)
+    ]));
+    assert!(!is_canonical(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::D, Turn::Clockwise),
+      Move(Face::U, Turn::Half)
+    ]));
+    assert!(is_canonical(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Half)
+    ]));
+  }
+
+  #[test]
+  fn sequences() {
+    // The number of canonical sequences of each length.
+    let counts = [1, 18, 270, 3888, 56376];
+    for (len, &count) in counts.iter().enumerate() {
+      let sequences: Vec<_> = canonical_sequences(len).collect();
+      assert_eq!(count, sequences.len());
+      assert!(sequences.iter().all(|s| s.len() == len && is_canonical(s)));
+    }
+
+    let mut sequences = canonical_sequences(2);
+    assert_eq!(
+      Some(vec![
+        Move(Face::U, Turn::Clockwise),
+        Move(Face::D, Turn::Clockwise)
+      ]),
+      sequences.next()
+    );
+    assert_eq!(
+      Some(vec![
+        Move(Face::U, Turn::Clockwise),
+        Move(Face::D, Turn::Half)
+      ]),
+      sequences.next()
+    );
+  }
+}
@@ -0,0 +1,179 @@
+use cube::{Cube, Move};
+use handoff::{HandoffCoord, HandoffTables};
+use phase0::{Phase0Coord, Phase0Tables};
+use phase1::Phase1Coord;
+
+/// A cube stored only as its coordinates, which are updated with the
+/// transition tables instead of permuting the pieces.
+///
+/// The phase 0 coordinates give the orientations, and the coordinates of
+/// `HandoffCoord`, the corner permutation and where the U, D and slice edges
+/// are, give the permutations. They are all defined for every cube and
+/// tracked with the G0 tables, so the cube can always be converted back to a
+/// `Cube`, and the phase 1 coordinates are known whenever it is in G1.
+#[derive(Clone, Copy)]
+pub struct CoordCube<'a> {
+  tables0: &'a Phase0Tables<'a>,
+  handoff: &'a HandoffTables<'a>,
+  phase0: Phase0Coord,
+  permutations: HandoffCoord,
+}
+
+impl<'a> CoordCube<'a> {
+  /// Create a `CoordCube` with the coordinates of `cube`.
+  pub fn new(
+    cube: &Cube,
+    tables0: &'a Phase0Tables<'a>,
+    handoff: &'a HandoffTables<'a>,
+  ) -> CoordCube<'a> {
+    CoordCube {
+      tables0,
+      handoff,
+      phase0: Phase0Coord::from(*cube),
+      permutations: HandoffCoord::from(*cube),
+    }
+  }
+
+  /// Update the coordinates by applying `m`.
+  pub fn apply_move(&mut self, m: Move) {
+    self.phase0 = self.tables0.transition(self.phase0, m);
+    self.permutations = self.handoff.apply(self.permutations, &[m]);
+  }
+
+  /// Update the coordinates by applying each of `moves`.
+  pub fn apply_moves(&mut self, moves: &[Move]) {
+    for &m in moves {
+      self.apply_move(m);
+    }
+  }
+
+  /// Check if the cube is in G1.
+  pub fn is_g1(&self) -> bool {
+    self.phase0.is_solved()
+  }
+
+  /// Check if the cube is solved.
+  pub fn is_solved(&self) -> bool {
+    self.is_g1() && self.permutations.phase1().is_solved()
+  }
+
+  /// The phase 0 coordinates of the cube.
+  pub fn phase0(&self) -> Phase0Coord {
+    self.phase0
+  }
+
+  /// The phase 1 coordinates of the cube, or `None` if it is not in G1,
+  /// where they are not defined.
+  pub fn phase1(&self) -> Option<Phase1Coord> {
+    if self.is_g1() {
+      Some(self.permutations.phase1())
+    } else {
+      None
+    }
+  }
+
+  /// Convert back to a `Cube`.
+  pub fn to_cube(&self) -> Cube {
+    let mut cube = self.permutations.permutations();
+    self.phase0.set_orientations(&mut cube);
+    cube
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{Face, Turn};
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use proptest::sample::select;
+  use tables::Tables;
+  use transition_table::G1_MOVES;
+
+  #[test]
+  fn g1() {
+    let tables0 = Tables::get().phase0();
+    let handoff = Tables::get().handoff();
+    let moves = [
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Half),
+      Move(Face::D, Turn::Counter),
+      Move(Face::F, Turn::Half),
+      Move(Face::U, Turn::Half),
+      Move(Face::L, Turn::Half),
+      Move(Face::B, Turn::Half),
+    ];
+    let mut cube = Cube::solved();
+    let mut coord = CoordCube::new(&cube, &tables0, &handoff);
+    assert!(coord.is_solved());
+    for &m in &moves {
+      cube = cube.apply_move(m);
+      coord.apply_move(m);
+      assert!(coord.is_g1());
+      assert_eq!(Some(Phase1Coord::from(cube)), coord.phase1());
+      assert_eq!(cube, coord.to_cube());
+    }
+    assert!(!coord.is_solved());
+  }
+
+  #[test]
+  fn g0() {
+    let tables0 = Tables::get().phase0();
+    let handoff = Tables::get().handoff();
+    let moves = [
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::F, Turn::Counter),
+      Move(Face::D, Turn::Half),
+      Move(Face::L, Turn::Clockwise),
+      Move(Face::B, Turn::Counter),
+    ];
+    let mut cube = Cube::solved();
+    let mut coord = CoordCube::new(&cube, &tables0, &handoff);
+    for &m in &moves {
+      cube = cube.apply_move(m);
+      coord.apply_move(m);
+      assert_eq!(Phase0Coord::from(cube), coord.phase0());
+      assert_eq!(cube, coord.to_cube());
+    }
+    assert!(!coord.is_g1());
+    assert_eq!(None, coord.phase1());
+    assert!(!coord.is_solved());
+
+    // The cube is still known when it returns to G1.
+    let mut coord = CoordCube::new(&Cube::solved(), &tables0, &handoff);
+    coord.apply_moves(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+    ]);
+    assert!(coord.is_g1());
+    assert_eq!(Some(Phase1Coord::from(Cube::solved())), coord.phase1());
+    assert!(coord.is_solved());
+  }
+
+  proptest! {
+    #[test]
+    fn g0_transitions(cube: Cube, moves: Vec<Move>) {
+      let tables0 = Tables::get().phase0();
+      let handoff = Tables::get().handoff();
+      let mut coord = CoordCube::new(&cube, &tables0, &handoff);
+      coord.apply_moves(&moves);
+      let cube = cube.apply_algorithm(&moves);
+      prop_assert_eq!(Phase0Coord::from(cube), coord.phase0());
+      prop_assert_eq!(cube, coord.to_cube());
+    }
+
+    #[test]
+    fn g1_transitions(
+      moves in vec(select(G1_MOVES), 0..30)
+    ) {
+      let tables0 = Tables::get().phase0();
+      let handoff = Tables::get().handoff();
+      let mut coord = CoordCube::new(&Cube::solved(), &tables0, &handoff);
+      coord.apply_moves(&moves);
+      let cube = Cube::from_algorithm(&moves);
+      prop_assert_eq!(Some(Phase1Coord::from(cube)), coord.phase1());
+      prop_assert_eq!(cube, coord.to_cube());
+    }
+  }
+}
@@ -0,0 +1,181 @@
+use cube::{Cube, Edge};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::collections::HashSet;
+use transition_table::{COCoord, Coord, EOCoord, Group, UD1Coord, G1_MOVES};
+
+/// The number of cosets of G1 in G0, which is the number of phase 0
+/// coordinates.
+pub const NUM_G1_COSETS: usize =
+  EOCoord::NUM_ELEMS * COCoord::NUM_ELEMS * UD1Coord::NUM_ELEMS;
+
+/// A coset `H * c` of H = G1 = <U, D, F2, B2, R2, L2>, which is every cube
+/// `h * c` with `h` in H: the cubes that `c` turns into when it is scrambled
+/// from an element of H instead of from solved.
+///
+/// The cubes of a coset have the same phase 0 coordinates, so phase 0 can not
+/// tell them apart, and the same moves take each of them into G1.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct G1Coset {
+  /// The edge orientations of the cubes.
+  pub eo: EOCoord,
+  /// The corner orientations of the cubes.
+  pub co: COCoord,
+  /// The positions of the E-slice edges of the cubes.
+  pub ud1: UD1Coord,
+}
+
+impl G1Coset {
+  /// The coset that `cube` is in.
+  pub fn of(cube: &Cube) -> G1Coset {
+    G1Coset {
+      eo: EOCoord::get_coord(cube),
+      co: COCoord::get_coord(cube),
+      ud1: UD1Coord::get_coord(cube),
+    }
+  }
+
+  /// The coset with the index `index`, which must be below `NUM_G1_COSETS`.
+  pub fn from_index(index: usize) -> G1Coset {
+    assert!(index < NUM_G1_COSETS);
+    let ud1 = index % UD1Coord::NUM_ELEMS;
+    let index = index / UD1Coord::NUM_ELEMS;
+    G1Coset {
+      eo: EOCoord::from(index / COCoord::NUM_ELEMS),
+      co: COCoord::from(index % COCoord::NUM_ELEMS),
+      ud1: UD1Coord::from(ud1),
+    }
+  }
+
+  /// The index of the coset, from 0 to `NUM_G1_COSETS - 1`, where 0 is G1.
+  pub fn index(&self) -> usize {
+    (usize::from(self.eo) * COCoord::NUM_ELEMS + usize::from(self.co))
+      * UD1Coord::NUM_ELEMS
+      + usize::from(self.ud1)
+  }
+
+  /// Check if this is G1 itself, which is the coset of the solved cube.
+  pub fn is_g1(&self) -> bool {
+    self.index() == 0
+  }
+
+  /// A cube in the coset.
+  pub fn representative(&self) -> Cube {
+    let mut cube = Cube::solved();
+    EOCoord::set_coord(&mut cube, self.eo);
+    COCoord::set_coord(&mut cube, self.co);
+    UD1Coord::set_coord(&mut cube, self.ud1);
+    cube
+  }
+
+  /// A random cube of the coset, with every cube equally likely.
+  pub fn sample(&self, rng: &mut impl Rng) -> Cube {
+    random_g1(rng) * self.representative()
+  }
+}
+
+/// A random element of G1, with every element equally likely.
+///
+/// The corners, the U and D layer edges and the E-slice edges are shuffled
+/// independently, and two E-slice edges are swapped if the parities of the
+/// permutations do not match, so each element is reached in exactly two
+/// ways.
+pub fn random_g1(rng: &mut impl Rng) -> Cube {
+  let mut cube = Cube::solved();
+  cube.cp.shuffle(rng);
+  let slice = Edge::FR as usize;
+  cube.ep[..slice].shuffle(rng);
+  cube.ep[slice..].shuffle(rng);
+  if !cube.has_valid_parity() {
+    cube.ep.swap(slice, slice + 1);
+  }
+  debug_assert!(Group::G1.contains(&cube));
+  cube
+}
+
+/// Every element of G1 that can be reached with at most `max_depth` moves of
+/// G1, in the order of their distance from solved.
+///
+/// There are almost 20 billion elements of G1, so this only fits in memory
+/// for small depths.
+pub fn g1_elements(max_depth: usize) -> Vec<Cube> {
+  let mut elements = vec![Cube::solved()];
+  let mut seen: HashSet<Cube> = elements.iter().cloned().collect();
+  let mut start = 0;
+  for _ in 0..max_depth {
+    let end = elements.len();
+    for i in start..end {
+      for &m in G1_MOVES {
+        let next = elements[i].apply_move(m);
+        if seen.insert(next) {
+          elements.push(next);
+        }
+      }
+    }
+    start = end;
+  }
+  elements
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, seeded_rng};
+  use phase0::{solve_phase0, Phase0Coord};
+  use tables::Tables;
+
+  #[test]
+  fn cosets() {
+    assert_eq!(2_217_093_120, NUM_G1_COSETS);
+    assert!(G1Coset::of(&Cube::solved()).is_g1());
+    let r = Cube::from_algorithm(&parse_algorithm("R").unwrap());
+    let coset = G1Coset::of(&r);
+    assert!(!coset.is_g1());
+    assert_eq!(coset, G1Coset::from_index(coset.index()));
+    assert_eq!(coset, G1Coset::of(&coset.representative()));
+
+    let mut rng = seeded_rng(1576);
+    for _ in 0..100 {
+      let index = rng.random_range(0..NUM_G1_COSETS);
+      let coset = G1Coset::from_index(index);
+      assert_eq!(index, coset.index());
+      let cube = coset.sample(&mut rng);
+      assert!(cube.verify().is_ok());
+      assert_eq!(coset, G1Coset::of(&cube));
+    }
+  }
+
+  #[test]
+  fn g1() {
+    let mut rng = seeded_rng(1576);
+    for _ in 0..100 {
+      let cube = random_g1(&mut rng);
+      assert!(cube.verify().is_ok());
+      assert!(Group::G1.contains(&cube));
+      assert!(G1Coset::of(&cube).is_g1());
+    }
+
+    // 1 + 10 + 67 elements, as U U2 is U' and U D is D U.
+    let elements = g1_elements(2);
+    assert_eq!(Cube::solved(), elements[0]);
+    assert_eq!(78, elements.len());
+    assert!(elements.iter().all(|c| Group::G1.contains(c)));
+    let r = Cube::from_algorithm(&parse_algorithm("R").unwrap());
+    assert!(!Group::G1.contains(&r));
+    assert!(Group::G0.contains(&r));
+  }
+
+  #[test]
+  fn phase0_lands_in_g1() {
+    let tables0 = Tables::get().phase0();
+    let mut rng = seeded_rng(1576);
+    for _ in 0..10 {
+      let cube = Cube::random(&mut rng);
+      let solution =
+        solve_phase0(Phase0Coord::from(cube), 12, &tables0).unwrap();
+      let g1 = cube.apply_algorithm(&solution);
+      assert!(Group::G1.contains(&g1));
+      assert!(G1Coset::of(&g1).is_g1());
+    }
+  }
+}
@@ -0,0 +1,166 @@
+use canonical::{skip_face, SEARCH_FACES};
+use cube::{Cube, Face, Move, Turn};
+use pruning_table::{init_prune_table_from, PruneTable};
+use std::sync::OnceLock;
+use transition_table::*;
+
+/// The tables of the cross on each face, which give the exact number of
+/// moves needed to solve it.
+///
+/// There is only one transition table, since the cross coordinate moves the
+/// same way whichever four edges it tracks, and a pruning table for each
+/// face, from the coordinate of its solved cross.
+pub struct CrossSolver {
+  pub(crate) cross_t: TransitionTable<CrossCoord>,
+  // The pruning table of each face, in the order of `Face::ALL`.
+  pub(crate) cross_p: Vec<PruneTable<CrossCoord>>,
+}
+
+impl CrossSolver {
+  /// Build the tables.
+  pub fn new() -> CrossSolver {
+    let cross_t = get_cross_transition_table();
+    let cross_p = Face::ALL
+      .iter()
+      .map(|&face| {
+        let solved = get_cross_coord(&Cube::solved(), face.adjacent_edges());
+        init_prune_table_from(&[solved], Group::G0.moves(), |c, m| {
+          cross_t[(c, m)]
+        })
+      })
+      .collect();
+    CrossSolver { cross_t, cross_p }
+  }
+
+  /// The tables shared by the whole program, which are built on the first
+  /// call.
+  pub fn get() -> &'static CrossSolver {
+    static TABLES: OnceLock<CrossSolver> = OnceLock::new();
+    TABLES.get_or_init(CrossSolver::new)
+  }
+
+  /// The number of moves needed to solve the cross on `face`.
+  pub fn distance(&self, cube: &Cube, face: Face) -> usize {
+    let coord = get_cross_coord(cube, face.adjacent_edges());
+    self.cross_p[face.index()][coord]
+  }
+
+  /// Every shortest solution of the cross on `face`.
+  ///
+  /// Only canonical sequences are returned, which never turn the same face
+  /// twice in a row. Turns of opposite faces are still found in both orders,
+  /// such as `U D` and `D U`.
+  pub fn solve(&self, cube: &Cube, face: Face) -> Vec<Vec<Move>> {
+    let coord = get_cross_coord(cube, face.adjacent_edges());
+    let prune = &self.cross_p[face.index()];
+    let mut solutions = vec![];
+    self.search(coord, prune, &mut vec![], &mut solutions);
+    solutions
+  }
+
+  // Add every shortest solution of `coord` after `solution` to `solutions`.
+  // The pruning table is exact, so only the moves that bring the cross one
+  // move closer are followed.
+  fn search(
+    &self,
+    coord: CrossCoord,
+    prune: &PruneTable<CrossCoord>,
+    solution: &mut Vec<Move>,
+    solutions: &mut Vec<Vec<Move>>,
+  ) {
+    let depth = prune[coord];
+    if depth == 0 {
+      solutions.push(solution.clone());
+      return;
+    }
+    for &face in &SEARCH_FACES {
+      if skip_face(solution, face) {
+        continue;
+      }
+      for &turn in &Turn::ALL {
+        let m = Move(face, turn);
+        let next = self.cross_t[(coord, m)];
+        if prune[next] + 1 != depth {
+          continue;
+        }
+        solution.push(m);
+        self.search(next, prune, solution, solutions);
+        solution.pop();
+      }
+    }
+  }
+}
+
+impl Default for CrossSolver {
+  fn default() -> CrossSolver {
+    CrossSolver::new()
+  }
+}
+
+/// Every shortest solution of the cross on each face, using the tables from
+/// `CrossSolver::get`, so the best cross of a scramble can be picked.
+pub fn solve_crosses(cube: &Cube) -> Vec<(Face, Vec<Vec<Move>>)> {
+  let solver = CrossSolver::get();
+  Face::ALL
+    .iter()
+    .map(|&face| (face, solver.solve(cube, face)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+  use pattern_database::{PatternDatabase, PieceSet};
+
+  #[test]
+  fn tables() {
+    let solver = CrossSolver::get();
+    // Every cross can be solved in at most 8 moves.
+    for prune in &solver.cross_p {
+      assert_eq!(Some(&8), prune.iter().max());
+    }
+  }
+
+  #[test]
+  fn solve() {
+    for (face, solutions) in solve_crosses(&Cube::solved()) {
+      assert_eq!(vec![Vec::<Move>::new()], solutions, "{:?}", face);
+    }
+
+    let scramble = "D2 F' U2 R' B L2 U' F D' R2 B2 L U F2 R D L' B' U2 F";
+    let cube = Cube::from_algorithm(&parse_algorithm(scramble).unwrap());
+    let solver = CrossSolver::get();
+    let d_cross =
+      PatternDatabase::new(PieceSet::new(&[], &Face::D.adjacent_edges()));
+    assert_eq!(
+      d_cross.distance(&cube),
+      Some(solver.distance(&cube, Face::D))
+    );
+    for (face, solutions) in solve_crosses(&cube) {
+      assert!(!solutions.is_empty());
+      let distance = solver.distance(&cube, face);
+      for solution in &solutions {
+        assert_eq!(distance, solution.len());
+        let cube = cube.apply_algorithm(solution);
+        assert!(face
+          .adjacent_edges()
+          .iter()
+          .all(|&e| cube.is_edge_solved(e)));
+      }
+    }
+
+    // A turn of F only has one shortest solution for the D cross.
+    let cube = Cube::from_algorithm(&parse_algorithm("F").unwrap());
+    assert_eq!(
+      vec![parse_algorithm("F'").unwrap()],
+      solve_crosses(&cube)[3].1
+    );
+    // Turns of opposite faces are found in both orders.
+    let cube = Cube::from_algorithm(&parse_algorithm("R L").unwrap());
+    let solutions = solver.solve(&cube, Face::D);
+    assert_eq!(2, solutions.len());
+    assert!(solutions.contains(&parse_algorithm("R' L'").unwrap()));
+    assert!(solutions.contains(&parse_algorithm("L' R'").unwrap()));
+  }
+}
@@ -0,0 +1,215 @@
+use cube::{Corner, Cube2, Face, Move, Turn, NUM_CORNERS};
+use std::sync::OnceLock;
+
+/// The moves of a 2x2x2 cube with DBL solved, which never move DBL.
+const MOVES: [Move; 9] = [
+  Move(Face::U, Turn::Clockwise),
+  Move(Face::U, Turn::Half),
+  Move(Face::U, Turn::Counter),
+  Move(Face::R, Turn::Clockwise),
+  Move(Face::R, Turn::Half),
+  Move(Face::R, Turn::Counter),
+  Move(Face::F, Turn::Clockwise),
+  Move(Face::F, Turn::Half),
+  Move(Face::F, Turn::Counter),
+];
+
+/// The positions of the corners other than DBL.
+const POSITIONS: [usize; 7] = [0, 1, 2, 3, 4, 5, 7];
+
+/// The number of permutations of the corners other than DBL.
+const NUM_PERMS: usize = 5040;
+/// The number of orientations of the corners other than DBL.
+const NUM_TWISTS: usize = 729;
+
+// The rank of the permutation of the corners other than DBL.
+fn perm_coord(cube: &Cube2) -> usize {
+  let perm = POSITIONS.map(|p| cube.cp[p] as usize);
+  let mut coord = 0;
+  for (i, &a) in perm.iter().enumerate() {
+    let smaller = perm[i + 1..].iter().filter(|&&b| b < a).count();
+    coord = coord * (perm.len() - i) + smaller;
+  }
+  coord
+}
+
+// The corners other than DBL with the permutation of rank `coord`, with
+// orientation 0.
+fn set_perm_coord(coord: usize) -> Cube2 {
+  let mut digits = [0; 7];
+  let mut coord = coord;
+  for i in (0..digits.len()).rev() {
+    digits[i] = coord % (digits.len() - i);
+    coord /= digits.len() - i;
+  }
+  let mut free = POSITIONS.to_vec();
+  let mut cube = Cube2::solved();
+  for (&p, &digit) in POSITIONS.iter().zip(&digits) {
+    cube.cp[p] = Corner::from(free.remove(digit));
+  }
+  cube
+}
+
+// The orientations of the corners other than DBL and DRB, as a base 3
+// number. The orientation of DRB follows from the others.
+fn twist_coord(cube: &Cube2) -> usize {
+  cube.co[..6]
+    .iter()
+    .fold(0, |coord, &co| coord * 3 + co as usize)
+}
+
+// The solved permutation with the orientations of `coord`.
+fn set_twist_coord(coord: usize) -> Cube2 {
+  let mut cube = Cube2::solved();
+  let mut coord = coord;
+  for p in (0..6).rev() {
+    cube.co[p] = (coord % 3) as u8;
+    coord /= 3;
+  }
+  let sum: u8 = cube.co.iter().sum();
+  cube.co[NUM_CORNERS - 1] = (3 - sum % 3) % 3;
+  cube
+}
+
+/// An optimal solver for the 2x2x2 cube.
+///
+/// A 2x2x2 cube only has 7! * 3^6 = 3674160 states once it is held with DBL
+/// solved, so the solver keeps the exact distance of every state, found by a
+/// breadth-first search with the moves of U, R and F, and solutions only
+/// turn those faces.
+pub struct Cube2Solver {
+  distances: Vec<u8>,
+}
+
+impl Cube2Solver {
+  /// Build the table of distances.
+  pub fn new() -> Cube2Solver {
+    let perm_t: Vec<[u16; 9]> = (0..NUM_PERMS)
+      .map(|coord| {
+        let cube = set_perm_coord(coord);
+        MOVES.map(|m| perm_coord(&cube.apply_move(m)) as u16)
+      })
+      .collect();
+    let twist_t: Vec<[u16; 9]> = (0..NUM_TWISTS)
+      .map(|coord| {
+        let cube = set_twist_coord(coord);
+        MOVES.map(|m| twist_coord(&cube.apply_move(m)) as u16)
+      })
+      .collect();
+
+    let mut distances = vec![u8::MAX; NUM_PERMS * NUM_TWISTS];
+    let solved = Cube2::solved();
+    let solved = perm_coord(&solved) * NUM_TWISTS + twist_coord(&solved);
+    distances[solved] = 0;
+    let mut frontier = vec![solved];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+      depth += 1;
+      let mut next = vec![];
+      for &coord in &frontier {
+        let (perm, twist) = (coord / NUM_TWISTS, coord % NUM_TWISTS);
+        for m in 0..MOVES.len() {
+          let coord =
+            perm_t[perm][m] as usize * NUM_TWISTS + twist_t[twist][m] as usize;
+          if distances[coord] == u8::MAX {
+            distances[coord] = depth;
+            next.push(coord);
+          }
+        }
+      }
+      frontier = next;
+    }
+    Cube2Solver { distances }
+  }
+
+  /// The solver shared by the whole program, which builds its table on the
+  /// first call.
+  pub fn get() -> &'static Cube2Solver {
+    static SOLVER: OnceLock<Cube2Solver> = OnceLock::new();
+    SOLVER.get_or_init(Cube2Solver::new)
+  }
+
+  /// The number of moves needed to solve `cube`.
+  pub fn distance(&self, cube: &Cube2) -> usize {
+    self.distances[Cube2Solver::coord(cube)] as usize
+  }
+
+  /// A shortest sequence of moves that solves `cube`, turning only U, R and
+  /// F, found by following moves that reduce the distance.
+  ///
+  /// The moves are for the cube as it is held, so it may end up solved but
+  /// held differently, if DBL was not solved to start with.
+  pub fn solve(&self, cube: &Cube2) -> Vec<Move> {
+    let mut cube = *cube;
+    let mut distance = self.distance(&cube);
+    let mut solution = Vec::with_capacity(distance);
+    while distance > 0 {
+      // The moves of U, R and F turn the faces that meet at one corner, so
+      // one of them is a shortest first move whichever corner stays put.
+      let m = *MOVES
+        .iter()
+        .find(|&&m| self.distance(&cube.apply_move(m)) + 1 == distance)
+        .expect("every state has a neighbour that is one move closer");
+      cube = cube.apply_move(m);
+      solution.push(m);
+      distance -= 1;
+    }
+    solution
+  }
+
+  // The index of `cube` in the table, once it is held with DBL solved.
+  fn coord(cube: &Cube2) -> usize {
+    let cube = cube.normalized();
+    perm_coord(&cube) * NUM_TWISTS + twist_coord(&cube)
+  }
+}
+
+impl Default for Cube2Solver {
+  fn default() -> Cube2Solver {
+    Cube2Solver::new()
+  }
+}
+
+/// A shortest solution of `cube`, using the table from `Cube2Solver::get`.
+pub fn solve_cube2(cube: &Cube2) -> Vec<Move> {
+  Cube2Solver::get().solve(cube)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+
+  #[test]
+  fn coords() {
+    for coord in 0..NUM_PERMS {
+      assert_eq!(coord, perm_coord(&set_perm_coord(coord)));
+    }
+    for coord in 0..NUM_TWISTS {
+      let cube = set_twist_coord(coord);
+      assert!(cube.verify().is_ok());
+      assert_eq!(coord, twist_coord(&cube));
+    }
+  }
+
+  #[test]
+  fn solve() {
+    let solver = Cube2Solver::get();
+    // Every state is reached, and God's number of the 2x2x2 cube is 11.
+    assert!(solver.distances.iter().all(|&d| d != u8::MAX));
+    assert_eq!(Some(&11), solver.distances.iter().max());
+    assert!(solve_cube2(&Cube2::solved()).is_empty());
+
+    let scramble = parse_algorithm("R U2 F' D L2 B R' U F2 L'").unwrap();
+    let cube = Cube2::from_algorithm(&scramble);
+    let solution = solve_cube2(&cube);
+    assert_eq!(solver.distance(&cube), solution.len());
+    assert!(solution.len() <= scramble.len());
+    assert!(cube.apply_algorithm(&solution).is_solved());
+
+    // Turning D is the same as turning U with the cube held differently.
+    let cube = Cube2::from_algorithm(&parse_algorithm("D").unwrap());
+    assert_eq!(1, solver.distance(&cube));
+    assert_eq!(parse_algorithm("U'").unwrap(), solve_cube2(&cube));
+  }
+}
@@ -0,0 +1,130 @@
+use cube::{Move, Symmetry};
+
+/// Which solutions are considered duplicates of each other.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Duplicates {
+  /// Only identical solutions are duplicates.
+  Identical,
+  /// Solutions that only differ in the order of moves on opposite faces,
+  /// such as `U D` and `D U`, are duplicates.
+  Commuting,
+  /// Solutions that are the same up to a symmetry of the cube, or the order
+  /// of moves on opposite faces, are duplicates.
+  Symmetric,
+}
+
+// The key used to compare solutions, where consecutive moves on the same axis
+// are sorted, as they commute.
+fn commuting_key(solution: &[Move]) -> Vec<(usize, u8)> {
+  let mut key: Vec<(usize, u8)> = solution
+    .iter()
+    .map(|&Move(f, turn)| (f.index(), u8::from(turn)))
+    .collect();
+  let mut start = 0;
+  for i in 1..=solution.len() {
+    if i == solution.len() || !solution[i].0.is_opposite(solution[start].0) {
+      key[start..i].sort();
+      start = i;
+    }
+  }
+  key
+}
+
+// The smallest `commuting_key` of all symmetric versions of `solution`.
+fn symmetric_key(solution: &[Move], syms: &[Symmetry]) -> Vec<(usize, u8)> {
+  syms
+    .iter()
+    .map(|s| {
+      let moves: Vec<Move> =
+        solution.iter().map(|&m| s.conjugate_move(m)).collect();
+      commuting_key(&moves)
+    })
+    .min()
+    .unwrap()
+}
+
+/// Remove the solutions that are `duplicates` of an earlier solution, keeping
+/// the order of the remaining solutions.
+pub fn dedup_solutions(
+  solutions: Vec<Vec<Move>>,
+  duplicates: Duplicates,
+) -> Vec<Vec<Move>> {
+  let syms: Vec<Symmetry> = Symmetry::all_48().collect();
+  let mut keys = vec![];
+  let mut unique = vec![];
+  for solution in solutions {
+    let key = match duplicates {
+      Duplicates::Identical => solution
+        .iter()
+        .map(|&Move(f, turn)| (f.index(), u8::from(turn)))
+        .collect(),
+      Duplicates::Commuting => commuting_key(&solution),
+      Duplicates::Symmetric => symmetric_key(&solution, &syms),
+    };
+    if !keys.contains(&key) {
+      keys.push(key);
+      unique.push(solution);
+    }
+  }
+  unique
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{Face, Turn};
+
+  #[test]
+  fn dedup() {
+    let solutions = vec![
+      vec![
+        Move(Face::U, Turn::Clockwise),
+        Move(Face::D, Turn::Half),
+        Move(Face::R, Turn::Clockwise),
+      ],
+      vec![
+        Move(Face::D, Turn::Half),
+        Move(Face::U, Turn::Clockwise),
+        Move(Face::R, Turn::Clockwise),
+      ],
+      vec![
+        Move(Face::U, Turn::Clockwise),
+        Move(Face::D, Turn::Half),
+        Move(Face::L, Turn::Counter),
+      ],
+      vec![
+        Move(Face::U, Turn::Counter),
+        Move(Face::D, Turn::Half),
+        Move(Face::L, Turn::Clockwise),
+      ],
+      vec![
+        Move(Face::F, Turn::Clockwise),
+        Move(Face::R, Turn::Clockwise),
+      ],
+    ];
+
+    let unique = dedup_solutions(solutions.clone(), Duplicates::Identical);
+    assert_eq!(solutions, unique);
+
+    let unique = dedup_solutions(solutions.clone(), Duplicates::Commuting);
+    assert_eq!(
+      vec![
+        solutions[0].clone(),
+        solutions[2].clone(),
+        solutions[3].clone(),
+        solutions[4].clone(),
+      ],
+      unique
+    );
+
+    let unique = dedup_solutions(solutions.clone(), Duplicates::Symmetric);
+    assert_eq!(
+      vec![
+        solutions[0].clone(),
+        solutions[2].clone(),
+        solutions[4].clone(),
+      ],
+      unique
+    );
+  }
+}
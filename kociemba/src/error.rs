@@ -0,0 +1,205 @@
+use cube::{CubeStateErr, FaceletErr, ParseError};
+use std::error;
+use std::fmt;
+use std::io;
+
+/// An error from solving a cube, or from reading the cube or the tables to
+/// solve it with.
+///
+/// Every error of the `cube` crate converts into this one, so applications
+/// can propagate all of them with `?`.
+#[derive(Debug)]
+pub enum Error {
+  /// The cube is not in a solvable state.
+  InvalidState(CubeStateErr),
+  /// No solution of at most `max_length` moves was found, either because
+  /// there is none or because the search was stopped first.
+  NoSolution { max_length: usize },
+  /// The tables could not be loaded by `load_tables`.
+  TableLoad(TableLoadError),
+  /// An algorithm could not be parsed.
+  Parse(ParseError),
+  /// A facelet string could not be parsed.
+  Facelets(FaceletErr),
+}
+
+impl fmt::Display for Error {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      Error::InvalidState(err) => write!(f, "invalid cube: {}", err),
+      Error::NoSolution { max_length } => {
+        write!(f, "no solution of at most {} moves", max_length)
+      }
+      Error::TableLoad(err) => write!(f, "failed to load tables: {}", err),
+      Error::Parse(err) => write!(f, "invalid algorithm: {}", err),
+      Error::Facelets(err) => write!(f, "invalid facelets: {}", err),
+    }
+  }
+}
+
+impl error::Error for Error {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      Error::InvalidState(err) => Some(err),
+      Error::NoSolution { .. } => None,
+      Error::TableLoad(err) => Some(err),
+      Error::Parse(err) => Some(err),
+      Error::Facelets(err) => Some(err),
+    }
+  }
+}
+
+impl From<CubeStateErr> for Error {
+  fn from(err: CubeStateErr) -> Error {
+    Error::InvalidState(err)
+  }
+}
+
+impl From<io::Error> for Error {
+  fn from(err: io::Error) -> Error {
+    Error::TableLoad(TableLoadError::Io(err))
+  }
+}
+
+impl From<TableLoadError> for Error {
+  fn from(err: TableLoadError) -> Error {
+    Error::TableLoad(err)
+  }
+}
+
+impl From<ParseError> for Error {
+  fn from(err: ParseError) -> Error {
+    Error::Parse(err)
+  }
+}
+
+impl From<FaceletErr> for Error {
+  fn from(err: FaceletErr) -> Error {
+    Error::Facelets(err)
+  }
+}
+
+/// An error from loading the tables saved by `save_tables`, or from checking
+/// them with `validate_tables`.
+#[derive(Debug)]
+pub enum TableLoadError {
+  /// The file could not be read, or a table has an entry that is out of
+  /// range.
+  Io(io::Error),
+  /// The file was not written by `save_tables`.
+  NotATablesFile,
+  /// The file was written with a different version of the file format.
+  FormatVersion { expected: u32, found: u32 },
+  /// The file was written by a different version of this crate.
+  CrateVersion { expected: String, found: String },
+  /// The file was written for different coordinates, such as a different
+  /// number of values of one of them.
+  Layout { expected: u64, found: u64 },
+  /// The tables do not match the checksum they were saved with, so the file
+  /// is corrupted.
+  Checksum { expected: u32, found: u32 },
+  /// There is more data after the tables.
+  TrailingData,
+  /// An entry of a table is wrong, found by `validate_tables`.
+  InvalidEntry { table: &'static str, index: usize },
+}
+
+impl fmt::Display for TableLoadError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match self {
+      TableLoadError::Io(err) => write!(f, "{}", err),
+      TableLoadError::NotATablesFile => write!(f, "not a tables file"),
+      TableLoadError::FormatVersion { expected, found } => write!(
+        f,
+        "tables file format version {}, expected {}",
+        found, expected
+      ),
+      TableLoadError::CrateVersion { expected, found } => write!(
+        f,
+        "tables saved by version {}, expected {}",
+        found, expected
+      ),
+      TableLoadError::Layout { expected, found } => write!(
+        f,
+        "tables layout {:016x}, expected {:016x}",
+        found, expected
+      ),
+      TableLoadError::Checksum { expected, found } => write!(
+        f,
+        "tables checksum {:08x}, expected {:08x}",
+        found, expected
+      ),
+      TableLoadError::TrailingData => {
+        write!(f, "trailing data after the tables")
+      }
+      TableLoadError::InvalidEntry { table, index } => {
+        write!(f, "invalid entry {} of the {} table", index, table)
+      }
+    }
+  }
+}
+
+impl error::Error for TableLoadError {
+  fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+    match self {
+      TableLoadError::Io(err) => Some(err),
+      _ => None,
+    }
+  }
+}
+
+impl From<io::Error> for TableLoadError {
+  fn from(err: io::Error) -> TableLoadError {
+    TableLoadError::Io(err)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, Cube};
+  use std::error::Error as _;
+
+  // Parse a facelet string and an algorithm, propagating both errors.
+  fn parse(facelets: &str, alg: &str) -> Result<Cube, Error> {
+    let cube = Cube::from_facelet_str(facelets)?;
+    Ok(cube.apply_algorithm(&parse_algorithm(alg)?))
+  }
+
+  #[test]
+  fn conversions() {
+    let solved = Cube::solved().to_facelet_str();
+    assert_eq!(Cube::solved(), parse(&solved, "R R'").unwrap());
+
+    let err = parse("UUU", "R").unwrap_err();
+    assert_eq!(
+      "invalid facelets: expected 54 facelets, found 3",
+      err.to_string()
+    );
+    assert!(err.source().is_some());
+
+    let err = parse(&solved, "R X").unwrap_err();
+    assert!(matches!(err, Error::Parse(_)));
+    assert!(err.to_string().starts_with("invalid algorithm: "));
+
+    let err = Error::from(CubeStateErr::ErrParity);
+    assert_eq!(
+      "invalid cube: corner and edge parity do not match",
+      err.to_string()
+    );
+    let err = Error::NoSolution { max_length: 5 };
+    assert_eq!("no solution of at most 5 moves", err.to_string());
+    assert!(err.source().is_none());
+
+    let err = Error::from(TableLoadError::Checksum {
+      expected: 0x1234,
+      found: 0xabcd,
+    });
+    assert_eq!(
+      "failed to load tables: tables checksum 0000abcd, expected 00001234",
+      err.to_string()
+    );
+    let err = Error::from(io::Error::from(io::ErrorKind::NotFound));
+    assert!(matches!(err, Error::TableLoad(TableLoadError::Io(_))));
+  }
+}
@@ -0,0 +1,213 @@
+use cube::{
+  expand_algorithm, extended_algorithm_length, Axis, ExtendedMove, Face,
+  Metric, Move, Orientation, Turn,
+};
+
+// The cheapest way found to execute the first moves of a solution, ending in
+// `orientation`.
+struct Execution {
+  orientation: Orientation,
+  // The length in the metric, and then the number of moves.
+  cost: (usize, usize),
+  // The `Execution` of the moves before the last one that this extends.
+  previous: usize,
+  moves: Vec<ExtendedMove>,
+}
+
+/// Rewrite `solution` so that only the faces in `faces` are turned, and so
+/// that it is as short as possible in `metric`, which is useful for robots
+/// that can only reach some faces, or for one-handed solving.
+///
+/// Each move is executed as a turn of the face, or as a wide turn of the
+/// opposite face, optionally after one rotation of the whole cube. Wide turns
+/// and rotations rotate the cube, so the moves after them are relabeled to
+/// the faces that they are executed on. The cube ends up solved, but possibly
+/// rotated.
+///
+/// Every choice is searched, so wide turns and rotations are only used when
+/// they make the executed solution shorter, such as replacing the turns of L
+/// with Rw when only R can be turned, which saves the rotations. Ties are
+/// broken by the number of moves, so rotations that count as nothing in
+/// `metric` are only added when they are needed.
+///
+/// Returns `None` if no face can be turned.
+pub fn execute_with_faces(
+  solution: &[Move],
+  faces: &[Face],
+  metric: Metric,
+) -> Option<Vec<ExtendedMove>> {
+  if faces.is_empty() {
+    return None;
+  }
+  let rotations = [Axis::X, Axis::Y, Axis::Z]
+    .iter()
+    .flat_map(|&axis| Turn::ALL.iter().map(move |&turn| (axis, turn)));
+  let rotations: Vec<_> =
+    std::iter::once(None).chain(rotations.map(Some)).collect();
+
+  let start = Execution {
+    orientation: Orientation::identity(),
+    cost: (0, 0),
+    previous: 0,
+    moves: vec![],
+  };
+  let mut layers = vec![vec![start]];
+  for &Move(face, n) in solution {
+    let mut layer: Vec<Execution> = vec![];
+    for (previous, execution) in layers.last().unwrap().iter().enumerate() {
+      for &rotation in &rotations {
+        let mut orientation = execution.orientation;
+        let mut moves = vec![];
+        if let Some((axis, turn)) = rotation {
+          moves.push(ExtendedMove::Rotation(axis, turn));
+          orientation = orientation.rotate(axis, turn);
+        }
+        let position = orientation.position(face);
+        // Turning the opposite face and the middle layer together turns
+        // `position` as well as rotating the cube.
+        let turns = [
+          (position, ExtendedMove::Face(Move(position, n))),
+          (
+            position.opposite(),
+            ExtendedMove::Wide(position.opposite(), n),
+          ),
+        ];
+        for &(turned, m) in &turns {
+          if !faces.contains(&turned) {
+            continue;
+          }
+          let mut moves = moves.clone();
+          moves.push(m);
+          let orientation = expand_algorithm(&[m], orientation).1;
+          let cost = (
+            execution.cost.0 + extended_algorithm_length(&moves, metric),
+            execution.cost.1 + moves.len(),
+          );
+          let next = Execution {
+            orientation,
+            cost,
+            previous,
+            moves,
+          };
+          match layer.iter_mut().find(|e| e.orientation == orientation) {
+            Some(e) if e.cost <= cost => {}
+            Some(e) => *e = next,
+            None => layer.push(next),
+          }
+        }
+      }
+    }
+    layers.push(layer);
+  }
+
+  let mut executed = vec![];
+  let last = layers.last().unwrap();
+  let mut index = (0..last.len()).min_by_key(|&i| last[i].cost).unwrap();
+  for layer in layers.iter().rev() {
+    let execution = &layer[index];
+    executed.splice(0..0, execution.moves.iter().cloned());
+    index = execution.previous;
+  }
+  Some(executed)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{
+    invert_algorithm, parse_algorithm, parse_extended_algorithm, Cube,
+  };
+
+  // Execute `solution` with `faces`, checking that the executed moves solve
+  // the cube that `solution` solves.
+  fn execute(solution: &[Move], faces: &[Face]) -> Vec<ExtendedMove> {
+    let executed =
+      execute_with_faces(solution, faces, Metric::Execution).unwrap();
+    let cube = Cube::from_algorithm(&invert_algorithm(solution));
+    assert_eq!(Cube::solved(), cube.apply_extended_algorithm(&executed));
+    executed
+  }
+
+  #[test]
+  fn wide() {
+    let faces = [Face::U, Face::R, Face::F];
+    let solution = [Move(Face::U, Turn::Clockwise), Move(Face::R, Turn::Half)];
+    assert_eq!(
+      vec![
+        ExtendedMove::Face(Move(Face::U, Turn::Clockwise)),
+        ExtendedMove::Face(Move(Face::R, Turn::Half)),
+      ],
+      execute(&solution, &faces)
+    );
+
+    // L F = Rw x' F = Rw U x'.
+    let solution = [
+      Move(Face::L, Turn::Clockwise),
+      Move(Face::F, Turn::Clockwise),
+    ];
+    assert_eq!(
+      vec![
+        ExtendedMove::Wide(Face::R, Turn::Clockwise),
+        ExtendedMove::Face(Move(Face::U, Turn::Clockwise)),
+      ],
+      execute(&solution, &faces)
+    );
+
+    // D2 L R' = Uw2 y2 L R' = Uw2 R L' y2, where L' is executed as Rw'.
+    let solution = [
+      Move(Face::D, Turn::Half),
+      Move(Face::L, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+    ];
+    assert_eq!(
+      vec![
+        ExtendedMove::Wide(Face::U, Turn::Half),
+        ExtendedMove::Face(Move(Face::R, Turn::Clockwise)),
+        ExtendedMove::Wide(Face::R, Turn::Counter),
+      ],
+      execute(&solution, &faces)
+    );
+  }
+
+  #[test]
+  fn rotation() {
+    // With only U and R, F U = x U x' U, but x' Uw Rw is shorter.
+    let faces = [Face::U, Face::R];
+    let solution = [
+      Move(Face::F, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+    ];
+    assert_eq!(
+      vec![
+        ExtendedMove::Rotation(Axis::X, Turn::Counter),
+        ExtendedMove::Wide(Face::U, Turn::Clockwise),
+        ExtendedMove::Wide(Face::R, Turn::Clockwise),
+      ],
+      execute(&solution, &faces)
+    );
+
+    assert_eq!(None, execute_with_faces(&solution, &[], Metric::Execution));
+  }
+
+  #[test]
+  fn shortest() {
+    // With only U and R, executing each L as Rw would take U away from the
+    // top, which needs more rotations than turning the cube over once.
+    let faces = [Face::U, Face::R];
+    let solution = parse_algorithm("R U L U' L' U2 L").unwrap();
+    let executed = execute(&solution, &faces);
+    assert_eq!(
+      parse_extended_algorithm("R U y2 R U' R' U2 R").unwrap(),
+      executed
+    );
+    assert_eq!(8, extended_algorithm_length(&executed, Metric::Execution));
+
+    // Rotations count as nothing in the half turn metric, so they are only
+    // added to reach a face that can not be turned otherwise.
+    let solution = parse_algorithm("F D").unwrap();
+    assert_eq!(
+      parse_extended_algorithm("x' Uw R").unwrap(),
+      execute_with_faces(&solution, &faces, Metric::Half).unwrap()
+    );
+  }
+}
@@ -0,0 +1,235 @@
+use canonical::{skip_face, SEARCH_FACES};
+use cross::CrossSolver;
+use cube::{Cube, F2LSlot, Face, Move, Turn};
+use pruning_table::{init_prune_table_from, PruneTable};
+use std::sync::OnceLock;
+use transition_table::*;
+
+/// The insertion of one F2L pair.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PairInsertion {
+  /// The slot that the pair is inserted into.
+  pub slot: F2LSlot,
+  /// The moves that insert the pair.
+  pub moves: Vec<Move>,
+}
+
+// The coordinates of the cross and of the pair of each slot, in the order of
+// `F2LSlot::ALL`.
+#[derive(Clone, Copy)]
+struct F2LCoords {
+  cross: CrossCoord,
+  pairs: [PairCoord; 4],
+}
+
+/// The tables of the F2L pairs, which find the shortest ways to insert a
+/// pair while keeping the cross on D and the slots that are already solved.
+///
+/// The search is over every face turn, with the exact number of moves needed
+/// to solve the cross, from the tables of `CrossSolver`, and to solve each
+/// pair as lower bounds.
+pub struct F2LSolver {
+  pair_t: TransitionTable<PairCoord>,
+  // The pruning table of each slot, in the order of `F2LSlot::ALL`.
+  pair_p: Vec<PruneTable<PairCoord>>,
+}
+
+impl F2LSolver {
+  /// Build the tables.
+  pub fn new() -> F2LSolver {
+    let pair_t = get_pair_transition_table();
+    let pair_p = F2LSlot::ALL
+      .iter()
+      .map(|&slot| {
+        let solved =
+          get_pair_coord(&Cube::solved(), slot.corner(), slot.edge());
+        init_prune_table_from(&[solved], Group::G0.moves(), |c, m| {
+          pair_t[(c, m)]
+        })
+      })
+      .collect();
+    F2LSolver { pair_t, pair_p }
+  }
+
+  /// The tables shared by the whole program, which are built on the first
+  /// call.
+  pub fn get() -> &'static F2LSolver {
+    static TABLES: OnceLock<F2LSolver> = OnceLock::new();
+    TABLES.get_or_init(F2LSolver::new)
+  }
+
+  /// Every shortest sequence of moves that solves `slot` of a cube with the
+  /// cross solved, keeping the cross and every slot that is already solved.
+  ///
+  /// Only canonical sequences are returned, which never turn the same face
+  /// twice in a row, like `CrossSolver::solve`.
+  pub fn solve_pair(&self, cube: &Cube, slot: F2LSlot) -> Vec<Vec<Move>> {
+    debug_assert!(cube.is_cross_solved());
+    let mut keep: Vec<usize> = cube
+      .solved_f2l_slots()
+      .into_iter()
+      .map(|slot| slot as usize)
+      .collect();
+    keep.push(slot as usize);
+
+    let coords = F2LCoords {
+      cross: CrossCoord::get_coord(cube),
+      pairs: [0, 1, 2, 3].map(|i| {
+        let slot = F2LSlot::ALL[i];
+        get_pair_coord(cube, slot.corner(), slot.edge())
+      }),
+    };
+    let mut solutions = vec![];
+    let mut depth = self.lower_bound(coords, &keep);
+    while solutions.is_empty() {
+      self.search(coords, &keep, depth, &mut vec![], &mut solutions);
+      depth += 1;
+    }
+    solutions
+  }
+
+  /// Every order that the unsolved slots of a cube with the cross solved can
+  /// be inserted in, with the first shortest insertion of each pair, from the
+  /// fewest moves in total to the most. A slot that is solved by inserting
+  /// another pair is left out of the rest of the order.
+  pub fn insertion_orders(&self, cube: &Cube) -> Vec<Vec<PairInsertion>> {
+    let mut orders = vec![];
+    self.insert_all(cube, &mut vec![], &mut orders);
+    orders.sort_by_key(|order| {
+      order
+        .iter()
+        .map(|insertion| insertion.moves.len())
+        .sum::<usize>()
+    });
+    orders
+  }
+
+  // Add each order that the unsolved slots of `cube` can be inserted in,
+  // after `order`, to `orders`.
+  fn insert_all(
+    &self,
+    cube: &Cube,
+    order: &mut Vec<PairInsertion>,
+    orders: &mut Vec<Vec<PairInsertion>>,
+  ) {
+    let solved = cube.solved_f2l_slots();
+    if solved.len() == F2LSlot::ALL.len() {
+      orders.push(order.clone());
+      return;
+    }
+    for &slot in F2LSlot::ALL.iter().filter(|s| !solved.contains(s)) {
+      let moves = self.solve_pair(cube, slot).swap_remove(0);
+      let next = cube.apply_algorithm(&moves);
+      order.push(PairInsertion { slot, moves });
+      self.insert_all(&next, order, orders);
+      order.pop();
+    }
+  }
+
+  // The largest lower bound for solving the cross and the pairs of `keep`.
+  fn lower_bound(&self, coords: F2LCoords, keep: &[usize]) -> usize {
+    let cross = CrossSolver::get().cross_p[Face::D.index()][coords.cross];
+    keep
+      .iter()
+      .map(|&i| self.pair_p[i][coords.pairs[i]])
+      .fold(cross, usize::max)
+  }
+
+  // Add every sequence of exactly `depth` moves after `solution` that solves
+  // the cross and the pairs of `keep` to `solutions`.
+  fn search(
+    &self,
+    coords: F2LCoords,
+    keep: &[usize],
+    depth: usize,
+    solution: &mut Vec<Move>,
+    solutions: &mut Vec<Vec<Move>>,
+  ) {
+    let lower_bound = self.lower_bound(coords, keep);
+    if lower_bound > depth {
+      return;
+    }
+    if depth == 0 {
+      solutions.push(solution.clone());
+      return;
+    }
+    let cross_t = &CrossSolver::get().cross_t;
+    for &face in &SEARCH_FACES {
+      if skip_face(solution, face) {
+        continue;
+      }
+      for &turn in &Turn::ALL {
+        let m = Move(face, turn);
+        let next = F2LCoords {
+          cross: cross_t[(coords.cross, m)],
+          pairs: coords.pairs.map(|pair| self.pair_t[(pair, m)]),
+        };
+        solution.push(m);
+        self.search(next, keep, depth - 1, solution, solutions);
+        solution.pop();
+      }
+    }
+  }
+}
+
+impl Default for F2LSolver {
+  fn default() -> F2LSolver {
+    F2LSolver::new()
+  }
+}
+
+/// Every shortest insertion of the pair of `slot`, using the tables from
+/// `F2LSolver::get`.
+pub fn solve_f2l_pair(cube: &Cube, slot: F2LSlot) -> Vec<Vec<Move>> {
+  F2LSolver::get().solve_pair(cube, slot)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+
+  #[test]
+  fn solve_pair() {
+    let solver = F2LSolver::get();
+    assert_eq!(
+      vec![Vec::<Move>::new()],
+      solver.solve_pair(&Cube::solved(), F2LSlot::FR)
+    );
+
+    let cube = Cube::from_algorithm(&parse_algorithm("R U R'").unwrap());
+    let solutions = solver.solve_pair(&cube, F2LSlot::FR);
+    assert!(solutions.contains(&parse_algorithm("R U' R'").unwrap()));
+    for solution in &solutions {
+      assert_eq!(3, solution.len());
+      let cube = cube.apply_algorithm(solution);
+      assert!(cube.is_f2l_solved());
+    }
+  }
+
+  #[test]
+  fn insertion_orders() {
+    let scramble = "R U R' U' F' U2 F L' U L U2 B U' B'";
+    let cube = Cube::from_algorithm(&parse_algorithm(scramble).unwrap());
+    assert!(cube.is_cross_solved());
+    let solver = F2LSolver::get();
+    let unsolved = 4 - cube.solved_f2l_slots().len();
+    let orders = solver.insertion_orders(&cube);
+    assert!(orders.len() <= (1..unsolved + 1).product::<usize>());
+
+    let total = |order: &Vec<PairInsertion>| -> usize {
+      order.iter().map(|insertion| insertion.moves.len()).sum()
+    };
+    for order in &orders {
+      let mut cube = cube;
+      for insertion in order {
+        cube = cube.apply_algorithm(&insertion.moves);
+        assert!(cube.is_f2l_slot_solved(insertion.slot));
+        assert!(cube.is_cross_solved());
+      }
+      assert!(cube.is_f2l_solved());
+      assert!(order.len() <= unsolved);
+      assert!(total(&orders[0]) <= total(order));
+    }
+  }
+}
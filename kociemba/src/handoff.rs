@@ -0,0 +1,152 @@
+use cube::{Cube, Edge, Face, Move};
+use phase1::Phase1Coord;
+use transition_table::*;
+
+const U_EDGES: [Edge; 4] = Face::U.adjacent_edges();
+const D_EDGES: [Edge; 4] = Face::D.adjacent_edges();
+const SLICE_EDGES: [Edge; 4] = [Edge::FR, Edge::FL, Edge::BL, Edge::BR];
+
+/// The coordinates of the pieces that phase 1 needs, which are defined for
+/// every cube so they can be tracked through the phase 0 moves.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HandoffCoord {
+  cp: CPCoord,
+  u_edges: EdgeGroupCoord,
+  d_edges: EdgeGroupCoord,
+  slice_edges: EdgeGroupCoord,
+}
+
+impl From<Cube> for HandoffCoord {
+  fn from(val: Cube) -> HandoffCoord {
+    HandoffCoord {
+      cp: CPCoord::get_coord(&val),
+      u_edges: get_edge_group_coord(&val, U_EDGES),
+      d_edges: get_edge_group_coord(&val, D_EDGES),
+      slice_edges: get_edge_group_coord(&val, SLICE_EDGES),
+    }
+  }
+}
+
+impl HandoffCoord {
+  /// The phase 1 coordinates, which are only defined once the cube is in G1.
+  pub(crate) fn phase1(&self) -> Phase1Coord {
+    // Each `set_coord` fills in the other edges, so only keep the U and D
+    // edges from each cube.
+    let mut u = Cube::solved();
+    set_edge_group_coord(&mut u, U_EDGES, self.u_edges);
+    let mut d = Cube::solved();
+    set_edge_group_coord(&mut d, D_EDGES, self.d_edges);
+    let mut cube = Cube::solved();
+    for i in 0..8 {
+      cube.ep[i] = if u.ep[i] < Edge::DR { u.ep[i] } else { d.ep[i] };
+    }
+    debug_assert!(cube.ep[..8].iter().all(|&e| e < Edge::FR));
+
+    // In G1 the slice edges are in the slice, so their permutation is the
+    // UD2 coordinate.
+    let ud2 = usize::from(self.slice_edges) % 24;
+    Phase1Coord::new(EPCoord::get_coord(&cube), self.cp, UD2Coord::from(ud2))
+  }
+
+  /// The cube with the corner and edge permutations of the coordinates,
+  /// and every piece oriented. Unlike `phase1`, this is defined for every
+  /// cube.
+  pub(crate) fn permutations(&self) -> Cube {
+    // Each `set_coord` fills in the other edges and may swap pieces to fix
+    // the parity, so only keep the pieces that it describes.
+    let mut cp = Cube::solved();
+    CPCoord::set_coord(&mut cp, self.cp);
+    let mut u = Cube::solved();
+    set_edge_group_coord(&mut u, U_EDGES, self.u_edges);
+    let mut d = Cube::solved();
+    set_edge_group_coord(&mut d, D_EDGES, self.d_edges);
+    let mut slice = Cube::solved();
+    set_edge_group_coord(&mut slice, SLICE_EDGES, self.slice_edges);
+
+    let mut cube = Cube::solved();
+    cube.cp = cp.cp;
+    for i in 0..12 {
+      cube.ep[i] = if u.ep[i] < Edge::DR {
+        u.ep[i]
+      } else if d.ep[i] >= Edge::DR && d.ep[i] < Edge::FR {
+        d.ep[i]
+      } else {
+        slice.ep[i]
+      };
+    }
+    debug_assert!(cube.verify().is_ok());
+    cube
+  }
+}
+
+/// The transition tables for the `HandoffCoord`, with G0 moves.
+pub struct HandoffTables<'a> {
+  cp_t: &'a TransitionTable<CPCoord>,
+  edge_group_t: &'a TransitionTable<EdgeGroupCoord>,
+}
+
+impl<'a> HandoffTables<'a> {
+  /// Create the `HandoffTables` from the G0 transition tables. The edge group
+  /// table is used for the U, D and E-slice edges.
+  pub fn new(
+    cp_t: &'a TransitionTable<CPCoord>,
+    edge_group_t: &'a TransitionTable<EdgeGroupCoord>,
+  ) -> HandoffTables<'a> {
+    HandoffTables { cp_t, edge_group_t }
+  }
+
+  // The new `HandoffCoord` after doing each of `moves`.
+  pub(crate) fn apply(
+    &self,
+    coord: HandoffCoord,
+    moves: &[Move],
+  ) -> HandoffCoord {
+    let mut coord = coord;
+    for &m in moves {
+      coord = HandoffCoord {
+        cp: self.cp_t[(coord.cp, m)],
+        u_edges: self.edge_group_t[(coord.u_edges, m)],
+        d_edges: self.edge_group_t[(coord.d_edges, m)],
+        slice_edges: self.edge_group_t[(coord.slice_edges, m)],
+      };
+    }
+    coord
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{Face, Turn};
+  use phase0::{phase0_all, Phase0Coord};
+  use tables::Tables;
+
+  #[test]
+  fn phase1() {
+    let tables = Tables::get();
+    let (tables0, handoff) = (tables.phase0(), tables.handoff());
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::F, Turn::Counter),
+      Move(Face::R, Turn::Counter),
+      Move(Face::D, Turn::Half),
+      Move(Face::B, Turn::Clockwise),
+    ]);
+    let start = HandoffCoord::from(c);
+    let mut count = 0;
+    for depth in 0..8 {
+      if count == 100 {
+        break;
+      }
+      phase0_all(Phase0Coord::from(c), depth, &tables0, |reduction| {
+        let coord = handoff.apply(start, reduction);
+        let g1 = c.apply_algorithm(reduction);
+        assert_eq!(HandoffCoord::from(g1), coord);
+        assert_eq!(Phase1Coord::from(g1), coord.phase1());
+        count += 1;
+        count < 100
+      });
+    }
+    assert_eq!(100, count);
+  }
+}
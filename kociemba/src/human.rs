@@ -0,0 +1,428 @@
+use cross::CrossSolver;
+use cube::{
+  expand_algorithm, parse_extended_algorithm, Corner, Cube, Edge, F2LSlot,
+  Face, Move, Orientation, Turn,
+};
+use f2l::F2LSolver;
+use std::sync::OnceLock;
+
+/// The OLL cases, by their usual number, with an algorithm that orients the
+/// last layer from each of them.
+pub(crate) const OLL_ALGORITHMS: [(&str, &str); 57] = [
+  ("OLL 1", "R U2 R2 F R F' U2 R' F R F'"),
+  ("OLL 2", "r U r' U2 r U2 R' U2 R U' r'"),
+  ("OLL 3", "r' R2 U R' U r U2 r' U M'"),
+  ("OLL 4", "M U' r U2 r' U' R U' R' M'"),
+  ("OLL 5", "l' U2 L U L' U l"),
+  ("OLL 6", "r U2 R' U' R U' r'"),
+  ("OLL 7", "r U R' U R U2 r'"),
+  ("OLL 8", "l' U' L U' L' U2 l"),
+  ("OLL 9", "R U R' U' R' F R2 U R' U' F'"),
+  ("OLL 10", "R U R' U R' F R F' R U2 R'"),
+  ("OLL 11", "r U R' U R' F R F' R U2 r'"),
+  ("OLL 12", "M' R' U' R U' R' U2 R U' R r'"),
+  ("OLL 13", "F U R U' R2 F' R U R U' R'"),
+  ("OLL 14", "R' F R U R' F' R F U' F'"),
+  ("OLL 15", "l' U' l L' U' L U l' U l"),
+  ("OLL 16", "r U r' R U R' U' r U' r'"),
+  ("OLL 17", "F R' F' R2 r' U R U' R' U' M'"),
+  ("OLL 18", "r U R' U R U2 r2 U' R U' R' U2 r"),
+  ("OLL 19", "r' R U R U R' U' M' R' F R F'"),
+  ("OLL 20", "r U R' U' M2 U R U' R' U' M'"),
+  ("OLL 21", "R U2 R' U' R U R' U' R U' R'"),
+  ("OLL 22", "R U2 R2 U' R2 U' R2 U2 R"),
+  ("OLL 23", "R2 D' R U2 R' D R U2 R"),
+  ("OLL 24", "r U R' U' r' F R F'"),
+  ("OLL 25", "F' r U R' U' r' F R"),
+  ("OLL 26", "R U2 R' U' R U' R'"),
+  ("OLL 27", "R U R' U R U2 R'"),
+  ("OLL 28", "r U R' U' M U R U' R'"),
+  ("OLL 29", "R U R' U' R U' R' F' U' F R U R'"),
+  ("OLL 30", "F R' F R2 U' R' U' R U R' F2"),
+  ("OLL 31", "R' U' F U R U' R' F' R"),
+  ("OLL 32", "L U F' U' L' U L F L'"),
+  ("OLL 33", "R U R' U' R' F R F'"),
+  ("OLL 34", "R U R2 U' R' F R U R U' F'"),
+  ("OLL 35", "R U2 R2 F R F' R U2 R'"),
+  ("OLL 36", "L' U' L U' L' U L U L F' L' F"),
+  ("OLL 37", "F R' F' R U R U' R'"),
+  ("OLL 38", "R U R' U R U' R' U' R' F R F'"),
+  ("OLL 39", "L F' L' U' L U F U' L'"),
+  ("OLL 40", "R' F R U R' U' F' U R"),
+  ("OLL 41", "R U R' U R U2 R' F R U R' U' F'"),
+  ("OLL 42", "R' U' R U' R' U2 R F R U R' U' F'"),
+  ("OLL 43", "F' U' L' U L F"),
+  ("OLL 44", "F U R U' R' F'"),
+  ("OLL 45", "F R U R' U' F'"),
+  ("OLL 46", "R' U' R' F R F' U R"),
+  ("OLL 47", "R' U' R' F R F' R' F R F' U R"),
+  ("OLL 48", "F R U R' U' R U R' U' F'"),
+  ("OLL 49", "r U' r2 U r2 U r2 U' r"),
+  ("OLL 50", "r' U r2 U' r2 U' r2 U r'"),
+  ("OLL 51", "F U R U' R' U R U' R' F'"),
+  ("OLL 52", "R U R' U R U' B U' B' R'"),
+  ("OLL 53", "l' U2 L U L' U' L U L' U l"),
+  ("OLL 54", "r U2 R' U' R U R' U' R U' r'"),
+  ("OLL 55", "R' F R U R U' R2 F' R2 U' R' U R U R'"),
+  ("OLL 56", "r' U' r U' R' U R U' R' U R r' U r"),
+  ("OLL 57", "R U R' U' M' U R U' r'"),
+];
+
+/// The PLL cases, by their usual name, with an algorithm that solves the
+/// last layer from each of them.
+pub(crate) const PLL_ALGORITHMS: [(&str, &str); 21] = [
+  ("Aa perm", "x R' U R' D2 R U' R' D2 R2 x'"),
+  ("Ab perm", "x R2 D2 R U R' D2 R U' R x'"),
+  ("E perm", "x' R U' R' D R U R' D' R U R' D R U' R' D' x"),
+  ("F perm", "R' U' F' R U R' U' R' F R2 U' R' U' R U R' U R"),
+  ("Ga perm", "R2 U R' U R' U' R U' R2 U' D R' U R D'"),
+  ("Gb perm", "R' U' R U D' R2 U R' U R U' R U' R2 D"),
+  ("Gc perm", "R2 U' R U' R U R' U R2 U D' R U' R' D"),
+  ("Gd perm", "R U R' U' D R2 U' R U' R' U R' U R2 D'"),
+  ("H perm", "M2 U M2 U2 M2 U M2"),
+  ("Ja perm", "x R2 F R F' R U2 r' U r U2 x'"),
+  ("Jb perm", "R U R' F' R U R' U' R' F R2 U' R'"),
+  (
+    "Na perm",
+    "R U R' U R U R' F' R U R' U' R' F R2 U' R' U2 R U' R'",
+  ),
+  ("Nb perm", "R' U R U' R' F' U' F R U R' F R' F' R U' R"),
+  ("Ra perm", "R U' R' U' R U R D R' U' R D' R' U2 R'"),
+  ("Rb perm", "R2 F R U R U' R' F' R U2 R' U2 R"),
+  ("T perm", "R U R' U' R' F R2 U' R' U' R U R' F'"),
+  ("Ua perm", "M2 U M U2 M' U M2"),
+  ("Ub perm", "M2 U' M U2 M' U' M2"),
+  ("V perm", "R' U R' U' y R' F' R2 U' R' U R' F R F"),
+  ("Y perm", "F R U' R' U' R U R' F' R U R' U' R' F R F'"),
+  ("Z perm", "M' U M2 U M2 U M' U2 M2"),
+];
+
+// All the permutations of four pieces.
+fn permutations_of_four() -> Vec<[usize; 4]> {
+  let mut perms = vec![];
+  for a in 0..4 {
+    for b in (0..4).filter(|&b| b != a) {
+      for c in (0..4).filter(|&c| c != a && c != b) {
+        perms.push([a, b, c, 6 - a - b - c]);
+      }
+    }
+  }
+  perms
+}
+
+/// Every solvable last layer with the first two layers solved, which the
+/// OLL and PLL algorithms are checked against. The corner orientations
+/// change slowest, then the edge orientations, the corner permutation and
+/// the edge permutation.
+pub(crate) fn last_layer_states() -> Vec<Cube> {
+  let mut cubes = vec![];
+  for co in 0..27 {
+    for eo in 0..8 {
+      for cp in permutations_of_four() {
+        for ep in permutations_of_four() {
+          let mut cube = Cube::solved();
+          for i in 0..4 {
+            cube.cp[i] = Corner::from(cp[i]);
+            cube.ep[i] = Edge::from(ep[i]);
+          }
+          for i in 0..3 {
+            cube.co[i] = (co / 3usize.pow(i as u32) % 3) as u8;
+            cube.eo[i] = (eo >> i & 1) as u8;
+          }
+          cube.co[3] = (6 - cube.co[..3].iter().sum::<u8>()) % 3;
+          cube.eo[3] = cube.eo[..3].iter().sum::<u8>() % 2;
+          if cube.has_valid_parity() {
+            cubes.push(cube);
+          }
+        }
+      }
+    }
+  }
+  cubes
+}
+
+/// The turns of U that line up the last layer before or after an algorithm,
+/// starting with none.
+pub(crate) const AUF: [&[Move]; 4] = [
+  &[],
+  &[Move(Face::U, Turn::Clockwise)],
+  &[Move(Face::U, Turn::Half)],
+  &[Move(Face::U, Turn::Counter)],
+];
+
+/// The stages of the CFOP method.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+  /// Solve the four D edges.
+  Cross,
+  /// Insert the corner and edge of a slot.
+  F2L(F2LSlot),
+  /// Orient the last layer.
+  Oll,
+  /// Permute the last layer.
+  Pll,
+}
+
+/// A step of a human solution, which solves one `Stage`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+  /// The stage that the step solves.
+  pub stage: Stage,
+  /// The name of the step, which is the case for OLL and PLL, such as
+  /// `OLL 27` or `T perm`, or `OLL skip` if the stage was already solved.
+  pub name: String,
+  /// The moves of the step, including any turns of U around an algorithm.
+  pub moves: Vec<Move>,
+}
+
+// An algorithm from one of the tables, expanded into face turns.
+pub(crate) struct Algorithm {
+  pub(crate) name: &'static str,
+  pub(crate) moves: Vec<Move>,
+  // The cube that the moves produce, to apply them all at once.
+  pub(crate) cube: Cube,
+}
+
+impl Algorithm {
+  pub(crate) fn new((name, alg): (&'static str, &str)) -> Algorithm {
+    let alg = parse_extended_algorithm(alg).expect("invalid table algorithm");
+    let (moves, _) = expand_algorithm(&alg, Orientation::identity());
+    let cube = Cube::from_algorithm(&moves);
+    Algorithm { name, moves, cube }
+  }
+}
+
+/// A solver that produces CFOP solutions: the cross, the four F2L pairs, and
+/// then the last layer with one OLL and one PLL algorithm.
+///
+/// The cross and each pair are found by `CrossSolver` and `F2LSolver`, so
+/// they are as short as possible for the pairs solved so far, and the last
+/// layer uses the embedded algorithm tables. The solutions are usually 50 to
+/// 70 moves, much longer than those of `solve`, but each step can be followed
+/// by a person.
+pub struct CfopSolver {
+  oll: Vec<Algorithm>,
+  pll: Vec<Algorithm>,
+}
+
+impl CfopSolver {
+  /// Expand the OLL and PLL algorithms. The tables of the cross and the F2L
+  /// pairs are shared with `CrossSolver::get` and `F2LSolver::get`.
+  pub fn new() -> CfopSolver {
+    CfopSolver {
+      oll: OLL_ALGORITHMS.iter().cloned().map(Algorithm::new).collect(),
+      pll: PLL_ALGORITHMS.iter().cloned().map(Algorithm::new).collect(),
+    }
+  }
+
+  /// The solver shared by the whole program, which is built on the first
+  /// call.
+  pub fn get() -> &'static CfopSolver {
+    static SOLVER: OnceLock<CfopSolver> = OnceLock::new();
+    SOLVER.get_or_init(CfopSolver::new)
+  }
+
+  /// Solve a cube, returning a step for the cross, one for each F2L slot in
+  /// the order they are solved, one for OLL and one for PLL. The cube must be
+  /// solvable.
+  pub fn solve(&self, cube: &Cube) -> Vec<Step> {
+    debug_assert!(cube.verify().is_ok());
+    let mut steps = vec![];
+    let mut cube = *cube;
+    let mut push = |cube: &mut Cube, step: Step| {
+      *cube = cube.apply_algorithm(&step.moves);
+      steps.push(step);
+    };
+
+    let moves = CrossSolver::get().solve(&cube, Face::D).swap_remove(0);
+    push(&mut cube, step(Stage::Cross, "Cross", moves));
+
+    // The slots that are already solved take no moves, and the rest are
+    // solved in the order of whichever is shortest next.
+    let mut solved = cube.solved_f2l_slots();
+    for &slot in &solved {
+      push(&mut cube, step(Stage::F2L(slot), slot_name(slot), vec![]));
+    }
+    while solved.len() < F2LSlot::ALL.len() {
+      let (slot, moves) = self.solve_pair(&cube, &solved);
+      solved.push(slot);
+      push(&mut cube, step(Stage::F2L(slot), slot_name(slot), moves));
+    }
+
+    let (name, moves) = self.solve_oll(&cube);
+    push(&mut cube, step(Stage::Oll, name, moves));
+    let (name, moves) = self.solve_pll(&cube);
+    push(&mut cube, step(Stage::Pll, name, moves));
+    debug_assert_eq!(Cube::solved(), cube);
+    steps
+  }
+
+  // Find the slot, other than the `solved` ones, that can be inserted with
+  // the fewest moves while keeping the cross and the `solved` slots.
+  fn solve_pair(
+    &self,
+    cube: &Cube,
+    solved: &[F2LSlot],
+  ) -> (F2LSlot, Vec<Move>) {
+    let f2l = F2LSolver::get();
+    F2LSlot::ALL
+      .iter()
+      .filter(|slot| !solved.contains(slot))
+      .map(|&slot| (slot, f2l.solve_pair(cube, slot).swap_remove(0)))
+      .min_by_key(|(_, moves)| moves.len())
+      .expect("every slot can be inserted")
+  }
+
+  // The shortest OLL algorithm, with a turn of U before it, that orients the
+  // last layer of `cube`.
+  fn solve_oll(&self, cube: &Cube) -> (&'static str, Vec<Move>) {
+    if cube.is_last_layer_oriented() {
+      return ("OLL skip", vec![]);
+    }
+    AUF
+      .iter()
+      .flat_map(|pre| self.oll.iter().map(move |alg| (pre, alg)))
+      .filter(|(pre, alg)| {
+        cube
+          .apply_algorithm(pre)
+          .compose(&alg.cube)
+          .is_last_layer_oriented()
+      })
+      .map(|(pre, alg)| (alg.name, [*pre, &alg.moves[..]].concat()))
+      .min_by_key(|(_, moves)| moves.len())
+      .expect("every OLL case is in the table")
+  }
+
+  // The shortest PLL algorithm, with turns of U before and after it, that
+  // solves `cube`, which must have an oriented last layer.
+  fn solve_pll(&self, cube: &Cube) -> (&'static str, Vec<Move>) {
+    if let Some(post) = AUF
+      .iter()
+      .find(|post| cube.apply_algorithm(post) == Cube::solved())
+    {
+      return ("PLL skip", post.to_vec());
+    }
+    let mut best: Option<(&'static str, Vec<Move>)> = None;
+    for pre in &AUF {
+      for alg in &self.pll {
+        let cube = cube.apply_algorithm(pre).compose(&alg.cube);
+        for post in &AUF {
+          if cube.apply_algorithm(post) != Cube::solved() {
+            continue;
+          }
+          let moves = [*pre, &alg.moves[..], *post].concat();
+          if best.as_ref().is_none_or(|(_, b)| moves.len() < b.len()) {
+            best = Some((alg.name, moves));
+          }
+        }
+      }
+    }
+    best.expect("every PLL case is in the table")
+  }
+}
+
+impl Default for CfopSolver {
+  fn default() -> CfopSolver {
+    CfopSolver::new()
+  }
+}
+
+fn step(stage: Stage, name: &str, moves: Vec<Move>) -> Step {
+  Step {
+    stage,
+    name: name.to_string(),
+    moves,
+  }
+}
+
+fn slot_name(slot: F2LSlot) -> &'static str {
+  match slot {
+    F2LSlot::FR => "F2L FR",
+    F2LSlot::FL => "F2L FL",
+    F2LSlot::BL => "F2L BL",
+    F2LSlot::BR => "F2L BR",
+  }
+}
+
+/// Solve a cube with the CFOP method, using the solver from
+/// `CfopSolver::get`, for trainers that want a solution a person could
+/// follow rather than the shortest one.
+pub fn solve_cfop(cube: &Cube) -> Vec<Step> {
+  CfopSolver::get().solve(cube)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+
+  #[test]
+  fn oll_table() {
+    let solver = CfopSolver::get();
+    for alg in &solver.oll {
+      assert!(alg.cube.is_f2l_solved(), "{} breaks F2L", alg.name);
+      assert!(!alg.cube.is_last_layer_oriented(), "{}", alg.name);
+    }
+
+    // Every orientation of the last layer is solved by exactly one case, up
+    // to a turn of U.
+    let mut cases = vec![];
+    let solved = Cube::solved();
+    let states = last_layer_states().into_iter();
+    for cube in states.filter(|c| c.cp == solved.cp && c.ep == solved.ep) {
+      let (name, moves) = solver.solve_oll(&cube);
+      assert!(cube.apply_algorithm(&moves).is_last_layer_oriented());
+      cases.push(name);
+    }
+    cases.sort();
+    cases.dedup();
+    assert_eq!(58, cases.len());
+  }
+
+  #[test]
+  fn pll_table() {
+    let solver = CfopSolver::get();
+    for alg in &solver.pll {
+      assert!(alg.cube.is_last_layer_oriented(), "{} breaks OLL", alg.name);
+    }
+
+    let mut cases = vec![];
+    let solved = Cube::solved();
+    let states = last_layer_states().into_iter();
+    for cube in states.filter(|c| c.co == solved.co && c.eo == solved.eo) {
+      let (name, moves) = solver.solve_pll(&cube);
+      assert_eq!(Cube::solved(), cube.apply_algorithm(&moves));
+      cases.push(name);
+    }
+    cases.sort();
+    cases.dedup();
+    assert_eq!(22, cases.len());
+  }
+
+  #[test]
+  fn solve() {
+    let steps = solve_cfop(&Cube::solved());
+    let names: Vec<&str> = steps.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+      vec![
+        "Cross", "F2L FR", "F2L FL", "F2L BL", "F2L BR", "OLL skip", "PLL skip"
+      ],
+      names
+    );
+    assert!(steps.iter().all(|s| s.moves.is_empty()));
+
+    let scramble = parse_algorithm("D2 R U2 F' L B2 R' U F").unwrap();
+    let mut cube = Cube::from_algorithm(&scramble);
+    let steps = solve_cfop(&cube);
+    assert_eq!(7, steps.len());
+    for step in &steps {
+      cube = cube.apply_algorithm(&step.moves);
+      match step.stage {
+        Stage::Cross => assert!(cube.is_cross_solved()),
+        Stage::F2L(slot) => assert!(cube.is_f2l_slot_solved(slot)),
+        Stage::Oll => assert!(cube.is_last_layer_oriented()),
+        Stage::Pll => assert_eq!(Cube::solved(), cube),
+      }
+    }
+  }
+}
@@ -0,0 +1,208 @@
+use cube::{algorithm_length, Cube, Move};
+use phase0::{Phase0Coord, Phase0Tables};
+use phase1::Phase1Tables;
+use two_phase::{Improving, Solution};
+
+/// A two-phase solver for a cube that keeps being turned, such as the cube
+/// of an interactive app, which can be solved again after every few moves.
+///
+/// The `Phase0Coord` of the cube is updated with the transition tables as
+/// each move is made. The last search also found the length of the shortest
+/// reduction to G1, and a move changes that length by at most its cost, so
+/// the next search skips the reductions that are too short to exist.
+/// Following the last solution does not search at all.
+pub struct IncrementalSolver<'a> {
+  tables0: Phase0Tables<'a>,
+  tables1: Phase1Tables<'a>,
+  cube: Cube,
+  coord: Phase0Coord,
+  // A lower bound of the length of the reductions of `coord`.
+  min_depth0: usize,
+  // The last solution, without the moves that followed it.
+  solution: Option<Solution>,
+  // The moves made since `solution` that did not follow it.
+  moves: Vec<Move>,
+}
+
+impl<'a> IncrementalSolver<'a> {
+  /// Create a solver for `cube`, which searches with `tables0` and
+  /// `tables1`.
+  pub fn new(
+    cube: &Cube,
+    tables0: &Phase0Tables<'a>,
+    tables1: &Phase1Tables<'a>,
+  ) -> IncrementalSolver<'a> {
+    IncrementalSolver {
+      tables0: *tables0,
+      tables1: *tables1,
+      cube: *cube,
+      coord: Phase0Coord::from(*cube),
+      min_depth0: 0,
+      solution: None,
+      moves: vec![],
+    }
+  }
+
+  /// The cube, after the moves made so far.
+  pub fn cube(&self) -> &Cube {
+    &self.cube
+  }
+
+  /// The `Phase0Coord` of the cube.
+  pub fn coord(&self) -> Phase0Coord {
+    self.coord
+  }
+
+  /// Make the move `m` on the cube.
+  pub fn apply_move(&mut self, m: Move) {
+    self.cube = self.cube.apply_move(m);
+    self.coord = self.tables0.transition(self.coord, m);
+    let cost = self.tables0.metric.move_cost(m);
+    self.min_depth0 = self.min_depth0.saturating_sub(cost);
+    let followed = match self.solution {
+      Some(ref mut solution) if self.moves.is_empty() => follow(solution, m),
+      _ => false,
+    };
+    if !followed {
+      self.moves.push(m);
+    }
+  }
+
+  /// Make each of `moves` on the cube.
+  pub fn apply_algorithm(&mut self, moves: &[Move]) {
+    for &m in moves {
+      self.apply_move(m);
+    }
+  }
+
+  /// Solve the cube, with a solution of at most `max_length` moves, or
+  /// `None` if there is none.
+  ///
+  /// If only the moves of the last solution were made, the rest of it is
+  /// returned. Otherwise undoing the moves and following the last solution
+  /// is also a solution, which is returned if the search does not find a
+  /// shorter one.
+  pub fn solve(&mut self, max_length: usize) -> Option<Vec<Move>> {
+    let metric = self.tables0.metric;
+    if let (true, Some(solution)) = (self.moves.is_empty(), &self.solution) {
+      if solution.length(metric) <= max_length {
+        return Some(solution.moves());
+      }
+    }
+    // The last solution and the moves are only replaced once a new solution
+    // is found, so a search that fails or is stopped can be retried.
+    let fallback = self.solution.as_ref().map(|s| s.after(&self.moves));
+    let mut solutions = Improving::from_coord(
+      &self.cube,
+      self.coord,
+      self.min_depth0,
+      &self.tables0,
+      &self.tables1,
+      None,
+    );
+    let mut best = match solutions.next() {
+      Some(first) => {
+        // The first solution has the shortest reduction.
+        self.min_depth0 = algorithm_length(&first.phase0, metric);
+        match fallback {
+          Some(f) if f.length(metric) < first.length(metric) => f,
+          _ => first,
+        }
+      }
+      None => fallback?,
+    };
+    while best.length(metric) > max_length {
+      match solutions.next() {
+        Some(solution) => best = solution,
+        None => return None,
+      }
+    }
+    let moves = best.moves();
+    self.solution = Some(best);
+    self.moves.clear();
+    Some(moves)
+  }
+}
+
+// Remove `m` from the start of `solution` if it is its first move, returning
+// whether it was.
+fn follow(solution: &mut Solution, m: Move) -> bool {
+  if solution.phase0.first() == Some(&m) {
+    solution.phase0.remove(0);
+    true
+  } else if solution.phase0.is_empty() && solution.phase1.first() == Some(&m) {
+    solution.g1 = solution.g1.apply_move(m);
+    solution.phase1.remove(0);
+    true
+  } else {
+    false
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, Face, Turn};
+  use std::sync::atomic::{AtomicBool, Ordering};
+  use tables::Tables;
+  use two_phase::two_phase;
+
+  #[test]
+  fn resolve() {
+    let (tables0, tables1) = (Tables::get().phase0(), Tables::get().phase1());
+    let scramble = parse_algorithm("R U R' U' F2 D L' B").unwrap();
+    let cube = Cube::from_algorithm(&scramble);
+    let mut solver = IncrementalSolver::new(&cube, &tables0, &tables1);
+    let solution = solver.solve(30).unwrap();
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+
+    // Following the solution keeps the rest of it.
+    solver.apply_algorithm(&solution[..2]);
+    assert_eq!(Some(solution[2..].to_vec()), solver.solve(30));
+
+    // Other moves update the coordinate and search again.
+    let moves = parse_algorithm("R U").unwrap();
+    solver.apply_algorithm(&moves);
+    let cube = cube.apply_algorithm(&solution[..2]).apply_algorithm(&moves);
+    assert_eq!(cube, *solver.cube());
+    assert_eq!(Phase0Coord::from(cube), solver.coord());
+    let resolved = solver.solve(30).unwrap();
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&resolved));
+    let fresh = two_phase(&cube, &tables0, &tables1, 30).unwrap();
+    assert!(resolved.len() <= fresh.len());
+    assert!(resolved.len() <= solution.len());
+
+    assert_eq!(None, solver.solve(2));
+    let mut solved =
+      IncrementalSolver::new(&Cube::solved(), &tables0, &tables1);
+    assert_eq!(Some(vec![]), solved.solve(0));
+  }
+
+  #[test]
+  fn failed_search() {
+    let stop = AtomicBool::new(false);
+    let tables0 = Tables::get().phase0().with_stop_flag(&stop);
+    let tables1 = Tables::get().phase1().with_stop_flag(&stop);
+    let scramble = parse_algorithm("R U R' U' F2 D L' B").unwrap();
+    let cube = Cube::from_algorithm(&scramble);
+    let mut solver = IncrementalSolver::new(&cube, &tables0, &tables1);
+    let solution = solver.solve(30).unwrap();
+
+    // With the search stopped, only undoing the moves and following the last
+    // solution solves the cube, so a search that fails must keep them.
+    stop.store(true, Ordering::Relaxed);
+    let m = Move(Face::R, Turn::Clockwise);
+    solver.apply_move(m);
+    assert_eq!(None, solver.solve(2));
+    let resolved = solver.solve(30).unwrap();
+    assert!(resolved.len() <= solution.len() + 1);
+    assert_eq!(
+      Cube::solved(),
+      cube.apply_move(m).apply_algorithm(&resolved)
+    );
+
+    // The new solution is followed without searching.
+    solver.apply_move(resolved[0]);
+    assert_eq!(Some(resolved[1..].to_vec()), solver.solve(30));
+  }
+}
@@ -1,27 +1,214 @@
 extern crate cube;
-
 #[cfg(test)]
 #[macro_use]
-extern crate lazy_static;
+extern crate proptest;
+extern crate rand;
+#[cfg(feature = "parallel")]
+extern crate rayon;
+#[cfg(feature = "tracing")]
+extern crate tracing;
+#[cfg(feature = "wasm")]
+extern crate wasm_bindgen;
+
+#[macro_use]
+mod trace;
 
+#[cfg(any(test, feature = "testing"))]
+mod brute_force;
+mod canonical;
+mod coord_cube;
+mod coset;
+mod cross;
+mod cube2;
+mod dedup;
+mod error;
+mod execution;
+mod f2l;
+mod handoff;
+mod human;
+mod incremental;
+mod optimal;
+mod pattern_database;
 mod phase0;
 mod phase1;
 mod pruning_table;
+mod puzzle;
+mod recognition;
+mod restricted;
+mod roux;
+mod stats;
+mod symmetry;
+mod tables;
+mod thistlethwaite;
 mod transition_table;
+mod two_phase;
+#[cfg(feature = "wasm")]
+mod wasm;
+mod wca;
+
+#[cfg(any(test, feature = "testing"))]
+pub use brute_force::brute_force;
+
+pub use canonical::canonical_sequences;
+pub use canonical::is_canonical;
+pub use canonical::CanonicalSequences;
+
+pub use coord_cube::CoordCube;
+
+pub use coset::g1_elements;
+pub use coset::random_g1;
+pub use coset::G1Coset;
+pub use coset::NUM_G1_COSETS;
+
+pub use cross::solve_crosses;
+pub use cross::CrossSolver;
+
+pub use cube2::solve_cube2;
+pub use cube2::Cube2Solver;
+
+pub use dedup::dedup_solutions;
+pub use dedup::Duplicates;
+
+pub use error::Error;
+pub use error::TableLoadError;
+
+pub use execution::execute_with_faces;
+
+pub use f2l::solve_f2l_pair;
+pub use f2l::F2LSolver;
+pub use f2l::PairInsertion;
+
+pub use handoff::HandoffCoord;
+pub use handoff::HandoffTables;
+
+pub use human::solve_cfop;
+pub use human::CfopSolver;
+pub use human::Stage;
+pub use human::Step;
+
+pub use incremental::IncrementalSolver;
+
+pub use optimal::solve_optimal;
+pub use optimal::OptimalSolver;
+
+pub use pattern_database::PatternDatabase;
+pub use pattern_database::PieceSet;
 
 pub use phase0::phase0;
+pub use phase0::phase0_all;
+pub use phase0::phase0_g1;
+pub use phase0::solve_phase0;
+pub use phase0::Phase0Coord;
+pub use phase0::Phase0Tables;
 pub use phase1::phase1;
+pub use phase1::solve_phase1;
+pub use phase1::Phase1Coord;
+pub use phase1::Phase1Tables;
 
 pub use pruning_table::get_co_prune_table;
 pub use pruning_table::get_cp_prune_table;
+pub use pruning_table::get_cp_ud2_prune_table;
+pub use pruning_table::get_cp_ud2_sym_prune_table;
 pub use pruning_table::get_eo_prune_table;
+pub use pruning_table::get_eo_ud1_prune_table;
+pub use pruning_table::get_eo_ud1_sym_prune_table;
 pub use pruning_table::get_ep_prune_table;
 pub use pruning_table::get_ud1_prune_table;
 pub use pruning_table::get_ud2_prune_table;
+pub use pruning_table::PruneTable;
+
+pub use puzzle::solve_pyraminx;
+pub use puzzle::PuzzleDatabase;
+pub use puzzle::PyraminxSolver;
+
+pub use recognition::coll_cases;
+pub use recognition::recognize_coll;
+pub use recognition::recognize_oll;
+pub use recognition::recognize_pll;
+pub use recognition::recognize_zbll;
+pub use recognition::zbll_cases;
+pub use recognition::CollCase;
+pub use recognition::OllCase;
+pub use recognition::PllCase;
+pub use recognition::ZbllCase;
+
+pub use restricted::solve_restricted;
+pub use restricted::RestrictedSolver;
+
+pub use roux::solve_roux;
+pub use roux::RouxSolver;
+pub use roux::RouxStage;
+pub use roux::RouxStep;
+
+pub use stats::coord_distribution;
+pub use stats::prune_table_distribution;
+pub use stats::sample_distribution;
+pub use stats::with_stats;
+pub use stats::DepthDistribution;
+pub use stats::PhaseStats;
+pub use stats::SearchStats;
+
+pub use symmetry::SymPruneTable;
+
+pub use tables::load_tables;
+pub use tables::save_tables;
+pub use tables::validate_tables;
+pub use tables::TableProgress;
+pub use tables::Tables;
+
+pub use thistlethwaite::solve_thistlethwaite;
+pub use thistlethwaite::Thistlethwaite;
 
 pub use transition_table::get_co_transition_table;
+pub use transition_table::get_cp_g0_transition_table;
 pub use transition_table::get_cp_transition_table;
+pub use transition_table::get_cross_coord;
+pub use transition_table::get_cross_transition_table;
+pub use transition_table::get_edge_group_coord;
+pub use transition_table::get_edge_group_transition_table;
 pub use transition_table::get_eo_transition_table;
 pub use transition_table::get_ep_transition_table;
+pub use transition_table::get_m_slice_transition_table;
+pub use transition_table::get_pair_coord;
+pub use transition_table::get_pair_transition_table;
 pub use transition_table::get_ud1_transition_table;
 pub use transition_table::get_ud2_transition_table;
+pub use transition_table::init_transition_table;
+pub use transition_table::move_index;
+pub use transition_table::Coord;
+pub use transition_table::Group;
+pub use transition_table::TransitionTable;
+pub use transition_table::G1_MOVES;
+pub use transition_table::NUM_MOVES;
+pub use transition_table::{COCoord, EOCoord, UD1Coord};
+pub use transition_table::{CPCoord, EPCoord, MSliceCoord, UD2Coord};
+pub use transition_table::{CrossCoord, EdgeGroupCoord, PairCoord};
+
+pub use two_phase::solve;
+pub use two_phase::solve_optimal_two_phase;
+pub use two_phase::solve_with_config;
+pub use two_phase::try_solve_with_config;
+pub use two_phase::two_phase;
+pub use two_phase::two_phase_combined;
+pub use two_phase::two_phase_constrained;
+pub use two_phase::two_phase_observed;
+#[cfg(feature = "parallel")]
+pub use two_phase::two_phase_parallel;
+pub use two_phase::two_phase_resolve;
+pub use two_phase::two_phase_with_algs;
+pub use two_phase::two_phase_with_config;
+pub use two_phase::Constraints;
+pub use two_phase::Observer;
+pub use two_phase::Progress;
+pub use two_phase::Solution;
+pub use two_phase::Solver;
+pub use two_phase::SolverConfig;
+pub use two_phase::SolverHandle;
+pub use two_phase::TwoPhaseSolver;
+
+pub use wca::random_state_scramble;
+pub use wca::scramble_round;
+pub use wca::scramble_set;
+pub use wca::ScrambleSet;
+pub use wca::EXTRAS_PER_ROUND;
+pub use wca::SCRAMBLES_PER_ROUND;
@@ -0,0 +1,165 @@
+use canonical::{skip_face, SEARCH_FACES};
+use cube::{Cube, Move, Turn};
+use pattern_database::{PatternDatabase, PieceSet};
+use std::sync::OnceLock;
+
+/// An optimal solver in the style of Korf, which runs IDA* with the largest
+/// lower bound of several pattern databases, so the first solution found is
+/// a shortest one.
+///
+/// Korf used a database of all the corners and two databases of six edges
+/// each, which are too large for the `HashMap` of a `PatternDatabase`.
+/// `OptimalSolver::get` uses four corners and two groups of four edges
+/// instead, which take a few seconds to build but only keep the search
+/// practical for cubes up to about 11 moves from solved.
+pub struct OptimalSolver {
+  databases: Vec<PatternDatabase>,
+}
+
+impl OptimalSolver {
+  /// Create a solver with the lower bounds of `databases`, which must have
+  /// been built with all 18 moves.
+  pub fn new(databases: Vec<PatternDatabase>) -> OptimalSolver {
+    OptimalSolver { databases }
+  }
+
+  /// The solver shared by the whole program, which builds its databases on
+  /// the first call.
+  pub fn get() -> &'static OptimalSolver {
+    static SOLVER: OnceLock<OptimalSolver> = OnceLock::new();
+    SOLVER.get_or_init(|| {
+      use cube::Corner::*;
+      use cube::Edge::*;
+      let corners = PieceSet::new(&[URF, UFL, ULB, UBR], &[]);
+      let u_edges = PieceSet::new(&[], &[UR, UF, UL, UB]);
+      let d_edges = PieceSet::new(&[], &[DR, DF, DL, DB]);
+      OptimalSolver::new(vec![
+        PatternDatabase::new(corners),
+        PatternDatabase::new(u_edges),
+        PatternDatabase::new(d_edges),
+      ])
+    })
+  }
+
+  // The largest lower bound of the databases.
+  fn lower_bound(&self, cube: &Cube) -> usize {
+    self
+      .databases
+      .iter()
+      .map(|db| db.distance(cube).unwrap_or(usize::MAX))
+      .max()
+      .unwrap_or(0)
+  }
+
+  /// Find a shortest solution of at most `max_depth` moves, or `None` if
+  /// there is none.
+  pub fn solve(&self, cube: &Cube, max_depth: usize) -> Option<Vec<Move>> {
+    let moves: Vec<Move> = SEARCH_FACES
+      .iter()
+      .flat_map(|&f| Turn::ALL.iter().map(move |&t| Move(f, t)))
+      .collect();
+    ida_star(cube, &moves, max_depth, &|cube| self.lower_bound(cube))
+  }
+}
+
+// Search for a solution of exactly `depth` more moves after `solution`.
+fn search(
+  cube: &Cube,
+  moves: &[Move],
+  depth: usize,
+  lower_bound: &dyn Fn(&Cube) -> usize,
+  solution: &mut Vec<Move>,
+) -> bool {
+  if depth == 0 {
+    return *cube == Cube::solved();
+  }
+  if lower_bound(cube) > depth {
+    return false;
+  }
+  for &m in moves {
+    if redundant(moves, solution, m) {
+      continue;
+    }
+    solution.push(m);
+    if search(&cube.apply_move(m), moves, depth - 1, lower_bound, solution) {
+      return true;
+    }
+    solution.pop();
+  }
+  false
+}
+
+// Check if `m` can be skipped after `solution`. The rules of `skip_face` only
+// hold for a face when every turn of it is in `moves`, since otherwise two
+// turns of the face may not merge into one move, so other faces only skip
+// the move that undoes the last one.
+fn redundant(moves: &[Move], solution: &[Move], m: Move) -> bool {
+  let Move(face, _) = m;
+  if Turn::ALL.iter().all(|&t| moves.contains(&Move(face, t))) {
+    return skip_face(solution, face);
+  }
+  solution.last() == Some(&m.inverse())
+}
+
+/// Find a shortest solution of at most `max_depth` moves that only turns
+/// with `moves`, or `None` if there is none, with IDA*.
+///
+/// `lower_bound` must never be more than the number of moves needed to solve
+/// a cube, and can be `usize::MAX` for cubes that `moves` can not solve. The
+/// moves are tried in the order of `moves`.
+pub(crate) fn ida_star(
+  cube: &Cube,
+  moves: &[Move],
+  max_depth: usize,
+  lower_bound: &dyn Fn(&Cube) -> usize,
+) -> Option<Vec<Move>> {
+  let mut solution = vec![];
+  (lower_bound(cube)..=max_depth)
+    .find(|&depth| search(cube, moves, depth, lower_bound, &mut solution))
+    .map(|_| solution)
+}
+
+/// Find a shortest solution of at most `max_depth` moves with the solver
+/// from `OptimalSolver::get`, or `None` if there is none.
+///
+/// Unlike `solve`, the solution is never longer than needed, but the search
+/// takes exponentially longer for deeper cubes.
+pub fn solve_optimal(cube: &Cube, max_depth: usize) -> Option<Vec<Move>> {
+  OptimalSolver::get().solve(cube, max_depth)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use brute_force::brute_force;
+  use cube::{parse_algorithm, Corner, Edge, Face};
+
+  #[test]
+  fn optimal() {
+    let solver = OptimalSolver::new(vec![
+      PatternDatabase::new(PieceSet::new(&[Corner::URF, Corner::DRB], &[])),
+      PatternDatabase::new(PieceSet::new(&[], &[Edge::UF, Edge::FR, Edge::DB])),
+    ]);
+    assert_eq!(Some(vec![]), solver.solve(&Cube::solved(), 0));
+
+    for alg in &["R U R' U'", "F2 D L' B U2 R'", "R U F' D2 L B'"] {
+      let cube = Cube::from_algorithm(&parse_algorithm(alg).unwrap());
+      let solution = solver.solve(&cube, 8).unwrap();
+      assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+      assert_eq!(brute_force(&cube, 8).unwrap().len(), solution.len());
+      assert_eq!(None, solver.solve(&cube, solution.len() - 1));
+    }
+  }
+
+  #[test]
+  fn shared_solver() {
+    let cube = Cube::from_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Half),
+      Move(Face::R, Turn::Clockwise),
+    ]);
+    let solution = solve_optimal(&cube, 5).unwrap();
+    assert_eq!(3, solution.len());
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+  }
+}
@@ -0,0 +1,233 @@
+use cube::{Corner, Cube, Edge, Face, Move, Turn, NUM_CORNERS, NUM_EDGES};
+use std::collections::HashMap;
+
+/// The corners and edges tracked by a `PatternDatabase`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct PieceSet {
+  corners: Vec<Corner>,
+  edges: Vec<Edge>,
+}
+
+impl PieceSet {
+  /// Track the pieces in `corners` and `edges`.
+  pub fn new(corners: &[Corner], edges: &[Edge]) -> PieceSet {
+    PieceSet {
+      corners: corners.to_vec(),
+      edges: edges.to_vec(),
+    }
+  }
+
+  /// The tracked corners.
+  pub fn corners(&self) -> &[Corner] {
+    &self.corners
+  }
+
+  /// The tracked edges.
+  pub fn edges(&self) -> &[Edge] {
+    &self.edges
+  }
+
+  /// The pattern of `cube`, which is `Cube::pack` of the cube with every
+  /// untracked corner replaced by the first untracked corner, every untracked
+  /// edge replaced by the first untracked edge, and their orientations
+  /// cleared. Two cubes have the same pattern exactly when their tracked
+  /// pieces are in the same positions and orientations.
+  pub fn pattern(&self, cube: &Cube) -> u128 {
+    let mut masked = *cube;
+    let mut corners = (0..NUM_CORNERS).map(Corner::from);
+    if let Some(blank) = corners.find(|c| !self.corners.contains(c)) {
+      for i in 0..NUM_CORNERS {
+        if !self.corners.contains(&masked.cp[i]) {
+          masked.cp[i] = blank;
+          masked.co[i] = 0;
+        }
+      }
+    }
+    let mut edges = (0..NUM_EDGES).map(Edge::from);
+    if let Some(blank) = edges.find(|e| !self.edges.contains(e)) {
+      for i in 0..NUM_EDGES {
+        if !self.edges.contains(&masked.ep[i]) {
+          masked.ep[i] = blank;
+          masked.eo[i] = 0;
+        }
+      }
+    }
+    masked.pack()
+  }
+}
+
+/// An exact-distance pattern database, which maps each pattern of a
+/// `PieceSet` to the minimum number of moves needed to solve its pieces.
+///
+/// This generalises the pruning tables of the two-phase algorithm to any
+/// subset of the pieces, such as the four cross edges, the pieces of an F2L
+/// slot or four corners. The patterns are found by a breadth-first search
+/// from the solved cube, so the database only fits in memory for small sets
+/// of pieces: four edges have 190080 patterns, and every extra edge
+/// multiplies that by up to 16.
+pub struct PatternDatabase {
+  pieces: PieceSet,
+  moves: Vec<Move>,
+  distances: HashMap<u128, usize>,
+}
+
+impl PatternDatabase {
+  /// Build the database of `pieces` for all 18 moves.
+  pub fn new(pieces: PieceSet) -> PatternDatabase {
+    let faces = [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L];
+    let moves: Vec<Move> = faces
+      .iter()
+      .flat_map(|&f| Turn::ALL.iter().map(move |&t| Move(f, t)))
+      .collect();
+    PatternDatabase::with_moves(pieces, &moves)
+  }
+
+  /// Build the database of `pieces`, where the pieces can only be solved
+  /// with `moves`.
+  pub fn with_moves(pieces: PieceSet, moves: &[Move]) -> PatternDatabase {
+    let mut distances = HashMap::new();
+    distances.insert(pieces.pattern(&Cube::solved()), 0);
+    // One cube for each pattern at the current depth. The search undoes the
+    // moves, so that it finds the distance to the solved pattern even if
+    // `moves` does not hold the inverse of each move.
+    let mut frontier = vec![Cube::solved()];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+      depth += 1;
+      let mut next = vec![];
+      for cube in &frontier {
+        for &m in moves {
+          let cube = cube.apply_move(m.inverse());
+          distances.entry(pieces.pattern(&cube)).or_insert_with(|| {
+            next.push(cube);
+            depth
+          });
+        }
+      }
+      frontier = next;
+    }
+    PatternDatabase {
+      pieces,
+      moves: moves.to_vec(),
+      distances,
+    }
+  }
+
+  /// The tracked pieces.
+  pub fn pieces(&self) -> &PieceSet {
+    &self.pieces
+  }
+
+  /// The number of patterns.
+  pub fn len(&self) -> usize {
+    self.distances.len()
+  }
+
+  /// Check if the database has no patterns, which is never the case.
+  pub fn is_empty(&self) -> bool {
+    self.distances.is_empty()
+  }
+
+  /// The largest number of moves needed to solve any pattern.
+  pub fn max_distance(&self) -> usize {
+    self.distances.values().cloned().max().unwrap_or(0)
+  }
+
+  /// The minimum number of moves needed to solve the tracked pieces of
+  /// `cube`, which is a lower bound for solving the whole cube.
+  ///
+  /// Returns `None` if the pieces can not be solved with the moves of the
+  /// database.
+  pub fn distance(&self, cube: &Cube) -> Option<usize> {
+    self.distances.get(&self.pieces.pattern(cube)).cloned()
+  }
+
+  /// A shortest sequence of moves that solves the tracked pieces of `cube`,
+  /// found by following moves that reduce the distance.
+  ///
+  /// Returns `None` if the pieces can not be solved with the moves of the
+  /// database.
+  pub fn solve(&self, cube: &Cube) -> Option<Vec<Move>> {
+    let mut cube = *cube;
+    let mut distance = self.distance(&cube)?;
+    let mut solution = Vec::with_capacity(distance);
+    while distance > 0 {
+      let m = *self
+        .moves
+        .iter()
+        .find(|&&m| self.distance(&cube.apply_move(m)) == Some(distance - 1))
+        .expect("every pattern has a neighbour that is one move closer");
+      cube = cube.apply_move(m);
+      solution.push(m);
+      distance -= 1;
+    }
+    Some(solution)
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+  use transition_table::G1_MOVES;
+
+  #[test]
+  fn cross() {
+    let cross = PieceSet::new(&[], &[Edge::DR, Edge::DF, Edge::DL, Edge::DB]);
+    let db = PatternDatabase::new(cross.clone());
+    // 12 * 11 * 10 * 9 positions, with 2^4 orientations.
+    assert_eq!(190080, db.len());
+    // Every cross can be solved in at most 8 moves.
+    assert_eq!(8, db.max_distance());
+    assert_eq!(Some(0), db.distance(&Cube::solved()));
+
+    // The U layer does not move the cross.
+    let u = Cube::from_algorithm(&[Move(Face::U, Turn::Clockwise)]);
+    assert_eq!(cross.pattern(&Cube::solved()), cross.pattern(&u));
+    assert_eq!(Some(0), db.distance(&u));
+    let r = Cube::from_algorithm(&[Move(Face::R, Turn::Clockwise)]);
+    assert_eq!(Some(1), db.distance(&r));
+
+    let scramble = parse_algorithm("R U F' D2 L B' R2 U' F D").unwrap();
+    let cube = Cube::from_algorithm(&scramble);
+    let solution = db.solve(&cube).unwrap();
+    assert_eq!(db.distance(&cube), Some(solution.len()));
+    let solved = cube.apply_algorithm(&solution);
+    assert_eq!(cross.pattern(&Cube::solved()), cross.pattern(&solved));
+  }
+
+  #[test]
+  fn corners_with_g1_moves() {
+    let corners = [Corner::URF, Corner::UFL, Corner::ULB, Corner::UBR];
+    let db =
+      PatternDatabase::with_moves(PieceSet::new(&corners, &[]), G1_MOVES);
+    // G1 keeps the corners oriented, and the U corners can reach any 4 of
+    // the 8 positions in any order.
+    assert_eq!(8 * 7 * 6 * 5, db.len());
+    let twisted = Cube::from_algorithm(&[Move(Face::R, Turn::Clockwise)]);
+    assert_eq!(None, db.distance(&twisted));
+    assert_eq!(None, db.solve(&twisted));
+
+    let cube = Cube::from_algorithm(&[
+      Move(Face::R, Turn::Half),
+      Move(Face::U, Turn::Clockwise),
+    ]);
+    assert_eq!(Some(2), db.distance(&cube));
+    assert_eq!(2, db.solve(&cube).unwrap().len());
+  }
+
+  #[test]
+  fn moves_without_inverses() {
+    let r = Move(Face::R, Turn::Clockwise);
+    let u = Move(Face::U, Turn::Clockwise);
+    let pieces = PieceSet::new(&[Corner::URF, Corner::UBR], &[]);
+    let db = PatternDatabase::with_moves(pieces, &[r, u]);
+    // R is undone by three more R turns.
+    let cube = Cube::from_algorithm(&[r]);
+    assert_eq!(Some(3), db.distance(&cube));
+    assert_eq!(Some(vec![r, r, r]), db.solve(&cube));
+    let cube = Cube::from_algorithm(&[r.inverse()]);
+    assert_eq!(Some(1), db.distance(&cube));
+    assert_eq!(Some(vec![r]), db.solve(&cube));
+  }
+}
@@ -1,20 +1,36 @@
-use cube::{Cube, Face, Move};
+use canonical::{skip_face, SEARCH_FACES};
+use cube::{algorithm_length, Cube, Face, Metric, Move, Turn};
+use pruning_table::PruneTable;
+use stats::record;
 use std::cmp::max;
+use std::sync::atomic::{AtomicBool, Ordering};
+use symmetry::SymPruneTable;
 use transition_table::COCoord;
 use transition_table::Coord;
 use transition_table::EOCoord;
+use transition_table::TransitionTable;
 use transition_table::UD1Coord;
+use transition_table::NUM_MOVES;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Phase0Coord {
-  eo: usize,
-  co: usize,
-  ud1: usize,
+  eo: EOCoord,
+  co: COCoord,
+  ud1: UD1Coord,
 }
 
 impl Phase0Coord {
-  fn is_solved(&self) -> bool {
-    self.eo == 0 && self.co == 0 && self.ud1 == 0
+  pub(crate) fn is_solved(&self) -> bool {
+    self.eo == EOCoord::from(0)
+      && self.co == COCoord::from(0)
+      && self.ud1 == UD1Coord::from(0)
+  }
+
+  // Set the orientations of `cube`, which must have every piece oriented,
+  // to the EO and CO coordinates.
+  pub(crate) fn set_orientations(&self, cube: &mut Cube) {
+    EOCoord::set_coord(cube, self.eo);
+    COCoord::set_coord(cube, self.co);
   }
 }
 
@@ -28,30 +44,94 @@ impl From<Cube> for Phase0Coord {
   }
 }
 
+#[derive(Clone, Copy)]
 pub struct Phase0Tables<'a> {
-  eo_t: &'a [[usize; 6]],
-  co_t: &'a [[usize; 6]],
-  ud1_t: &'a [[usize; 6]],
-  eo_p: &'a [usize],
-  co_p: &'a [usize],
-  ud1_p: &'a [usize],
+  eo_t: &'a TransitionTable<EOCoord>,
+  co_t: &'a TransitionTable<COCoord>,
+  ud1_t: &'a TransitionTable<UD1Coord>,
+  co_p: &'a PruneTable<COCoord>,
+  eo_ud1_p: &'a SymPruneTable<UD1Coord, EOCoord>,
+  order_moves: bool,
+  stop: Option<&'a AtomicBool>,
+  pub(crate) metric: Metric,
 }
 
 impl<'a> Phase0Tables<'a> {
-  // The new `Phase0Coord` after doing the `face` move.
-  // note: This only does quarter turns.
-  fn transition(&self, coord: Phase0Coord, face: Face) -> Phase0Coord {
-    let eo = self.eo_t[coord.eo][usize::from(face)];
-    let co = self.co_t[coord.co][usize::from(face)];
-    let ud1 = self.ud1_t[coord.ud1][usize::from(face)];
+  /// Create the `Phase0Tables` from the G0 transition tables, the CO pruning
+  /// table and the combined EO and UD1 pruning table, reduced by symmetry.
+  pub fn new(
+    eo_t: &'a TransitionTable<EOCoord>,
+    co_t: &'a TransitionTable<COCoord>,
+    ud1_t: &'a TransitionTable<UD1Coord>,
+    co_p: &'a PruneTable<COCoord>,
+    eo_ud1_p: &'a SymPruneTable<UD1Coord, EOCoord>,
+  ) -> Phase0Tables<'a> {
+    Phase0Tables {
+      eo_t,
+      co_t,
+      ud1_t,
+      co_p,
+      eo_ud1_p,
+      order_moves: false,
+      stop: None,
+      metric: Metric::Half,
+    }
+  }
+
+  /// Try the moves at each node of the search in order of increasing pruning
+  /// depth of the coordinates that they reach, instead of the fixed face
+  /// order. This visits fewer nodes on hard positions, but can find a
+  /// different solution of the same length.
+  pub fn with_move_ordering(self, order_moves: bool) -> Phase0Tables<'a> {
+    Phase0Tables {
+      order_moves,
+      ..self
+    }
+  }
+
+  /// Stop the search as soon as `stop` is set, which makes it return that
+  /// nothing was found. The flag is checked as each node is searched, so
+  /// another thread can set it to abort a long search or to bound its
+  /// latency.
+  pub fn with_stop_flag(self, stop: &'a AtomicBool) -> Phase0Tables<'a> {
+    Phase0Tables {
+      stop: Some(stop),
+      ..self
+    }
+  }
+
+  /// Count the moves of the search in `metric`, so that the depths of the
+  /// search are the costs of the reductions instead of their number of
+  /// moves.
+  ///
+  /// The pruning tables store the number of moves, which is never more than
+  /// the cost in any metric, so they still bound the search.
+  pub fn with_metric(self, metric: Metric) -> Phase0Tables<'a> {
+    Phase0Tables { metric, ..self }
+  }
+
+  // Check if the search has been stopped.
+  pub(crate) fn stopped(&self) -> bool {
+    self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+  }
+
+  /// The new `Phase0Coord` after doing the move `m`, which only takes a
+  /// lookup in each transition table, so coordinates can be kept up to date
+  /// as moves are made instead of computing them from the `Cube` each time.
+  pub fn transition(&self, coord: Phase0Coord, m: Move) -> Phase0Coord {
+    record(|s| s.phase0.table_lookups += 3);
+    let eo = self.eo_t[(coord.eo, m)];
+    let co = self.co_t[(coord.co, m)];
+    let ud1 = self.ud1_t[(coord.ud1, m)];
     Phase0Coord { eo, co, ud1 }
   }
 
   // The maximum prune depth for `coord`.
   fn prune_depth(&self, coord: Phase0Coord) -> usize {
+    record(|s| s.phase0.table_lookups += 2);
     max(
-      self.eo_p[coord.eo],
-      max(self.co_p[coord.co], self.ud1_p[coord.ud1]),
+      self.co_p[coord.co],
+      self.eo_ud1_p[(coord.ud1, coord.eo)] as usize,
     )
   }
 }
@@ -64,14 +144,14 @@ fn solution_check(solution: &[Move]) -> bool {
       // Phase0 cannot end in U or D.
       Move(Face::U, _) | Move(Face::D, _) => return false,
       // Phase0 cannot end a half turn.
-      Move(_, 2) => return false,
+      Move(_, Turn::Half) => return false,
       _ => (),
     }
 
     if len > 1 {
       // Phase 0 cannot end in A2 B, where A and B are opposite faces.
       match &solution[len - 2..] {
-        &[Move(f1, 2), Move(f2, _)] if f1.is_opposite(f2) => {
+        &[Move(f1, Turn::Half), Move(f2, _)] if f1.is_opposite(f2) => {
           return false;
         }
         _ => (),
@@ -81,214 +161,529 @@ fn solution_check(solution: &[Move]) -> bool {
   true
 }
 
-// Check if a face should be skipped.
-fn skip_face(solution: &[Move], face: Face) -> bool {
-  let len = solution.len();
-  if len > 0 {
-    // Check for A A.
-    match solution[len - 1] {
-      Move(previous_face, _) if previous_face == face => return true,
-      _ => (),
-    }
+// The moves to try after the node `coord` of the search, in the order that
+// they are tried.
+struct Successors {
+  coord: Phase0Coord,
+  moves: [Move; NUM_MOVES],
+  len: usize,
+  next: usize,
+}
 
-    if len > 1 {
-      // Check for A B A where A and B are opposite faces.
-      match &solution[len - 2..] {
-        &[Move(f1, _), Move(f2, _)] if f1.is_opposite(f2) && f1 == face => {
-          return true;
-        }
-        _ => (),
-      }
+impl Successors {
+  // The next move to try, with the coordinate that it reaches.
+  fn next_move(
+    &mut self,
+    tables: &Phase0Tables,
+  ) -> Option<(Move, Phase0Coord)> {
+    if self.next == self.len {
+      return None;
     }
+    let m = self.moves[self.next];
+    self.next += 1;
+    Some((m, tables.transition(self.coord, m)))
   }
-  false
 }
 
-/// Phase 0: Reduce a cube from G0 to G1.
-pub fn phase0(
+// What a node of the search leads to.
+enum Node {
+  // The node is a reduction to G1.
+  Reduction,
+  // No reduction can be reached from the node.
+  DeadEnd,
+  // The successors of the node have to be searched.
+  Inner(Successors),
+}
+
+// Visit the node `coord`, reached by `solution`, with `depth_remaining` moves
+// left to search.
+fn visit(
   coord: Phase0Coord,
   depth_remaining: usize,
   tables: &Phase0Tables,
-  solution: &mut Vec<Move>,
-) -> bool {
+  solution: &[Move],
+) -> Node {
+  record(|s| {
+    s.phase0.nodes += 1;
+    s.phase0.max_depth = max(s.phase0.max_depth, depth_remaining);
+  });
   if depth_remaining == 0 {
-    if !solution_check(solution) {
-      return false;
+    if !solution_check(solution) || !coord.is_solved() {
+      return Node::DeadEnd;
     }
-    return coord.is_solved();
+    return Node::Reduction;
+  }
+
+  if tables.stopped() {
+    return Node::DeadEnd;
   }
 
   if depth_remaining < tables.prune_depth(coord) {
-    return false;
+    record(|s| s.phase0.prune_cutoffs += 1);
+    return Node::DeadEnd;
   }
 
-  for &f in &[Face::U, Face::D, Face::F, Face::B, Face::R, Face::L] {
+  let mut moves = [Move(Face::U, Turn::Clockwise); NUM_MOVES];
+  let mut len = 0;
+  for &f in &SEARCH_FACES {
     if skip_face(solution, f) {
       continue;
     }
-    let mut next = coord;
-    for i in 0..3 {
-      next = tables.transition(next, f);
-      solution.push(Move(f, i + 1));
-      if phase0(next, depth_remaining - 1, tables, solution) {
-        return true;
+    for &turn in &Turn::ALL {
+      if tables.metric.move_cost(Move(f, turn)) > depth_remaining {
+        continue;
       }
-      solution.pop();
+      moves[len] = Move(f, turn);
+      len += 1;
     }
   }
-  false
+  if tables.order_moves {
+    let mut depths = [(0, Move(Face::U, Turn::Clockwise)); NUM_MOVES];
+    for (d, &m) in depths.iter_mut().zip(&moves[..len]) {
+      *d = (tables.prune_depth(tables.transition(coord, m)), m);
+    }
+    depths[..len].sort_by_key(|d| d.0);
+    for (m, d) in moves.iter_mut().zip(&depths[..len]) {
+      *m = d.1;
+    }
+  }
+  Node::Inner(Successors {
+    coord,
+    moves,
+    len,
+    next: 0,
+  })
 }
 
-#[cfg(test)]
-mod tests {
-  use super::*;
-  use pruning_table::*;
-  use transition_table::*;
-
-  lazy_static! {
-    static ref CO_T: Vec<[usize; 6]> = { get_co_transition_table() };
-    static ref EO_T: Vec<[usize; 6]> = { get_eo_transition_table() };
-    static ref UD1_T: Vec<[usize; 6]> = { get_ud1_transition_table() };
-    static ref CO_P: Box<[usize]> = { get_co_prune_table(&CO_T) };
-    static ref EO_P: Box<[usize]> = { get_eo_prune_table(&EO_T) };
-    static ref UD1_P: Box<[usize]> = { get_ud1_prune_table(&UD1_T) };
-    static ref PHASE0TABLES: Phase0Tables<'static> = {
-      Phase0Tables {
-        co_t: &CO_T,
-        eo_t: &EO_T,
-        ud1_t: &UD1_T,
-        co_p: &CO_P,
-        eo_p: &EO_P,
-        ud1_p: &UD1_P,
+// Search for reductions to G1, calling `found` with each one. Returns true,
+// with the reduction in `solution`, as soon as `found` returns false.
+fn search(
+  coord: Phase0Coord,
+  depth_remaining: usize,
+  tables: &Phase0Tables,
+  solution: &mut Vec<Move>,
+  found: &mut dyn FnMut(&[Move]) -> bool,
+) -> bool {
+  match visit(coord, depth_remaining, tables, solution) {
+    Node::Reduction => !found(solution),
+    Node::DeadEnd => false,
+    Node::Inner(mut successors) => {
+      while let Some((m, next)) = successors.next_move(tables) {
+        solution.push(m);
+        let depth_remaining = depth_remaining - tables.metric.move_cost(m);
+        if search(next, depth_remaining, tables, solution, found) {
+          return true;
+        }
+        solution.pop();
       }
+      false
+    }
+  }
+}
+
+/// An `Iterator` over the reductions of a coordinate to G1 with exactly
+/// `depth` moves, in the order that `phase0_all` finds them, which can be
+/// resumed after each one.
+pub(crate) struct Reductions<'a> {
+  tables: Phase0Tables<'a>,
+  depth: usize,
+  solution: Vec<Move>,
+  // The successors of each node on the path to the current one.
+  stack: Vec<Successors>,
+  // Whether the coordinate is itself a reduction that was not returned yet.
+  root: bool,
+}
+
+impl<'a> Reductions<'a> {
+  pub(crate) fn new(
+    coord: Phase0Coord,
+    depth: usize,
+    tables: &Phase0Tables<'a>,
+  ) -> Reductions<'a> {
+    let mut reductions = Reductions {
+      tables: *tables,
+      depth,
+      solution: Vec::with_capacity(depth),
+      stack: Vec::with_capacity(depth),
+      root: false,
     };
+    match visit(coord, depth, tables, &[]) {
+      Node::Reduction => reductions.root = true,
+      Node::DeadEnd => (),
+      Node::Inner(successors) => reductions.stack.push(successors),
+    }
+    reductions
+  }
+}
+
+impl Iterator for Reductions<'_> {
+  type Item = Vec<Move>;
+  fn next(&mut self) -> Option<Vec<Move>> {
+    if self.root {
+      self.root = false;
+      return Some(vec![]);
+    }
+    while let Some(successors) = self.stack.last_mut() {
+      let (m, next) = match successors.next_move(&self.tables) {
+        Some(successor) => successor,
+        None => {
+          self.stack.pop();
+          self.solution.pop();
+          continue;
+        }
+      };
+      self.solution.push(m);
+      let depth_remaining =
+        self.depth - algorithm_length(&self.solution, self.tables.metric);
+      match visit(next, depth_remaining, &self.tables, &self.solution) {
+        Node::Reduction => {
+          let reduction = self.solution.clone();
+          self.solution.pop();
+          return Some(reduction);
+        }
+        Node::DeadEnd => {
+          self.solution.pop();
+        }
+        Node::Inner(successors) => self.stack.push(successors),
+      }
+    }
+    None
   }
+}
+
+/// Phase 0: Reduce a cube from G0 to G1.
+pub fn phase0(
+  coord: Phase0Coord,
+  depth_remaining: usize,
+  tables: &Phase0Tables,
+  solution: &mut Vec<Move>,
+) -> bool {
+  search(coord, depth_remaining, tables, solution, &mut |_| false)
+}
+
+/// Phase 0: Call `found` with every reduction of `coord` to G1 with exactly
+/// `depth` moves, in the order they are searched, until `found` returns
+/// false.
+///
+/// The shortest reduction does not always give the shortest total solution,
+/// so trying more reductions can find shorter solutions.
+pub fn phase0_all<F: FnMut(&[Move]) -> bool>(
+  coord: Phase0Coord,
+  depth: usize,
+  tables: &Phase0Tables,
+  mut found: F,
+) {
+  search(coord, depth, tables, &mut vec![], &mut found);
+}
+
+/// Phase 0: Reduce `cube` from G0 to G1 with exactly `depth` moves, returning
+/// the moves and the G1 `Cube` that they reach.
+pub fn phase0_g1(
+  cube: &Cube,
+  depth: usize,
+  tables: &Phase0Tables,
+) -> Option<(Vec<Move>, Cube)> {
+  let mut solution = vec![];
+  if !phase0(Phase0Coord::from(*cube), depth, tables, &mut solution) {
+    return None;
+  }
+  let g1 = cube.apply_algorithm(&solution);
+  Some((solution, g1))
+}
+
+/// Find the shortest reduction of `coord` to G1 of at most `max_depth` moves,
+/// by searching with increasing depths, starting from the pruning tables'
+/// lower bound.
+pub fn solve_phase0(
+  coord: Phase0Coord,
+  max_depth: usize,
+  tables: &Phase0Tables,
+) -> Option<Vec<Move>> {
+  trace_span!(DEBUG, "phase0", max_depth);
+  (tables.prune_depth(coord)..=max_depth).find_map(|depth| {
+    trace_event!(TRACE, depth, "phase 0 depth");
+    let mut solution = vec![];
+    if phase0(coord, depth, tables, &mut solution) {
+      Some(solution)
+    } else {
+      None
+    }
+  })
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use tables::Tables;
 
   fn check_is_solved(cube: Cube, solution: &[Move]) -> bool {
-    let solved = solution.iter().fold(cube, |acc, &cur| acc.apply_move(cur));
+    let solved = cube.apply_algorithm(solution);
     Phase0Coord::from(solved).is_solved()
   }
 
   #[test]
   fn basic() {
+    let tables0 = Tables::get().phase0();
     let mut solution = vec![];
     let c = Cube::solved();
-    assert!(phase0(c.into(), 0, &PHASE0TABLES, &mut solution));
+    assert!(phase0(c.into(), 0, &tables0, &mut solution));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::U, 1));
-    assert!(phase0(c.into(), 0, &PHASE0TABLES, &mut solution));
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    assert!(phase0(c.into(), 0, &tables0, &mut solution));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 1));
-    assert!(!phase0(c.into(), 0, &PHASE0TABLES, &mut solution));
-    assert!(phase0(c.into(), 1, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::F, 1)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::F, Turn::Clockwise));
+    assert!(!phase0(c.into(), 0, &tables0, &mut solution));
+    assert!(phase0(c.into(), 1, &tables0, &mut solution));
+    assert!(matches!(&solution[..], [Move(Face::F, Turn::Clockwise)]));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 3));
-    let c = c.apply_move(Move(Face::R, 3));
-    assert!(!phase0(c.into(), 0, &PHASE0TABLES, &mut solution));
-    assert!(!phase0(c.into(), 1, &PHASE0TABLES, &mut solution));
-    assert!(phase0(c.into(), 2, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::R, 1), Move(Face::F, 1)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::F, Turn::Counter));
+    let c = c.apply_move(Move(Face::R, Turn::Counter));
+    assert!(!phase0(c.into(), 0, &tables0, &mut solution));
+    assert!(!phase0(c.into(), 1, &tables0, &mut solution));
+    assert!(phase0(c.into(), 2, &tables0, &mut solution));
+    assert!(matches!(
+      &solution[..],
+      [
+        Move(Face::R, Turn::Clockwise),
+        Move(Face::F, Turn::Clockwise)
+      ]
+    ));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 1));
-    let c = c.apply_move(Move(Face::F, 2));
-    let c = c.apply_move(Move(Face::R, 1));
-    assert!(phase0(c.into(), 3, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::R, 3), Move(Face::F, 2), Move(Face::R, 1)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::F, Turn::Half));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    assert!(phase0(c.into(), 3, &tables0, &mut solution));
+    assert!(matches!(
+      &solution[..],
+      [
+        Move(Face::R, Turn::Counter),
+        Move(Face::F, Turn::Half),
+        Move(Face::R, Turn::Clockwise)
+      ]
+    ));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 1));
-    assert!(!phase0(c.into(), 2, &PHASE0TABLES, &mut solution));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    assert!(!phase0(c.into(), 2, &tables0, &mut solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::B, 1));
-    let c = c.apply_move(Move(Face::R, 2));
-    assert!(phase0(c.into(), 2, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::R, 2), Move(Face::B, 1)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::B, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::R, Turn::Half));
+    assert!(phase0(c.into(), 2, &tables0, &mut solution));
+    assert!(matches!(
+      &solution[..],
+      [Move(Face::R, Turn::Half), Move(Face::B, Turn::Clockwise)]
+    ));
     assert!(check_is_solved(c, &solution));
     let mut solution = vec![];
-    assert!(phase0(c.into(), 4, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::U, 2), Move(Face::D, 2), Move(Face::L, 2), Move(Face::F, 1)] => {
-        true
-      }
-      _ => false,
-    });
+    assert!(phase0(c.into(), 4, &tables0, &mut solution));
+    assert!(matches!(
+      &solution[..],
+      [
+        Move(Face::U, Turn::Half),
+        Move(Face::D, Turn::Half),
+        Move(Face::L, Turn::Half),
+        Move(Face::F, Turn::Clockwise)
+      ]
+    ));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::L, 1));
-    let c = c.apply_move(Move(Face::R, 1));
-    assert!(phase0(c.into(), 2, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::R, 1), Move(Face::L, 1)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::L, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    assert!(phase0(c.into(), 2, &tables0, &mut solution));
+    assert!(matches!(
+      &solution[..],
+      [
+        Move(Face::R, Turn::Clockwise),
+        Move(Face::L, Turn::Clockwise)
+      ]
+    ));
     assert!(check_is_solved(c, &solution));
     let mut solution = vec![];
-    assert!(phase0(c.into(), 5, &PHASE0TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::U, 2), Move(Face::D, 2), Move(Face::F, 2), Move(Face::R, 1), Move(Face::L, 1)] => {
-        true
-      }
-      _ => false,
-    });
+    assert!(phase0(c.into(), 5, &tables0, &mut solution));
+    assert!(matches!(
+      &solution[..],
+      [
+        Move(Face::U, Turn::Half),
+        Move(Face::D, Turn::Half),
+        Move(Face::F, Turn::Half),
+        Move(Face::R, Turn::Clockwise),
+        Move(Face::L, Turn::Clockwise)
+      ]
+    ));
     assert!(check_is_solved(c, &solution));
   }
 
+  #[test]
+  fn g1_state() {
+    let tables0 = Tables::get().phase0();
+    let c = Cube::solved();
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::F, Turn::Counter));
+    let c = c.apply_move(Move(Face::R, Turn::Counter));
+    assert!(phase0_g1(&c, 1, &tables0).is_none());
+    let (solution, g1) = phase0_g1(&c, 2, &tables0).unwrap();
+    assert!(matches!(
+      &solution[..],
+      [
+        Move(Face::R, Turn::Clockwise),
+        Move(Face::F, Turn::Clockwise)
+      ]
+    ));
+    assert_eq!(
+      Cube::solved().apply_move(Move(Face::U, Turn::Clockwise)),
+      g1
+    );
+    assert!(Phase0Coord::from(g1).is_solved());
+  }
+
   #[test]
   fn prune() {
+    let tables0 = Tables::get().phase0();
     // CO and UD1 require 2 moves.
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 1));
-    let c = c.apply_move(Move(Face::U, 1));
-    assert_eq!(2, PHASE0TABLES.prune_depth(c.into()));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    assert_eq!(2, tables0.prune_depth(c.into()));
 
     // CO depth is 4 moves, UD1 is 3 moves (F' U F).
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 1));
-    let c = c.apply_move(Move(Face::U, 1));
-    let c = c.apply_move(Move(Face::R, 3));
-    let c = c.apply_move(Move(Face::U, 3));
-    assert_eq!(4, PHASE0TABLES.prune_depth(c.into()));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::R, Turn::Counter));
+    let c = c.apply_move(Move(Face::U, Turn::Counter));
+    assert_eq!(4, tables0.prune_depth(c.into()));
 
     // CO depth is 5 moves (F2 U2 R' U F), EO and UD1 are 0 moves.
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 1));
-    let c = c.apply_move(Move(Face::U, 1));
-    let c = c.apply_move(Move(Face::R, 3));
-    let c = c.apply_move(Move(Face::U, 1));
-    let c = c.apply_move(Move(Face::R, 1));
-    let c = c.apply_move(Move(Face::U, 2));
-    let c = c.apply_move(Move(Face::R, 3));
-    assert_eq!(5, PHASE0TABLES.prune_depth(c.into()));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::R, Turn::Counter));
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::R, Turn::Clockwise));
+    let c = c.apply_move(Move(Face::U, Turn::Half));
+    let c = c.apply_move(Move(Face::R, Turn::Counter));
+    assert_eq!(5, tables0.prune_depth(c.into()));
+  }
+
+  #[test]
+  fn iterative_deepening() {
+    let tables0 = Tables::get().phase0();
+    let c = Cube::solved();
+    assert_eq!(Some(vec![]), solve_phase0(c.into(), 5, &tables0));
+
+    let c = c.apply_algorithm(&[
+      Move(Face::F, Turn::Counter),
+      Move(Face::R, Turn::Counter),
+    ]);
+    assert_eq!(None, solve_phase0(c.into(), 1, &tables0));
+    assert_eq!(
+      Some(vec![
+        Move(Face::R, Turn::Clockwise),
+        Move(Face::F, Turn::Clockwise)
+      ]),
+      solve_phase0(c.into(), 5, &tables0)
+    );
+  }
+
+  #[test]
+  fn all() {
+    let tables0 = Tables::get().phase0();
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::F, Turn::Counter),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Clockwise),
+    ]);
+    let mut reductions = vec![];
+    phase0_all(c.into(), 3, &tables0, |moves| {
+      reductions.push(moves.to_vec());
+      true
+    });
+    assert!(reductions.len() > 1);
+    assert!(reductions
+      .iter()
+      .all(|r| r.len() == 3 && check_is_solved(c, r)));
+
+    let mut solution = vec![];
+    assert!(phase0(c.into(), 3, &tables0, &mut solution));
+    assert_eq!(reductions[0], solution);
+
+    // Stop after the second reduction.
+    let mut count = 0;
+    phase0_all(c.into(), 3, &tables0, |_| {
+      count += 1;
+      count < 2
+    });
+    assert_eq!(2, count);
+  }
+
+  #[test]
+  fn move_ordering() {
+    let tables0 = Tables::get().phase0();
+    let ordered = tables0.with_move_ordering(true);
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Counter),
+      Move(Face::F, Turn::Clockwise),
+      Move(Face::L, Turn::Half),
+      Move(Face::B, Turn::Counter),
+      Move(Face::D, Turn::Clockwise),
+    ]);
+    let solution = solve_phase0(c.into(), 12, &tables0).unwrap();
+    let ordered = solve_phase0(c.into(), 12, &ordered).unwrap();
+    assert_eq!(solution.len(), ordered.len());
+    assert!(check_is_solved(c, &ordered));
+  }
+
+  #[test]
+  fn stop_flag() {
+    let tables0 = Tables::get().phase0();
+    let c = Cube::solved().apply_move(Move(Face::F, Turn::Clockwise));
+    let stop = AtomicBool::new(false);
+    let stoppable = tables0.with_stop_flag(&stop);
+    assert!(solve_phase0(c.into(), 5, &stoppable).is_some());
+    stop.store(true, Ordering::Relaxed);
+    assert_eq!(None, solve_phase0(c.into(), 5, &stoppable));
+  }
+
+  #[test]
+  fn reductions() {
+    let tables0 = Tables::get().phase0();
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::F, Turn::Half),
+      Move(Face::R, Turn::Clockwise),
+    ]);
+    for depth in 0..6 {
+      let mut all = vec![];
+      phase0_all(c.into(), depth, &tables0, |reduction| {
+        all.push(reduction.to_vec());
+        true
+      });
+      let reductions: Vec<_> =
+        Reductions::new(c.into(), depth, &tables0).collect();
+      assert_eq!(all, reductions);
+    }
+    assert_eq!(
+      1,
+      Reductions::new(Cube::solved().into(), 0, &tables0).count()
+    );
   }
 }
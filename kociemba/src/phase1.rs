@@ -1,20 +1,33 @@
-use cube::{Cube, Face, Move};
+use canonical::skip_face;
+use cube::{Cube, Metric, Move};
+use pruning_table::PruneTable;
+use stats::record;
 use std::cmp::max;
+use std::sync::atomic::{AtomicBool, Ordering};
+use symmetry::SymPruneTable;
 use transition_table::CPCoord;
 use transition_table::Coord;
 use transition_table::EPCoord;
+use transition_table::TransitionTable;
 use transition_table::UD2Coord;
+use transition_table::G1_MOVES;
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub struct Phase1Coord {
-  ep: usize,
-  cp: usize,
-  ud2: usize,
+  ep: EPCoord,
+  cp: CPCoord,
+  ud2: UD2Coord,
 }
 
 impl Phase1Coord {
-  fn is_solved(&self) -> bool {
-    self.ep == 0 && self.cp == 0 && self.ud2 == 0
+  pub(crate) fn new(ep: EPCoord, cp: CPCoord, ud2: UD2Coord) -> Phase1Coord {
+    Phase1Coord { ep, cp, ud2 }
+  }
+
+  pub(crate) fn is_solved(&self) -> bool {
+    self.ep == EPCoord::from(0)
+      && self.cp == CPCoord::from(0)
+      && self.ud2 == UD2Coord::from(0)
   }
 }
 
@@ -28,60 +41,96 @@ impl From<Cube> for Phase1Coord {
   }
 }
 
+#[derive(Clone, Copy)]
 pub struct Phase1Tables<'a> {
-  ep_t: &'a [[usize; 6]],
-  cp_t: &'a [[usize; 6]],
-  ud2_t: &'a [[usize; 6]],
-  ep_p: &'a [usize],
-  cp_p: &'a [usize],
-  ud2_p: &'a [usize],
+  ep_t: &'a TransitionTable<EPCoord>,
+  cp_t: &'a TransitionTable<CPCoord>,
+  ud2_t: &'a TransitionTable<UD2Coord>,
+  ep_p: &'a PruneTable<EPCoord>,
+  cp_ud2_p: &'a SymPruneTable<CPCoord, UD2Coord>,
+  order_moves: bool,
+  stop: Option<&'a AtomicBool>,
+  pub(crate) metric: Metric,
 }
 
 impl<'a> Phase1Tables<'a> {
-  // The new `Phase1Coord` after doing the `face` move.
-  // note: This is a quarter turn for U/D and half turn for FBRL.
-  fn transition(&self, coord: Phase1Coord, face: Face) -> Phase1Coord {
-    let ep = self.ep_t[coord.ep][usize::from(face)];
-    let cp = self.cp_t[coord.cp][usize::from(face)];
-    let ud2 = self.ud2_t[coord.ud2][usize::from(face)];
+  /// Create the `Phase1Tables` from the G1 transition tables, the EP pruning
+  /// table and the combined CP and UD2 pruning table, reduced by symmetry.
+  pub fn new(
+    ep_t: &'a TransitionTable<EPCoord>,
+    cp_t: &'a TransitionTable<CPCoord>,
+    ud2_t: &'a TransitionTable<UD2Coord>,
+    ep_p: &'a PruneTable<EPCoord>,
+    cp_ud2_p: &'a SymPruneTable<CPCoord, UD2Coord>,
+  ) -> Phase1Tables<'a> {
+    Phase1Tables {
+      ep_t,
+      cp_t,
+      ud2_t,
+      ep_p,
+      cp_ud2_p,
+      order_moves: false,
+      stop: None,
+      metric: Metric::Half,
+    }
+  }
+
+  /// Try the moves at each node of the search in order of increasing pruning
+  /// depth of the coordinates that they reach, instead of the fixed face
+  /// order. This visits fewer nodes on hard positions, but can find a
+  /// different solution of the same length.
+  pub fn with_move_ordering(self, order_moves: bool) -> Phase1Tables<'a> {
+    Phase1Tables {
+      order_moves,
+      ..self
+    }
+  }
+
+  /// Stop the search as soon as `stop` is set, which makes it return that
+  /// nothing was found. The flag is checked as each node is searched, so
+  /// another thread can set it to abort a long search or to bound its
+  /// latency.
+  pub fn with_stop_flag(self, stop: &'a AtomicBool) -> Phase1Tables<'a> {
+    Phase1Tables {
+      stop: Some(stop),
+      ..self
+    }
+  }
+
+  /// Count the moves of the search in `metric`, like
+  /// `Phase0Tables::with_metric`.
+  pub fn with_metric(self, metric: Metric) -> Phase1Tables<'a> {
+    Phase1Tables { metric, ..self }
+  }
+
+  // Check if the search has been stopped.
+  pub(crate) fn stopped(&self) -> bool {
+    self.stop.is_some_and(|stop| stop.load(Ordering::Relaxed))
+  }
+
+  /// The new `Phase1Coord` after doing the move `m`, which must be a G1
+  /// move, like `Phase0Tables::transition`.
+  pub fn transition(&self, coord: Phase1Coord, m: Move) -> Phase1Coord {
+    record(|s| s.phase1.table_lookups += 3);
+    let ep = self.ep_t[(coord.ep, m)];
+    let cp = self.cp_t[(coord.cp, m)];
+    let ud2 = self.ud2_t[(coord.ud2, m)];
     Phase1Coord { ep, cp, ud2 }
   }
 
   // The maximum prune depth for `coord`.
   fn prune_depth(&self, coord: Phase1Coord) -> usize {
+    record(|s| s.phase1.table_lookups += 2);
     max(
       self.ep_p[coord.ep],
-      max(self.cp_p[coord.cp], self.ud2_p[coord.ud2]),
+      self.cp_ud2_p[(coord.cp, coord.ud2)] as usize,
     )
   }
 }
 
 // Check if a solution is valid.
 fn solution_check(_solution: &[Move]) -> bool {
-  return true;
-}
-
-// Check if a face should be skipped.
-fn skip_face(solution: &[Move], face: Face) -> bool {
-  let len = solution.len();
-  if len > 0 {
-    // Check for A A.
-    match solution[len - 1] {
-      Move(previous_face, _) if previous_face == face => return true,
-      _ => (),
-    }
-
-    if len > 1 {
-      // Check for A B A where A and B are opposite faces.
-      match &solution[len - 2..] {
-        &[Move(f1, _), Move(f2, _)] if f1.is_opposite(f2) && f1 == face => {
-          return true;
-        }
-        _ => (),
-      }
-    }
-  }
-  false
+  true
 }
 
 /// Phase 1: Reduce a cube from G1 to solved.
@@ -91,6 +140,10 @@ pub fn phase1(
   tables: &Phase1Tables,
   solution: &mut Vec<Move>,
 ) -> bool {
+  record(|s| {
+    s.phase1.nodes += 1;
+    s.phase1.max_depth = max(s.phase1.max_depth, depth_remaining);
+  });
   if depth_remaining == 0 {
     if !solution_check(solution) {
       return false;
@@ -98,106 +151,161 @@ pub fn phase1(
     return coord.is_solved();
   }
 
+  if tables.stopped() {
+    return false;
+  }
+
   if depth_remaining < tables.prune_depth(coord) {
+    record(|s| s.phase1.prune_cutoffs += 1);
     return false;
   }
 
-  for &f in &[Face::U, Face::D, Face::F, Face::B, Face::R, Face::L] {
-    if skip_face(solution, f) {
+  let mut successors = [(0, G1_MOVES[0], coord); G1_MOVES.len()];
+  let mut len = 0;
+  for &m in G1_MOVES {
+    if skip_face(solution, m.0) || tables.metric.move_cost(m) > depth_remaining
+    {
       continue;
     }
+    successors[len] = (0, m, tables.transition(coord, m));
+    len += 1;
+  }
+  let successors = &mut successors[..len];
+  if tables.order_moves {
+    for s in successors.iter_mut() {
+      s.0 = tables.prune_depth(s.2);
+    }
+    successors.sort_by_key(|s| s.0);
+  }
 
-    // FBRL are half turns only.
-    let move_range = if f == Face::U || f == Face::D {
-      0..3
-    } else {
-      1..2
-    };
-    let mut next = coord;
-    for i in move_range {
-      next = tables.transition(next, f);
-      solution.push(Move(f, i + 1));
-      if phase1(next, depth_remaining - 1, tables, solution) {
-        return true;
-      }
-      solution.pop();
+  for &(_, m, next) in successors.iter() {
+    solution.push(m);
+    let depth_remaining = depth_remaining - tables.metric.move_cost(m);
+    if phase1(next, depth_remaining, tables, solution) {
+      return true;
     }
+    solution.pop();
   }
   false
 }
 
+/// Find the shortest solution of `coord` of at most `max_depth` moves, by
+/// searching with increasing depths, starting from the pruning tables' lower
+/// bound.
+pub fn solve_phase1(
+  coord: Phase1Coord,
+  max_depth: usize,
+  tables: &Phase1Tables,
+) -> Option<Vec<Move>> {
+  trace_span!(DEBUG, "phase1", max_depth);
+  (tables.prune_depth(coord)..=max_depth).find_map(|depth| {
+    trace_event!(TRACE, depth, "phase 1 depth");
+    let mut solution = vec![];
+    if phase1(coord, depth, tables, &mut solution) {
+      Some(solution)
+    } else {
+      None
+    }
+  })
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
-  use pruning_table::*;
-  use transition_table::*;
-
-  lazy_static! {
-    static ref CP_T: Vec<[usize; 6]> = { get_cp_transition_table() };
-    static ref EP_T: Vec<[usize; 6]> = { get_ep_transition_table() };
-    static ref UD2_T: Vec<[usize; 6]> = { get_ud2_transition_table() };
-    static ref CP_P: Box<[usize]> = { get_cp_prune_table(&CP_T) };
-    static ref EP_P: Box<[usize]> = { get_ep_prune_table(&EP_T) };
-    static ref UD2_P: Box<[usize]> = { get_ud2_prune_table(&UD2_T) };
-    static ref PHASE1TABLES: Phase1Tables<'static> = {
-      Phase1Tables {
-        cp_t: &CP_T,
-        ep_t: &EP_T,
-        ud2_t: &UD2_T,
-        cp_p: &CP_P,
-        ep_p: &EP_P,
-        ud2_p: &UD2_P,
-      }
-    };
-  }
+  use cube::{Face, Turn};
+  use tables::Tables;
 
   fn check_is_solved(cube: Cube, solution: &[Move]) -> bool {
-    let solved = solution.iter().fold(cube, |acc, &cur| acc.apply_move(cur));
+    let solved = cube.apply_algorithm(solution);
     Phase1Coord::from(solved).is_solved()
   }
 
   #[test]
   fn basic() {
+    let tables1 = Tables::get().phase1();
     let mut solution = vec![];
     let c = Cube::solved();
-    assert!(phase1(c.into(), 0, &PHASE1TABLES, &mut solution));
+    assert!(phase1(c.into(), 0, &tables1, &mut solution));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::U, 1));
-    assert!(!phase1(c.into(), 0, &PHASE1TABLES, &mut solution));
-    assert!(phase1(c.into(), 1, &PHASE1TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::U, 3)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::U, Turn::Clockwise));
+    assert!(!phase1(c.into(), 0, &tables1, &mut solution));
+    assert!(phase1(c.into(), 1, &tables1, &mut solution));
+    assert!(matches!(&solution[..], [Move(Face::U, Turn::Counter)]));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 2));
-    assert!(!phase1(c.into(), 0, &PHASE1TABLES, &mut solution));
-    assert!(!phase1(c.into(), 2, &PHASE1TABLES, &mut solution));
-    assert!(phase1(c.into(), 1, &PHASE1TABLES, &mut solution));
-    assert!(match &solution[..] {
-      [Move(Face::R, 2)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::R, Turn::Half));
+    assert!(!phase1(c.into(), 0, &tables1, &mut solution));
+    assert!(!phase1(c.into(), 2, &tables1, &mut solution));
+    assert!(phase1(c.into(), 1, &tables1, &mut solution));
+    assert!(matches!(&solution[..], [Move(Face::R, Turn::Half)]));
     assert!(check_is_solved(c, &solution));
 
     let mut solution = vec![];
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::R, 2));
-    let c = c.apply_move(Move(Face::F, 2));
-    assert!(!phase1(c.into(), 0, &PHASE1TABLES, &mut solution));
-    assert!(!phase1(c.into(), 1, &PHASE1TABLES, &mut solution));
-    assert!(phase1(c.into(), 2, &PHASE1TABLES, &mut solution));
-
-    assert!(match &solution[..] {
-      [Move(Face::F, 2), Move(Face::R, 2)] => true,
-      _ => false,
-    });
+    let c = c.apply_move(Move(Face::R, Turn::Half));
+    let c = c.apply_move(Move(Face::F, Turn::Half));
+    assert!(!phase1(c.into(), 0, &tables1, &mut solution));
+    assert!(!phase1(c.into(), 1, &tables1, &mut solution));
+    assert!(phase1(c.into(), 2, &tables1, &mut solution));
+
+    assert!(matches!(
+      &solution[..],
+      [Move(Face::F, Turn::Half), Move(Face::R, Turn::Half)]
+    ));
     assert!(check_is_solved(c, &solution));
   }
+
+  #[test]
+  fn transitions() {
+    let tables1 = Tables::get().phase1();
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::R, Turn::Half),
+      Move(Face::U, Turn::Counter),
+    ]);
+    for &m in G1_MOVES {
+      assert_eq!(
+        Phase1Coord::from(c.apply_move(m)),
+        tables1.transition(c.into(), m)
+      );
+    }
+  }
+
+  #[test]
+  fn iterative_deepening() {
+    let tables1 = Tables::get().phase1();
+    let c = Cube::solved();
+    assert_eq!(Some(vec![]), solve_phase1(c.into(), 5, &tables1));
+
+    let c = c
+      .apply_algorithm(&[Move(Face::R, Turn::Half), Move(Face::F, Turn::Half)]);
+    assert_eq!(None, solve_phase1(c.into(), 1, &tables1));
+    assert_eq!(
+      Some(vec![Move(Face::F, Turn::Half), Move(Face::R, Turn::Half)]),
+      solve_phase1(c.into(), 5, &tables1)
+    );
+  }
+
+  #[test]
+  fn move_ordering() {
+    let tables1 = Tables::get().phase1();
+    let ordered = tables1.with_move_ordering(true);
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::R, Turn::Half),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::F, Turn::Half),
+      Move(Face::D, Turn::Counter),
+      Move(Face::L, Turn::Half),
+      Move(Face::B, Turn::Half),
+      Move(Face::U, Turn::Half),
+    ]);
+    let solution = solve_phase1(c.into(), 18, &tables1).unwrap();
+    let ordered = solve_phase1(c.into(), 18, &ordered).unwrap();
+    assert_eq!(solution.len(), ordered.len());
+    assert!(check_is_solved(c, &ordered));
+  }
 }
@@ -1,85 +1,394 @@
-use cube::Face;
+use cube::Move;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::ops::{Deref, Index};
+use symmetry::SymPruneTable;
+use transition_table::*;
 
-fn init_prune_table_inner(
-  coord: usize,
-  prune_table: &mut [usize],
-  trans_table: &[[usize; 6]],
-  max_depth: usize,
+/// A pruning table, which maps a coordinate to the minimum number of moves
+/// needed to solve it.
+///
+/// A table can also be indexed by a pair of coordinates, `(A, B)`, which
+/// gives a better lower bound than the separate tables for `A` and `B`.
+pub struct PruneTable<T>(Box<[usize]>, PhantomData<T>);
+
+/// A coordinate that indexes a `PruneTable`.
+pub(crate) trait PruneCoord: Copy + Send + Sync {
+  /// Number of elements in the `PruneTable`.
+  const NUM_ELEMS: usize;
+  /// The coordinate's index into the `PruneTable`.
+  fn index(self) -> usize;
+  /// The coordinate for an index into the `PruneTable`.
+  fn from_index(index: usize) -> Self;
+}
+
+impl<T: Coord> PruneCoord for T {
+  const NUM_ELEMS: usize = <T as Coord>::NUM_ELEMS;
+
+  fn index(self) -> usize {
+    self.into()
+  }
+
+  fn from_index(index: usize) -> T {
+    T::from(index)
+  }
+}
+
+impl<A: Coord, B: Coord> PruneCoord for (A, B) {
+  const NUM_ELEMS: usize = <A as Coord>::NUM_ELEMS * <B as Coord>::NUM_ELEMS;
+
+  fn index(self) -> usize {
+    self.0.into() * <B as Coord>::NUM_ELEMS + self.1.into()
+  }
+
+  fn from_index(index: usize) -> (A, B) {
+    let b = <B as Coord>::NUM_ELEMS;
+    (A::from(index / b), B::from(index % b))
+  }
+}
+
+impl<T: PruneCoord> Index<T> for PruneTable<T> {
+  type Output = usize;
+  fn index(&self, coord: T) -> &usize {
+    &self.0[coord.index()]
+  }
+}
+
+impl<T> Deref for PruneTable<T> {
+  type Target = [usize];
+  fn deref(&self) -> &[usize] {
+    &self.0
+  }
+}
+
+impl<T> PruneTable<T> {
+  /// A hash of the table in the format of `save_tables`, which is the same
+  /// on every platform, to check that the table has not changed.
+  pub fn fingerprint(&self) -> u64 {
+    fingerprint(|mut w| self.write_to(&mut w))
+  }
+
+  /// Write the table as text, with the depth of each coordinate on its own
+  /// line.
+  pub fn write_text<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    for depth in self.0.iter() {
+      writeln!(w, "{}", depth)?;
+    }
+    Ok(())
+  }
+
+  // Write the table as one byte per coordinate.
+  pub(crate) fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    let bytes = self
+      .0
+      .iter()
+      .map(|&depth| {
+        if depth > u8::MAX as usize {
+          return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "pruning table depth does not fit in a byte",
+          ));
+        }
+        Ok(depth as u8)
+      })
+      .collect::<io::Result<Vec<u8>>>()?;
+    w.write_all(&bytes)
+  }
+
+  // Read a table written by `write_to`.
+  pub(crate) fn read_from<R: Read>(r: &mut R) -> io::Result<Self>
+  where
+    T: PruneCoord,
+  {
+    let mut bytes = vec![0; T::NUM_ELEMS];
+    r.read_exact(&mut bytes)?;
+    let table = bytes.into_iter().map(|depth| depth as usize).collect();
+    Ok(PruneTable(table, PhantomData))
+  }
+}
+
+// Find the coordinates at `depth + 1`, given a table filled up to `depth`.
+// Every move in `moves` can be undone by another one, so an unvisited
+// coordinate is at `depth + 1` if one of its neighbours is at `depth`.
+fn next_depth<T, F>(
+  table: &[usize],
+  moves: &[Move],
+  turn: &F,
   depth: usize,
-) {
-  // End the current search branch if max_depth is reached or the current
-  // coordinate was already reached at a lower depth.
-  if depth == max_depth || prune_table[coord] <= depth {
-    return;
-  }
-  // Save the current depth for this coordinate.
-  prune_table[coord] = depth;
-  for &f in &[Face::U, Face::D, Face::F, Face::B, Face::R, Face::L] {
-    let mut new_coord = coord;
-    for _ in 0..3 {
-      new_coord = trans_table[new_coord][usize::from(f)];
-      init_prune_table_inner(
-        new_coord,
-        prune_table,
-        trans_table,
-        max_depth,
-        depth + 1,
-      );
+) -> Vec<usize>
+where
+  T: PruneCoord,
+  F: Fn(T, Move) -> T + Sync,
+{
+  let unvisited = table.len();
+  let next = |(index, &d): (usize, &usize)| {
+    if d != unvisited {
+      return d;
     }
+    let coord = T::from_index(index);
+    let reached = moves
+      .iter()
+      .any(|&m| table[turn(coord, m).index()] == depth);
+    if reached {
+      depth + 1
+    } else {
+      d
+    }
+  };
+
+  #[cfg(feature = "parallel")]
+  let entries = table.par_iter().enumerate();
+  #[cfg(not(feature = "parallel"))]
+  let entries = table.iter().enumerate();
+  entries.map(next).collect()
+}
+
+/// Initialise a pruning table, where `turn` gives the coordinate after each
+/// of `moves`. The pruning table stores the depth of each coordinate.
+///
+/// The table is filled breadth first, one depth at a time, until no new
+/// coordinates are found, so every depth is the exact distance to the solved
+/// coordinate. With the `parallel` feature, each depth is filled using
+/// multiple threads.
+pub(crate) fn init_prune_table<T, F>(moves: &[Move], turn: F) -> PruneTable<T>
+where
+  T: PruneCoord,
+  F: Fn(T, Move) -> T + Sync,
+{
+  init_prune_table_from(&[T::from_index(0)], moves, turn)
+}
+
+/// Initialise a pruning table like `init_prune_table`, where every
+/// coordinate in `targets` is at depth 0, so the table stores the distance to
+/// the nearest of them. Coordinates that can not reach a target keep the
+/// depth `T::NUM_ELEMS`.
+pub(crate) fn init_prune_table_from<T, F>(
+  targets: &[T],
+  moves: &[Move],
+  turn: F,
+) -> PruneTable<T>
+where
+  T: PruneCoord,
+  F: Fn(T, Move) -> T + Sync,
+{
+  fill_prune_table(targets, moves, turn, &mut |_| ())
+}
+
+// Initialise a pruning table like `init_prune_table_from`, calling
+// `progress` with the fraction of the coordinates whose depth is known after
+// each new depth is filled.
+fn fill_prune_table<T, F>(
+  targets: &[T],
+  moves: &[Move],
+  turn: F,
+  progress: &mut dyn FnMut(f64),
+) -> PruneTable<T>
+where
+  T: PruneCoord,
+  F: Fn(T, Move) -> T + Sync,
+{
+  let mut table = vec![T::NUM_ELEMS; T::NUM_ELEMS];
+  for &t in targets {
+    table[t.index()] = 0;
+  }
+  let mut filled = targets.len();
+  for depth in 0.. {
+    let next = next_depth(&table, moves, &turn, depth);
+    let found = next.iter().filter(|&&d| d == depth + 1).count();
+    table = next;
+    if found == 0 {
+      break;
+    }
+    filled += found;
+    progress(filled as f64 / T::NUM_ELEMS as f64);
   }
+  PruneTable(table.into_boxed_slice(), PhantomData)
+}
+
+// Initialise a pruning table from a transition table.
+pub(crate) fn init_single_prune_table<T: Coord>(
+  trans_table: &TransitionTable<T>,
+) -> PruneTable<T> {
+  single_prune_table_with_progress(trans_table, &mut |_| ())
 }
 
-/// Initialise a pruning table from a transition table. The pruning table
-/// stores the depth of each coordinate.
-fn init_prune_table(
-  trans_table: &[[usize; 6]],
-  max_depth: usize,
-  table_size: usize,
-) -> Box<[usize]> {
-  let mut table = vec![table_size; table_size];
-  init_prune_table_inner(0, &mut table, trans_table, max_depth, 0);
-  table.into_boxed_slice()
+// Initialise a pruning table from a transition table, reporting its progress
+// like `fill_prune_table`.
+pub(crate) fn single_prune_table_with_progress<T: Coord>(
+  trans_table: &TransitionTable<T>,
+  progress: &mut dyn FnMut(f64),
+) -> PruneTable<T> {
+  let turn = |coord: T, m| trans_table[(coord, m)];
+  fill_prune_table(&[T::from_index(0)], T::GROUP.moves(), turn, progress)
 }
 
 /// Get the G0 CO prune table.
-pub fn get_co_prune_table(co_trans: &[[usize; 6]]) -> Box<[usize]> {
-  init_prune_table(&co_trans[..], 7, co_trans.len())
+pub fn get_co_prune_table(
+  co_trans: &TransitionTable<COCoord>,
+) -> PruneTable<COCoord> {
+  init_single_prune_table(co_trans)
 }
 
 /// Get the G0 EO prune table.
-pub fn get_eo_prune_table(eo_trans: &[[usize; 6]]) -> Box<[usize]> {
-  init_prune_table(&eo_trans[..], 8, eo_trans.len())
+pub fn get_eo_prune_table(
+  eo_trans: &TransitionTable<EOCoord>,
+) -> PruneTable<EOCoord> {
+  init_single_prune_table(eo_trans)
 }
 
 /// Get the G0 UD1 prune table.
-pub fn get_ud1_prune_table(ud1_trans: &[[usize; 6]]) -> Box<[usize]> {
-  init_prune_table(&ud1_trans[..], 6, ud1_trans.len())
+pub fn get_ud1_prune_table(
+  ud1_trans: &TransitionTable<UD1Coord>,
+) -> PruneTable<UD1Coord> {
+  init_single_prune_table(ud1_trans)
+}
+
+/// Get the G0 prune table for EO and UD1 together.
+pub fn get_eo_ud1_prune_table(
+  eo_trans: &TransitionTable<EOCoord>,
+  ud1_trans: &TransitionTable<UD1Coord>,
+) -> PruneTable<(EOCoord, UD1Coord)> {
+  eo_ud1_prune_table_with_progress(eo_trans, ud1_trans, &mut |_| ())
+}
+
+// Get the G0 prune table for EO and UD1 together, reporting its progress
+// like `fill_prune_table`.
+fn eo_ud1_prune_table_with_progress(
+  eo_trans: &TransitionTable<EOCoord>,
+  ud1_trans: &TransitionTable<UD1Coord>,
+  progress: &mut dyn FnMut(f64),
+) -> PruneTable<(EOCoord, UD1Coord)> {
+  let turn = |(eo, ud1): (EOCoord, UD1Coord), m| {
+    (eo_trans[(eo, m)], ud1_trans[(ud1, m)])
+  };
+  let solved = (EOCoord::from(0), UD1Coord::from(0));
+  fill_prune_table(&[solved], Group::G0.moves(), turn, progress)
+}
+
+/// Get the G0 prune table for EO and UD1 together, reduced by symmetry.
+pub fn get_eo_ud1_sym_prune_table(
+  eo_trans: &TransitionTable<EOCoord>,
+  ud1_trans: &TransitionTable<UD1Coord>,
+) -> SymPruneTable<UD1Coord, EOCoord> {
+  eo_ud1_sym_prune_table_with_progress(eo_trans, ud1_trans, &mut |_| ())
+}
+
+// Get the G0 prune table for EO and UD1 together, reduced by symmetry,
+// reporting the progress of filling the full table like `fill_prune_table`.
+pub(crate) fn eo_ud1_sym_prune_table_with_progress(
+  eo_trans: &TransitionTable<EOCoord>,
+  ud1_trans: &TransitionTable<UD1Coord>,
+  progress: &mut dyn FnMut(f64),
+) -> SymPruneTable<UD1Coord, EOCoord> {
+  let table = eo_ud1_prune_table_with_progress(eo_trans, ud1_trans, progress);
+  SymPruneTable::new(|ud1, eo| table[(eo, ud1)])
 }
 
 /// Get the G1 CP prune table.
-pub fn get_cp_prune_table(cp_trans: &[[usize; 6]]) -> Box<[usize]> {
-  init_prune_table(&cp_trans[..], 14, cp_trans.len())
+pub fn get_cp_prune_table(
+  cp_trans: &TransitionTable<CPCoord>,
+) -> PruneTable<CPCoord> {
+  init_single_prune_table(cp_trans)
+}
+
+/// Get the G1 prune table for CP and UD2 together.
+pub fn get_cp_ud2_prune_table(
+  cp_trans: &TransitionTable<CPCoord>,
+  ud2_trans: &TransitionTable<UD2Coord>,
+) -> PruneTable<(CPCoord, UD2Coord)> {
+  cp_ud2_prune_table_with_progress(cp_trans, ud2_trans, &mut |_| ())
+}
+
+// Get the G1 prune table for CP and UD2 together, reporting its progress
+// like `fill_prune_table`.
+fn cp_ud2_prune_table_with_progress(
+  cp_trans: &TransitionTable<CPCoord>,
+  ud2_trans: &TransitionTable<UD2Coord>,
+  progress: &mut dyn FnMut(f64),
+) -> PruneTable<(CPCoord, UD2Coord)> {
+  let turn = |(cp, ud2): (CPCoord, UD2Coord), m| {
+    (cp_trans[(cp, m)], ud2_trans[(ud2, m)])
+  };
+  let solved = (CPCoord::from(0), UD2Coord::from(0));
+  fill_prune_table(&[solved], Group::G1.moves(), turn, progress)
+}
+
+/// Get the G1 prune table for CP and UD2 together, reduced by symmetry.
+pub fn get_cp_ud2_sym_prune_table(
+  cp_trans: &TransitionTable<CPCoord>,
+  ud2_trans: &TransitionTable<UD2Coord>,
+) -> SymPruneTable<CPCoord, UD2Coord> {
+  cp_ud2_sym_prune_table_with_progress(cp_trans, ud2_trans, &mut |_| ())
+}
+
+// Get the G1 prune table for CP and UD2 together, reduced by symmetry,
+// reporting the progress of filling the full table like `fill_prune_table`.
+pub(crate) fn cp_ud2_sym_prune_table_with_progress(
+  cp_trans: &TransitionTable<CPCoord>,
+  ud2_trans: &TransitionTable<UD2Coord>,
+  progress: &mut dyn FnMut(f64),
+) -> SymPruneTable<CPCoord, UD2Coord> {
+  let table = cp_ud2_prune_table_with_progress(cp_trans, ud2_trans, progress);
+  SymPruneTable::new(|cp, ud2| table[(cp, ud2)])
 }
 
 /// Get the G1 EP prune table.
-pub fn get_ep_prune_table(ep_trans: &[[usize; 6]]) -> Box<[usize]> {
-  init_prune_table(&ep_trans[..], 9, ep_trans.len())
+pub fn get_ep_prune_table(
+  ep_trans: &TransitionTable<EPCoord>,
+) -> PruneTable<EPCoord> {
+  init_single_prune_table(ep_trans)
 }
 
 /// Get the G1 UD2 prune table.
-pub fn get_ud2_prune_table(ud2_trans: &[[usize; 6]]) -> Box<[usize]> {
-  init_prune_table(&ud2_trans[..], 5, ud2_trans.len())
+pub fn get_ud2_prune_table(
+  ud2_trans: &TransitionTable<UD2Coord>,
+) -> PruneTable<UD2Coord> {
+  init_single_prune_table(ud2_trans)
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
-  use transition_table::*;
+
+  // Every move changes the depth by at most one, and every coordinate except
+  // the solved one has a neighbour that is one move closer.
+  fn check_neighbours<T: PruneCoord>(
+    prune_table: &PruneTable<T>,
+    group: Group,
+    turn: impl Fn(T, Move) -> T,
+  ) {
+    assert_eq!(0, prune_table[T::from_index(0)]);
+    for (index, &depth) in prune_table.iter().enumerate() {
+      let mut closer = false;
+      for &m in group.moves() {
+        let neighbour = prune_table[turn(T::from_index(index), m)];
+        assert!(depth.abs_diff(neighbour) <= 1);
+        closer |= neighbour + 1 == depth;
+      }
+      assert!(depth == 0 || closer);
+    }
+  }
+
+  #[test]
+  fn progress() {
+    let co_t = get_co_transition_table();
+    let mut fractions = vec![];
+    let co_p =
+      single_prune_table_with_progress(&co_t, &mut |f| fractions.push(f));
+    assert!(*co_p == *get_co_prune_table(&co_t));
+    // One report for each of the depths 1 to 6.
+    assert_eq!(6, fractions.len());
+    assert!(fractions.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(Some(&1.0), fractions.last());
+  }
 
   #[test]
   fn co_prune() {
     let co_t = get_co_transition_table();
     let co_p = get_co_prune_table(&co_t);
+    check_neighbours(&co_p, Group::G0, |c, m| co_t[(c, m)]);
     assert!(co_p.iter().all(|&depth| depth < co_t.len()));
     assert_eq!(&6, co_p.iter().max().unwrap());
   }
@@ -88,6 +397,7 @@ mod tests {
   fn eo_prune() {
     let eo_t = get_eo_transition_table();
     let eo_p = get_eo_prune_table(&eo_t);
+    check_neighbours(&eo_p, Group::G0, |c, m| eo_t[(c, m)]);
     assert!(eo_p.iter().all(|&depth| depth < eo_t.len()));
     assert_eq!(&7, eo_p.iter().max().unwrap());
   }
@@ -96,22 +406,83 @@ mod tests {
   fn ud1_prune() {
     let ud1_t = get_ud1_transition_table();
     let ud1_p = get_ud1_prune_table(&ud1_t);
+    check_neighbours(&ud1_p, Group::G0, |c, m| ud1_t[(c, m)]);
     assert!(ud1_p.iter().all(|&depth| depth < ud1_t.len()));
     assert_eq!(&5, ud1_p.iter().max().unwrap());
   }
 
+  #[test]
+  fn eo_ud1_prune() {
+    let eo_t = get_eo_transition_table();
+    let ud1_t = get_ud1_transition_table();
+    let eo_p = get_eo_prune_table(&eo_t);
+    let ud1_p = get_ud1_prune_table(&ud1_t);
+    let eo_ud1_p = get_eo_ud1_prune_table(&eo_t, &ud1_t);
+    check_neighbours(&eo_ud1_p, Group::G0, |(eo, ud1), m| {
+      (eo_t[(eo, m)], ud1_t[(ud1, m)])
+    });
+    assert_eq!(eo_t.len() * ud1_t.len(), eo_ud1_p.len());
+    for eo in 0..eo_t.len() {
+      for ud1 in 0..ud1_t.len() {
+        let (eo, ud1) = (EOCoord::from(eo), UD1Coord::from(ud1));
+        assert!(eo_ud1_p[(eo, ud1)] >= eo_p[eo].max(ud1_p[ud1]));
+      }
+    }
+    assert_eq!(&9, eo_ud1_p.iter().max().unwrap());
+
+    let sym_p = get_eo_ud1_sym_prune_table(&eo_t, &ud1_t);
+    assert!(sym_p.len() * 10 < eo_ud1_p.len());
+    for eo in 0..eo_t.len() {
+      for ud1 in 0..ud1_t.len() {
+        let (eo, ud1) = (EOCoord::from(eo), UD1Coord::from(ud1));
+        assert_eq!(eo_ud1_p[(eo, ud1)], sym_p[(ud1, eo)] as usize);
+      }
+    }
+  }
+
   #[test]
   fn cp_prune() {
     let cp_t = get_cp_transition_table();
     let cp_p = get_cp_prune_table(&cp_t);
+    check_neighbours(&cp_p, Group::G1, |c, m| cp_t[(c, m)]);
     assert!(cp_p.iter().all(|&depth| depth < cp_t.len()));
     assert_eq!(&13, cp_p.iter().max().unwrap());
   }
 
+  #[test]
+  fn cp_ud2_prune() {
+    let cp_t = get_cp_transition_table();
+    let ud2_t = get_ud2_transition_table();
+    let cp_p = get_cp_prune_table(&cp_t);
+    let ud2_p = get_ud2_prune_table(&ud2_t);
+    let cp_ud2_p = get_cp_ud2_prune_table(&cp_t, &ud2_t);
+    check_neighbours(&cp_ud2_p, Group::G1, |(cp, ud2), m| {
+      (cp_t[(cp, m)], ud2_t[(ud2, m)])
+    });
+    assert_eq!(cp_t.len() * ud2_t.len(), cp_ud2_p.len());
+    for cp in 0..cp_t.len() {
+      for ud2 in 0..ud2_t.len() {
+        let (cp, ud2) = (CPCoord::from(cp), UD2Coord::from(ud2));
+        assert!(cp_ud2_p[(cp, ud2)] >= cp_p[cp].max(ud2_p[ud2]));
+      }
+    }
+    assert_eq!(&14, cp_ud2_p.iter().max().unwrap());
+
+    let sym_p = get_cp_ud2_sym_prune_table(&cp_t, &ud2_t);
+    assert_eq!(2768 * ud2_t.len(), sym_p.len());
+    for cp in 0..cp_t.len() {
+      for ud2 in 0..ud2_t.len() {
+        let (cp, ud2) = (CPCoord::from(cp), UD2Coord::from(ud2));
+        assert_eq!(cp_ud2_p[(cp, ud2)], sym_p[(cp, ud2)] as usize);
+      }
+    }
+  }
+
   #[test]
   fn ep_prune() {
     let ep_t = get_ep_transition_table();
     let ep_p = get_ep_prune_table(&ep_t);
+    check_neighbours(&ep_p, Group::G1, |c, m| ep_t[(c, m)]);
     assert!(ep_p.iter().all(|&depth| depth < ep_t.len()));
     assert_eq!(&8, ep_p.iter().max().unwrap());
   }
@@ -120,6 +491,7 @@ mod tests {
   fn ud2_prune() {
     let ud2_t = get_ud2_transition_table();
     let ud2_p = get_ud2_prune_table(&ud2_t);
+    check_neighbours(&ud2_p, Group::G1, |c, m| ud2_t[(c, m)]);
     assert!(ud2_p.iter().all(|&depth| depth < ud2_t.len()));
     assert_eq!(&4, ud2_p.iter().max().unwrap());
   }
@@ -0,0 +1,212 @@
+use cube::{PermutationPuzzle, Pyraminx, PyraminxMove, Vertex};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// An exact-distance table of any `PermutationPuzzle`, which maps each
+/// pattern of the puzzle to the minimum number of moves needed to solve it,
+/// like a `PatternDatabase` does for the cube.
+///
+/// The patterns are found by a breadth-first search from the solved state,
+/// so the table only fits in memory for puzzles with up to a few million
+/// patterns.
+pub struct PuzzleDatabase<P: PermutationPuzzle> {
+  moves: Vec<P::Move>,
+  pattern: fn(&P) -> P,
+  distances: HashMap<P, usize>,
+}
+
+impl<P: PermutationPuzzle> PuzzleDatabase<P> {
+  /// Build the table of every state of the puzzle, for all its moves.
+  pub fn new() -> PuzzleDatabase<P> {
+    PuzzleDatabase::with_moves(&P::moves(), |p| *p)
+  }
+
+  /// Build the table of the patterns that `pattern` maps the states to,
+  /// which can only be solved with `moves`. The pattern of a state must
+  /// only depend on the pieces that the search cares about, and `pattern`
+  /// must keep the solved state.
+  pub fn with_moves(
+    moves: &[P::Move],
+    pattern: fn(&P) -> P,
+  ) -> PuzzleDatabase<P> {
+    let mut distances = HashMap::new();
+    distances.insert(pattern(&P::solved()), 0);
+    let mut frontier = vec![pattern(&P::solved())];
+    let mut depth = 0;
+    while !frontier.is_empty() {
+      depth += 1;
+      let mut next = vec![];
+      for state in &frontier {
+        for &m in moves {
+          let state = pattern(&state.apply_move(m));
+          distances.entry(state).or_insert_with(|| {
+            next.push(state);
+            depth
+          });
+        }
+      }
+      frontier = next;
+    }
+    PuzzleDatabase {
+      moves: moves.to_vec(),
+      pattern,
+      distances,
+    }
+  }
+
+  /// The number of patterns.
+  pub fn len(&self) -> usize {
+    self.distances.len()
+  }
+
+  /// Check if the table has no patterns, which is never the case.
+  pub fn is_empty(&self) -> bool {
+    self.distances.is_empty()
+  }
+
+  /// The largest number of moves needed to solve any pattern.
+  pub fn max_distance(&self) -> usize {
+    self.distances.values().cloned().max().unwrap_or(0)
+  }
+
+  /// The minimum number of moves needed to solve the pattern of `state`.
+  ///
+  /// Returns `None` if it can not be solved with the moves of the table.
+  pub fn distance(&self, state: &P) -> Option<usize> {
+    self.distances.get(&(self.pattern)(state)).cloned()
+  }
+
+  /// A shortest sequence of moves that solves the pattern of `state`, found
+  /// by following moves that reduce the distance.
+  ///
+  /// Returns `None` if it can not be solved with the moves of the table.
+  pub fn solve(&self, state: &P) -> Option<Vec<P::Move>> {
+    let mut state = *state;
+    let mut distance = self.distance(&state)?;
+    let mut solution = Vec::with_capacity(distance);
+    while distance > 0 {
+      let m = *self
+        .moves
+        .iter()
+        .find(|&&m| self.distance(&state.apply_move(m)) == Some(distance - 1))
+        .expect("every pattern has a neighbour that is one move closer");
+      state = state.apply_move(m);
+      solution.push(m);
+      distance -= 1;
+    }
+    Some(solution)
+  }
+}
+
+impl<P: PermutationPuzzle> Default for PuzzleDatabase<P> {
+  fn default() -> PuzzleDatabase<P> {
+    PuzzleDatabase::new()
+  }
+}
+
+/// An optimal solver for the Pyraminx, which finds the fewest turns of the
+/// layers.
+///
+/// The tips are left out of the table, which then has 6!/2 * 2^5 * 3^4 =
+/// 933120 patterns, since each of them can be solved with one move at the
+/// end, which is how Pyraminx solutions are usually counted.
+pub struct PyraminxSolver {
+  layers: PuzzleDatabase<Pyraminx>,
+}
+
+impl PyraminxSolver {
+  /// Build the table.
+  pub fn new() -> PyraminxSolver {
+    let moves: Vec<PyraminxMove> =
+      Pyraminx::moves().into_iter().filter(|m| !m.tip).collect();
+    PyraminxSolver {
+      layers: PuzzleDatabase::with_moves(&moves, Pyraminx::without_tips),
+    }
+  }
+
+  /// The solver shared by the whole program, which builds its table on the
+  /// first call.
+  pub fn get() -> &'static PyraminxSolver {
+    static SOLVER: OnceLock<PyraminxSolver> = OnceLock::new();
+    SOLVER.get_or_init(PyraminxSolver::new)
+  }
+
+  /// A solution of `pyraminx` with the fewest turns of the layers, followed
+  /// by a turn of each tip that is still not solved.
+  pub fn solve(&self, pyraminx: &Pyraminx) -> Vec<PyraminxMove> {
+    let mut solution = self
+      .layers
+      .solve(pyraminx)
+      .expect("every Pyraminx can be solved");
+    let pyraminx = pyraminx.apply_algorithm(&solution);
+    for &vertex in &Vertex::ALL {
+      let clockwise = match pyraminx.tips[vertex as usize] {
+        0 => continue,
+        1 => false,
+        _ => true,
+      };
+      solution.push(PyraminxMove {
+        vertex,
+        clockwise,
+        tip: true,
+      });
+    }
+    solution
+  }
+}
+
+impl Default for PyraminxSolver {
+  fn default() -> PyraminxSolver {
+    PyraminxSolver::new()
+  }
+}
+
+/// A solution of `pyraminx` with the fewest turns of the layers, using the
+/// table from `PyraminxSolver::get`.
+pub fn solve_pyraminx(pyraminx: &Pyraminx) -> Vec<PyraminxMove> {
+  PyraminxSolver::get().solve(pyraminx)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, parse_pyraminx_algorithm, Cube2};
+
+  #[test]
+  fn puzzle_database() {
+    // The 2x2x2 cube with only U and R, which never move DLF and DBL.
+    let moves = parse_algorithm("U U2 U' R R2 R'").unwrap();
+    let db = PuzzleDatabase::<Cube2>::with_moves(&moves, |c| *c);
+    assert_eq!(29160, db.len());
+    let cube = Cube2::from_algorithm(&parse_algorithm("R U R' U'").unwrap());
+    let solution = db.solve(&cube).unwrap();
+    assert_eq!(Some(4), db.distance(&cube));
+    assert_eq!(4, solution.len());
+    assert!(cube.apply_algorithm(&solution).is_solved());
+    let f = Cube2::from_algorithm(&parse_algorithm("F").unwrap());
+    assert_eq!(None, db.distance(&f));
+  }
+
+  #[test]
+  fn pyraminx() {
+    let solver = PyraminxSolver::get();
+    assert_eq!(933120, solver.layers.len());
+    // God's number of the Pyraminx without its tips is 11.
+    assert_eq!(11, solver.layers.max_distance());
+    assert!(solve_pyraminx(&Pyraminx::solved()).is_empty());
+
+    let scramble =
+      parse_pyraminx_algorithm("R U' L' U R' B L' U' l b'").unwrap();
+    let pyraminx = Pyraminx::from_algorithm(&scramble);
+    let solution = solve_pyraminx(&pyraminx);
+    assert!(solution.iter().filter(|m| !m.tip).count() <= 8);
+    assert!(pyraminx.apply_algorithm(&solution).is_solved());
+
+    let pyraminx =
+      Pyraminx::from_algorithm(&parse_pyraminx_algorithm("U").unwrap());
+    assert_eq!(
+      parse_pyraminx_algorithm("U'").unwrap(),
+      solve_pyraminx(&pyraminx)
+    );
+  }
+}
@@ -0,0 +1,539 @@
+use cube::{Corner, Cube, Edge, Face, Move};
+use human::{
+  last_layer_states, Algorithm, AUF, OLL_ALGORITHMS, PLL_ALGORITHMS,
+};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use two_phase::solve;
+
+/// An OLL case, as recognized by `recognize_oll`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct OllCase {
+  /// The name of the case, such as `OLL 27`, or `OLL skip` if the last layer
+  /// is already oriented.
+  pub name: &'static str,
+  /// The turn of U that lines up the last layer before the algorithm of the
+  /// case, if any.
+  pub auf: Option<Move>,
+}
+
+/// A PLL case, as recognized by `recognize_pll`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PllCase {
+  /// The name of the case, such as `T perm`, or `PLL skip` if the last layer
+  /// only needs a turn of U.
+  pub name: &'static str,
+  /// The turn of U before the algorithm of the case, if any.
+  pub pre_auf: Option<Move>,
+  /// The turn of U after the algorithm of the case, if any.
+  pub post_auf: Option<Move>,
+}
+
+const LAST_LAYER_CORNERS: [Corner; 4] = Face::U.adjacent_corners();
+
+const LAST_LAYER_EDGES: [Edge; 4] = Face::U.adjacent_edges();
+
+// The orientation of each last layer piece, which is all that an OLL
+// algorithm depends on.
+fn orientation_key(cube: &Cube) -> [u8; 8] {
+  let mut key = [0; 8];
+  for (i, &c) in LAST_LAYER_CORNERS.iter().enumerate() {
+    key[i] = cube.corner_orientation_at(c);
+  }
+  for (i, &e) in LAST_LAYER_EDGES.iter().enumerate() {
+    key[i + 4] = cube.edge_orientation_at(e);
+  }
+  key
+}
+
+// The piece at each last layer position.
+fn permutation_key(cube: &Cube) -> [u8; 8] {
+  let mut key = [0; 8];
+  for (i, &c) in LAST_LAYER_CORNERS.iter().enumerate() {
+    key[i] = cube.corner_at(c) as u8;
+  }
+  for (i, &e) in LAST_LAYER_EDGES.iter().enumerate() {
+    key[i + 4] = cube.edge_at(e) as u8;
+  }
+  key
+}
+
+// The turn of U by `quarter_turns`, as a cube.
+fn u_turn(quarter_turns: usize) -> Cube {
+  Cube::from_algorithm(AUF[quarter_turns % 4])
+}
+
+// Every last layer case, keyed by the orientation or permutation of the
+// last layer before the turns of U.
+struct Cases {
+  oll: HashMap<[u8; 8], OllCase>,
+  pll: HashMap<[u8; 8], PllCase>,
+}
+
+impl Cases {
+  // Undo each algorithm of the tables, after every turn of U, to find the
+  // cases they solve. Where a case is symmetric, the fewest turns of U are
+  // kept.
+  fn new() -> Cases {
+    let mut oll = HashMap::new();
+    oll.insert(
+      orientation_key(&Cube::solved()),
+      OllCase {
+        name: "OLL skip",
+        auf: None,
+      },
+    );
+    for alg in OLL_ALGORITHMS.iter().cloned().map(Algorithm::new) {
+      let case = alg.cube.inverse();
+      for auf in 0..4 {
+        let cube = case.compose(&u_turn(4 - auf));
+        oll.entry(orientation_key(&cube)).or_insert(OllCase {
+          name: alg.name,
+          auf: Move::new(Face::U, auf as u8),
+        });
+      }
+    }
+
+    let mut pll = HashMap::new();
+    for post in 0..4 {
+      pll.insert(
+        permutation_key(&u_turn(4 - post)),
+        PllCase {
+          name: "PLL skip",
+          pre_auf: None,
+          post_auf: Move::new(Face::U, post as u8),
+        },
+      );
+    }
+    for alg in PLL_ALGORITHMS.iter().cloned().map(Algorithm::new) {
+      let case = alg.cube.inverse();
+      for pre in 0..4 {
+        for post in 0..4 {
+          let cube = u_turn(4 - post).compose(&case).compose(&u_turn(4 - pre));
+          pll.entry(permutation_key(&cube)).or_insert(PllCase {
+            name: alg.name,
+            pre_auf: Move::new(Face::U, pre as u8),
+            post_auf: Move::new(Face::U, post as u8),
+          });
+        }
+      }
+    }
+    Cases { oll, pll }
+  }
+
+  fn get() -> &'static Cases {
+    static CASES: OnceLock<Cases> = OnceLock::new();
+    CASES.get_or_init(Cases::new)
+  }
+}
+
+/// Recognize the OLL case of a cube with the first two layers solved, or
+/// `None` if they are not.
+///
+/// The case is found from the orientation of the last layer after every turn
+/// of U, so it does not matter how the last layer is turned. Doing the `auf`
+/// and then the algorithm of the case from `CfopSolver` orients the last
+/// layer.
+pub fn recognize_oll(cube: &Cube) -> Option<OllCase> {
+  if !cube.is_f2l_solved() {
+    return None;
+  }
+  Cases::get().oll.get(&orientation_key(cube)).cloned()
+}
+
+/// Recognize the PLL case of a cube with an oriented last layer, or `None` if
+/// it is not oriented.
+///
+/// Doing the `pre_auf`, the algorithm of the case from `CfopSolver`, and the
+/// `post_auf` solves the cube.
+pub fn recognize_pll(cube: &Cube) -> Option<PllCase> {
+  if !cube.is_last_layer_oriented() {
+    return None;
+  }
+  Cases::get().pll.get(&permutation_key(cube)).cloned()
+}
+
+/// A COLL case, as recognized by `recognize_coll`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CollCase {
+  /// The name of the case, which is the shape of the corners followed by a
+  /// number, such as `COLL T 3`, or `COLL skip` if the corners only need a
+  /// turn of U.
+  pub name: &'static str,
+  /// The turn of U before the algorithm, if any.
+  pub auf: Option<Move>,
+  /// An algorithm that solves the corners up to a turn of U, keeping the
+  /// first two layers and the orientation of the edges.
+  pub algorithm: &'static [Move],
+}
+
+/// A ZBLL case, as recognized by `recognize_zbll`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ZbllCase {
+  /// The name of the case, which is the shape of the corners followed by a
+  /// number, such as `ZBLL U 12`. Cases with oriented corners are PLL cases,
+  /// and are named after them.
+  pub name: &'static str,
+  /// The turn of U before the algorithm, if any.
+  pub pre_auf: Option<Move>,
+  /// The turn of U after the algorithm, if any.
+  pub post_auf: Option<Move>,
+  /// An algorithm that solves the case.
+  pub algorithm: &'static [Move],
+}
+
+/// The OLL cases with oriented edges, which give the shapes of the corners
+/// that COLL and ZBLL cases are named after.
+const SHAPES: [(&str, &str); 7] = [
+  ("OLL 21", "H"),
+  ("OLL 22", "Pi"),
+  ("OLL 23", "U"),
+  ("OLL 24", "T"),
+  ("OLL 25", "L"),
+  ("OLL 26", "AS"),
+  ("OLL 27", "S"),
+];
+
+// Every last layer with oriented edges, with the last layer edges solved if
+// `with_edges` is false, or swapping UL and UB where the corners need it.
+fn last_layers(with_edges: bool) -> Vec<Cube> {
+  let solved = Cube::solved();
+  let mut swapped = solved;
+  swapped.ep.swap(2, 3);
+  last_layer_states()
+    .into_iter()
+    .filter(|c| c.eo == solved.eo)
+    .filter(|c| with_edges || c.ep == solved.ep || c.ep == swapped.ep)
+    .collect()
+}
+
+// The name of the shape of the corners of `cube`, which is the same after
+// any turn of U.
+fn shape(cube: &Cube) -> &'static str {
+  let shape_key = |cube: &Cube| {
+    let co: Vec<u8> = LAST_LAYER_CORNERS
+      .iter()
+      .map(|&c| cube.corner_orientation_at(c))
+      .collect();
+    (0..4).map(|i| [&co[i..], &co[..i]].concat()).min()
+  };
+  let key = shape_key(cube);
+  SHAPES
+    .iter()
+    .find(|&&(oll, _)| {
+      let alg = OLL_ALGORITHMS.iter().find(|&&(name, _)| name == oll);
+      let alg = Algorithm::new(*alg.expect("every shape is in the table"));
+      shape_key(&alg.cube.inverse()) == key
+    })
+    .map_or("O", |&(_, shape)| shape)
+}
+
+// The key of the corners of the last layer, followed by the edges if
+// `with_edges` is true.
+fn last_layer_key(cube: &Cube, with_edges: bool) -> [u8; 12] {
+  let mut key = [0; 12];
+  key[..4].copy_from_slice(&orientation_key(cube)[..4]);
+  key[4..].copy_from_slice(&permutation_key(cube));
+  if !with_edges {
+    key[8..].copy_from_slice(&[0; 4]);
+  }
+  key
+}
+
+// The cases of COLL or ZBLL, with an algorithm for each from `solve`.
+struct Subset {
+  names: Vec<String>,
+  algorithms: Vec<Vec<Move>>,
+  // The case of each last layer, with the turns of U before and after its
+  // algorithm.
+  states: HashMap<[u8; 12], (usize, usize, usize)>,
+}
+
+impl Subset {
+  // Group the last layers into cases that are the same up to turns of U
+  // before and after, named by the shape of the corners and the order they
+  // are found in.
+  fn new(prefix: &str, with_edges: bool) -> Subset {
+    let mut subset = Subset {
+      names: vec![],
+      algorithms: vec![],
+      states: HashMap::new(),
+    };
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for case in last_layers(with_edges) {
+      if subset
+        .states
+        .contains_key(&last_layer_key(&case, with_edges))
+      {
+        continue;
+      }
+      let index = subset.names.len();
+      let shape = shape(&case);
+      let name = match (shape, with_edges) {
+        ("O", true) => recognize_pll(&case).expect("a PLL case").name.into(),
+        ("O", false) if case.cp == Cube::solved().cp => {
+          format!("{} skip", prefix)
+        }
+        _ => {
+          let count = counts.entry(shape).or_insert(0);
+          *count += 1;
+          format!("{} {} {}", prefix, shape, count)
+        }
+      };
+      subset.names.push(name);
+      subset.algorithms.push(solve(&case));
+      for pre in 0..4 {
+        for post in 0..4 {
+          let cube = u_turn(4 - post).compose(&case).compose(&u_turn(4 - pre));
+          let key = last_layer_key(&cube, with_edges);
+          subset.states.entry(key).or_insert((index, pre, post));
+        }
+      }
+    }
+    subset
+  }
+
+  fn coll() -> &'static Subset {
+    static COLL: OnceLock<Subset> = OnceLock::new();
+    COLL.get_or_init(|| Subset::new("COLL", false))
+  }
+
+  fn zbll() -> &'static Subset {
+    static ZBLL: OnceLock<Subset> = OnceLock::new();
+    ZBLL.get_or_init(|| Subset::new("ZBLL", true))
+  }
+
+  fn coll_case(
+    &'static self,
+    (case, pre, _): (usize, usize, usize),
+  ) -> CollCase {
+    CollCase {
+      name: &self.names[case],
+      auf: Move::new(Face::U, pre as u8),
+      algorithm: &self.algorithms[case],
+    }
+  }
+
+  fn zbll_case(
+    &'static self,
+    (case, pre, post): (usize, usize, usize),
+  ) -> ZbllCase {
+    ZbllCase {
+      name: &self.names[case],
+      pre_auf: Move::new(Face::U, pre as u8),
+      post_auf: Move::new(Face::U, post as u8),
+      algorithm: &self.algorithms[case],
+    }
+  }
+}
+
+// Whether the first two layers are solved and the last layer edges are
+// oriented, which is where COLL and ZBLL start.
+fn edges_oriented(cube: &Cube) -> bool {
+  cube.is_f2l_solved()
+    && LAST_LAYER_EDGES
+      .iter()
+      .all(|&e| cube.edge_orientation_at(e) == 0)
+}
+
+/// Recognize the COLL case of a cube with the first two layers solved and
+/// the last layer edges oriented, or `None` if it is not.
+///
+/// Doing the `auf` and then the `algorithm` solves the corners up to a turn
+/// of U, and leaves only the edges to permute. The algorithms are found with
+/// `solve` the first time a COLL case is recognized, rather than taken from
+/// a table, so they are not the ones a person would learn.
+pub fn recognize_coll(cube: &Cube) -> Option<CollCase> {
+  if !edges_oriented(cube) {
+    return None;
+  }
+  let subset = Subset::coll();
+  let state = subset.states.get(&last_layer_key(cube, false))?;
+  Some(subset.coll_case(*state))
+}
+
+/// Recognize the ZBLL case of a cube with the first two layers solved and
+/// the last layer edges oriented, or `None` if it is not.
+///
+/// Doing the `pre_auf`, the `algorithm` and the `post_auf` solves the cube.
+/// Like `recognize_coll`, the algorithms are found with `solve` the first
+/// time a ZBLL case is recognized, which takes several seconds.
+pub fn recognize_zbll(cube: &Cube) -> Option<ZbllCase> {
+  if !edges_oriented(cube) {
+    return None;
+  }
+  let subset = Subset::zbll();
+  let state = subset.states.get(&last_layer_key(cube, true))?;
+  Some(subset.zbll_case(*state))
+}
+
+/// Every COLL case, with no turn of U before its algorithm.
+pub fn coll_cases() -> Vec<CollCase> {
+  let subset = Subset::coll();
+  (0..subset.names.len())
+    .map(|case| subset.coll_case((case, 0, 0)))
+    .collect()
+}
+
+/// Every ZBLL case, with no turns of U around its algorithm.
+pub fn zbll_cases() -> Vec<ZbllCase> {
+  let subset = Subset::zbll();
+  (0..subset.names.len())
+    .map(|case| subset.zbll_case((case, 0, 0)))
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, Turn};
+
+  // The moves of the table algorithm called `name`.
+  fn algorithm(table: &[(&'static str, &str)], name: &str) -> Vec<Move> {
+    table
+      .iter()
+      .cloned()
+      .map(Algorithm::new)
+      .find(|alg| alg.name == name)
+      .map_or(vec![], |alg| alg.moves)
+  }
+
+  #[test]
+  fn oll() {
+    assert_eq!(
+      None,
+      recognize_oll(&Cube::from_algorithm(&[Move(Face::R, Turn::Clockwise)]))
+    );
+
+    let cube =
+      Cube::from_algorithm(&parse_algorithm("R U2 R' U' R U' R'").unwrap());
+    let case = recognize_oll(&cube).unwrap();
+    assert_eq!("OLL 27", case.name);
+
+    let mut names = vec![];
+    let solved = Cube::solved();
+    let states = last_layer_states().into_iter();
+    for cube in states.filter(|c| c.cp == solved.cp && c.ep == solved.ep) {
+      let case = recognize_oll(&cube).unwrap();
+      let cube = cube
+        .apply_algorithm(&case.auf.into_iter().collect::<Vec<_>>())
+        .apply_algorithm(&algorithm(&OLL_ALGORITHMS, case.name));
+      assert!(cube.is_last_layer_oriented(), "{}", case.name);
+      names.push(case.name);
+    }
+    names.sort();
+    names.dedup();
+    assert_eq!(58, names.len());
+  }
+
+  #[test]
+  fn pll() {
+    let scramble =
+      parse_algorithm("R U R' F' R U R' U' R' F R2 U' R' U").unwrap();
+    let cube = Cube::from_algorithm(&scramble);
+    assert_eq!("Jb perm", recognize_pll(&cube).unwrap().name);
+    assert_eq!(
+      None,
+      recognize_pll(&Cube::from_algorithm(&[Move(Face::F, Turn::Clockwise)]))
+    );
+
+    let mut names = vec![];
+    let solved = Cube::solved();
+    let states = last_layer_states().into_iter();
+    for cube in states.filter(|c| c.co == solved.co && c.eo == solved.eo) {
+      let case = recognize_pll(&cube).unwrap();
+      let moves: Vec<Move> = case
+        .pre_auf
+        .into_iter()
+        .chain(algorithm(&PLL_ALGORITHMS, case.name))
+        .chain(case.post_auf)
+        .collect();
+      assert_eq!(
+        Cube::solved(),
+        cube.apply_algorithm(&moves),
+        "{}",
+        case.name
+      );
+      names.push(case.name);
+    }
+    names.sort();
+    names.dedup();
+    assert_eq!(22, names.len());
+  }
+
+  // The number of cases of each shape, by the second word of their names.
+  fn shapes(names: &[&str]) -> HashMap<String, usize> {
+    let mut shapes = HashMap::new();
+    for name in names {
+      let shape = name.split(' ').nth(1).unwrap_or("");
+      *shapes.entry(shape.to_string()).or_insert(0) += 1;
+    }
+    shapes
+  }
+
+  #[test]
+  fn coll() {
+    let oll_27 = parse_algorithm("R U2 R' U' R U' R'").unwrap();
+    assert_eq!(None, recognize_coll(&Cube::from_algorithm(&oll_27[..3])));
+    let name = recognize_coll(&Cube::from_algorithm(&oll_27)).unwrap().name;
+    assert!(name.starts_with("COLL S "), "{}", name);
+
+    let cases = coll_cases();
+    let names: Vec<&str> = cases.iter().map(|case| case.name).collect();
+    let shapes = shapes(&names);
+    assert_eq!(43, names.len());
+    assert_eq!(Some(&4), shapes.get("H"));
+    assert_eq!(Some(&6), shapes.get("S"));
+    assert_eq!(Some(&2), shapes.get("O"));
+
+    // Every algorithm solves the corners of its case, whatever the edges.
+    for cube in last_layers(true) {
+      let case = recognize_coll(&cube).unwrap();
+      let moves: Vec<Move> = case
+        .auf
+        .into_iter()
+        .chain(case.algorithm.to_vec())
+        .collect();
+      let cube = cube.apply_algorithm(&moves);
+      assert!(edges_oriented(&cube), "{}", case.name);
+      let corners = last_layer_key(&cube, false);
+      assert!(
+        (0..4).any(|u| last_layer_key(&u_turn(u), false) == corners),
+        "{}",
+        case.name
+      );
+    }
+  }
+
+  #[test]
+  fn zbll() {
+    let cube = Cube::from_algorithm(&[Move(Face::F, Turn::Clockwise)]);
+    assert_eq!(None, recognize_zbll(&cube));
+
+    let cases = zbll_cases();
+    let names: Vec<&str> = cases.iter().map(|case| case.name).collect();
+    let shapes = shapes(&names);
+    assert_eq!(494, names.len());
+    assert_eq!(Some(&40), shapes.get("H"));
+    assert_eq!(Some(&72), shapes.get("T"));
+    assert_eq!(Some(&21), shapes.get("perm"));
+    assert!(names.contains(&"PLL skip"));
+
+    // Every algorithm solves its case.
+    for cube in last_layers(true) {
+      let case = recognize_zbll(&cube).unwrap();
+      let moves: Vec<Move> = case
+        .pre_auf
+        .into_iter()
+        .chain(case.algorithm.to_vec())
+        .chain(case.post_auf)
+        .collect();
+      assert_eq!(
+        Cube::solved(),
+        cube.apply_algorithm(&moves),
+        "{}",
+        case.name
+      );
+    }
+  }
+}
@@ -0,0 +1,139 @@
+use cube::{Cube, Move};
+use optimal::ida_star;
+use pattern_database::{PatternDatabase, PieceSet};
+
+/// A solver that only turns with a subset of the moves, such as the moves of
+/// R and U for 2-gen algorithms, which finds the shortest solutions with
+/// them.
+///
+/// The tables of the two-phase algorithm assume all 18 moves, so the solver
+/// builds its own pattern databases for its moves instead, which only hold
+/// the patterns that those moves reach. That keeps them small for the
+/// subsets that are usually searched, but building them for most of the 18
+/// moves takes a few seconds.
+pub struct RestrictedSolver {
+  moves: Vec<Move>,
+  databases: Vec<PatternDatabase>,
+}
+
+impl RestrictedSolver {
+  /// Create a solver that only turns with `moves`, with databases of the U
+  /// corners, the D corners, the U edges, the D edges and the E-slice edges.
+  pub fn new(moves: &[Move]) -> RestrictedSolver {
+    use cube::Corner::*;
+    use cube::Edge::*;
+    RestrictedSolver::with_pieces(
+      moves,
+      &[
+        PieceSet::new(&[URF, UFL, ULB, UBR], &[]),
+        PieceSet::new(&[DFR, DLF, DBL, DRB], &[]),
+        PieceSet::new(&[], &[UR, UF, UL, UB]),
+        PieceSet::new(&[], &[DR, DF, DL, DB]),
+        PieceSet::new(&[], &[FR, FL, BL, BR]),
+      ],
+    )
+  }
+
+  /// Create a solver that only turns with `moves`, with a database of each
+  /// of `pieces` for those moves.
+  pub fn with_pieces(moves: &[Move], pieces: &[PieceSet]) -> RestrictedSolver {
+    RestrictedSolver {
+      moves: moves.to_vec(),
+      databases: pieces
+        .iter()
+        .map(|p| PatternDatabase::with_moves(p.clone(), moves))
+        .collect(),
+    }
+  }
+
+  /// The moves that the solver turns with.
+  pub fn moves(&self) -> &[Move] {
+    &self.moves
+  }
+
+  // The largest lower bound of the databases, which is `usize::MAX` if the
+  // pieces of any of them can not be solved with the moves.
+  fn lower_bound(&self, cube: &Cube) -> usize {
+    self
+      .databases
+      .iter()
+      .map(|db| db.distance(cube).unwrap_or(usize::MAX))
+      .max()
+      .unwrap_or(0)
+  }
+
+  /// Find a shortest solution of at most `max_depth` moves that only turns
+  /// with the moves of the solver, or `None` if there is none.
+  pub fn solve(&self, cube: &Cube, max_depth: usize) -> Option<Vec<Move>> {
+    ida_star(cube, &self.moves, max_depth, &|cube| self.lower_bound(cube))
+  }
+}
+
+/// Find a shortest solution of at most `max_depth` moves that only turns
+/// with `moves`, or `None` if there is none.
+///
+/// This builds a `RestrictedSolver` for every call, so keep one to solve
+/// several cubes with the same moves.
+pub fn solve_restricted(
+  cube: &Cube,
+  moves: &[Move],
+  max_depth: usize,
+) -> Option<Vec<Move>> {
+  RestrictedSolver::new(moves).solve(cube, max_depth)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{parse_algorithm, Face, Turn};
+
+  fn two_gen() -> Vec<Move> {
+    [Face::R, Face::U]
+      .iter()
+      .flat_map(|&f| Turn::ALL.iter().map(move |&t| Move(f, t)))
+      .collect()
+  }
+
+  #[test]
+  fn two_gen_solutions() {
+    let solver = RestrictedSolver::new(&two_gen());
+    assert_eq!(two_gen(), solver.moves());
+    assert_eq!(Some(vec![]), solver.solve(&Cube::solved(), 0));
+
+    // Sune.
+    let sune = parse_algorithm("R U R' U R U2 R'").unwrap();
+    let cube = Cube::from_algorithm(&sune);
+    let solution = solver.solve(&cube, 10).unwrap();
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+    assert!(solution.iter().all(|m| two_gen().contains(m)));
+    assert_eq!(7, solution.len());
+    assert_eq!(None, solver.solve(&cube, 6));
+
+    // F can not be undone with R and U.
+    let cube = Cube::from_algorithm(&parse_algorithm("F").unwrap());
+    assert_eq!(None, solver.solve(&cube, 10));
+  }
+
+  #[test]
+  fn quarter_turns_only() {
+    let moves = parse_algorithm("R R' U U'").unwrap();
+    let cube = Cube::from_algorithm(&parse_algorithm("R2 U2").unwrap());
+    let solution = solve_restricted(&cube, &moves, 4).unwrap();
+    assert_eq!(4, solution.len());
+    assert!(solution.iter().all(|m| moves.contains(m)));
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+  }
+
+  #[test]
+  fn moves_without_inverses() {
+    // R' is undone by R, even though R' can not be turned.
+    let moves = parse_algorithm("R U").unwrap();
+    let solver = RestrictedSolver::new(&moves);
+    let cube = Cube::from_algorithm(&parse_algorithm("R'").unwrap());
+    assert_eq!(Some(parse_algorithm("R").unwrap()), solver.solve(&cube, 5));
+    let cube = Cube::from_algorithm(&parse_algorithm("R U").unwrap());
+    let solution = solver.solve(&cube, 8).unwrap();
+    assert_eq!(6, solution.len());
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+  }
+}
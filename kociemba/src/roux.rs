@@ -0,0 +1,507 @@
+use cube::{
+  expand_algorithm, parse_extended_algorithm, Corner, Cube, Edge, ExtendedMove,
+  Face, Move, Orientation, OrientedCube, RouxBlock, Slice, Turn,
+};
+use human::{OLL_ALGORITHMS, PLL_ALGORITHMS};
+use pattern_database::{PatternDatabase, PieceSet};
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// The stages of the Roux method.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RouxStage {
+  /// Build the 1x2x3 block on L.
+  FirstBlock,
+  /// Build the 1x2x3 block on R, without breaking the first block.
+  SecondBlock,
+  /// Solve the U corners, ignoring the M slice.
+  Cmll,
+  /// Solve the last six edges and the centers with U and M.
+  Lse,
+}
+
+/// A step of a Roux solution, which solves one `RouxStage`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RouxStep {
+  /// The stage that the step solves.
+  pub stage: RouxStage,
+  /// The name of the step, which for CMLL lists the algorithms used, such as
+  /// `OLL 26, T perm`, or `CMLL skip`.
+  pub name: String,
+  /// The moves of the step, held with the first block at the bottom left.
+  pub moves: Vec<ExtendedMove>,
+}
+
+// The quarter, half and counter turns of each of `moves`.
+fn all_turns(moves: &[fn(Turn) -> ExtendedMove]) -> Vec<ExtendedMove> {
+  moves
+    .iter()
+    .flat_map(|m| Turn::ALL.iter().map(move |&turn| m(turn)))
+    .collect()
+}
+
+/// The moves of the second block, which never move the first block.
+fn second_block_moves() -> Vec<ExtendedMove> {
+  all_turns(&[
+    |t| ExtendedMove::Face(Move(Face::U, t)),
+    |t| ExtendedMove::Face(Move(Face::R, t)),
+    |t| ExtendedMove::Wide(Face::R, t),
+    |t| ExtendedMove::Slice(Slice::M, t),
+  ])
+}
+
+/// The moves of the last six edges, which never move either block.
+fn lse_moves() -> Vec<ExtendedMove> {
+  all_turns(&[
+    |t| ExtendedMove::Face(Move(Face::U, t)),
+    |t| ExtendedMove::Slice(Slice::M, t),
+  ])
+}
+
+// Whether `a` and `b` turn the same layers, so that they should not follow
+// each other in a search.
+fn same_layers(a: ExtendedMove, b: ExtendedMove) -> bool {
+  match (a, b) {
+    (ExtendedMove::Face(Move(f, _)), ExtendedMove::Face(Move(g, _))) => f == g,
+    (ExtendedMove::Slice(s, _), ExtendedMove::Slice(t, _)) => s == t,
+    (ExtendedMove::Wide(f, _), ExtendedMove::Wide(g, _)) => f == g,
+    _ => false,
+  }
+}
+
+// A number for each `Orientation`.
+fn orientation_index(orientation: Orientation) -> usize {
+  orientation.up().index() * 6 + orientation.front().index()
+}
+
+// An exact-distance table like `PatternDatabase`, for moves that can turn
+// the middle layers. The pattern of the pieces is relative to the centers,
+// so it is kept together with where the centers are.
+struct BlockTable {
+  pieces: PieceSet,
+  moves: Vec<ExtendedMove>,
+  distances: HashMap<(u128, usize), usize>,
+}
+
+impl BlockTable {
+  // Build the table of `pieces` for `moves`, which must include the inverse
+  // of each move, by a breadth-first search from `targets`.
+  fn new(
+    pieces: PieceSet,
+    moves: Vec<ExtendedMove>,
+    targets: &[OrientedCube],
+  ) -> BlockTable {
+    let mut table = BlockTable {
+      pieces,
+      moves,
+      distances: HashMap::new(),
+    };
+    let mut frontier = vec![];
+    for target in targets {
+      if table.distances.insert(table.key(target), 0).is_none() {
+        frontier.push(*target);
+      }
+    }
+    let mut depth = 0;
+    while !frontier.is_empty() {
+      depth += 1;
+      let mut next = vec![];
+      for cube in &frontier {
+        for &m in &table.moves {
+          let cube = cube.apply_move(m);
+          table.distances.entry(table.key(&cube)).or_insert_with(|| {
+            next.push(cube);
+            depth
+          });
+        }
+      }
+      frontier = next;
+    }
+    table
+  }
+
+  fn key(&self, cube: &OrientedCube) -> (u128, usize) {
+    let pattern = self.pieces.pattern(&cube.cube());
+    (pattern, orientation_index(cube.orientation()))
+  }
+
+  fn distance(&self, cube: &OrientedCube) -> Option<usize> {
+    self.distances.get(&self.key(cube)).cloned()
+  }
+
+  // A shortest sequence of moves to a target, found by following moves that
+  // reduce the distance.
+  fn solve(&self, cube: &OrientedCube) -> Option<Vec<ExtendedMove>> {
+    let mut cube = *cube;
+    let mut distance = self.distance(&cube)?;
+    let mut solution = vec![];
+    while distance > 0 {
+      let m = *self
+        .moves
+        .iter()
+        .find(|&&m| self.distance(&cube.apply_move(m)) == Some(distance - 1))
+        .expect("every pattern has a neighbour that is one move closer");
+      cube = cube.apply_move(m);
+      solution.push(m);
+      distance -= 1;
+    }
+    Some(solution)
+  }
+}
+
+// An algorithm from the OLL and PLL tables, which ends with the cube held
+// the way it started.
+struct Algorithm {
+  name: &'static str,
+  moves: Vec<ExtendedMove>,
+}
+
+// The algorithms of `table` that do not rotate the cube overall.
+fn algorithms(table: &[(&'static str, &str)]) -> Vec<Algorithm> {
+  table
+    .iter()
+    .map(|&(name, alg)| Algorithm {
+      name,
+      moves: parse_extended_algorithm(alg).expect("invalid table algorithm"),
+    })
+    .filter(|alg| {
+      let (_, orientation) =
+        expand_algorithm(&alg.moves, Orientation::identity());
+      orientation == Orientation::identity()
+    })
+    .collect()
+}
+
+/// The turns of U that line up the last layer before an algorithm, starting
+/// with none.
+const AUF: [&[ExtendedMove]; 4] = [
+  &[],
+  &[ExtendedMove::Face(Move(Face::U, Turn::Clockwise))],
+  &[ExtendedMove::Face(Move(Face::U, Turn::Half))],
+  &[ExtendedMove::Face(Move(Face::U, Turn::Counter))],
+];
+
+const U_CORNERS: [Corner; 4] = Face::U.adjacent_corners();
+
+// The cube relative to centers that line up with the blocks, or `None` if
+// the blocks are not solved for any turn of the M slice.
+fn aligned(cube: &OrientedCube) -> Option<Cube> {
+  let m = ExtendedMove::Slice(Slice::M, Turn::Clockwise);
+  (0..4)
+    .map(|k| cube.apply_algorithm(&vec![m; k]).cube())
+    .find(|c| RouxBlock::ALL.iter().all(|&b| c.is_roux_block_solved(b)))
+}
+
+// Whether the U corners of `cube` have orientation 0.
+fn corners_oriented(cube: &OrientedCube) -> bool {
+  aligned(cube)
+    .is_some_and(|c| U_CORNERS.iter().all(|&u| c.corner_orientation_at(u) == 0))
+}
+
+// Whether the U corners of `cube` are solved after some turn of U.
+fn corners_solved(cube: &OrientedCube) -> bool {
+  aligned(cube).is_some_and(|c| {
+    (0..4).any(|u| {
+      let c = match Move::new(Face::U, u) {
+        Some(m) => c.apply_move(m),
+        None => c,
+      };
+      U_CORNERS.iter().all(|&u| c.is_corner_solved(u))
+    })
+  })
+}
+
+/// A solver that produces Roux solutions: the first block, the second
+/// block, the U corners with CMLL, and then the last six edges.
+///
+/// The blocks and the last six edges are found by search, so each is as
+/// short as possible for the moves Roux uses: any face turn for the first
+/// block, R, r, U and M for the second, and U and M for the last six edges.
+/// CMLL is solved in two looks, orienting and then permuting the corners,
+/// with the OLL and PLL algorithms of `CfopSolver`, since the edges can be
+/// left anywhere.
+pub struct RouxSolver {
+  // Lower bounds for the first block, which together track all its pieces.
+  first_block: [PatternDatabase; 2],
+  // The back square of the second block, then the front pair.
+  square: BlockTable,
+  pair: BlockTable,
+  lse: BlockTable,
+  oll: Vec<Algorithm>,
+  pll: Vec<Algorithm>,
+}
+
+impl RouxSolver {
+  /// Build the tables of the blocks and the last six edges.
+  pub fn new() -> RouxSolver {
+    use cube::Corner::*;
+    use cube::Edge::*;
+    let first_block = [
+      PatternDatabase::new(PieceSet::new(&[DLF, DBL], &[DL])),
+      PatternDatabase::new(PieceSet::new(&[], &[DL, FL, BL])),
+    ];
+
+    // The second block is solved whatever turn the M slice is left at.
+    let m = ExtendedMove::Slice(Slice::M, Turn::Clockwise);
+    let targets: Vec<OrientedCube> = (0..4)
+      .map(|k| OrientedCube::solved().apply_algorithm(&vec![m; k]))
+      .collect();
+    let square = BlockTable::new(
+      PieceSet::new(&[DRB], &[DR, BR]),
+      second_block_moves(),
+      &targets,
+    );
+    let pair = BlockTable::new(
+      PieceSet::new(&[DFR], &[FR]),
+      second_block_moves(),
+      &targets,
+    );
+
+    let lse: [Edge; 6] = [UR, UF, UL, UB, DF, DB];
+    let lse = BlockTable::new(
+      PieceSet::new(&[URF, UFL, ULB, UBR], &lse),
+      lse_moves(),
+      &[OrientedCube::solved()],
+    );
+
+    RouxSolver {
+      first_block,
+      square,
+      pair,
+      lse,
+      oll: algorithms(&OLL_ALGORITHMS),
+      pll: algorithms(&PLL_ALGORITHMS),
+    }
+  }
+
+  /// The solver shared by the whole program, which builds its tables on the
+  /// first call.
+  pub fn get() -> &'static RouxSolver {
+    static SOLVER: OnceLock<RouxSolver> = OnceLock::new();
+    SOLVER.get_or_init(RouxSolver::new)
+  }
+
+  /// Solve a cube, returning a step for each `RouxStage`. The cube must be
+  /// solvable.
+  pub fn solve(&self, cube: &Cube) -> Vec<RouxStep> {
+    debug_assert!(cube.verify().is_ok());
+    let mut cube = OrientedCube::from(*cube);
+    let mut steps = vec![];
+    let mut push = |cube: &mut OrientedCube, step: RouxStep| {
+      *cube = cube.apply_algorithm(&step.moves);
+      steps.push(step);
+    };
+
+    let moves = self.solve_first_block(&cube.cube());
+    push(&mut cube, step(RouxStage::FirstBlock, "First block", moves));
+
+    let square = self.square.solve(&cube).expect("the square is solvable");
+    let moves = self.solve_pair(&cube.apply_algorithm(&square), square);
+    push(
+      &mut cube,
+      step(RouxStage::SecondBlock, "Second block", moves),
+    );
+
+    let (name, moves) = self.solve_cmll(&cube);
+    push(&mut cube, step(RouxStage::Cmll, &name, moves));
+
+    let moves = self
+      .lse
+      .solve(&cube)
+      .expect("the last six edges are solvable");
+    push(&mut cube, step(RouxStage::Lse, "LSE", moves));
+    debug_assert_eq!(Cube::solved(), cube.cube());
+    steps
+  }
+
+  // The largest lower bound for the first block.
+  fn first_block_bound(&self, cube: &Cube) -> usize {
+    self
+      .first_block
+      .iter()
+      .map(|db| db.distance(cube).unwrap_or(usize::MAX))
+      .max()
+      .unwrap_or(0)
+  }
+
+  // Search for face turns after `solution` that solve the first block in
+  // exactly `depth` moves.
+  fn search_first_block(
+    &self,
+    cube: &Cube,
+    depth: usize,
+    solution: &mut Vec<Move>,
+  ) -> bool {
+    let lower_bound = self.first_block_bound(cube);
+    if depth == 0 || lower_bound > depth {
+      return lower_bound == 0;
+    }
+    for &face in &[Face::U, Face::D, Face::F, Face::B, Face::R, Face::L] {
+      if ::canonical::skip_face(solution, face) {
+        continue;
+      }
+      for &turn in &Turn::ALL {
+        let m = Move(face, turn);
+        solution.push(m);
+        if self.search_first_block(&cube.apply_move(m), depth - 1, solution) {
+          return true;
+        }
+        solution.pop();
+      }
+    }
+    false
+  }
+
+  fn solve_first_block(&self, cube: &Cube) -> Vec<ExtendedMove> {
+    let mut solution = vec![];
+    let mut depth = self.first_block_bound(cube);
+    while !self.search_first_block(cube, depth, &mut solution) {
+      depth += 1;
+    }
+    solution.into_iter().map(ExtendedMove::Face).collect()
+  }
+
+  // Search for second block moves after `solution` that insert the front
+  // pair and keep the square in exactly `depth` moves.
+  fn search_pair(
+    &self,
+    cube: &OrientedCube,
+    depth: usize,
+    solution: &mut Vec<ExtendedMove>,
+  ) -> bool {
+    let lower_bound = [&self.square, &self.pair]
+      .iter()
+      .map(|t| t.distance(cube).unwrap_or(usize::MAX))
+      .max()
+      .unwrap_or(0);
+    if depth == 0 || lower_bound > depth {
+      return lower_bound == 0;
+    }
+    for &m in &self.pair.moves {
+      if solution.last().is_some_and(|&last| same_layers(last, m)) {
+        continue;
+      }
+      solution.push(m);
+      if self.search_pair(&cube.apply_move(m), depth - 1, solution) {
+        return true;
+      }
+      solution.pop();
+    }
+    false
+  }
+
+  // Insert the front pair after the moves of the square, which are `square`.
+  fn solve_pair(
+    &self,
+    cube: &OrientedCube,
+    square: Vec<ExtendedMove>,
+  ) -> Vec<ExtendedMove> {
+    let mut solution = square;
+    let mut depth = 0;
+    while !self.search_pair(cube, depth, &mut solution) {
+      depth += 1;
+    }
+    solution
+  }
+
+  // The shortest algorithm of `algs`, with a turn of U before it, that takes
+  // `cube` to a cube where `done` holds.
+  fn shortest<'a>(
+    cube: &OrientedCube,
+    algs: &'a [Algorithm],
+    done: fn(&OrientedCube) -> bool,
+  ) -> (&'a str, Vec<ExtendedMove>) {
+    AUF
+      .iter()
+      .flat_map(|pre| algs.iter().map(move |alg| (pre, alg)))
+      .map(|(pre, alg)| (alg.name, [*pre, &alg.moves[..]].concat()))
+      .filter(|(_, moves)| done(&cube.apply_algorithm(moves)))
+      .min_by_key(|(_, moves)| moves.len())
+      .expect("every case is in the table")
+  }
+
+  // Orient and then permute the U corners.
+  fn solve_cmll(&self, cube: &OrientedCube) -> (String, Vec<ExtendedMove>) {
+    let mut names = vec![];
+    let mut moves = vec![];
+    let mut cube = *cube;
+    if !corners_oriented(&cube) {
+      let (name, orient) =
+        RouxSolver::shortest(&cube, &self.oll, corners_oriented);
+      cube = cube.apply_algorithm(&orient);
+      names.push(name);
+      moves.extend(orient);
+    }
+    if !corners_solved(&cube) {
+      let (name, permute) =
+        RouxSolver::shortest(&cube, &self.pll, corners_solved);
+      names.push(name);
+      moves.extend(permute);
+    }
+    if names.is_empty() {
+      return ("CMLL skip".to_string(), moves);
+    }
+    (names.join(", "), moves)
+  }
+}
+
+impl Default for RouxSolver {
+  fn default() -> RouxSolver {
+    RouxSolver::new()
+  }
+}
+
+fn step(stage: RouxStage, name: &str, moves: Vec<ExtendedMove>) -> RouxStep {
+  RouxStep {
+    stage,
+    name: name.to_string(),
+    moves,
+  }
+}
+
+/// Solve a cube with the Roux method, using the solver from
+/// `RouxSolver::get`.
+pub fn solve_roux(cube: &Cube) -> Vec<RouxStep> {
+  RouxSolver::get().solve(cube)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn tables() {
+    let solver = RouxSolver::get();
+    // V perm ends with a y rotation, so it can not be used for CMLL.
+    assert_eq!(20, solver.pll.len());
+    assert!(solver.lse.distances.len() > 1000);
+    assert!(solver.lse.distances.values().all(|&d| d <= 20));
+  }
+
+  #[test]
+  fn solve() {
+    let steps = solve_roux(&Cube::solved());
+    let names: Vec<&str> = steps.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(
+      vec!["First block", "Second block", "CMLL skip", "LSE"],
+      names
+    );
+    assert!(steps.iter().all(|s| s.moves.is_empty()));
+
+    let scramble = parse_extended_algorithm("R U2 F' L D B2 U' R2").unwrap();
+    let mut cube = OrientedCube::solved().apply_algorithm(&scramble);
+    let steps = solve_roux(&cube.cube());
+    for step in &steps {
+      cube = cube.apply_algorithm(&step.moves);
+      match step.stage {
+        RouxStage::FirstBlock => {
+          assert!(cube.cube().is_roux_block_solved(RouxBlock::First))
+        }
+        RouxStage::SecondBlock => assert!(aligned(&cube).is_some()),
+        RouxStage::Cmll => assert!(corners_solved(&cube)),
+        RouxStage::Lse => assert_eq!(Cube::solved(), cube.cube()),
+      }
+    }
+    assert!(steps[3].moves.iter().all(|m| lse_moves().contains(m)));
+  }
+}
@@ -0,0 +1,260 @@
+use cube::Cube;
+use pruning_table::{init_single_prune_table, PruneTable};
+use rand::Rng;
+#[cfg(feature = "stats")]
+use std::cell::Cell;
+use std::fmt;
+use std::time::{Duration, Instant};
+use transition_table::{Coord, TransitionTable};
+
+/// The counters of one phase of a search.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PhaseStats {
+  /// The number of nodes visited.
+  pub nodes: u64,
+  /// The number of nodes that were cut off by the pruning tables.
+  pub prune_cutoffs: u64,
+  /// The number of lookups into the transition and pruning tables.
+  pub table_lookups: u64,
+  /// The largest depth bound that was searched.
+  pub max_depth: usize,
+}
+
+/// Statistics about the searches run by `with_stats`, for benchmarking
+/// changes to the tables and pruning.
+///
+/// The counters are only kept with the `stats` feature, and are all zero
+/// without it, so that the searches do not pay for them.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SearchStats {
+  /// The phase 0 counters.
+  pub phase0: PhaseStats,
+  /// The phase 1 counters.
+  pub phase1: PhaseStats,
+  /// How long the search took.
+  pub elapsed: Duration,
+}
+
+#[cfg(feature = "stats")]
+thread_local! {
+  static STATS: Cell<SearchStats> = Cell::new(SearchStats::default());
+}
+
+// Update the counters of the current thread.
+#[cfg(feature = "stats")]
+pub(crate) fn record<F: FnOnce(&mut SearchStats)>(f: F) {
+  STATS.with(|stats| {
+    let mut s = stats.get();
+    f(&mut s);
+    stats.set(s);
+  });
+}
+
+#[cfg(not(feature = "stats"))]
+#[inline(always)]
+pub(crate) fn record<F: FnOnce(&mut SearchStats)>(_f: F) {}
+
+// Reset the counters of the current thread, returning their old values.
+fn take() -> SearchStats {
+  #[cfg(feature = "stats")]
+  return STATS.with(|stats| stats.take());
+  #[cfg(not(feature = "stats"))]
+  SearchStats::default()
+}
+
+/// Run `f`, returning its result and the statistics of the searches it ran
+/// on this thread, such as `phase0`, `phase1` or `solve`.
+///
+/// The counters are reset when `f` starts, so calls can not be nested. This
+/// measures the elapsed time with `std::time::Instant`, so it is not available
+/// on wasm32-unknown-unknown.
+pub fn with_stats<T, F: FnOnce() -> T>(f: F) -> (T, SearchStats) {
+  take();
+  let start = Instant::now();
+  let result = f();
+  let mut stats = take();
+  stats.elapsed = start.elapsed();
+  (result, stats)
+}
+
+/// The number of states at each distance from solved, such as the depths of
+/// every coordinate of a pruning table, or of a sample of random cubes.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct DepthDistribution {
+  /// The number of states at each depth, starting from depth 0.
+  pub counts: Vec<u64>,
+  /// The number of states that can not be solved, such as the coordinates
+  /// of a table built from a subset of moves that can not reach them.
+  pub unreachable: u64,
+}
+
+impl DepthDistribution {
+  /// Count one more state at `depth`, or an unreachable one for `None`.
+  pub fn add(&mut self, depth: Option<usize>) {
+    match depth {
+      Some(depth) => {
+        if self.counts.len() <= depth {
+          self.counts.resize(depth + 1, 0);
+        }
+        self.counts[depth] += 1;
+      }
+      None => self.unreachable += 1,
+    }
+  }
+
+  /// The number of states that were counted, including unreachable ones.
+  pub fn total(&self) -> u64 {
+    self.counts.iter().sum::<u64>() + self.unreachable
+  }
+
+  /// The largest depth of a state, which is God's number of the space when
+  /// every state was counted, or `None` if no state can be solved.
+  pub fn max_depth(&self) -> Option<usize> {
+    self.counts.iter().rposition(|&count| count > 0)
+  }
+
+  /// The mean depth of the states that can be solved.
+  pub fn mean(&self) -> f64 {
+    let solvable: u64 = self.counts.iter().sum();
+    let sum: u64 = (0..).zip(&self.counts).map(|(d, &c)| d * c).sum();
+    sum as f64 / solvable as f64
+  }
+}
+
+impl fmt::Display for DepthDistribution {
+  /// Format one line per depth, with the depth and its number of states,
+  /// followed by the unreachable states, if there are any.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (depth, count) in self.counts.iter().enumerate() {
+      writeln!(f, "{:>2}: {}", depth, count)?;
+    }
+    if self.unreachable > 0 {
+      writeln!(f, " -: {}", self.unreachable)?;
+    }
+    Ok(())
+  }
+}
+
+/// The depths of every coordinate of a pruning table. Coordinates that the
+/// table could not reach are counted as unreachable.
+pub fn prune_table_distribution<T>(table: &PruneTable<T>) -> DepthDistribution {
+  let mut distribution = DepthDistribution::default();
+  for &depth in table.iter() {
+    distribution.add(if depth < table.len() {
+      Some(depth)
+    } else {
+      None
+    });
+  }
+  distribution
+}
+
+/// The depths of every value of the coordinate `T`, found by a breadth-first
+/// search over the moves of `T::GROUP`, as used for its pruning table.
+pub fn coord_distribution<T: Coord>(
+  trans_table: &TransitionTable<T>,
+) -> DepthDistribution {
+  prune_table_distribution(&init_single_prune_table(trans_table))
+}
+
+/// The depths of `num_samples` random states, with every state equally
+/// likely, for spaces that are too large to search exhaustively. `depth`
+/// gives the depth of a cube, such as the length of an optimal solution or
+/// the lower bound of a pruning table.
+pub fn sample_distribution<F>(
+  num_samples: usize,
+  rng: &mut impl Rng,
+  mut depth: F,
+) -> DepthDistribution
+where
+  F: FnMut(&Cube) -> Option<usize>,
+{
+  let mut distribution = DepthDistribution::default();
+  for _ in 0..num_samples {
+    distribution.add(depth(&Cube::random(rng)));
+  }
+  distribution
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{seeded_rng, Face, Move, Turn};
+  use phase1::solve_phase1;
+  use pruning_table::{get_co_prune_table, get_eo_ud1_prune_table};
+  use tables::Tables;
+  use transition_table::*;
+
+  #[test]
+  fn phase1_stats() {
+    let tables1 = Tables::get().phase1();
+    let c = Cube::solved()
+      .apply_algorithm(&[Move(Face::R, Turn::Half), Move(Face::U, Turn::Half)]);
+    let (solution, stats) = with_stats(|| solve_phase1(c.into(), 5, &tables1));
+    assert_eq!(2, solution.unwrap().len());
+    assert_eq!(PhaseStats::default(), stats.phase0);
+    if cfg!(feature = "stats") {
+      assert!(stats.phase1.nodes >= 3);
+      assert!(stats.phase1.prune_cutoffs > 0);
+      assert!(stats.phase1.table_lookups > stats.phase1.nodes);
+      assert_eq!(2, stats.phase1.max_depth);
+    } else {
+      assert_eq!(PhaseStats::default(), stats.phase1);
+    }
+
+    // The counters start again for every call.
+    let (_, again) = with_stats(|| solve_phase1(c.into(), 5, &tables1));
+    assert_eq!(stats.phase1, again.phase1);
+  }
+
+  #[test]
+  fn coord_distributions() {
+    let eo_t = get_eo_transition_table();
+    let eo = coord_distribution(&eo_t);
+    assert_eq!(EOCoord::NUM_ELEMS as u64, eo.total());
+    assert_eq!(0, eo.unreachable);
+    assert_eq!(Some(7), eo.max_depth());
+    assert_eq!(1, eo.counts[0]);
+    // Only quarter turns of F and B flip edges, and F and F' flip the same
+    // ones.
+    assert_eq!(2, eo.counts[1]);
+
+    let co = coord_distribution(&get_co_transition_table());
+    assert_eq!(COCoord::NUM_ELEMS as u64, co.total());
+    assert_eq!(Some(6), co.max_depth());
+    assert_eq!(
+      Some(4),
+      coord_distribution(&get_ud2_transition_table()).max_depth()
+    );
+
+    let ud1_t = get_ud1_transition_table();
+    let pair = prune_table_distribution(&get_eo_ud1_prune_table(&eo_t, &ud1_t));
+    assert_eq!((eo_t.len() * ud1_t.len()) as u64, pair.total());
+    assert_eq!(Some(9), pair.max_depth());
+    assert!(pair.mean() > eo.mean());
+
+    let text = eo.to_string();
+    assert_eq!(8, text.lines().count());
+    assert_eq!(" 0: 1", text.lines().next().unwrap());
+  }
+
+  #[test]
+  fn sampled_distribution() {
+    let co_t = get_co_transition_table();
+    let co_p = get_co_prune_table(&co_t);
+    let mut rng = seeded_rng(1575);
+    let sample = sample_distribution(1000, &mut rng, |cube| {
+      Some(co_p[COCoord::get_coord(cube)])
+    });
+    assert_eq!(1000, sample.total());
+    assert!(sample.max_depth().unwrap() <= 6);
+
+    let mut distribution = DepthDistribution::default();
+    distribution.add(Some(2));
+    distribution.add(None);
+    assert_eq!(vec![0, 0, 1], distribution.counts);
+    assert_eq!(2, distribution.total());
+    assert_eq!(2.0, distribution.mean());
+    assert_eq!(" 0: 0\n 1: 0\n 2: 1\n -: 1\n", distribution.to_string());
+  }
+}
@@ -0,0 +1,217 @@
+use cube::{Cube, Symmetry, NUM_UD_SYMMETRIES};
+use std::io::{self, Read, Write};
+use std::marker::PhantomData;
+use std::ops::Index;
+use transition_table::{fingerprint, Coord};
+
+// The coordinate of a cube with coordinate `coord` after conjugating it by
+// `sym`.
+fn conjugate<T: Coord>(coord: T, sym: Symmetry) -> T {
+  let mut cube = Cube::solved();
+  T::set_coord(&mut cube, coord);
+  T::get_coord(&cube.conjugate(sym))
+}
+
+// How to reduce a `S` coordinate to the representative of its class.
+#[derive(Clone, Copy, PartialEq)]
+struct SymEntry {
+  // The class of the coordinate.
+  class: u16,
+  // The symmetry that conjugates the coordinate to its representative.
+  sym: u8,
+  // The `T` coordinate of a cube with this `S` and a solved `T`, after
+  // conjugating it by `sym`.
+  flip: u16,
+}
+
+/// A pruning table for a pair of coordinates `(S, T)`, reduced by the 16
+/// symmetries that keep the UD axis.
+///
+/// Conjugating a cube by a symmetry does not change the number of moves
+/// needed to solve it, so each `S` is conjugated to the representative of
+/// its class and only the depths of the representatives are stored. This
+/// makes the table about 16 times smaller than a `PruneTable<(S, T)>`.
+#[derive(PartialEq)]
+pub struct SymPruneTable<S, T> {
+  entries: Box<[SymEntry]>,
+  // Each `T` conjugated by each symmetry, ignoring `S`.
+  t_conj: Box<[u16]>,
+  depths: Box<[u8]>,
+  marker: PhantomData<(S, T)>,
+}
+
+impl<S: Coord, T: Coord> Index<(S, T)> for SymPruneTable<S, T> {
+  type Output = u8;
+  fn index(&self, (s, t): (S, T)) -> &u8 {
+    let entry = self.entries[s.into()];
+    let sym = entry.sym as usize;
+    // Conjugating `T` can depend on `S`, such as EO, where conjugating flips
+    // the edges depending on where the slice edges are. Each edge is flipped
+    // by the symmetry or not, so this is the XOR of conjugating `T` on its
+    // own and conjugating `S` on its own.
+    let t = self.t_conj[sym * T::NUM_ELEMS + t.into()] ^ entry.flip;
+    &self.depths[entry.class as usize * T::NUM_ELEMS + t as usize]
+  }
+}
+
+impl<S, T> SymPruneTable<S, T> {
+  /// The number of depths stored in the table.
+  pub fn len(&self) -> usize {
+    self.depths.len()
+  }
+
+  /// Check if the table stores no depths, which is never the case.
+  pub fn is_empty(&self) -> bool {
+    self.depths.is_empty()
+  }
+
+  /// A hash of the table in the format of `save_tables`, which is the same
+  /// on every platform, to check that the table has not changed.
+  pub fn fingerprint(&self) -> u64
+  where
+    T: Coord,
+  {
+    fingerprint(|mut w| self.write_to(&mut w))
+  }
+
+  /// Write the table as text, with a line for each `S` that has the depth of
+  /// each `T`, as if the table was not reduced by symmetry.
+  pub fn write_text<W: Write>(&self, w: &mut W) -> io::Result<()>
+  where
+    S: Coord,
+    T: Coord,
+  {
+    for s in 0..S::NUM_ELEMS {
+      let depths: Vec<String> = (0..T::NUM_ELEMS)
+        .map(|t| self[(S::from(s), T::from(t))].to_string())
+        .collect();
+      writeln!(w, "{}", depths.join(" "))?;
+    }
+    Ok(())
+  }
+
+  // Build the table, where `depth` gives the depth of each pair of
+  // coordinates.
+  pub(crate) fn new<F>(depth: F) -> SymPruneTable<S, T>
+  where
+    S: Coord,
+    T: Coord,
+    F: Fn(S, T) -> usize,
+  {
+    let mut entries: Vec<Option<SymEntry>> = vec![None; S::NUM_ELEMS];
+    let mut reps = vec![];
+    for s in 0..S::NUM_ELEMS {
+      if entries[s].is_some() {
+        continue;
+      }
+      let class = reps.len() as u16;
+      reps.push(S::from(s));
+      for sym in Symmetry::all() {
+        let index: usize = conjugate(S::from(s), sym).into();
+        if entries[index].is_none() {
+          let inverse = sym.inverse();
+          let mut cube = Cube::solved();
+          S::set_coord(&mut cube, S::from(index));
+          let flip = T::get_coord(&cube.conjugate(inverse));
+          entries[index] = Some(SymEntry {
+            class,
+            sym: inverse.index() as u8,
+            flip: flip.into() as u16,
+          });
+        }
+      }
+    }
+
+    let t_conj = Symmetry::all()
+      .flat_map(|sym| {
+        (0..T::NUM_ELEMS).map(move |t| conjugate(T::from(t), sym).into() as u16)
+      })
+      .collect();
+    let depths = reps
+      .iter()
+      .flat_map(|&rep| (0..T::NUM_ELEMS).map(move |t| (rep, T::from(t))))
+      .map(|(rep, t)| depth(rep, t) as u8)
+      .collect();
+    SymPruneTable {
+      entries: entries.into_iter().map(Option::unwrap).collect(),
+      t_conj,
+      depths,
+      marker: PhantomData,
+    }
+  }
+
+  // Write the table, with each entry as a class, symmetry and flip, followed
+  // by the conjugated `T`s and the depths.
+  pub(crate) fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()>
+  where
+    T: Coord,
+  {
+    let num_classes = self.depths.len() / T::NUM_ELEMS;
+    w.write_all(&(num_classes as u32).to_le_bytes())?;
+    for entry in self.entries.iter() {
+      w.write_all(&entry.class.to_le_bytes())?;
+      w.write_all(&[entry.sym])?;
+      w.write_all(&entry.flip.to_le_bytes())?;
+    }
+    for t in self.t_conj.iter() {
+      w.write_all(&t.to_le_bytes())?;
+    }
+    w.write_all(&self.depths)
+  }
+
+  // Read a table written by `write_to`, checking that every entry is valid.
+  pub(crate) fn read_from<R: Read>(r: &mut R) -> io::Result<Self>
+  where
+    S: Coord,
+    T: Coord,
+  {
+    let invalid =
+      || io::Error::new(io::ErrorKind::InvalidData, "invalid symmetry table");
+    let read_u16 = |r: &mut R| -> io::Result<u16> {
+      let mut bytes = [0; 2];
+      r.read_exact(&mut bytes)?;
+      Ok(u16::from_le_bytes(bytes))
+    };
+
+    let mut bytes = [0; 4];
+    r.read_exact(&mut bytes)?;
+    let num_classes = u32::from_le_bytes(bytes) as usize;
+    if num_classes > S::NUM_ELEMS {
+      return Err(invalid());
+    }
+    let mut entries = Vec::with_capacity(S::NUM_ELEMS);
+    for _ in 0..S::NUM_ELEMS {
+      let class = read_u16(r)?;
+      let mut sym = [0];
+      r.read_exact(&mut sym)?;
+      let flip = read_u16(r)?;
+      if class as usize >= num_classes
+        || sym[0] as usize >= NUM_UD_SYMMETRIES
+        || flip as usize >= T::NUM_ELEMS
+      {
+        return Err(invalid());
+      }
+      entries.push(SymEntry {
+        class,
+        sym: sym[0],
+        flip,
+      });
+    }
+    let mut t_conj = Vec::with_capacity(NUM_UD_SYMMETRIES * T::NUM_ELEMS);
+    for _ in 0..NUM_UD_SYMMETRIES * T::NUM_ELEMS {
+      let t = read_u16(r)?;
+      if t as usize >= T::NUM_ELEMS {
+        return Err(invalid());
+      }
+      t_conj.push(t);
+    }
+    let mut depths = vec![0; num_classes * T::NUM_ELEMS];
+    r.read_exact(&mut depths)?;
+    Ok(SymPruneTable {
+      entries: entries.into_boxed_slice(),
+      t_conj: t_conj.into_boxed_slice(),
+      depths: depths.into_boxed_slice(),
+      marker: PhantomData,
+    })
+  }
+}
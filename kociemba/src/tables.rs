@@ -0,0 +1,783 @@
+use cube::{Cube, Move, NUM_UD_SYMMETRIES};
+use error::TableLoadError;
+use handoff::HandoffTables;
+use phase0::Phase0Tables;
+use phase1::Phase1Tables;
+use pruning_table::*;
+use rand::Rng;
+use std::fs::File;
+use std::io::{self, BufReader, BufWriter, Read, Write};
+use std::path::Path;
+use std::sync::{Arc, OnceLock};
+use symmetry::SymPruneTable;
+use transition_table::*;
+
+// The start of every tables file, followed by `VERSION`.
+const MAGIC: &[u8; 8] = b"KOCIEMBA";
+// The version of the tables file format, which must change whenever the
+// layout or the meaning of the tables changes.
+const VERSION: u32 = 8;
+// The version of this crate, which is saved with the tables.
+const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+// The CRC-32 of each byte, for the IEEE polynomial.
+const CRC_TABLE: [u32; 256] = {
+  let mut table = [0; 256];
+  let mut i = 0;
+  while i < 256 {
+    let mut crc = i as u32;
+    let mut bit = 0;
+    while bit < 8 {
+      crc = if crc & 1 == 1 {
+        (crc >> 1) ^ 0xedb8_8320
+      } else {
+        crc >> 1
+      };
+      bit += 1;
+    }
+    table[i] = crc;
+    i += 1;
+  }
+  table
+};
+
+// A reader or writer that computes the CRC-32 of the bytes that go through
+// it.
+struct Checksum<T> {
+  inner: T,
+  crc: u32,
+}
+
+impl<T> Checksum<T> {
+  fn new(inner: T) -> Checksum<T> {
+    Checksum { inner, crc: !0 }
+  }
+
+  fn update(&mut self, bytes: &[u8]) {
+    for &b in bytes {
+      self.crc =
+        CRC_TABLE[((self.crc ^ b as u32) & 0xff) as usize] ^ (self.crc >> 8);
+    }
+  }
+
+  fn crc(&self) -> u32 {
+    !self.crc
+  }
+}
+
+impl<W: Write> Write for Checksum<W> {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    let n = self.inner.write(buf)?;
+    self.update(&buf[..n]);
+    Ok(n)
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    self.inner.flush()
+  }
+}
+
+impl<R: Read> Read for Checksum<R> {
+  fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+    let n = self.inner.read(buf)?;
+    self.update(&buf[..n]);
+    Ok(n)
+  }
+}
+
+// A hash of the number of values of each coordinate, in the order of the
+// tables, and of the number of moves and symmetries, so that tables saved for
+// different coordinates are not loaded.
+fn layout_hash() -> u64 {
+  let layout = [
+    <EOCoord as Coord>::NUM_ELEMS,
+    <COCoord as Coord>::NUM_ELEMS,
+    <UD1Coord as Coord>::NUM_ELEMS,
+    <EPCoord as Coord>::NUM_ELEMS,
+    <CPCoord as Coord>::NUM_ELEMS,
+    <UD2Coord as Coord>::NUM_ELEMS,
+    <EdgeGroupCoord as Coord>::NUM_ELEMS,
+    NUM_MOVES,
+    NUM_UD_SYMMETRIES,
+  ];
+  fingerprint(|w| {
+    for n in layout {
+      w.write_all(&(n as u64).to_le_bytes())?;
+    }
+    Ok(())
+  })
+}
+
+/// The progress of building the `Tables`, reported by `Tables::with_progress`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct TableProgress {
+  /// The name of the table being built, such as `"CP and UD2 pruning"`.
+  pub table: &'static str,
+  /// How much of the table has been built, from 0 to 1.
+  ///
+  /// Pruning tables report the fraction of their coordinates whose depth is
+  /// known after each depth is filled, and other tables only report 0 and 1.
+  pub fraction: f64,
+}
+
+// Build the table named `table` with `build`, reporting 0 and 1 around it and
+// the fractions that `build` reports in between.
+fn build<T>(
+  table: &'static str,
+  progress: &mut dyn FnMut(TableProgress),
+  build: impl FnOnce(&mut dyn FnMut(f64)) -> T,
+) -> T {
+  trace_span!(DEBUG, "build_table", table);
+  progress(TableProgress {
+    table,
+    fraction: 0.0,
+  });
+  let built =
+    build(&mut |fraction| progress(TableProgress { table, fraction }));
+  progress(TableProgress {
+    table,
+    fraction: 1.0,
+  });
+  built
+}
+
+// A table of any type, for `Tables::fingerprints` and
+// `Tables::write_table_text`.
+trait DumpTable {
+  fn fingerprint(&self) -> u64;
+  fn write_text(&self, w: &mut dyn Write) -> io::Result<()>;
+}
+
+impl<T: Coord> DumpTable for TransitionTable<T> {
+  fn fingerprint(&self) -> u64 {
+    self.fingerprint()
+  }
+  fn write_text(&self, mut w: &mut dyn Write) -> io::Result<()> {
+    self.write_text(&mut w)
+  }
+}
+
+impl<T> DumpTable for PruneTable<T> {
+  fn fingerprint(&self) -> u64 {
+    self.fingerprint()
+  }
+  fn write_text(&self, mut w: &mut dyn Write) -> io::Result<()> {
+    self.write_text(&mut w)
+  }
+}
+
+impl<S: Coord, T: Coord> DumpTable for SymPruneTable<S, T> {
+  fn fingerprint(&self) -> u64 {
+    self.fingerprint()
+  }
+  fn write_text(&self, mut w: &mut dyn Write) -> io::Result<()> {
+    self.write_text(&mut w)
+  }
+}
+
+/// All the transition and pruning tables needed by the two-phase algorithm.
+///
+/// Building the tables is slow, so use `Tables::get` to share one copy of
+/// them, or `load_tables` to load tables saved by `save_tables`.
+pub struct Tables {
+  eo_t: TransitionTable<EOCoord>,
+  co_t: TransitionTable<COCoord>,
+  ud1_t: TransitionTable<UD1Coord>,
+  co_p: PruneTable<COCoord>,
+  eo_ud1_p: SymPruneTable<UD1Coord, EOCoord>,
+  ep_t: TransitionTable<EPCoord>,
+  cp_t: TransitionTable<CPCoord>,
+  ud2_t: TransitionTable<UD2Coord>,
+  ep_p: PruneTable<EPCoord>,
+  cp_ud2_p: SymPruneTable<CPCoord, UD2Coord>,
+  cp_g0_t: TransitionTable<CPCoord>,
+  edge_group_t: TransitionTable<EdgeGroupCoord>,
+}
+
+// The phase 0 tables, generated by the build script.
+#[cfg(feature = "embedded-tables")]
+static PHASE0_TABLES: &[u8] =
+  include_bytes!(concat!(env!("OUT_DIR"), "/phase0.tables"));
+
+// The tables used by phase 0.
+type Phase0Data = (
+  TransitionTable<EOCoord>,
+  TransitionTable<COCoord>,
+  TransitionTable<UD1Coord>,
+  PruneTable<COCoord>,
+  SymPruneTable<UD1Coord, EOCoord>,
+);
+
+// Read the phase 0 tables that the build script embedded in the library,
+// which is too quick to report progress for.
+#[cfg(feature = "embedded-tables")]
+fn phase0_data(_progress: &mut dyn FnMut(TableProgress)) -> Phase0Data {
+  fn read(r: &mut &[u8]) -> io::Result<Phase0Data> {
+    Ok((
+      TransitionTable::read_from(r)?,
+      TransitionTable::read_from(r)?,
+      TransitionTable::read_from(r)?,
+      PruneTable::read_from(r)?,
+      SymPruneTable::read_from(r)?,
+    ))
+  }
+  read(&mut &PHASE0_TABLES[..]).expect("the embedded tables are valid")
+}
+
+// Build the phase 0 tables.
+#[cfg(not(feature = "embedded-tables"))]
+fn phase0_data(progress: &mut dyn FnMut(TableProgress)) -> Phase0Data {
+  let eo_t = build("EO transition", progress, |_| get_eo_transition_table());
+  let co_t = build("CO transition", progress, |_| get_co_transition_table());
+  let ud1_t = build("UD1 transition", progress, |_| get_ud1_transition_table());
+  let co_p = build("CO pruning", progress, |p| {
+    single_prune_table_with_progress(&co_t, p)
+  });
+  let eo_ud1_p = build("EO and UD1 pruning", progress, |p| {
+    eo_ud1_sym_prune_table_with_progress(&eo_t, &ud1_t, p)
+  });
+  (eo_t, co_t, ud1_t, co_p, eo_ud1_p)
+}
+
+impl Tables {
+  /// Build all the tables.
+  ///
+  /// With the `embedded-tables` feature, the phase 0 tables are built by the
+  /// build script and embedded in the library, so they are only read here.
+  pub fn new() -> Tables {
+    Tables::with_progress(|_| ())
+  }
+
+  /// Build all the tables like `Tables::new`, calling `progress` as each
+  /// table is built, such as to show a progress bar.
+  ///
+  /// Each table reports a fraction of 0 when it starts and 1 when it is
+  /// done, and the tables are built one after the other. With the
+  /// `embedded-tables` feature, nothing is reported for the phase 0 tables.
+  pub fn with_progress(mut progress: impl FnMut(TableProgress)) -> Tables {
+    trace_span!(INFO, "build_tables");
+    let progress: &mut dyn FnMut(TableProgress) = &mut progress;
+    let (eo_t, co_t, ud1_t, co_p, eo_ud1_p) = phase0_data(progress);
+    let ep_t = build("EP transition", progress, |_| get_ep_transition_table());
+    let cp_t = build("CP transition", progress, |_| get_cp_transition_table());
+    let ud2_t =
+      build("UD2 transition", progress, |_| get_ud2_transition_table());
+    let ep_p = build("EP pruning", progress, |p| {
+      single_prune_table_with_progress(&ep_t, p)
+    });
+    let cp_ud2_p = build("CP and UD2 pruning", progress, |p| {
+      cp_ud2_sym_prune_table_with_progress(&cp_t, &ud2_t, p)
+    });
+    Tables {
+      co_p,
+      eo_ud1_p,
+      ep_p,
+      cp_ud2_p,
+      eo_t,
+      co_t,
+      ud1_t,
+      ep_t,
+      cp_t,
+      ud2_t,
+      cp_g0_t: build("G0 CP transition", progress, |_| {
+        get_cp_g0_transition_table()
+      }),
+      edge_group_t: build("edge group transition", progress, |_| {
+        get_edge_group_transition_table()
+      }),
+    }
+  }
+
+  /// The tables shared by the whole program, which are built by the first
+  /// call.
+  pub fn get() -> &'static Tables {
+    shared_tables()
+  }
+
+  /// The same tables as `Tables::get`, as an `Arc` that can be stored
+  /// alongside tables built or loaded separately, such as in a
+  /// `SolverHandle`.
+  pub fn shared() -> Arc<Tables> {
+    shared_tables().clone()
+  }
+
+  /// The tables used by phase 0.
+  pub fn phase0(&self) -> Phase0Tables<'_> {
+    Phase0Tables::new(
+      &self.eo_t,
+      &self.co_t,
+      &self.ud1_t,
+      &self.co_p,
+      &self.eo_ud1_p,
+    )
+  }
+
+  /// The tables used by phase 1.
+  pub fn phase1(&self) -> Phase1Tables<'_> {
+    Phase1Tables::new(
+      &self.ep_t,
+      &self.cp_t,
+      &self.ud2_t,
+      &self.ep_p,
+      &self.cp_ud2_p,
+    )
+  }
+
+  /// The tables used to hand off from phase 0 to phase 1.
+  pub fn handoff(&self) -> HandoffTables<'_> {
+    HandoffTables::new(&self.cp_g0_t, &self.edge_group_t)
+  }
+
+  // Each table with its name, in the order of `save_tables`.
+  fn named(&self) -> [(&'static str, &dyn DumpTable); 12] {
+    [
+      ("EO transition", &self.eo_t),
+      ("CO transition", &self.co_t),
+      ("UD1 transition", &self.ud1_t),
+      ("CO pruning", &self.co_p),
+      ("EO and UD1 pruning", &self.eo_ud1_p),
+      ("EP transition", &self.ep_t),
+      ("CP transition", &self.cp_t),
+      ("UD2 transition", &self.ud2_t),
+      ("EP pruning", &self.ep_p),
+      ("CP and UD2 pruning", &self.cp_ud2_p),
+      ("G0 CP transition", &self.cp_g0_t),
+      ("edge group transition", &self.edge_group_t),
+    ]
+  }
+
+  /// The name and fingerprint of each table, in the order that they are
+  /// saved, with the same names as `TableProgress`.
+  ///
+  /// The fingerprints only depend on the contents of the tables, so they
+  /// catch a change to the coordinates or the moves that changes a table.
+  pub fn fingerprints(&self) -> Vec<(&'static str, u64)> {
+    self
+      .named()
+      .iter()
+      .map(|&(name, table)| (name, table.fingerprint()))
+      .collect()
+  }
+
+  /// Write the table named `table`, as named by `Tables::fingerprints`, as
+  /// text with `write_text`, to compare the tables line by line when their
+  /// fingerprints differ.
+  pub fn write_table_text<W: Write>(
+    &self,
+    table: &str,
+    w: &mut W,
+  ) -> io::Result<()> {
+    match self.named().iter().find(|&&(name, _)| name == table) {
+      Some(&(_, table)) => table.write_text(w),
+      None => Err(io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("no table named {:?}", table),
+      )),
+    }
+  }
+
+  fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    w.write_all(MAGIC)?;
+    w.write_all(&VERSION.to_le_bytes())?;
+    w.write_all(&[CRATE_VERSION.len() as u8])?;
+    w.write_all(CRATE_VERSION.as_bytes())?;
+    w.write_all(&layout_hash().to_le_bytes())?;
+    let mut w = Checksum::new(w);
+    self.eo_t.write_to(&mut w)?;
+    self.co_t.write_to(&mut w)?;
+    self.ud1_t.write_to(&mut w)?;
+    self.co_p.write_to(&mut w)?;
+    self.eo_ud1_p.write_to(&mut w)?;
+    self.ep_t.write_to(&mut w)?;
+    self.cp_t.write_to(&mut w)?;
+    self.ud2_t.write_to(&mut w)?;
+    self.ep_p.write_to(&mut w)?;
+    self.cp_ud2_p.write_to(&mut w)?;
+    self.cp_g0_t.write_to(&mut w)?;
+    self.edge_group_t.write_to(&mut w)?;
+    let crc = w.crc();
+    w.inner.write_all(&crc.to_le_bytes())
+  }
+
+  fn read_from<R: Read>(r: &mut R) -> Result<Tables, TableLoadError> {
+    let mut magic = [0; 8];
+    r.read_exact(&mut magic)?;
+    if &magic != MAGIC {
+      return Err(TableLoadError::NotATablesFile);
+    }
+    let found = u32::from_le_bytes(read_array(r)?);
+    if found != VERSION {
+      return Err(TableLoadError::FormatVersion {
+        expected: VERSION,
+        found,
+      });
+    }
+    let [len] = read_array(r)?;
+    let mut version = vec![0; len as usize];
+    r.read_exact(&mut version)?;
+    if version != CRATE_VERSION.as_bytes() {
+      return Err(TableLoadError::CrateVersion {
+        expected: CRATE_VERSION.to_string(),
+        found: String::from_utf8_lossy(&version).into_owned(),
+      });
+    }
+    let found = u64::from_le_bytes(read_array(r)?);
+    if found != layout_hash() {
+      return Err(TableLoadError::Layout {
+        expected: layout_hash(),
+        found,
+      });
+    }
+
+    let mut r = Checksum::new(r);
+    let tables = Tables {
+      eo_t: TransitionTable::read_from(&mut r)?,
+      co_t: TransitionTable::read_from(&mut r)?,
+      ud1_t: TransitionTable::read_from(&mut r)?,
+      co_p: PruneTable::read_from(&mut r)?,
+      eo_ud1_p: SymPruneTable::read_from(&mut r)?,
+      ep_t: TransitionTable::read_from(&mut r)?,
+      cp_t: TransitionTable::read_from(&mut r)?,
+      ud2_t: TransitionTable::read_from(&mut r)?,
+      ep_p: PruneTable::read_from(&mut r)?,
+      cp_ud2_p: SymPruneTable::read_from(&mut r)?,
+      cp_g0_t: TransitionTable::read_from(&mut r)?,
+      edge_group_t: TransitionTable::read_from(&mut r)?,
+    };
+    let found = r.crc();
+    let expected = u32::from_le_bytes(read_array(r.inner)?);
+    if found != expected {
+      return Err(TableLoadError::Checksum { expected, found });
+    }
+    if r.inner.read(&mut [0])? != 0 {
+      return Err(TableLoadError::TrailingData);
+    }
+    Ok(tables)
+  }
+}
+
+// The tables returned by `Tables::get` and `Tables::shared`.
+fn shared_tables() -> &'static Arc<Tables> {
+  static TABLES: OnceLock<Arc<Tables>> = OnceLock::new();
+  TABLES.get_or_init(|| Arc::new(Tables::new()))
+}
+
+impl Default for Tables {
+  fn default() -> Tables {
+    Tables::new()
+  }
+}
+
+// Read `N` bytes.
+fn read_array<R: Read, const N: usize>(r: &mut R) -> io::Result<[u8; N]> {
+  let mut bytes = [0; N];
+  r.read_exact(&mut bytes)?;
+  Ok(bytes)
+}
+
+/// Save all the tables to the file at `path`, so that they can be loaded by
+/// `load_tables` instead of being built again.
+///
+/// The file starts with a header of the file format version, the crate
+/// version and a hash of the coordinates' sizes, followed by the transition
+/// tables as little-endian `u16`s and the pruning tables as one byte per
+/// entry, and ends with a CRC-32 of the tables. The tables reduced by
+/// symmetry also store how to reduce each coordinate.
+pub fn save_tables<P: AsRef<Path>>(tables: &Tables, path: P) -> io::Result<()> {
+  let mut w = BufWriter::new(File::create(path)?);
+  tables.write_to(&mut w)?;
+  w.flush()
+}
+
+/// Load the tables saved by `save_tables`.
+///
+/// Fails if the file was not written by `save_tables`, was written by
+/// another version of this crate or for other coordinates, or does not match
+/// its checksum.
+pub fn load_tables<P: AsRef<Path>>(path: P) -> Result<Tables, TableLoadError> {
+  trace_span!(INFO, "load_tables", path = %path.as_ref().display());
+  Tables::read_from(&mut BufReader::new(File::open(path)?))
+}
+
+// The coordinate after applying `m` to a cube with the coordinate `coord`,
+// computed from the cube instead of a table.
+fn turn<T: Coord>(coord: T, m: Move) -> T {
+  let mut cube = Cube::solved();
+  T::set_coord(&mut cube, coord);
+  T::get_coord(&cube.apply_move(m))
+}
+
+// Check `num_samples` random entries of a transition table with the moves of
+// `group`.
+fn check_transitions<T: Coord + PartialEq>(
+  table: &'static str,
+  trans_table: &TransitionTable<T>,
+  group: Group,
+  num_samples: usize,
+  rng: &mut impl Rng,
+) -> Result<(), TableLoadError> {
+  for _ in 0..num_samples {
+    let index = rng.random_range(0..T::NUM_ELEMS);
+    let coord = T::from(index);
+    if group
+      .moves()
+      .iter()
+      .any(|&m| trans_table[(coord, m)] != turn(coord, m))
+    {
+      return Err(TableLoadError::InvalidEntry { table, index });
+    }
+  }
+  Ok(())
+}
+
+// Check `num_samples` random entries of a pruning table, where `depth` gives
+// the depth of a coordinate and `turn` the coordinate after a move: only the
+// solved coordinate is at depth 0, every move changes the depth by at most
+// one, and every other coordinate has a move that takes it one move closer.
+fn check_depths<P, D, F>(
+  table: &'static str,
+  depth: D,
+  turn: F,
+  group: Group,
+  num_samples: usize,
+  rng: &mut impl Rng,
+) -> Result<(), TableLoadError>
+where
+  P: PruneCoord,
+  D: Fn(P) -> usize,
+  F: Fn(P, Move) -> P,
+{
+  for _ in 0..num_samples {
+    let index = rng.random_range(0..P::NUM_ELEMS);
+    let coord = P::from_index(index);
+    let d = depth(coord);
+    let mut valid = (d == 0) == (index == 0);
+    let mut closer = false;
+    for &m in group.moves() {
+      let neighbour = depth(turn(coord, m));
+      valid &= d.abs_diff(neighbour) <= 1;
+      closer |= neighbour + 1 == d;
+    }
+    if !valid || !(d == 0 || closer) {
+      return Err(TableLoadError::InvalidEntry { table, index });
+    }
+  }
+  Ok(())
+}
+
+/// Check `num_samples` random entries of each table against the coordinates
+/// of cubes, such as after `load_tables`, to find tables that were saved
+/// wrongly or by a buggy version.
+///
+/// The transitions are checked against turning a cube with the coordinate,
+/// and the depths against the depths of their neighbours, so this is much
+/// quicker than building the tables again, but can miss a few wrong entries.
+pub fn validate_tables(
+  tables: &Tables,
+  num_samples: usize,
+  rng: &mut impl Rng,
+) -> Result<(), TableLoadError> {
+  let n = num_samples;
+  check_transitions("EO", &tables.eo_t, Group::G0, n, rng)?;
+  check_transitions("CO", &tables.co_t, Group::G0, n, rng)?;
+  check_transitions("UD1", &tables.ud1_t, Group::G0, n, rng)?;
+  check_transitions("EP", &tables.ep_t, Group::G1, n, rng)?;
+  check_transitions("CP", &tables.cp_t, Group::G1, n, rng)?;
+  check_transitions("UD2", &tables.ud2_t, Group::G1, n, rng)?;
+  check_transitions("G0 CP", &tables.cp_g0_t, Group::G0, n, rng)?;
+  check_transitions("edge group", &tables.edge_group_t, Group::G0, n, rng)?;
+
+  let co_p = |co| tables.co_p[co];
+  check_depths("CO pruning", co_p, turn::<COCoord>, Group::G0, n, rng)?;
+  let ep_p = |ep| tables.ep_p[ep];
+  check_depths("EP pruning", ep_p, turn::<EPCoord>, Group::G1, n, rng)?;
+  let eo_ud1_p = |(eo, ud1)| tables.eo_ud1_p[(ud1, eo)] as usize;
+  let eo_ud1_t =
+    |(eo, ud1): (EOCoord, UD1Coord), m| (turn(eo, m), turn(ud1, m));
+  check_depths("EO UD1 pruning", eo_ud1_p, eo_ud1_t, Group::G0, n, rng)?;
+  let cp_ud2_p = |(cp, ud2)| tables.cp_ud2_p[(cp, ud2)] as usize;
+  let cp_ud2_t =
+    |(cp, ud2): (CPCoord, UD2Coord), m| (turn(cp, m), turn(ud2, m));
+  check_depths("CP UD2 pruning", cp_ud2_p, cp_ud2_t, Group::G1, n, rng)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::seeded_rng;
+  use std::env;
+  use std::fs;
+  use std::process;
+
+  #[test]
+  fn save_load() {
+    let cached = Tables::get();
+    // The process id keeps test runs that overlap from sharing the file.
+    let name = format!("kociemba-save-load-{}.tables", process::id());
+    let path = env::temp_dir().join(name);
+    save_tables(cached, &path).unwrap();
+    let tables = load_tables(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert!(*tables.eo_t == *cached.eo_t);
+    assert!(*tables.co_t == *cached.co_t);
+    assert!(*tables.ud1_t == *cached.ud1_t);
+    assert!(*tables.co_p == *cached.co_p);
+    assert!(tables.eo_ud1_p == cached.eo_ud1_p);
+    assert!(*tables.ep_t == *cached.ep_t);
+    assert!(*tables.cp_t == *cached.cp_t);
+    assert!(*tables.ud2_t == *cached.ud2_t);
+    assert!(*tables.ep_p == *cached.ep_p);
+    assert!(tables.cp_ud2_p == cached.cp_ud2_p);
+    assert!(*tables.cp_g0_t == *cached.cp_g0_t);
+    assert!(*tables.edge_group_t == *cached.edge_group_t);
+  }
+
+  // The length of the header before the tables.
+  const HEADER_LEN: usize = 8 + 4 + 1 + CRATE_VERSION.len() + 8;
+  // Where the CO pruning table starts, after the G0 transition tables.
+  const CO_P: usize = HEADER_LEN
+    + (<EOCoord as Coord>::NUM_ELEMS
+      + <COCoord as Coord>::NUM_ELEMS
+      + <UD1Coord as Coord>::NUM_ELEMS)
+      * NUM_MOVES
+      * 2;
+
+  // Replace the checksum at the end of `buf` with the checksum of its tables.
+  fn fix_checksum(buf: &mut [u8]) {
+    let end = buf.len() - 4;
+    let mut checksum = Checksum::new(io::sink());
+    checksum.write_all(&buf[HEADER_LEN..end]).unwrap();
+    buf[end..].copy_from_slice(&checksum.crc().to_le_bytes());
+  }
+
+  #[cfg(feature = "embedded-tables")]
+  #[test]
+  fn embedded() {
+    let (eo_t, co_t, ud1_t, co_p, eo_ud1_p) = phase0_data(&mut |_| ());
+    let built_eo_t = get_eo_transition_table();
+    let built_ud1_t = get_ud1_transition_table();
+    assert!(*eo_t == *built_eo_t);
+    assert!(*co_t == *get_co_transition_table());
+    assert!(*ud1_t == *built_ud1_t);
+    assert!(*co_p == *get_co_prune_table(&co_t));
+    assert!(eo_ud1_p == get_eo_ud1_sym_prune_table(&built_eo_t, &built_ud1_t));
+  }
+
+  #[test]
+  fn invalid() {
+    let mut buf = vec![];
+    Tables::get().write_to(&mut buf).unwrap();
+    let read = |bytes: &[u8]| Tables::read_from(&mut &bytes[..]).err().unwrap();
+
+    let mut bad_magic = buf.clone();
+    bad_magic[0] = b'X';
+    assert!(matches!(read(&bad_magic), TableLoadError::NotATablesFile));
+
+    let mut bad_version = buf.clone();
+    bad_version[8] += 1;
+    match read(&bad_version) {
+      TableLoadError::FormatVersion { expected, found } => {
+        assert_eq!((VERSION, VERSION + 1), (expected, found))
+      }
+      err => panic!("unexpected error: {}", err),
+    }
+
+    let mut bad_crate_version = buf.clone();
+    bad_crate_version[13] = b'9';
+    let err = read(&bad_crate_version);
+    assert!(matches!(err, TableLoadError::CrateVersion { .. }));
+
+    let mut bad_layout = buf.clone();
+    bad_layout[HEADER_LEN - 1] ^= 1;
+    assert!(matches!(read(&bad_layout), TableLoadError::Layout { .. }));
+
+    let mut bad_coord = buf.clone();
+    bad_coord[HEADER_LEN..HEADER_LEN + 2]
+      .copy_from_slice(&0xffffu16.to_le_bytes());
+    match read(&bad_coord) {
+      TableLoadError::Io(err) => {
+        assert_eq!(io::ErrorKind::InvalidData, err.kind())
+      }
+      err => panic!("unexpected error: {}", err),
+    }
+
+    // A changed depth is still a valid table, but does not match the
+    // checksum.
+    let mut bad_depth = buf.clone();
+    bad_depth[CO_P + 5] += 1;
+    assert!(matches!(read(&bad_depth), TableLoadError::Checksum { .. }));
+    let mut bad_checksum = buf.clone();
+    *bad_checksum.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+      read(&bad_checksum),
+      TableLoadError::Checksum { .. }
+    ));
+
+    let mut trailing = buf.clone();
+    trailing.push(0);
+    assert!(matches!(read(&trailing), TableLoadError::TrailingData));
+
+    match read(&buf[..buf.len() - 1]) {
+      TableLoadError::Io(err) => {
+        assert_eq!(io::ErrorKind::UnexpectedEof, err.kind())
+      }
+      err => panic!("unexpected error: {}", err),
+    }
+  }
+
+  #[test]
+  fn validate() {
+    let mut rng = seeded_rng(1577);
+    validate_tables(Tables::get(), 100, &mut rng).unwrap();
+
+    // Tables with every CO depth set to 1, saved with a matching checksum,
+    // load but do not validate.
+    let mut buf = vec![];
+    Tables::get().write_to(&mut buf).unwrap();
+    buf[CO_P..CO_P + <COCoord as Coord>::NUM_ELEMS].fill(1);
+    fix_checksum(&mut buf);
+    let tables = Tables::read_from(&mut &buf[..]).unwrap();
+    let err = validate_tables(&tables, 10, &mut rng).unwrap_err();
+    assert!(matches!(
+      err,
+      TableLoadError::InvalidEntry {
+        table: "CO pruning",
+        ..
+      }
+    ));
+  }
+
+  // The fingerprints of the tables, which only change when the coordinates or
+  // the moves change.
+  const FINGERPRINTS: [(&str, u64); 12] = [
+    ("EO transition", 0x562ae5d1a69995e5),
+    ("CO transition", 0xa6adea879f9ab23d),
+    ("UD1 transition", 0xa438e9f901b91279),
+    ("CO pruning", 0xe813d2dc42b9de4d),
+    ("EO and UD1 pruning", 0xf933d8d9a9e63883),
+    ("EP transition", 0x1f217e063c9e8d69),
+    ("CP transition", 0x8084e4aacad9f91d),
+    ("UD2 transition", 0x2f03a50e00270935),
+    ("EP pruning", 0xe50c3cc2769558ed),
+    ("CP and UD2 pruning", 0xbdb66115f014b695),
+    ("G0 CP transition", 0x080855bd150a0579),
+    ("edge group transition", 0x50206a64149d5fb9),
+  ];
+
+  #[test]
+  fn fingerprints() {
+    assert_eq!(FINGERPRINTS.to_vec(), Tables::get().fingerprints());
+    let mut text = vec![];
+    Tables::get()
+      .write_table_text("CO pruning", &mut text)
+      .unwrap();
+    let text = String::from_utf8(text).unwrap();
+    assert_eq!(<COCoord as Coord>::NUM_ELEMS, text.lines().count());
+    assert_eq!(Some("0"), text.lines().next());
+    let err = Tables::get()
+      .write_table_text("no such table", &mut vec![])
+      .unwrap_err();
+    assert_eq!(io::ErrorKind::InvalidInput, err.kind());
+  }
+}
@@ -0,0 +1,320 @@
+use canonical::skip_face;
+use cube::{Cube, Face, Move, Turn};
+use pruning_table::{init_prune_table, init_prune_table_from, PruneTable};
+use std::sync::OnceLock;
+use transition_table::*;
+
+/// The moves that keep the edges oriented, which are any turn of U, D, R and
+/// L and half turns of F and B. Phase 1 reduces a cube to the group they
+/// generate.
+const EO_MOVES: &[Move] = &[
+  Move(Face::U, Turn::Clockwise),
+  Move(Face::U, Turn::Half),
+  Move(Face::U, Turn::Counter),
+  Move(Face::D, Turn::Clockwise),
+  Move(Face::D, Turn::Half),
+  Move(Face::D, Turn::Counter),
+  Move(Face::R, Turn::Clockwise),
+  Move(Face::R, Turn::Half),
+  Move(Face::R, Turn::Counter),
+  Move(Face::L, Turn::Clockwise),
+  Move(Face::L, Turn::Half),
+  Move(Face::L, Turn::Counter),
+  Move(Face::F, Turn::Half),
+  Move(Face::B, Turn::Half),
+];
+
+/// The half turns of every face, which generate the group that phase 3
+/// reduces a cube to.
+const HALF_TURNS: &[Move] = &[
+  Move(Face::U, Turn::Half),
+  Move(Face::D, Turn::Half),
+  Move(Face::F, Turn::Half),
+  Move(Face::B, Turn::Half),
+  Move(Face::R, Turn::Half),
+  Move(Face::L, Turn::Half),
+];
+
+/// The tables of Thistlethwaite's algorithm, which solves a cube in four
+/// phases, each restricted to fewer moves than the last:
+///
+/// 1. All moves, until the edges are oriented.
+/// 2. `EO_MOVES`, until the corners are oriented and the E-slice edges are in
+///    the E slice, which is the G1 of the two-phase algorithm.
+/// 3. `G1_MOVES`, until the corners can be solved with half turns and the
+///    M-slice edges are in the M slice.
+/// 4. Half turns, until the cube is solved.
+///
+/// Every phase uses coordinates and transition tables like the two-phase
+/// algorithm. The first two phases have an exact pruning table, so they find
+/// a shortest sequence for their phase without backtracking. The last two
+/// phases search with the larger of two pruning tables as a lower bound,
+/// which keeps the tables small.
+///
+/// The solutions are much longer than those of `solve`, at around 30 to 40
+/// moves, but the tables are smaller and every phase is easy to follow.
+pub struct Thistlethwaite {
+  eo_t: TransitionTable<EOCoord>,
+  eo_p: PruneTable<EOCoord>,
+  co_t: TransitionTable<COCoord>,
+  ud1_t: TransitionTable<UD1Coord>,
+  co_ud1_p: PruneTable<(COCoord, UD1Coord)>,
+  cp_t: TransitionTable<CPCoord>,
+  cp_g3_p: PruneTable<CPCoord>,
+  m_slice_t: TransitionTable<MSliceCoord>,
+  m_slice_p: PruneTable<MSliceCoord>,
+  cp_p: PruneTable<CPCoord>,
+  ep_t: TransitionTable<EPCoord>,
+  ud2_t: TransitionTable<UD2Coord>,
+  ep_ud2_p: PruneTable<(EPCoord, UD2Coord)>,
+}
+
+impl Thistlethwaite {
+  /// Build the tables.
+  pub fn new() -> Thistlethwaite {
+    let eo_t = get_eo_transition_table();
+    let eo_p =
+      init_prune_table(Group::G0.moves(), |eo: EOCoord, m| eo_t[(eo, m)]);
+
+    let co_t = get_co_transition_table();
+    let ud1_t = get_ud1_transition_table();
+    let co_ud1_p =
+      init_prune_table(EO_MOVES, |(co, ud1): (COCoord, UD1Coord), m| {
+        (co_t[(co, m)], ud1_t[(ud1, m)])
+      });
+
+    // The corner permutations that can be solved with half turns are the
+    // targets of phase 3.
+    let cp_t = get_cp_transition_table();
+    let cp_p = init_prune_table(HALF_TURNS, |cp: CPCoord, m| cp_t[(cp, m)]);
+    let targets: Vec<CPCoord> = (0..CPCoord::NUM_ELEMS)
+      .map(CPCoord::from)
+      .filter(|&cp| cp_p[cp] < CPCoord::NUM_ELEMS)
+      .collect();
+    let cp_g3_p =
+      init_prune_table_from(&targets, G1_MOVES, |cp: CPCoord, m| cp_t[(cp, m)]);
+    let m_slice_t = get_m_slice_transition_table();
+    let m_slice_p = init_prune_table(G1_MOVES, |m_slice: MSliceCoord, m| {
+      m_slice_t[(m_slice, m)]
+    });
+
+    let ep_t = get_ep_transition_table();
+    let ud2_t = get_ud2_transition_table();
+    let ep_ud2_p =
+      init_prune_table(HALF_TURNS, |(ep, ud2): (EPCoord, UD2Coord), m| {
+        (ep_t[(ep, m)], ud2_t[(ud2, m)])
+      });
+
+    Thistlethwaite {
+      eo_t,
+      eo_p,
+      co_t,
+      ud1_t,
+      co_ud1_p,
+      cp_t,
+      cp_g3_p,
+      m_slice_t,
+      m_slice_p,
+      cp_p,
+      ep_t,
+      ud2_t,
+      ep_ud2_p,
+    }
+  }
+
+  /// The tables shared by the whole program, which are built on the first
+  /// call.
+  pub fn get() -> &'static Thistlethwaite {
+    static TABLES: OnceLock<Thistlethwaite> = OnceLock::new();
+    TABLES.get_or_init(Thistlethwaite::new)
+  }
+
+  /// Solve a cube, returning the moves of each of the four phases. The cube
+  /// must be solvable.
+  pub fn solve(&self, cube: &Cube) -> [Vec<Move>; 4] {
+    debug_assert!(cube.verify().is_ok());
+
+    let eo = EOCoord::get_coord(cube);
+    let phase1 = solve_phase(
+      eo,
+      Group::G0.moves(),
+      |eo, m| self.eo_t[(eo, m)],
+      |eo| self.eo_p[eo],
+    );
+    let cube = cube.apply_algorithm(&phase1);
+
+    let co_ud1 = (COCoord::get_coord(&cube), UD1Coord::get_coord(&cube));
+    let phase2 = solve_phase(
+      co_ud1,
+      EO_MOVES,
+      |(co, ud1), m| (self.co_t[(co, m)], self.ud1_t[(ud1, m)]),
+      |co_ud1| self.co_ud1_p[co_ud1],
+    );
+    let cube = cube.apply_algorithm(&phase2);
+
+    let cp_m_slice = (CPCoord::get_coord(&cube), MSliceCoord::get_coord(&cube));
+    let phase3 = solve_phase(
+      cp_m_slice,
+      G1_MOVES,
+      |(cp, m_slice), m| (self.cp_t[(cp, m)], self.m_slice_t[(m_slice, m)]),
+      |(cp, m_slice)| self.cp_g3_p[cp].max(self.m_slice_p[m_slice]),
+    );
+    let cube = cube.apply_algorithm(&phase3);
+
+    let coords = (
+      CPCoord::get_coord(&cube),
+      EPCoord::get_coord(&cube),
+      UD2Coord::get_coord(&cube),
+    );
+    let phase4 = solve_phase(
+      coords,
+      HALF_TURNS,
+      |(cp, ep, ud2), m| {
+        (self.cp_t[(cp, m)], self.ep_t[(ep, m)], self.ud2_t[(ud2, m)])
+      },
+      |(cp, ep, ud2)| self.cp_p[cp].max(self.ep_ud2_p[(ep, ud2)]),
+    );
+
+    [phase1, phase2, phase3, phase4]
+  }
+}
+
+impl Default for Thistlethwaite {
+  fn default() -> Thistlethwaite {
+    Thistlethwaite::new()
+  }
+}
+
+// Search for moves after `solution` that take `coord` to a coordinate with a
+// `bound` of 0 in exactly `depth` moves.
+fn search<C, T, B>(
+  coord: C,
+  depth: usize,
+  moves: &[Move],
+  turn: &T,
+  bound: &B,
+  solution: &mut Vec<Move>,
+) -> bool
+where
+  C: Copy,
+  T: Fn(C, Move) -> C,
+  B: Fn(C) -> usize,
+{
+  let lower_bound = bound(coord);
+  if depth == 0 || lower_bound > depth {
+    return lower_bound == 0;
+  }
+  for &m in moves {
+    if skip_face(solution, m.0) {
+      continue;
+    }
+    solution.push(m);
+    if search(turn(coord, m), depth - 1, moves, turn, bound, solution) {
+      return true;
+    }
+    solution.pop();
+  }
+  false
+}
+
+// Find a shortest sequence of `moves` that takes `coord` to a coordinate
+// with a `bound` of 0, by IDA*.
+fn solve_phase<C, T, B>(
+  coord: C,
+  moves: &[Move],
+  turn: T,
+  bound: B,
+) -> Vec<Move>
+where
+  C: Copy,
+  T: Fn(C, Move) -> C,
+  B: Fn(C) -> usize,
+{
+  let mut solution = vec![];
+  let mut depth = bound(coord);
+  while !search(coord, depth, moves, &turn, &bound, &mut solution) {
+    depth += 1;
+  }
+  solution
+}
+
+/// Solve a cube with Thistlethwaite's algorithm, using the tables from
+/// `Thistlethwaite::get`.
+///
+/// This is an alternative to `solve`, which finds shorter solutions, and is
+/// mostly useful to compare the two methods.
+pub fn solve_thistlethwaite(cube: &Cube) -> Vec<Move> {
+  Thistlethwaite::get().solve(cube).concat()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+  use two_phase::solve;
+
+  #[test]
+  fn tables() {
+    let tables = Thistlethwaite::get();
+    assert_eq!(&7, tables.eo_p.iter().max().unwrap());
+    assert!(tables.co_ud1_p.iter().all(|&d| d < 2187 * 495));
+    assert_eq!(96, tables.cp_p.iter().filter(|&&d| d < 40320).count());
+    assert!(tables.cp_g3_p.iter().all(|&d| d < 40320));
+    assert!(tables.m_slice_p.iter().all(|&d| d < 70));
+    assert_eq!(
+      6912,
+      tables.ep_ud2_p.iter().filter(|&&d| d < 40320 * 24).count()
+    );
+  }
+
+  #[test]
+  fn phases() {
+    let scramble = "D2 F' U2 R' B L2 U' F D' R2 B2 L U F2 R D L' B' U2 F";
+    let cube = Cube::from_algorithm(&parse_algorithm(scramble).unwrap());
+    let [phase1, phase2, phase3, phase4] = Thistlethwaite::get().solve(&cube);
+
+    let cube = cube.apply_algorithm(&phase1);
+    assert_eq!([0; 12], cube.eo);
+    assert!(phase2.iter().all(|m| EO_MOVES.contains(m)));
+
+    let cube = cube.apply_algorithm(&phase2);
+    assert_eq!([0; 8], cube.co);
+    assert_eq!(UD1Coord::from(0), UD1Coord::get_coord(&cube));
+    assert!(phase3.iter().all(|m| G1_MOVES.contains(m)));
+
+    let cube = cube.apply_algorithm(&phase3);
+    assert_eq!(MSliceCoord::from(0), MSliceCoord::get_coord(&cube));
+    assert!(phase4.iter().all(|m| HALF_TURNS.contains(m)));
+
+    let cube = cube.apply_algorithm(&phase4);
+    assert_eq!(Cube::solved(), cube);
+  }
+
+  #[test]
+  fn same_cube_as_two_phase() {
+    assert!(solve_thistlethwaite(&Cube::solved()).is_empty());
+
+    for alg in &[
+      "R U R' U'",
+      "F B' U2 D R L' F2",
+      "L2 B D' R F U2 L' D B2 R' U F' D2 L",
+    ] {
+      let cube = Cube::from_algorithm(&parse_algorithm(alg).unwrap());
+      let solution = solve_thistlethwaite(&cube);
+      assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+      let two_phase = solve(&cube);
+      assert_eq!(
+        cube.apply_algorithm(&two_phase),
+        cube.apply_algorithm(&solution)
+      );
+    }
+
+    // A cube in G1 only needs the last two phases.
+    let cube =
+      Cube::from_algorithm(&parse_algorithm("U R2 D' F2 L2 U2").unwrap());
+    let [phase1, phase2, phase3, phase4] = Thistlethwaite::get().solve(&cube);
+    assert!(phase1.is_empty() && phase2.is_empty());
+    let solution = [phase3, phase4].concat();
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+  }
+}
@@ -0,0 +1,19 @@
+// Instrumentation with the `tracing` crate, which expands to nothing without
+// the `tracing` feature. Each macro takes the name of a `tracing::Level`,
+// followed by the arguments of `tracing::span!` or `tracing::event!`.
+
+// Enter a span until the end of the enclosing block.
+macro_rules! trace_span {
+  ($level:ident, $($args:tt)*) => {
+    #[cfg(feature = "tracing")]
+    let _span = ::tracing::span!(::tracing::Level::$level, $($args)*).entered();
+  };
+}
+
+// Record an event.
+macro_rules! trace_event {
+  ($level:ident, $($args:tt)*) => {
+    #[cfg(feature = "tracing")]
+    ::tracing::event!(::tracing::Level::$level, $($args)*);
+  };
+}
@@ -1,79 +1,204 @@
-use cube::{Cube, Edge, Face, Move};
-
-pub(crate) enum Group {
+use cube::{perm_coord, Corner, Cube, Edge, Face, Move, Turn};
+use std::io::{self, Read, Write};
+use std::ops::{Deref, Index};
+
+/// The groups of cubes that the two phases search in.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Group {
+  /// Every cube, reached with quarter turns of every face.
   G0,
+  /// The cubes reached with quarter turns of U and D and half turns of the
+  /// other faces, which phase 0 reduces a cube to.
   G1,
 }
 
-pub(crate) trait Coord {
+/// The number of moves, which is a quarter, half and counter-clockwise turn of
+/// each face.
+pub const NUM_MOVES: usize = 18;
+
+/// All the moves, in the order of their `move_index`.
+const G0_MOVES: [Move; NUM_MOVES] = [
+  Move(Face::U, Turn::Clockwise),
+  Move(Face::U, Turn::Half),
+  Move(Face::U, Turn::Counter),
+  Move(Face::R, Turn::Clockwise),
+  Move(Face::R, Turn::Half),
+  Move(Face::R, Turn::Counter),
+  Move(Face::F, Turn::Clockwise),
+  Move(Face::F, Turn::Half),
+  Move(Face::F, Turn::Counter),
+  Move(Face::D, Turn::Clockwise),
+  Move(Face::D, Turn::Half),
+  Move(Face::D, Turn::Counter),
+  Move(Face::B, Turn::Clockwise),
+  Move(Face::B, Turn::Half),
+  Move(Face::B, Turn::Counter),
+  Move(Face::L, Turn::Clockwise),
+  Move(Face::L, Turn::Half),
+  Move(Face::L, Turn::Counter),
+];
+
+/// The moves that keep a cube in G1, which are any turn of U and D and half
+/// turns of the other faces, in the order that phase 1 searches them.
+pub const G1_MOVES: &[Move] = &[
+  Move(Face::U, Turn::Clockwise),
+  Move(Face::U, Turn::Half),
+  Move(Face::U, Turn::Counter),
+  Move(Face::D, Turn::Clockwise),
+  Move(Face::D, Turn::Half),
+  Move(Face::D, Turn::Counter),
+  Move(Face::F, Turn::Half),
+  Move(Face::B, Turn::Half),
+  Move(Face::R, Turn::Half),
+  Move(Face::L, Turn::Half),
+];
+
+impl Group {
+  /// The moves that keep a cube in the group.
+  pub fn moves(&self) -> &'static [Move] {
+    match self {
+      Group::G0 => &G0_MOVES,
+      Group::G1 => G1_MOVES,
+    }
+  }
+
+  /// Check if `cube` is in the group. A cube is in G1 when it is in G0, and
+  /// every edge and corner is oriented and the E-slice edges are in the
+  /// E-slice.
+  pub fn contains(&self, cube: &Cube) -> bool {
+    match self {
+      Group::G0 => cube.verify().is_ok(),
+      Group::G1 => {
+        Group::G0.contains(cube)
+          && usize::from(EOCoord::get_coord(cube)) == 0
+          && usize::from(COCoord::get_coord(cube)) == 0
+          && usize::from(UD1Coord::get_coord(cube)) == 0
+      }
+    }
+  }
+}
+
+/// The index of `m` in an entry of a `TransitionTable`, which is the face
+/// followed by the turn.
+pub fn move_index(m: Move) -> usize {
+  m.0.index() * 3 + m.1.quarter_turns() as usize - 1
+}
+
+/// A coordinate, which describes part of the state of a cube as a number
+/// from 0 to `NUM_ELEMS - 1`.
+///
+/// Implementations must keep these invariants, which the tables rely on:
+///
+/// * `From<usize>` panics for values of `NUM_ELEMS` or more, and
+///   `Into<usize>` gives back the same value, which indexes the tables.
+/// * `get_coord` gives a coordinate below `NUM_ELEMS` for every cube in
+///   `GROUP`.
+/// * `set_coord` only changes the part of the cube that the coordinate
+///   describes, and keeps a cube in `GROUP` in `GROUP`, so that
+///   `get_coord` of the result is the coordinate that was set.
+/// * The coordinate after a move of `GROUP` only depends on the coordinate
+///   before it, so that a `TransitionTable` can be built for it.
+pub trait Coord: Copy + Send + Sync + From<usize> + Into<usize> {
   /// Number of elements in `Coord`'s transition table.
   const NUM_ELEMS: usize;
   /// Which `Group` this `Coord` is defined for.
   const GROUP: Group;
   /// Modify `Cube` to have the given coordinate.
-  fn set_coord(cube: &mut Cube, coord: usize);
+  fn set_coord(cube: &mut Cube, coord: Self);
   /// Get the coordinate for a given `Cube`.
-  fn get_coord(cube: &Cube) -> usize;
+  fn get_coord(cube: &Cube) -> Self;
+}
+
+// Conversions between a coordinate and its raw value, which is also the
+// coordinate's index into its tables.
+macro_rules! coord_conversions {
+  ($coord:ident) => {
+    coord_conversions!($coord, u16);
+  };
+  ($coord:ident, $int:ty) => {
+    impl From<usize> for $coord {
+      fn from(val: usize) -> $coord {
+        assert!(val < <$coord as Coord>::NUM_ELEMS);
+        $coord(val as $int)
+      }
+    }
+
+    impl From<$coord> for usize {
+      fn from(val: $coord) -> usize {
+        val.0 as usize
+      }
+    }
+  };
 }
 
 /// The G0 EO coordinate is an 11-bit number where each bit corresponds
 /// to the orientation of the edge at that index. The 12th edge's orientation
 /// is calculated based on the first 11 edge orientations.
-pub(crate) struct EOCoord;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EOCoord(u16);
+
+coord_conversions!(EOCoord);
 
 impl Coord for EOCoord {
   const NUM_ELEMS: usize = 2048; // 2 ^ 11
   const GROUP: Group = Group::G0;
 
-  fn set_coord(cube: &mut Cube, eo: usize) {
-    assert!(eo < Self::NUM_ELEMS);
-    let mut eo = eo;
+  fn set_coord(cube: &mut Cube, eo: EOCoord) {
+    let mut eo = usize::from(eo);
     for i in (0..11).rev() {
       cube.eo[i] = (eo & 1) as u8;
       cube.eo[11] ^= (eo & 1) as u8;
       eo >>= 1;
     }
-    cube.verify().unwrap();
+    debug_assert!(cube.verify().is_ok());
   }
 
-  fn get_coord(cube: &Cube) -> usize {
-    cube.eo[..11]
+  fn get_coord(cube: &Cube) -> EOCoord {
+    let eo = cube.eo[..11]
       .iter()
-      .fold(0, |acc, &cur| (acc | cur as usize) << 1) >> 1
+      .fold(0, |acc, &cur| (acc | cur as usize) << 1)
+      >> 1;
+    eo.into()
   }
 }
 
 /// The G0 CO coordinate is 7 digit base-3 number where each digit corresponds
 /// to the orientation of the corner at that index. The 8th corner's orientation
 /// is calculated based on the first 7 corner orientations.
-pub(crate) struct COCoord;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct COCoord(u16);
+
+coord_conversions!(COCoord);
 
 impl Coord for COCoord {
   const NUM_ELEMS: usize = 2187; // 3 ^ 7
   const GROUP: Group = Group::G0;
 
-  fn set_coord(cube: &mut Cube, co: usize) {
-    assert!(co < Self::NUM_ELEMS);
-    let mut co = co;
+  fn set_coord(cube: &mut Cube, co: COCoord) {
+    let mut co = usize::from(co);
     for i in (0..7).rev() {
       cube.co[i] = (co % 3) as u8;
       co /= 3;
       cube.co[7] = ((cube.co[7] + 3) - cube.co[i]) % 3;
     }
-    cube.verify().unwrap();
+    debug_assert!(cube.verify().is_ok());
   }
 
-  fn get_coord(cube: &Cube) -> usize {
-    cube.co[..7]
+  fn get_coord(cube: &Cube) -> COCoord {
+    let co = cube.co[..7]
       .iter()
-      .fold(0usize, |acc, &cur| (acc * 3) + (cur as usize))
+      .fold(0usize, |acc, &cur| (acc * 3) + (cur as usize));
+    co.into()
   }
 }
 
 /// The G0 UD1 coordinate encodes the position of the four E-slice
 /// edges (FR, FL, BL, BR).
 /// The actual permutation of the slice edges is ignored.
-pub(crate) struct UD1Coord;
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UD1Coord(u16);
+
+coord_conversions!(UD1Coord);
 
 impl Coord for UD1Coord {
   const NUM_ELEMS: usize = 495; // 12 choose 4
@@ -114,8 +239,8 @@ impl Coord for UD1Coord {
   ///   +---+---+---+---+---+---+---+---+---+---+----+----+
   ///   | - | - | X | - | - | X | - | X | - | X |  - |  - |
   ///   +---+---+---+---+---+---+---+---+---+---+----+----+
-  fn set_coord(cube: &mut Cube, coord: usize) {
-    let mut coord = coord;
+  fn set_coord(cube: &mut Cube, coord: UD1Coord) {
+    let mut coord = usize::from(coord);
     cube.ep.copy_from_slice(&[Edge::UR; 12]);
     let slice_edges = [Edge::FR, Edge::FL, Edge::BL, Edge::BR];
     let mut k = 3;
@@ -146,7 +271,7 @@ impl Coord for UD1Coord {
       // Swap two corners to fix parity.
       cube.cp.swap(0, 1);
     }
-    cube.verify().unwrap();
+    debug_assert!(cube.verify().is_ok());
   }
 
   /// The UD coordinate is calculated using binomial coefficients.
@@ -179,7 +304,7 @@ impl Coord for UD1Coord {
   ///   N = 2, K -= 1, Slice edge
   ///
   ///   Coordinate = 165 + 36 + 7 + 6 + 5 + 4 = 223
-  fn get_coord(cube: &Cube) -> usize {
+  fn get_coord(cube: &Cube) -> UD1Coord {
     let mut coord = 0;
     let mut k = 3;
     for i in (0..12).rev() {
@@ -192,7 +317,7 @@ impl Coord for UD1Coord {
         k -= 1;
       }
     }
-    coord
+    coord.into()
   }
 }
 
@@ -203,7 +328,7 @@ pub fn get_nth_zero(val: u8, n: u8) -> u8 {
     val |= val + 1;
   }
   //let val = val as u8;
-  return (!val).trailing_zeros() as u8;
+  (!val).trailing_zeros() as u8
 }
 
 struct FactorialDigits<I: Iterator<Item = usize>> {
@@ -263,42 +388,24 @@ fn set_perm_coord<P: From<usize>>(perm: &mut [P], coord: usize) {
   }
 }
 
-/// The number of inversions with regards to the element `i`.
-fn num_inversions_of<P: PartialOrd>(perm: &[P], i: usize) -> usize {
-  if perm.len() <= i {
-    return 0;
-  }
-  let p = &perm[i];
-  perm[..i].iter().filter(|&j| j > p).count()
-  //perm[i..].iter().filter(|&j| j < p).count()
-}
-
-/// A `Iterator` over the number of inversions of each element
-/// of the permutation `perm`.
-fn get_perm_inversions<'a, P: PartialOrd + 'a>(
-  perm: &'a [P],
-) -> impl Iterator<Item = usize> + 'a {
-  (0..perm.len()).map(move |i| num_inversions_of(&perm, i))
-  //(0..perm.len()).rev().map(move |i| num_inversions_of(&perm, i))
-}
-
-/// TODO: sum_i  i! * |{p(j) > p(i) : j < i}|
-fn get_perm_coord<P: PartialOrd + ::std::fmt::Debug>(perm: &[P]) -> usize {
-  //println!("{:?}", get_perm_inversions(perm).collect::<Vec<_>>());
-  get_perm_inversions(perm)
-    .zip((0..).map(factorial))
-    .fold(0, |acc, (f, p)| acc + f * p)
-}
+/// The G1 EP coordinate encodes the permutation of the U and D edges (UR, UF,
+/// UL, UB, DR, DF, DL, DB), which stay in the U and D layers in G1. The
+/// coordinate is the sum of `i! * n` for each position `i`, where `n` is the
+/// number of edges before it that are greater than the edge at `i`.
+///
+/// Setting the coordinate swaps two corners if needed to keep the cube
+/// solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EPCoord(u16);
 
-/// The G1 EP coordinate encodes the positions of the U and D edges.
-pub(crate) struct EPCoord;
+coord_conversions!(EPCoord);
 
 impl Coord for EPCoord {
   const NUM_ELEMS: usize = 40320; // 8!
   const GROUP: Group = Group::G1;
 
-  fn set_coord(cube: &mut Cube, ep: usize) {
-    set_perm_coord(&mut cube.ep[0..8], ep);
+  fn set_coord(cube: &mut Cube, ep: EPCoord) {
+    set_perm_coord(&mut cube.ep[0..8], ep.into());
 
     if !cube.has_valid_parity() {
       // Swap two corners to fix parity.
@@ -307,20 +414,28 @@ impl Coord for EPCoord {
     debug_assert!(cube.verify().is_ok());
   }
 
-  fn get_coord(cube: &Cube) -> usize {
-    get_perm_coord(&cube.ep[0..8])
+  fn get_coord(cube: &Cube) -> EPCoord {
+    perm_coord(&cube.ep[0..8]).into()
   }
 }
 
-/// The G1 CP coordinate encodes the positions of the corners.
-pub(crate) struct CPCoord;
+/// The G1 CP coordinate encodes the permutation of the corners, in the same way
+/// as the G1 EP coordinate. Every corner permutation can be reached with the
+/// moves of G1, and the coordinate is also used with the G0 moves, to track
+/// the corners through phase 0.
+///
+/// Setting the coordinate swaps two edges if needed to keep the cube solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CPCoord(u16);
+
+coord_conversions!(CPCoord);
 
 impl Coord for CPCoord {
   const NUM_ELEMS: usize = 40320; // 8!
   const GROUP: Group = Group::G1;
 
-  fn set_coord(cube: &mut Cube, cp: usize) {
-    set_perm_coord(&mut cube.cp, cp);
+  fn set_coord(cube: &mut Cube, cp: CPCoord) {
+    set_perm_coord(&mut cube.cp, cp.into());
 
     if !cube.has_valid_parity() {
       // Swap two edges to fix parity.
@@ -329,21 +444,29 @@ impl Coord for CPCoord {
     debug_assert!(cube.verify().is_ok());
   }
 
-  fn get_coord(cube: &Cube) -> usize {
-    get_perm_coord(&cube.cp)
+  fn get_coord(cube: &Cube) -> CPCoord {
+    perm_coord(&cube.cp).into()
   }
 }
 
-/// The G1 UD2 coordinate encodes the positions of the E-slice edges.
-pub(crate) struct UD2Coord;
+/// The G1 UD2 coordinate encodes the permutation of the E-slice edges (FR, FL,
+/// BL, BR), which stay in the E slice in G1, in the same way as the G1 EP
+/// coordinate.
+///
+/// Setting the coordinate swaps two corners if needed to keep the cube
+/// solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct UD2Coord(u16);
+
+coord_conversions!(UD2Coord);
 
 impl Coord for UD2Coord {
   const NUM_ELEMS: usize = 24; // 4!
   const GROUP: Group = Group::G1;
 
-  fn set_coord(cube: &mut Cube, ud2: usize) {
+  fn set_coord(cube: &mut Cube, ud2: UD2Coord) {
     let mut edge_offsets = [0, 1, 2, 3];
-    set_perm_coord(&mut edge_offsets, ud2);
+    set_perm_coord(&mut edge_offsets, ud2.into());
 
     cube.ep[8..12]
       .iter_mut()
@@ -357,62 +480,535 @@ impl Coord for UD2Coord {
     debug_assert!(cube.verify().is_ok());
   }
 
-  fn get_coord(cube: &Cube) -> usize {
-    get_perm_coord(&cube.ep[8..12])
+  fn get_coord(cube: &Cube) -> UD2Coord {
+    perm_coord(&cube.ep[8..12]).into()
   }
 }
 
-fn init_transition_table<T: Coord>() -> Vec<[usize; 6]> {
-  let mut v = vec![[0; 6]; T::NUM_ELEMS];
-  let turn_counts = match T::GROUP {
-    Group::G0 => [1; 6],
-    Group::G1 => [1, 1, 2, 2, 2, 2],
-  };
-  let turns = [Face::U, Face::D, Face::F, Face::B, Face::R, Face::L];
+// The U and D edge positions, with the M-slice positions (UF, UB, DF, DB)
+// last, so that the solved cube has the M-slice coordinate 0.
+const M_SLICE_POSITIONS: [usize; 8] = [0, 2, 4, 6, 1, 3, 5, 7];
+
+const M_SLICE_EDGES: [Edge; 4] = [Edge::UF, Edge::UB, Edge::DF, Edge::DB];
+
+/// The G1 M-slice coordinate encodes which of the U and D edge positions hold
+/// the M-slice edges (UF, UB, DF, DB), in the same way as the UD1 coordinate
+/// encodes the positions of the E-slice edges. The permutation of the edges is
+/// ignored.
+///
+/// Setting the coordinate swaps two corners if needed to keep the cube
+/// solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MSliceCoord(u16);
+
+coord_conversions!(MSliceCoord);
+
+impl Coord for MSliceCoord {
+  const NUM_ELEMS: usize = 70; // 8 choose 4
+  const GROUP: Group = Group::G1;
 
-  for i in 0..v.len() {
+  fn set_coord(cube: &mut Cube, coord: MSliceCoord) {
+    let mut coord = usize::from(coord);
+    let mut is_m_slice = [false; 8];
+    let mut k = 3;
+    for i in (0..8).rev() {
+      let binomial = choose(i, k);
+      if binomial > coord {
+        is_m_slice[i] = true;
+        if k == 0 {
+          break;
+        }
+        k -= 1;
+      } else {
+        coord -= binomial;
+      }
+    }
+
+    let mut m_slice = M_SLICE_EDGES.iter();
+    let mut others = [Edge::UR, Edge::UL, Edge::DR, Edge::DL].iter();
+    for (&p, &m) in M_SLICE_POSITIONS.iter().zip(&is_m_slice) {
+      cube.ep[p] = *if m { m_slice.next() } else { others.next() }.unwrap();
+    }
+
+    if !cube.has_valid_parity() {
+      // Swap two corners to fix parity.
+      cube.cp.swap(0, 1);
+    }
+    debug_assert!(cube.verify().is_ok());
+  }
+
+  fn get_coord(cube: &Cube) -> MSliceCoord {
+    let mut coord = 0;
+    let mut k = 3;
+    for i in (0..8).rev() {
+      if !M_SLICE_EDGES.contains(&cube.ep[M_SLICE_POSITIONS[i]]) {
+        coord += choose(i, k);
+      } else {
+        if k == 0 {
+          break;
+        }
+        k -= 1;
+      }
+    }
+    coord.into()
+  }
+}
+
+/// The edge group coordinate encodes the positions and permutation of four
+/// edges, which are the U edges (UR, UF, UL, UB) for `get_coord` and
+/// `set_coord`. Unlike the G1 EP coordinate, it is defined for every cube, so
+/// it can be tracked through phase 0.
+///
+/// The coordinate is the positions, encoded like the UD1 coordinate, times 24
+/// plus the permutation of the edges in the order of their positions. It only
+/// depends on where each of the four edges is, so the transition table of the
+/// U edges is also the transition table of the D and E-slice edges, with
+/// `get_edge_group_coord`.
+///
+/// Setting the coordinate fills the other positions with the other edges in
+/// order, and swaps two corners if needed to keep the cube solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct EdgeGroupCoord(u16);
+
+coord_conversions!(EdgeGroupCoord);
+
+const EDGE_GROUP_EDGES: [Edge; 4] = Face::U.adjacent_edges();
+
+impl Coord for EdgeGroupCoord {
+  const NUM_ELEMS: usize = 11880; // 12 choose 4 * 4!
+  const GROUP: Group = Group::G0;
+
+  fn set_coord(cube: &mut Cube, coord: EdgeGroupCoord) {
+    set_edge_group_coord(cube, EDGE_GROUP_EDGES, coord);
+  }
+
+  fn get_coord(cube: &Cube) -> EdgeGroupCoord {
+    get_edge_group_coord(cube, EDGE_GROUP_EDGES)
+  }
+}
+
+// Set the edge group coordinate of any four `edges`, like
+// `EdgeGroupCoord::set_coord`.
+pub(crate) fn set_edge_group_coord(
+  cube: &mut Cube,
+  edges: [Edge; 4],
+  coord: EdgeGroupCoord,
+) {
+  let coord = usize::from(coord);
+  let mut positions = coord / 24;
+  let mut offsets = [0usize; 4];
+  set_perm_coord(&mut offsets, coord % 24);
+
+  let mut is_group = [false; 12];
+  let mut k = 3;
+  for i in (0..12).rev() {
+    let binomial = choose(i, k);
+    if binomial > positions {
+      is_group[i] = true;
+      if k == 0 {
+        break;
+      }
+      k -= 1;
+    } else {
+      positions -= binomial;
+    }
+  }
+
+  let mut group = offsets.iter().map(|&o| edges[o]);
+  let mut others = (0..12).map(Edge::from).filter(|e| !edges.contains(e));
+  for (i, &g) in is_group.iter().enumerate() {
+    cube.ep[i] = if g { group.next() } else { others.next() }.unwrap();
+  }
+
+  if !cube.has_valid_parity() {
+    // Swap two corners to fix parity.
+    cube.cp.swap(0, 1);
+  }
+  debug_assert!(cube.verify().is_ok());
+}
+
+/// Get the edge group coordinate of any four `edges`, which changes with each
+/// move the same way as the coordinate of the U edges.
+pub fn get_edge_group_coord(cube: &Cube, edges: [Edge; 4]) -> EdgeGroupCoord {
+  let mut positions = 0;
+  let mut k = 3;
+  for i in (0..12).rev() {
+    if !edges.contains(&cube.ep[i]) {
+      positions += choose(i, k);
+    } else {
+      if k == 0 {
+        break;
+      }
+      k -= 1;
+    }
+  }
+  let group: Vec<Edge> = cube
+    .ep
+    .iter()
+    .cloned()
+    .filter(|e| edges.contains(e))
+    .collect();
+  (positions * 24 + perm_coord(&group)).into()
+}
+
+/// The cross coordinate encodes the positions and orientations of four
+/// edges, which are the D edges (DR, DF, DL, DB) for `get_coord` and
+/// `set_coord`. The position of each edge, in order, is ranked among the
+/// positions that the edges before it are not at, and the orientations are
+/// one bit per edge.
+///
+/// The coordinate only depends on where each of the four edges is, not on
+/// which edges they are, so the transition table of the D edges is also the
+/// transition table of any other four edges, with `get_cross_coord`.
+///
+/// Setting the coordinate flips another edge and swaps two corners if needed
+/// to keep the cube solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct CrossCoord(u32);
+
+coord_conversions!(CrossCoord, u32);
+
+const CROSS_EDGES: [Edge; 4] = Face::D.adjacent_edges();
+
+impl Coord for CrossCoord {
+  const NUM_ELEMS: usize = 190080; // 12 * 11 * 10 * 9 * 2^4
+  const GROUP: Group = Group::G0;
+
+  fn set_coord(cube: &mut Cube, coord: CrossCoord) {
+    let coord = usize::from(coord);
+    let mut positions = coord / 16;
+    let mut ranks = [0; 4];
+    for i in (0..4).rev() {
+      ranks[i] = positions % (12 - i);
+      positions /= 12 - i;
+    }
+
+    let mut free: Vec<usize> = (0..12).collect();
+    for (i, &rank) in ranks.iter().enumerate() {
+      let p = free.remove(rank);
+      cube.ep[p] = CROSS_EDGES[i];
+      cube.eo[p] = (coord >> i & 1) as u8;
+    }
+    let mut others =
+      (0..12).map(Edge::from).filter(|e| !CROSS_EDGES.contains(e));
+    for &p in &free {
+      cube.ep[p] = others.next().unwrap();
+      cube.eo[p] = 0;
+    }
+
+    if cube.eo.iter().sum::<u8>() % 2 == 1 {
+      // Flip another edge to fix the orientation.
+      cube.eo[free[0]] = 1;
+    }
+    if !cube.has_valid_parity() {
+      // Swap two corners to fix parity.
+      cube.cp.swap(0, 1);
+    }
+    debug_assert!(cube.verify().is_ok());
+  }
+
+  fn get_coord(cube: &Cube) -> CrossCoord {
+    get_cross_coord(cube, CROSS_EDGES)
+  }
+}
+
+/// The pair coordinate encodes the position and orientation of a corner and
+/// an edge, which are DFR and FR for `get_coord` and `set_coord`, as the
+/// corner's position times 3 plus its orientation, times 24, plus the edge's
+/// position times 2 plus its orientation.
+///
+/// Like the cross coordinate, it only depends on where the pieces are, so
+/// `get_pair_coord` gives the coordinate of any other corner and edge.
+///
+/// Setting the coordinate twists another corner, flips another edge and
+/// swaps two other corners if needed to keep the cube solvable.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PairCoord(u16);
+
+coord_conversions!(PairCoord);
+
+impl Coord for PairCoord {
+  const NUM_ELEMS: usize = 576; // 8 * 3 * 12 * 2
+  const GROUP: Group = Group::G0;
+
+  fn set_coord(cube: &mut Cube, coord: PairCoord) {
+    let coord = usize::from(coord);
+    let (corner, edge) = (coord / 24, coord % 24);
+
+    let position = corner / 3;
+    let others: Vec<usize> = (0..8).filter(|&p| p != position).collect();
+    let mut corners = (0..8).map(Corner::from).filter(|&c| c != Corner::DFR);
+    cube.cp[position] = Corner::DFR;
+    cube.co[position] = (corner % 3) as u8;
+    for &p in &others {
+      cube.cp[p] = corners.next().unwrap();
+      cube.co[p] = 0;
+    }
+    // Twist another corner to fix the orientation.
+    cube.co[others[0]] = (3 - cube.co[position]) % 3;
+
+    let position = edge / 2;
+    let mut edges = (0..12).map(Edge::from).filter(|&e| e != Edge::FR);
+    for p in (0..12).filter(|&p| p != position) {
+      cube.ep[p] = edges.next().unwrap();
+      cube.eo[p] = 0;
+    }
+    cube.ep[position] = Edge::FR;
+    cube.eo[position] = (edge % 2) as u8;
+    // Flip another edge to fix the orientation.
+    cube.eo[(position + 1) % 12] = cube.eo[position];
+
+    if !cube.has_valid_parity() {
+      // Swap two other corners to fix parity.
+      cube.cp.swap(others[1], others[2]);
+    }
+    debug_assert!(cube.verify().is_ok());
+  }
+
+  fn get_coord(cube: &Cube) -> PairCoord {
+    get_pair_coord(cube, Corner::DFR, Edge::FR)
+  }
+}
+
+// A hash of the bytes written to it, with FNV-1a, which is the same on every
+// platform, unlike `DefaultHasher`.
+struct Fnv(u64);
+
+impl Write for Fnv {
+  fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    for &b in buf {
+      self.0 = (self.0 ^ b as u64).wrapping_mul(0x100_0000_01b3);
+    }
+    Ok(buf.len())
+  }
+
+  fn flush(&mut self) -> io::Result<()> {
+    Ok(())
+  }
+}
+
+// The FNV-1a hash of the bytes that `write` writes, such as a table written
+// in the format of `save_tables`.
+pub(crate) fn fingerprint(
+  write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> u64 {
+  let mut hash = Fnv(0xcbf2_9ce4_8422_2325);
+  write(&mut hash).expect("the table fits the format");
+  hash.0
+}
+
+/// A transition table, which maps a coordinate and a `Move` to the coordinate
+/// reached by that move.
+///
+/// Each coordinate has an entry for every move, in the order of
+/// `move_index`, so the table can also be indexed by `(coord, move)`. The
+/// entries of the moves that leave the table's group are the coordinate
+/// itself, and are not meaningful.
+pub struct TransitionTable<T>(Vec<[T; NUM_MOVES]>);
+
+impl<T: Coord> Index<T> for TransitionTable<T> {
+  type Output = [T; NUM_MOVES];
+  fn index(&self, coord: T) -> &[T; NUM_MOVES] {
+    &self.0[coord.into()]
+  }
+}
+
+impl<T: Coord> Index<(T, Move)> for TransitionTable<T> {
+  type Output = T;
+  fn index(&self, (coord, m): (T, Move)) -> &T {
+    &self.0[coord.into()][move_index(m)]
+  }
+}
+
+impl<T> Deref for TransitionTable<T> {
+  type Target = [[T; NUM_MOVES]];
+  fn deref(&self) -> &[[T; NUM_MOVES]] {
+    &self.0
+  }
+}
+
+impl<T: Coord> TransitionTable<T> {
+  /// A hash of the table in the format of `save_tables`, which is the same
+  /// on every platform, to check that the table has not changed.
+  pub fn fingerprint(&self) -> u64 {
+    fingerprint(|mut w| self.write_to(&mut w))
+  }
+
+  /// Write the table as text, with a line for each coordinate that has the
+  /// coordinate after each move, in the order of `move_index`.
+  pub fn write_text<W: Write>(&self, w: &mut W) -> io::Result<()> {
+    for entry in &self.0 {
+      let coords: Vec<String> =
+        entry.iter().map(|&c| c.into().to_string()).collect();
+      writeln!(w, "{}", coords.join(" "))?;
+    }
+    Ok(())
+  }
+}
+
+// Check if the coordinates of `T` do not fit in a `u16`, so their tables
+// are written as `u32`s.
+fn is_wide<T: Coord>() -> bool {
+  T::NUM_ELEMS > 1 << 16
+}
+
+impl<T> TransitionTable<T> {
+  // Write the table as little-endian `u16`s, or `u32`s for coordinates that
+  // do not fit in a `u16`, one per move for each coordinate.
+  pub(crate) fn write_to<W: Write>(&self, w: &mut W) -> io::Result<()>
+  where
+    T: Coord,
+  {
+    for entry in &self.0 {
+      for &coord in entry {
+        let coord: usize = coord.into();
+        if is_wide::<T>() {
+          w.write_all(&(coord as u32).to_le_bytes())?;
+        } else {
+          w.write_all(&(coord as u16).to_le_bytes())?;
+        }
+      }
+    }
+    Ok(())
+  }
+
+  // Read a table written by `write_to`, checking that every entry is a valid
+  // coordinate.
+  pub(crate) fn read_from<R: Read>(r: &mut R) -> io::Result<Self>
+  where
+    T: Coord,
+  {
+    let mut v = vec![[T::from(0); NUM_MOVES]; T::NUM_ELEMS];
+    for entry in v.iter_mut() {
+      for coord in entry.iter_mut() {
+        let val = if is_wide::<T>() {
+          let mut bytes = [0; 4];
+          r.read_exact(&mut bytes)?;
+          u32::from_le_bytes(bytes) as usize
+        } else {
+          let mut bytes = [0; 2];
+          r.read_exact(&mut bytes)?;
+          u16::from_le_bytes(bytes) as usize
+        };
+        if val >= T::NUM_ELEMS {
+          return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "invalid coordinate in transition table",
+          ));
+        }
+        *coord = T::from(val);
+      }
+    }
+    Ok(TransitionTable(v))
+  }
+}
+
+/// Build the transition table of any `Coord`, with the moves of its `GROUP`.
+///
+/// Each coordinate is set on the solved cube with `set_coord`, turned, and
+/// read back with `get_coord`.
+pub fn init_transition_table<T: Coord>() -> TransitionTable<T> {
+  init_group_transition_table(T::GROUP)
+}
+
+// Initialise a transition table with the moves of `group`, which can be a
+// larger group than the one `T` is defined for.
+fn init_group_transition_table<T: Coord>(group: Group) -> TransitionTable<T> {
+  let mut v: Vec<_> =
+    (0..T::NUM_ELEMS).map(|i| [T::from(i); NUM_MOVES]).collect();
+  for (i, entry) in v.iter_mut().enumerate() {
     let mut c = Cube::solved();
-    T::set_coord(&mut c, i);
-    for (&f, &dir) in turns.iter().zip(&turn_counts) {
-      let nc = c.apply_move(Move(f, dir));
-      let coord = T::get_coord(&nc);
-      assert!(coord < T::NUM_ELEMS);
-      v[i][usize::from(f)] = coord;
+    T::set_coord(&mut c, i.into());
+    for &m in group.moves() {
+      entry[move_index(m)] = T::get_coord(&c.apply_move(m));
     }
   }
-  v
+  TransitionTable(v)
 }
 
 /// Get the G0 CO transition table.
-pub fn get_co_transition_table() -> Vec<[usize; 6]> {
+pub fn get_co_transition_table() -> TransitionTable<COCoord> {
   init_transition_table::<COCoord>()
 }
 
 /// Get the G0 EO transition table.
-pub fn get_eo_transition_table() -> Vec<[usize; 6]> {
+pub fn get_eo_transition_table() -> TransitionTable<EOCoord> {
   init_transition_table::<EOCoord>()
 }
 
 /// Get the G0 UD1 transition table.
-pub fn get_ud1_transition_table() -> Vec<[usize; 6]> {
+pub fn get_ud1_transition_table() -> TransitionTable<UD1Coord> {
   init_transition_table::<UD1Coord>()
 }
 
 /// Get the G1 EP transition table.
-pub fn get_ep_transition_table() -> Vec<[usize; 6]> {
+pub fn get_ep_transition_table() -> TransitionTable<EPCoord> {
   init_transition_table::<EPCoord>()
 }
 
 /// Get the G1 CP transition table.
-pub fn get_cp_transition_table() -> Vec<[usize; 6]> {
+pub fn get_cp_transition_table() -> TransitionTable<CPCoord> {
   init_transition_table::<CPCoord>()
 }
 
 /// Get the G1 UD2 transition table.
-pub fn get_ud2_transition_table() -> Vec<[usize; 6]> {
+pub fn get_ud2_transition_table() -> TransitionTable<UD2Coord> {
   init_transition_table::<UD2Coord>()
 }
 
+/// Get the G1 M-slice transition table.
+pub fn get_m_slice_transition_table() -> TransitionTable<MSliceCoord> {
+  init_transition_table::<MSliceCoord>()
+}
+
+/// Get the CP transition table for G0 moves, which tracks CP through
+/// phase 0.
+pub fn get_cp_g0_transition_table() -> TransitionTable<CPCoord> {
+  init_group_transition_table::<CPCoord>(Group::G0)
+}
+
+/// Get the G0 edge group transition table, which is the transition table of
+/// the U, D and E-slice edges.
+pub fn get_edge_group_transition_table() -> TransitionTable<EdgeGroupCoord> {
+  init_transition_table::<EdgeGroupCoord>()
+}
+
+/// Get the G0 cross transition table.
+pub fn get_cross_transition_table() -> TransitionTable<CrossCoord> {
+  init_transition_table::<CrossCoord>()
+}
+
+/// Get the G0 pair transition table.
+pub fn get_pair_transition_table() -> TransitionTable<PairCoord> {
+  init_transition_table::<PairCoord>()
+}
+
+/// Get the pair coordinate of any `corner` and `edge`, which changes with
+/// each move the same way as the coordinate of DFR and FR.
+pub fn get_pair_coord(cube: &Cube, corner: Corner, edge: Edge) -> PairCoord {
+  let c = cube.cp.iter().position(|&c| c == corner).unwrap();
+  let e = cube.ep.iter().position(|&e| e == edge).unwrap();
+  let corner = c * 3 + cube.co[c] as usize;
+  let edge = e * 2 + cube.eo[e] as usize;
+  (corner * 24 + edge).into()
+}
+
+/// Get the cross coordinate of any four `edges`, which changes with each move
+/// the same way as the coordinate of the D edges.
+pub fn get_cross_coord(cube: &Cube, edges: [Edge; 4]) -> CrossCoord {
+  let mut free: Vec<usize> = (0..12).collect();
+  let mut positions = 0;
+  let mut orientations = 0;
+  for (i, &e) in edges.iter().enumerate() {
+    let p = cube.ep.iter().position(|&edge| edge == e).unwrap();
+    let rank = free.iter().position(|&f| f == p).unwrap();
+    positions = positions * free.len() + rank;
+    free.remove(rank);
+    orientations |= (cube.eo[p] as usize) << i;
+  }
+  (positions * 16 + orientations).into()
+}
+
 fn factorial(n: usize) -> usize {
   (1..n + 1).product()
 }
@@ -428,23 +1024,26 @@ mod tests {
   use cube::Corner::*;
   use cube::Edge::*;
   use cube::{NUM_CORNERS, NUM_EDGES};
+  use proptest::collection::vec;
+  use proptest::prelude::*;
+  use proptest::sample::select;
 
   fn exhaustive_coord_check<T: Coord>() {
     for i in 0..T::NUM_ELEMS {
       let mut c = Cube::solved();
-      T::set_coord(&mut c, i);
-      assert_eq!(i, T::get_coord(&c));
+      T::set_coord(&mut c, i.into());
+      assert_eq!(i, T::get_coord(&c).into());
     }
   }
 
   #[test]
   fn eo_coord() {
     let c = Cube::solved();
-    assert_eq!(0, EOCoord::get_coord(&c));
+    assert_eq!(EOCoord::from(0), EOCoord::get_coord(&c));
 
-    for i in 1..4 {
-      let c = c.apply_move(Move(Face::U, i));
-      assert_eq!(0, EOCoord::get_coord(&c));
+    for &turn in &Turn::ALL {
+      let c = c.apply_move(Move(Face::U, turn));
+      assert_eq!(EOCoord::from(0), EOCoord::get_coord(&c));
     }
 
     let c = Cube::new(
@@ -453,7 +1052,10 @@ mod tests {
       [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
       [1; NUM_EDGES],
     );
-    assert_eq!(EOCoord::NUM_ELEMS - 1, EOCoord::get_coord(&c));
+    assert_eq!(
+      EOCoord::from(EOCoord::NUM_ELEMS - 1),
+      EOCoord::get_coord(&c)
+    );
   }
 
   #[test]
@@ -461,8 +1063,11 @@ mod tests {
     let eo = get_eo_transition_table();
 
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::U, 3));
-    assert_eq!(0, eo[EOCoord::get_coord(&c)][usize::from(Face::U)]);
+    let c = c.apply_move(Move(Face::U, Turn::Counter));
+    assert_eq!(
+      EOCoord::from(0),
+      eo[(EOCoord::get_coord(&c), Move(Face::U, Turn::Clockwise))]
+    );
   }
 
   #[test]
@@ -473,11 +1078,11 @@ mod tests {
   #[test]
   fn co_coord() {
     let c = Cube::solved();
-    assert_eq!(0, COCoord::get_coord(&c));
+    assert_eq!(COCoord::from(0), COCoord::get_coord(&c));
 
-    for i in 1..4 {
-      let c = c.apply_move(Move(Face::U, i));
-      assert_eq!(0, COCoord::get_coord(&c));
+    for &turn in &Turn::ALL {
+      let c = c.apply_move(Move(Face::U, turn));
+      assert_eq!(COCoord::from(0), COCoord::get_coord(&c));
     }
 
     let c = Cube::new(
@@ -486,7 +1091,10 @@ mod tests {
       [UR, UF, UL, UB, DR, DF, DL, DB, FR, FL, BL, BR],
       [0; NUM_EDGES],
     );
-    assert_eq!(COCoord::NUM_ELEMS - 1, COCoord::get_coord(&c));
+    assert_eq!(
+      COCoord::from(COCoord::NUM_ELEMS - 1),
+      COCoord::get_coord(&c)
+    );
   }
 
   #[test]
@@ -494,8 +1102,11 @@ mod tests {
     let co = get_co_transition_table();
 
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 3));
-    assert_eq!(0, co[COCoord::get_coord(&c)][usize::from(Face::F)]);
+    let c = c.apply_move(Move(Face::F, Turn::Counter));
+    assert_eq!(
+      COCoord::from(0),
+      co[(COCoord::get_coord(&c), Move(Face::F, Turn::Clockwise))]
+    );
   }
 
   #[test]
@@ -508,8 +1119,11 @@ mod tests {
     let ud1 = get_ud1_transition_table();
 
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 3));
-    assert_eq!(0, ud1[UD1Coord::get_coord(&c)][usize::from(Face::F)]);
+    let c = c.apply_move(Move(Face::F, Turn::Counter));
+    assert_eq!(
+      UD1Coord::from(0),
+      ud1[(UD1Coord::get_coord(&c), Move(Face::F, Turn::Clockwise))]
+    );
   }
 
   #[test]
@@ -522,8 +1136,11 @@ mod tests {
     let ep = get_ep_transition_table();
 
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 2));
-    assert_eq!(0, ep[EPCoord::get_coord(&c)][usize::from(Face::F)]);
+    let c = c.apply_move(Move(Face::F, Turn::Half));
+    assert_eq!(
+      EPCoord::from(0),
+      ep[(EPCoord::get_coord(&c), Move(Face::F, Turn::Half))]
+    );
   }
 
   #[test]
@@ -531,13 +1148,46 @@ mod tests {
     exhaustive_coord_check::<EPCoord>();
   }
 
+  #[test]
+  fn g1_coords() {
+    let c = Cube::solved();
+    for &turn in &Turn::ALL {
+      let c = c.apply_move(Move(Face::U, turn));
+      assert_ne!(EPCoord::from(0), EPCoord::get_coord(&c));
+      assert_ne!(CPCoord::from(0), CPCoord::get_coord(&c));
+      assert_eq!(UD2Coord::from(0), UD2Coord::get_coord(&c));
+    }
+
+    let c = Cube::new(
+      [DRB, DBL, DLF, DFR, UBR, ULB, UFL, URF],
+      [0; NUM_CORNERS],
+      [DB, DL, DF, DR, UB, UL, UF, UR, BR, BL, FL, FR],
+      [0; NUM_EDGES],
+    );
+    assert_eq!(
+      EPCoord::from(EPCoord::NUM_ELEMS - 1),
+      EPCoord::get_coord(&c)
+    );
+    assert_eq!(
+      CPCoord::from(CPCoord::NUM_ELEMS - 1),
+      CPCoord::get_coord(&c)
+    );
+    assert_eq!(
+      UD2Coord::from(UD2Coord::NUM_ELEMS - 1),
+      UD2Coord::get_coord(&c)
+    );
+  }
+
   #[test]
   fn cp_transition() {
     let cp = get_cp_transition_table();
 
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 2));
-    assert_eq!(0, cp[CPCoord::get_coord(&c)][usize::from(Face::F)]);
+    let c = c.apply_move(Move(Face::F, Turn::Half));
+    assert_eq!(
+      CPCoord::from(0),
+      cp[(CPCoord::get_coord(&c), Move(Face::F, Turn::Half))]
+    );
   }
 
   #[test]
@@ -550,8 +1200,11 @@ mod tests {
     let ud2 = get_ud2_transition_table();
 
     let c = Cube::solved();
-    let c = c.apply_move(Move(Face::F, 2));
-    assert_eq!(0, ud2[UD2Coord::get_coord(&c)][usize::from(Face::F)]);
+    let c = c.apply_move(Move(Face::F, Turn::Half));
+    assert_eq!(
+      UD2Coord::from(0),
+      ud2[(UD2Coord::get_coord(&c), Move(Face::F, Turn::Half))]
+    );
   }
 
   #[test]
@@ -559,6 +1212,169 @@ mod tests {
     exhaustive_coord_check::<UD2Coord>();
   }
 
+  #[test]
+  fn m_slice_coord() {
+    let m_slice = get_m_slice_transition_table();
+    let c = Cube::solved();
+    assert_eq!(MSliceCoord::from(0), MSliceCoord::get_coord(&c));
+    for &m in G1_MOVES {
+      let next = c.apply_move(m);
+      // Only the U and D quarter turns move edges between the slices.
+      let quarter = m.0 == Face::U || m.0 == Face::D;
+      assert_eq!(
+        quarter && m.1 != Turn::Half,
+        MSliceCoord::get_coord(&next) != MSliceCoord::from(0)
+      );
+      assert_eq!(
+        MSliceCoord::get_coord(&next),
+        m_slice[(MSliceCoord::from(0), m)]
+      );
+    }
+    exhaustive_coord_check::<MSliceCoord>();
+  }
+
+  #[test]
+  fn edge_group_coords_exhaustive() {
+    exhaustive_coord_check::<EdgeGroupCoord>();
+    let slice_edges = [Edge::FR, Edge::FL, Edge::BL, Edge::BR];
+    for &edges in &[Face::D.adjacent_edges(), slice_edges] {
+      for i in 0..EdgeGroupCoord::NUM_ELEMS {
+        let mut c = Cube::solved();
+        set_edge_group_coord(&mut c, edges, i.into());
+        assert_eq!(i, get_edge_group_coord(&c, edges).into());
+      }
+    }
+  }
+
+  #[test]
+  fn cross_coord() {
+    let cross = get_cross_transition_table();
+    let f_edges = [UF, FR, DF, FL];
+    let c = Cube::from_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::F, Turn::Half),
+      Move(Face::D, Turn::Counter),
+    ]);
+    for &m in Group::G0.moves() {
+      let next = c.apply_move(m);
+      assert_eq!(
+        CrossCoord::get_coord(&next),
+        cross[(CrossCoord::get_coord(&c), m)]
+      );
+      assert_eq!(
+        get_cross_coord(&next, f_edges),
+        cross[(get_cross_coord(&c, f_edges), m)]
+      );
+    }
+    exhaustive_coord_check::<CrossCoord>();
+
+    // Cross coordinates do not fit in a `u16`, so they are written as `u32`s.
+    let mut bytes = vec![];
+    cross.write_to(&mut bytes).unwrap();
+    assert_eq!(CrossCoord::NUM_ELEMS * NUM_MOVES * 4, bytes.len());
+    let read = TransitionTable::<CrossCoord>::read_from(&mut &bytes[..]);
+    assert!(read.unwrap().0 == cross.0);
+  }
+
+  #[test]
+  fn pair_coord() {
+    let pair = get_pair_transition_table();
+    let c = Cube::from_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Counter),
+      Move(Face::B, Turn::Half),
+    ]);
+    for &m in Group::G0.moves() {
+      let next = c.apply_move(m);
+      assert_eq!(
+        PairCoord::get_coord(&next),
+        pair[(PairCoord::get_coord(&c), m)]
+      );
+      assert_eq!(
+        get_pair_coord(&next, DBL, UL),
+        pair[(get_pair_coord(&c, DBL, UL), m)]
+      );
+    }
+    exhaustive_coord_check::<PairCoord>();
+  }
+
+  #[test]
+  fn move_indices() {
+    for (i, &m) in Group::G0.moves().iter().enumerate() {
+      assert_eq!(i, move_index(m));
+    }
+    for &m in Group::G1.moves() {
+      assert!(Group::G0.moves().contains(&m));
+      assert!(Group::G1.moves().contains(&m.inverse()));
+    }
+  }
+
+  #[test]
+  fn group_contains() {
+    let c = Cube::solved().apply_move(Move(Face::R, Turn::Half));
+    assert!(Group::G1.contains(&c));
+    assert!(!Group::G1.contains(&c.apply_move(Move(Face::R, Turn::Clockwise))));
+
+    // Swapping two edges keeps the G1 coordinates solved, but the cube is not
+    // solvable.
+    let mut swapped = Cube::solved();
+    swapped.ep.swap(0, 1);
+    assert!(!Group::G0.contains(&swapped));
+    assert!(!Group::G1.contains(&swapped));
+  }
+
+  #[test]
+  fn g0_transitions() {
+    let cp = get_cp_g0_transition_table();
+    let edge_group = get_edge_group_transition_table();
+    let slice_edges = [Edge::FR, Edge::FL, Edge::BL, Edge::BR];
+
+    let c = Cube::solved().apply_move(Move(Face::R, Turn::Half));
+    let c = c.apply_move(Move(Face::F, Turn::Counter));
+    for &m in Group::G0.moves() {
+      let next = c.apply_move(m);
+      assert_eq!(CPCoord::get_coord(&next), cp[(CPCoord::get_coord(&c), m)]);
+      assert_eq!(
+        EdgeGroupCoord::get_coord(&next),
+        edge_group[(EdgeGroupCoord::get_coord(&c), m)]
+      );
+      for &edges in &[Face::D.adjacent_edges(), slice_edges] {
+        assert_eq!(
+          get_edge_group_coord(&next, edges),
+          edge_group[(get_edge_group_coord(&c, edges), m)]
+        );
+      }
+    }
+  }
+
+  // Check that setting the coordinate of `cube` on the solved cube gives
+  // back the same coordinate.
+  fn round_trips<T: Coord>(cube: &Cube) -> bool {
+    let coord: usize = T::get_coord(cube).into();
+    let mut c = Cube::solved();
+    T::set_coord(&mut c, coord.into());
+    coord == T::get_coord(&c).into()
+  }
+
+  proptest! {
+    #[test]
+    fn g0_coord_round_trips(cube: Cube) {
+      prop_assert!(round_trips::<EOCoord>(&cube));
+      prop_assert!(round_trips::<COCoord>(&cube));
+      prop_assert!(round_trips::<UD1Coord>(&cube));
+      prop_assert!(round_trips::<CPCoord>(&cube));
+      prop_assert!(round_trips::<EdgeGroupCoord>(&cube));
+    }
+
+    #[test]
+    fn g1_coord_round_trips(moves in vec(select(G1_MOVES), 0..30)) {
+      let cube = Cube::from_algorithm(&moves);
+      prop_assert!(round_trips::<EPCoord>(&cube));
+      prop_assert!(round_trips::<UD2Coord>(&cube));
+      prop_assert!(round_trips::<MSliceCoord>(&cube));
+    }
+  }
+
   #[test]
   fn fact_digits() {
     let digits = factorial_digits(463, 6);
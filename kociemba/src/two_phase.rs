@@ -0,0 +1,1277 @@
+#[cfg(feature = "parallel")]
+use canonical::SEARCH_FACES;
+#[cfg(feature = "parallel")]
+use cube::Turn;
+use cube::{
+  algorithm_length, invert_algorithm, simplify_algorithm, Cube, Face, Metric,
+  Move,
+};
+use error::Error;
+use handoff::{HandoffCoord, HandoffTables};
+#[cfg(feature = "parallel")]
+use phase0::phase0;
+use phase0::{phase0_all, phase0_g1, Phase0Coord, Phase0Tables, Reductions};
+use phase1::{phase1, Phase1Coord, Phase1Tables};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use std::cmp::min;
+use std::sync::atomic::AtomicBool;
+#[cfg(feature = "parallel")]
+use std::sync::atomic::Ordering;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tables::Tables;
+
+/// The maximum number of moves needed to reduce any cube to G1.
+const PHASE0_MAX_DEPTH: usize = 12;
+/// The maximum number of moves needed to solve any cube in G1.
+const PHASE1_MAX_DEPTH: usize = 18;
+
+// The most that a phase 0 reduction can cost in the metric of `tables0`.
+fn phase0_max_depth(tables0: &Phase0Tables) -> usize {
+  PHASE0_MAX_DEPTH * tables0.metric.max_move_cost()
+}
+
+// The most that a phase 1 solution can cost in the metric of `tables1`.
+fn phase1_max_depth(tables1: &Phase1Tables) -> usize {
+  PHASE1_MAX_DEPTH * tables1.metric.max_move_cost()
+}
+
+/// A solution found by the two-phase algorithm.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Solution {
+  /// The moves that reduce the cube to G1.
+  pub phase0: Vec<Move>,
+  /// The G1 `Cube` reached after the phase 0 moves.
+  pub g1: Cube,
+  /// The moves that solve the G1 `Cube`.
+  pub phase1: Vec<Move>,
+}
+
+impl Solution {
+  /// All the moves of the solution.
+  pub fn moves(&self) -> Vec<Move> {
+    let mut moves = self.phase0.clone();
+    moves.extend_from_slice(&self.phase1);
+    moves
+  }
+
+  /// The number of moves in the solution.
+  pub fn len(&self) -> usize {
+    self.phase0.len() + self.phase1.len()
+  }
+
+  /// Check if the solution has no moves, which means the cube was solved.
+  pub fn is_empty(&self) -> bool {
+    self.len() == 0
+  }
+
+  /// The length of the solution counted in `metric`.
+  pub fn length(&self, metric: Metric) -> usize {
+    algorithm_length(&self.phase0, metric)
+      + algorithm_length(&self.phase1, metric)
+  }
+
+  // The solution of the cube after `moves`, which undoes them and then
+  // follows this solution. The undone moves are simplified into phase 0.
+  pub(crate) fn after(&self, moves: &[Move]) -> Solution {
+    let mut phase0 = invert_algorithm(moves);
+    phase0.extend_from_slice(&self.phase0);
+    Solution {
+      phase0: simplify_algorithm(&phase0),
+      g1: self.g1,
+      phase1: self.phase1.clone(),
+    }
+  }
+}
+
+/// Moves that a solution is required to start or end with.
+#[derive(Clone, Debug, Default)]
+pub struct Constraints {
+  /// Moves that the solution must start with.
+  pub prefix: Vec<Move>,
+  /// Moves that the solution must end with.
+  pub suffix: Vec<Move>,
+}
+
+impl Constraints {
+  /// The `Cube` that the unconstrained part of the solution has to solve.
+  ///
+  /// The prefix is applied to `cube`, and the suffix is moved to the start
+  /// by applying `cube` on top of the cube the suffix produces, so that
+  /// solving the result leaves exactly the suffix to finish the solve.
+  pub fn start(&self, cube: &Cube) -> Cube {
+    let cube = cube.apply_algorithm(&self.prefix);
+    let suffix = Cube::from_algorithm(&self.suffix);
+    suffix.compose(&cube)
+  }
+
+  /// Splice the prefix and suffix around `solution`.
+  pub fn splice(&self, solution: &[Move]) -> Vec<Move> {
+    let mut moves = self.prefix.clone();
+    moves.extend_from_slice(solution);
+    moves.extend_from_slice(&self.suffix);
+    moves
+  }
+
+  /// The length of the moves fixed by the constraints, counted in `metric`.
+  fn length(&self, metric: Metric) -> usize {
+    algorithm_length(&self.prefix, metric)
+      + algorithm_length(&self.suffix, metric)
+  }
+}
+
+/// An event reported while a two-phase search is running.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Progress {
+  /// Phase 0 found a reduction to G1 of `length` moves.
+  Phase0Found { length: usize },
+  /// Phase 1 started searching for a solution of `bound` moves.
+  Phase1Started { bound: usize },
+  /// A complete solution of `length` moves was found, which is shorter than
+  /// any solution found before it.
+  SolutionFound { length: usize },
+}
+
+/// Receives `Progress` events from a two-phase search.
+pub trait Observer {
+  /// Called for each `Progress` event of the search.
+  fn progress(&mut self, event: Progress);
+}
+
+impl<F: FnMut(Progress)> Observer for F {
+  fn progress(&mut self, event: Progress) {
+    self(event)
+  }
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm.
+///
+/// Phase 0 searches for the shortest reduction to G1, and phase 1 then
+/// searches for the shortest solution from there. Returns `None` if no
+/// solution of at most `max_length` moves was found.
+pub fn two_phase(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  max_length: usize,
+) -> Option<Solution> {
+  two_phase_observed(cube, tables0, tables1, max_length, &mut |_| ())
+}
+
+/// The options of `solve_with_config`.
+#[derive(Clone, Debug)]
+pub struct SolverConfig {
+  /// The maximum length of the solution, counted in `metric`.
+  pub max_length: usize,
+  /// The metric that solutions are searched and counted in, as set by
+  /// `Phase0Tables::with_metric`.
+  pub metric: Metric,
+  /// How long to search for a solution of at most `max_length` moves, or
+  /// `None` to search until one is found or there are none.
+  ///
+  /// Time limits need `std::time::Instant`, which panics on
+  /// wasm32-unknown-unknown, so leave this as `None` there.
+  pub time_limit: Option<Duration>,
+  /// Whether both phases try the moves in order of the pruning depths that
+  /// they reach, as set by `Phase0Tables::with_move_ordering`.
+  pub move_ordering: bool,
+  /// A flag that stops the search when it is set, as set by
+  /// `Phase0Tables::with_stop_flag`, or `None` to never stop early.
+  pub stop: Option<Arc<AtomicBool>>,
+}
+
+impl Default for SolverConfig {
+  /// Accept any solution, which is at most 30 moves.
+  fn default() -> SolverConfig {
+    SolverConfig {
+      max_length: PHASE0_MAX_DEPTH + PHASE1_MAX_DEPTH,
+      metric: Metric::Half,
+      time_limit: None,
+      move_ordering: false,
+      stop: None,
+    }
+  }
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm, using the tables from
+/// `Tables::get`.
+///
+/// Building the tables takes far longer than solving a cube, so the first
+/// call is much slower than the following ones.
+pub fn solve(cube: &Cube) -> Vec<Move> {
+  solve_with_config(cube, &SolverConfig::default())
+    .expect("the two-phase search is exhaustive")
+}
+
+/// Solve a cube like `solve`, with the solution limited by `config`.
+///
+/// The search keeps trying more phase 0 reductions until it finds a solution
+/// of at most `config.max_length` moves. Returns `None` if there is none, or
+/// if none was found within `config.time_limit` or before `config.stop` was
+/// set.
+pub fn solve_with_config(
+  cube: &Cube,
+  config: &SolverConfig,
+) -> Option<Vec<Move>> {
+  let tables = Tables::get();
+  two_phase_with_config(cube, &tables.phase0(), &tables.phase1(), config)
+}
+
+/// Solve a cube like `solve_with_config`, checking that it is in a solvable
+/// state first.
+///
+/// Fails with `Error::InvalidState` if it is not, and with
+/// `Error::NoSolution` where `solve_with_config` would return `None`.
+pub fn try_solve_with_config(
+  cube: &Cube,
+  config: &SolverConfig,
+) -> Result<Vec<Move>, Error> {
+  cube.verify()?;
+  solve_with_config(cube, config).ok_or(Error::NoSolution {
+    max_length: config.max_length,
+  })
+}
+
+/// Solve a cube like `solve_with_config`, with tables that were already
+/// built.
+pub fn two_phase_with_config(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  config: &SolverConfig,
+) -> Option<Vec<Move>> {
+  trace_span!(
+    INFO,
+    "solve",
+    max_length = config.max_length,
+    metric = ?config.metric
+  );
+  let deadline = config.time_limit.map(|limit| Instant::now() + limit);
+  let mut tables0 = tables0
+    .with_move_ordering(config.move_ordering)
+    .with_metric(config.metric);
+  let mut tables1 = tables1
+    .with_move_ordering(config.move_ordering)
+    .with_metric(config.metric);
+  if let Some(ref stop) = config.stop {
+    tables0 = tables0.with_stop_flag(stop);
+    tables1 = tables1.with_stop_flag(stop);
+  }
+  let solution = improve(cube, &tables0, &tables1, config.max_length, deadline)
+    .filter(|solution| solution.length(config.metric) <= config.max_length);
+  trace_event!(
+    INFO,
+    length = ?solution.as_ref().map(|s| s.length(config.metric)),
+    "solve finished"
+  );
+  solution.map(|solution| solution.moves())
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm, reporting the progress
+/// of the search to `observer`.
+pub fn two_phase_observed(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  max_length: usize,
+  observer: &mut dyn Observer,
+) -> Option<Solution> {
+  trace_span!(DEBUG, "two_phase", max_length);
+  for depth0 in 0..=min(max_length, phase0_max_depth(tables0)) {
+    trace_event!(TRACE, depth0, "phase 0 depth");
+    let (mut solution, g1) = match phase0_g1(cube, depth0, tables0) {
+      Some(reduction) => reduction,
+      None => continue,
+    };
+    trace_event!(DEBUG, length = depth0, "phase 0 reduction found");
+    observer.progress(Progress::Phase0Found { length: depth0 });
+
+    let moves0 = solution.len();
+    let coord1 = Phase1Coord::from(g1);
+    for depth1 in 0..=min(max_length - depth0, phase1_max_depth(tables1)) {
+      trace_event!(TRACE, depth1, "phase 1 depth");
+      observer.progress(Progress::Phase1Started { bound: depth1 });
+      // Phase 1 continues from the phase 0 moves, so that it does not start
+      // with a move that could have been merged into phase 0.
+      if phase1(coord1, depth1, tables1, &mut solution) {
+        let length = algorithm_length(&solution, tables0.metric);
+        trace_event!(DEBUG, length, depth0, depth1, "solution found");
+        observer.progress(Progress::SolutionFound { length });
+        let phase1 = solution.split_off(moves0);
+        return Some(Solution {
+          phase0: solution,
+          g1,
+          phase1,
+        });
+      }
+    }
+  }
+  None
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm, handing off every phase
+/// 0 reduction directly to phase 1.
+///
+/// The phase 1 coordinates of each reduction are found with the `handoff`
+/// transition tables instead of applying the reduction to the cube, so every
+/// reduction of each length is tried until one leads to a solution of at
+/// most `max_length` moves.
+pub fn two_phase_combined(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  handoff: &HandoffTables,
+  max_length: usize,
+) -> Option<Solution> {
+  let coord0 = Phase0Coord::from(*cube);
+  let start = HandoffCoord::from(*cube);
+  let mut found = None;
+  for depth0 in 0..=min(max_length, phase0_max_depth(tables0)) {
+    phase0_all(coord0, depth0, tables0, |reduction| {
+      let coord1 = handoff.apply(start, reduction).phase1();
+      let mut solution = reduction.to_vec();
+      for depth1 in 0..=min(max_length - depth0, phase1_max_depth(tables1)) {
+        if phase1(coord1, depth1, tables1, &mut solution) {
+          found = Some((solution, reduction.len()));
+          return false;
+        }
+      }
+      true
+    });
+    if let Some((mut solution, moves0)) = found {
+      let phase1 = solution.split_off(moves0);
+      return Some(Solution {
+        g1: cube.apply_algorithm(&solution),
+        phase0: solution,
+        phase1,
+      });
+    }
+  }
+  None
+}
+
+/// Solve a cube like `two_phase`, with the phase 0 search split across
+/// threads by its first move.
+///
+/// For each phase 0 length, each of the 18 first moves is searched on its own
+/// thread, and its first reduction is handed to phase 1. The tables are only
+/// read, so they are shared by every thread. The first solution found stops
+/// the other threads, so the solution can differ between runs. The threads
+/// are raced with their own stop flag, which replaces any stop flag of the
+/// tables.
+#[cfg(feature = "parallel")]
+pub fn two_phase_parallel(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  max_length: usize,
+) -> Option<Solution> {
+  let coord0 = Phase0Coord::from(*cube);
+  let first_moves: Vec<Move> = SEARCH_FACES
+    .iter()
+    .flat_map(|&f| Turn::ALL.iter().map(move |&turn| Move(f, turn)))
+    .collect();
+  // Solve the G1 cube reached by `reduction`, which costs `depth0`.
+  let finish = |reduction: Vec<Move>, depth0: usize, tables1: &Phase1Tables| {
+    let g1 = cube.apply_algorithm(&reduction);
+    let coord1 = Phase1Coord::from(g1);
+    let moves0 = reduction.len();
+    let mut solution = reduction;
+    for depth1 in 0..=min(max_length - depth0, phase1_max_depth(tables1)) {
+      if phase1(coord1, depth1, tables1, &mut solution) {
+        let phase1 = solution.split_off(moves0);
+        return Some(Solution {
+          phase0: solution,
+          g1,
+          phase1,
+        });
+      }
+    }
+    None
+  };
+
+  if coord0.is_solved() {
+    if let Some(solution) = finish(vec![], 0, tables1) {
+      return Some(solution);
+    }
+  }
+  for depth0 in 1..=min(max_length, phase0_max_depth(tables0)) {
+    let race = AtomicBool::new(false);
+    let tables0 = tables0.with_stop_flag(&race);
+    let tables1 = tables1.with_stop_flag(&race);
+    let found = first_moves.par_iter().find_map_any(|&m| {
+      let cost = tables0.metric.move_cost(m);
+      if cost > depth0 {
+        return None;
+      }
+      let mut reduction = vec![m];
+      let next = tables0.transition(coord0, m);
+      if !phase0(next, depth0 - cost, &tables0, &mut reduction) {
+        return None;
+      }
+      let solution = finish(reduction, depth0, &tables1)?;
+      race.store(true, Ordering::Relaxed);
+      Some(solution)
+    });
+    if found.is_some() {
+      return found;
+    }
+  }
+  None
+}
+
+// An `Iterator` over the solutions found from every phase 0 reduction, each
+// shorter than the last, with phase 1 only searching for solutions shorter
+// than the best one so far. It ends when no shorter solution can be found, or
+// when `deadline` has passed after the first solution.
+pub(crate) struct Improving<'a> {
+  cube: Cube,
+  coord0: Phase0Coord,
+  tables0: Phase0Tables<'a>,
+  tables1: Phase1Tables<'a>,
+  deadline: Option<Instant>,
+  depth0: usize,
+  reductions: Reductions<'a>,
+  best: Option<usize>,
+}
+
+impl<'a> Improving<'a> {
+  fn new(
+    cube: &Cube,
+    tables0: &Phase0Tables<'a>,
+    tables1: &Phase1Tables<'a>,
+    deadline: Option<Instant>,
+  ) -> Improving<'a> {
+    let coord0 = Phase0Coord::from(*cube);
+    Improving::from_coord(cube, coord0, 0, tables0, tables1, deadline)
+  }
+
+  // Search `cube`, whose `Phase0Coord` is `coord0`, starting from reductions
+  // of `min_depth0` moves, for when shorter ones are known not to exist.
+  pub(crate) fn from_coord(
+    cube: &Cube,
+    coord0: Phase0Coord,
+    min_depth0: usize,
+    tables0: &Phase0Tables<'a>,
+    tables1: &Phase1Tables<'a>,
+    deadline: Option<Instant>,
+  ) -> Improving<'a> {
+    Improving {
+      cube: *cube,
+      coord0,
+      tables0: *tables0,
+      tables1: *tables1,
+      deadline,
+      depth0: min_depth0,
+      reductions: Reductions::new(coord0, min_depth0, tables0),
+      best: None,
+    }
+  }
+}
+
+impl Iterator for Improving<'_> {
+  type Item = Solution;
+  fn next(&mut self) -> Option<Solution> {
+    let max_length = match self.best {
+      Some(best) => best.checked_sub(1)?,
+      None => phase0_max_depth(&self.tables0) + phase1_max_depth(&self.tables1),
+    };
+    // No reduction longer than `max_length` can lead to a shorter solution.
+    let max_depth0 = min(max_length, phase0_max_depth(&self.tables0));
+    loop {
+      if self.depth0 > max_depth0 {
+        return None;
+      }
+      if let (Some(_), Some(deadline)) = (self.best, self.deadline) {
+        if Instant::now() >= deadline {
+          return None;
+        }
+      }
+      let reduction = match self.reductions.next() {
+        Some(reduction) => reduction,
+        None => {
+          self.depth0 += 1;
+          trace_event!(TRACE, depth0 = self.depth0, "phase 0 depth");
+          if self.depth0 <= max_depth0 {
+            self.reductions =
+              Reductions::new(self.coord0, self.depth0, &self.tables0);
+          }
+          continue;
+        }
+      };
+
+      let depth0 = self.depth0;
+      let g1 = self.cube.apply_algorithm(&reduction);
+      let coord1 = Phase1Coord::from(g1);
+      let moves0 = reduction.len();
+      let mut solution = reduction;
+      for depth1 in
+        0..=min(max_length - depth0, phase1_max_depth(&self.tables1))
+      {
+        if phase1(coord1, depth1, &self.tables1, &mut solution) {
+          let length = algorithm_length(&solution, self.tables0.metric);
+          trace_event!(DEBUG, length, depth0, depth1, "solution found");
+          self.best = Some(length);
+          let phase1 = solution.split_off(moves0);
+          return Some(Solution {
+            phase0: solution,
+            g1,
+            phase1,
+          });
+        }
+      }
+    }
+  }
+}
+
+// Search every phase 0 reduction for solutions, each shorter than the last,
+// until one of at most `target` moves is found, or `deadline` has passed.
+// The search always runs until it has found one solution, unless the tables
+// have a stop flag that is set.
+fn improve(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  target: usize,
+  deadline: Option<Instant>,
+) -> Option<Solution> {
+  trace_span!(DEBUG, "improve", target);
+  let mut best = None;
+  for solution in Improving::new(cube, tables0, tables1, deadline) {
+    let done = solution.length(tables0.metric) <= target;
+    best = Some(solution);
+    if done {
+      break;
+    }
+  }
+  best
+}
+
+/// A two-phase solver, which can keep searching for shorter solutions.
+#[derive(Clone, Copy)]
+pub struct Solver<'a> {
+  tables0: Phase0Tables<'a>,
+  tables1: Phase1Tables<'a>,
+}
+
+impl<'a> Solver<'a> {
+  /// Create a `Solver` that searches with `tables0` and `tables1`.
+  pub fn new(
+    tables0: &Phase0Tables<'a>,
+    tables1: &Phase1Tables<'a>,
+  ) -> Solver<'a> {
+    Solver {
+      tables0: *tables0,
+      tables1: *tables1,
+    }
+  }
+
+  /// An `Iterator` over solutions of `cube`, each shorter than the last, which
+  /// continues the search every time the next one is asked for.
+  ///
+  /// Take the first solution for speed, or keep taking them until a timeout
+  /// for shorter ones. The iterator ends once two-phase can not find anything
+  /// shorter, which can take a long time.
+  pub fn solutions(&self, cube: &Cube) -> impl Iterator<Item = Vec<Move>> + 'a {
+    Improving::new(cube, &self.tables0, &self.tables1, None)
+      .map(|solution| solution.moves())
+  }
+}
+
+/// Shared tables and the options to solve with them, which is cheap to clone
+/// into worker threads.
+///
+/// Unlike `Solver`, which borrows its tables, a handle owns a reference count
+/// of them, so it can be moved into threads that outlive the caller, such as
+/// the workers of a server.
+#[derive(Clone)]
+pub struct SolverHandle {
+  tables: Arc<Tables>,
+  config: SolverConfig,
+}
+
+impl SolverHandle {
+  /// Create a handle that solves with `tables` and the default
+  /// `SolverConfig`.
+  pub fn new(tables: Arc<Tables>) -> SolverHandle {
+    SolverHandle {
+      tables,
+      config: SolverConfig::default(),
+    }
+  }
+
+  /// Solve with `config` instead.
+  pub fn with_config(self, config: SolverConfig) -> SolverHandle {
+    SolverHandle { config, ..self }
+  }
+
+  /// The tables that the handle solves with.
+  pub fn tables(&self) -> &Arc<Tables> {
+    &self.tables
+  }
+
+  /// The options that the handle solves with.
+  pub fn config(&self) -> &SolverConfig {
+    &self.config
+  }
+
+  /// Solve a cube like `solve_with_config`, with the handle's tables and
+  /// options.
+  pub fn solve(&self, cube: &Cube) -> Option<Vec<Move>> {
+    let (tables0, tables1) = (self.tables.phase0(), self.tables.phase1());
+    two_phase_with_config(cube, &tables0, &tables1, &self.config)
+  }
+}
+
+impl Default for SolverHandle {
+  /// A handle to the tables from `Tables::shared`.
+  fn default() -> SolverHandle {
+    SolverHandle::new(Tables::shared())
+  }
+}
+
+/// A two-phase solver that owns its tables and options, so it can be stored
+/// and passed around without the lifetimes of `Phase0Tables` and
+/// `Phase1Tables`.
+///
+/// The functions that take borrowed tables are still available for searches
+/// that this does not cover, or to share one copy of the tables.
+pub struct TwoPhaseSolver {
+  tables: Tables,
+  config: SolverConfig,
+}
+
+impl TwoPhaseSolver {
+  /// Create a solver with newly built tables and the default `SolverConfig`.
+  pub fn new() -> TwoPhaseSolver {
+    TwoPhaseSolver::with_tables(Tables::new())
+  }
+
+  /// Create a solver with `tables`, such as tables loaded by `load_tables`,
+  /// and the default `SolverConfig`.
+  pub fn with_tables(tables: Tables) -> TwoPhaseSolver {
+    TwoPhaseSolver {
+      tables,
+      config: SolverConfig::default(),
+    }
+  }
+
+  /// Solve with `config` instead.
+  pub fn with_config(self, config: SolverConfig) -> TwoPhaseSolver {
+    TwoPhaseSolver { config, ..self }
+  }
+
+  /// The tables that the solver solves with.
+  pub fn tables(&self) -> &Tables {
+    &self.tables
+  }
+
+  /// The options that the solver solves with.
+  pub fn config(&self) -> &SolverConfig {
+    &self.config
+  }
+
+  /// Solve a cube like `solve_with_config`, with the solver's tables and
+  /// options.
+  pub fn solve(&self, cube: &Cube) -> Option<Vec<Move>> {
+    let (tables0, tables1) = (self.tables.phase0(), self.tables.phase1());
+    two_phase_with_config(cube, &tables0, &tables1, &self.config)
+  }
+
+  /// Solve a cube like `solve`, with a solution of at most `max_length`
+  /// moves instead of the solver's `max_length`.
+  pub fn solve_with_limit(
+    &self,
+    cube: &Cube,
+    max_length: usize,
+  ) -> Option<Vec<Move>> {
+    let config = SolverConfig {
+      max_length,
+      ..self.config.clone()
+    };
+    let (tables0, tables1) = (self.tables.phase0(), self.tables.phase1());
+    two_phase_with_config(cube, &tables0, &tables1, &config)
+  }
+}
+
+impl Default for TwoPhaseSolver {
+  fn default() -> TwoPhaseSolver {
+    TwoPhaseSolver::new()
+  }
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm, and keep searching for
+/// shorter solutions until `time_budget` has passed.
+///
+/// Every phase 0 reduction is tried, not just the first one of each length,
+/// with phase 1 only searching for solutions shorter than the best one so
+/// far. The search ends early if the best solution is known to be the
+/// shortest that two-phase can find, and always runs until it has found one
+/// solution.
+///
+/// Panics if the tables have a stop flag that is set before any solution is
+/// found.
+pub fn solve_optimal_two_phase(
+  cube: &Cube,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  time_budget: Duration,
+) -> Solution {
+  let deadline = Instant::now() + time_budget;
+  improve(cube, tables0, tables1, 0, Some(deadline))
+    .expect("the two-phase search is exhaustive")
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm, where the solution has
+/// to start and end with the moves given in `constraints`.
+///
+/// `max_length` includes the constrained moves.
+pub fn two_phase_constrained(
+  cube: &Cube,
+  constraints: &Constraints,
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  max_length: usize,
+) -> Option<Vec<Move>> {
+  let fixed = constraints.length(tables0.metric);
+  if fixed > max_length {
+    return None;
+  }
+  let start = constraints.start(cube);
+  two_phase(&start, tables0, tables1, max_length - fixed)
+    .map(|solution| constraints.splice(&solution.moves()))
+}
+
+/// Solve a cube with Kociemba's two-phase algorithm, where the solution has
+/// to end with one of the algorithms in `algs`, optionally followed by a turn
+/// of the U face. This allows the last part of the solution, such as the last
+/// layer, to be restricted to a set of known algorithms.
+///
+/// The shortest solution found for any of the algorithms is returned.
+pub fn two_phase_with_algs(
+  cube: &Cube,
+  algs: &[Vec<Move>],
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  max_length: usize,
+) -> Option<Vec<Move>> {
+  let mut best: Option<Vec<Move>> = None;
+  for alg in algs {
+    for auf in 0..4 {
+      let mut suffix = alg.clone();
+      suffix.extend(Move::new(Face::U, auf));
+      let constraints = Constraints {
+        prefix: vec![],
+        suffix,
+      };
+      let max_length = match best {
+        Some(ref best) if best.is_empty() => return Some(vec![]),
+        Some(ref best) => algorithm_length(best, tables0.metric) - 1,
+        None => max_length,
+      };
+      let solution =
+        two_phase_constrained(cube, &constraints, tables0, tables1, max_length);
+      if solution.is_some() {
+        best = solution;
+      }
+    }
+  }
+  best
+}
+
+/// Update `solution` after `moves` were applied to the cube it solves, such as
+/// when following the solution on a real cube.
+///
+/// If `moves` are the first moves of `solution` and the rest of it is at most
+/// `max_length` moves, it is returned without searching. Otherwise undoing
+/// `moves` and following `solution` is still a solution, so it bounds the
+/// length of a new search, which only has to find something shorter. Returns
+/// `None` if there is no solution of at most `max_length` moves.
+pub fn two_phase_resolve(
+  solution: &Solution,
+  moves: &[Move],
+  tables0: &Phase0Tables,
+  tables1: &Phase1Tables,
+  max_length: usize,
+) -> Option<Solution> {
+  let all = solution.moves();
+  if all.starts_with(moves) {
+    let done = moves.len();
+    let rest = if done <= solution.phase0.len() {
+      Solution {
+        phase0: solution.phase0[done..].to_vec(),
+        g1: solution.g1,
+        phase1: solution.phase1.clone(),
+      }
+    } else {
+      let done = done - solution.phase0.len();
+      Solution {
+        phase0: vec![],
+        g1: solution.g1.apply_algorithm(&solution.phase1[..done]),
+        phase1: solution.phase1[done..].to_vec(),
+      }
+    };
+    if rest.length(tables0.metric) <= max_length {
+      return Some(rest);
+    }
+  }
+
+  let cube = solution
+    .g1
+    .apply_algorithm(&invert_algorithm(&solution.phase0))
+    .apply_algorithm(moves);
+
+  let fallback = solution.after(moves);
+  match fallback.length(tables0.metric) {
+    0 => Some(fallback),
+    len if len <= max_length => {
+      two_phase(&cube, tables0, tables1, len - 1).or(Some(fallback))
+    }
+    _ => two_phase(&cube, tables0, tables1, max_length),
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::{CubeStateErr, Turn};
+  use std::{env, fs, process};
+  use tables::{load_tables, save_tables, Tables};
+
+  fn scramble() -> Cube {
+    Cube::solved().apply_algorithm(&[
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Counter),
+      Move(Face::F, Turn::Half),
+      Move(Face::D, Turn::Clockwise),
+      Move(Face::L, Turn::Counter),
+      Move(Face::B, Turn::Clockwise),
+    ])
+  }
+
+  #[test]
+  fn solve() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let c = Cube::solved();
+    let solution = two_phase(&c, &tables0, &tables1, 0).unwrap();
+    assert!(solution.is_empty());
+    assert_eq!(c, solution.g1);
+
+    let c = scramble();
+    let solution = two_phase(&c, &tables0, &tables1, 30).unwrap();
+    assert!(solution.len() <= 30);
+    assert_eq!(c.apply_algorithm(&solution.phase0), solution.g1);
+    assert_eq!(
+      Cube::solved(),
+      solution.g1.apply_algorithm(&solution.phase1)
+    );
+
+    let c = Cube::solved().apply_move(Move(Face::R, Turn::Clockwise));
+    assert!(two_phase(&c, &tables0, &tables1, 0).is_none());
+  }
+
+  #[test]
+  fn handle() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<Tables>();
+    assert_send_sync::<Phase0Tables>();
+    assert_send_sync::<Phase1Tables>();
+    assert_send_sync::<HandoffTables>();
+    assert_send_sync::<Solver>();
+    assert_send_sync::<SolverHandle>();
+    assert_send_sync::<SolverConfig>();
+    assert_send_sync::<TwoPhaseSolver>();
+    assert_send_sync::<::cross::CrossSolver>();
+    assert_send_sync::<::cube2::Cube2Solver>();
+    assert_send_sync::<::f2l::F2LSolver>();
+    assert_send_sync::<::human::CfopSolver>();
+    assert_send_sync::<::optimal::OptimalSolver>();
+    assert_send_sync::<::pattern_database::PatternDatabase>();
+    assert_send_sync::<::puzzle::PyraminxSolver>();
+    assert_send_sync::<::roux::RouxSolver>();
+    assert_send_sync::<::thistlethwaite::Thistlethwaite>();
+
+    assert!(Arc::ptr_eq(&Tables::shared(), &Tables::shared()));
+    let handle = SolverHandle::default().with_config(SolverConfig {
+      max_length: 22,
+      ..SolverConfig::default()
+    });
+    assert_eq!(22, handle.config().max_length);
+    let c = scramble();
+    let workers: Vec<_> = (0..4)
+      .map(|_| {
+        let handle = handle.clone();
+        std::thread::spawn(move || handle.solve(&c).unwrap())
+      })
+      .collect();
+    for worker in workers {
+      let solution = worker.join().unwrap();
+      assert!(solution.len() <= 22);
+      assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+    }
+  }
+
+  #[test]
+  fn owning_solver() {
+    let name = format!("kociemba-owning-solver-{}.tables", process::id());
+    let path = env::temp_dir().join(name);
+    save_tables(Tables::get(), &path).unwrap();
+    let tables = load_tables(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    let solver = TwoPhaseSolver::with_tables(tables);
+    assert_eq!(30, solver.config().max_length);
+    assert_eq!(Some(vec![]), solver.solve(&Cube::solved()));
+    let c = scramble();
+    let solution = solver.solve(&c).unwrap();
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+
+    // The limit is only for that search, and only shorter solutions are
+    // accepted.
+    let r = Cube::solved().apply_move(Move(Face::R, Turn::Clockwise));
+    assert_eq!(None, solver.solve_with_limit(&r, 0));
+    assert_eq!(Some(1), solver.solve_with_limit(&r, 1).map(|s| s.len()));
+    let solver = solver.with_config(SolverConfig {
+      max_length: 0,
+      ..SolverConfig::default()
+    });
+    assert_eq!(None, solver.solve(&r));
+    assert!(solver.solve_with_limit(&c, 30).is_some());
+  }
+
+  #[test]
+  fn combined() {
+    let tables = Tables::get();
+    let (tables0, tables1) = (tables.phase0(), tables.phase1());
+    let handoff = tables.handoff();
+    let c = scramble();
+    let first = two_phase(&c, &tables0, &tables1, 30).unwrap();
+    let solution =
+      two_phase_combined(&c, &tables0, &tables1, &handoff, first.len())
+        .unwrap();
+    assert!(solution.len() <= first.len());
+    assert_eq!(c.apply_algorithm(&solution.phase0), solution.g1);
+    assert_eq!(
+      Cube::solved(),
+      solution.g1.apply_algorithm(&solution.phase1)
+    );
+
+    let c = Cube::solved().apply_move(Move(Face::R, Turn::Clockwise));
+    assert!(two_phase_combined(&c, &tables0, &tables1, &handoff, 0).is_none());
+  }
+
+  #[test]
+  fn with_algs() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let sune = vec![
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Half),
+      Move(Face::R, Turn::Counter),
+    ];
+    let sexy = vec![
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Counter),
+    ];
+    let algs = vec![sune.clone(), sexy.clone()];
+
+    // The inverse of sexy, then a U turn, is solved by U' and sexy.
+    let c = Cube::solved().apply_algorithm(&[
+      Move(Face::U, Turn::Clockwise),
+      Move(Face::R, Turn::Clockwise),
+      Move(Face::U, Turn::Counter),
+      Move(Face::R, Turn::Counter),
+      Move(Face::U, Turn::Clockwise),
+    ]);
+    let solution =
+      two_phase_with_algs(&c, &algs, &tables0, &tables1, 20).unwrap();
+    assert_eq!(5, solution.len());
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+    assert_eq!(&sexy[..], &solution[1..]);
+
+    let c = scramble();
+    let solution =
+      two_phase_with_algs(&c, &algs, &tables0, &tables1, 40).unwrap();
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+    let ends_with_alg = |alg: &[Move]| {
+      (0..2).any(|auf| {
+        let end = solution.len() - auf;
+        end >= alg.len() && &solution[end - alg.len()..end] == alg
+      })
+    };
+    assert!(ends_with_alg(&sune) || ends_with_alg(&sexy));
+  }
+
+  #[test]
+  fn observed() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let mut events = vec![];
+    let c = Cube::solved().apply_move(Move(Face::R, Turn::Half));
+    let c = c.apply_move(Move(Face::F, Turn::Counter));
+    let solution =
+      two_phase_observed(&c, &tables0, &tables1, 10, &mut |event| {
+        events.push(event)
+      })
+      .unwrap();
+    assert_eq!(2, solution.len());
+    assert_eq!(
+      vec![
+        Progress::Phase0Found { length: 1 },
+        Progress::Phase1Started { bound: 0 },
+        Progress::Phase1Started { bound: 1 },
+        Progress::SolutionFound { length: 2 },
+      ],
+      events
+    );
+  }
+
+  #[test]
+  fn constrained() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let c = scramble();
+    let constraints = Constraints {
+      prefix: vec![Move(Face::U, Turn::Half), Move(Face::F, Turn::Clockwise)],
+      suffix: vec![
+        Move(Face::R, Turn::Clockwise),
+        Move(Face::L, Turn::Counter),
+      ],
+    };
+    let solution =
+      two_phase_constrained(&c, &constraints, &tables0, &tables1, 34).unwrap();
+    assert!(solution.len() <= 34);
+    assert_eq!(&constraints.prefix[..], &solution[..2]);
+    assert_eq!(&constraints.suffix[..], &solution[solution.len() - 2..]);
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+
+    assert!(
+      two_phase_constrained(&c, &constraints, &tables0, &tables1, 3).is_none()
+    );
+  }
+
+  #[test]
+  fn solve_cube() {
+    let c = scramble();
+    assert_eq!(Cube::solved(), c.apply_algorithm(&super::solve(&c)));
+    assert!(super::solve(&Cube::solved()).is_empty());
+  }
+
+  #[test]
+  fn optimal() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let c = scramble();
+    let first = two_phase(&c, &tables0, &tables1, 30).unwrap();
+    let solution = solve_optimal_two_phase(
+      &c,
+      &tables0,
+      &tables1,
+      Duration::from_millis(500),
+    );
+    assert!(solution.len() <= first.len());
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution.moves()));
+    assert_eq!(c.apply_algorithm(&solution.phase0), solution.g1);
+
+    // A solution is always found, even without any time.
+    let solution =
+      solve_optimal_two_phase(&c, &tables0, &tables1, Duration::from_millis(0));
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution.moves()));
+  }
+
+  #[test]
+  fn config() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let c = scramble();
+    let first = two_phase(&c, &tables0, &tables1, 30).unwrap();
+    let config = SolverConfig {
+      max_length: first.len(),
+      metric: Metric::Half,
+      time_limit: None,
+      move_ordering: false,
+      stop: None,
+    };
+    let solution =
+      two_phase_with_config(&c, &tables0, &tables1, &config).unwrap();
+    assert!(solution.len() <= first.len());
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+
+    let config = SolverConfig {
+      move_ordering: true,
+      ..config
+    };
+    let solution =
+      two_phase_with_config(&c, &tables0, &tables1, &config).unwrap();
+    assert!(solution.len() <= first.len());
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+
+    // The scramble is 8 moves, so this can not be met.
+    let config = SolverConfig {
+      max_length: 3,
+      metric: Metric::Half,
+      time_limit: Some(Duration::from_millis(100)),
+      move_ordering: false,
+      stop: None,
+    };
+    assert_eq!(None, two_phase_with_config(&c, &tables0, &tables1, &config));
+
+    // A search that is stopped finds nothing.
+    let config = SolverConfig {
+      stop: Some(Arc::new(AtomicBool::new(true))),
+      ..SolverConfig::default()
+    };
+    assert_eq!(None, two_phase_with_config(&c, &tables0, &tables1, &config));
+    assert!(two_phase(&c, &tables0, &tables1, 30).is_some());
+  }
+
+  #[test]
+  fn metric() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let c = scramble();
+    let htm = two_phase(&c, &tables0, &tables1, 30).unwrap();
+    let config = SolverConfig {
+      max_length: htm.length(Metric::Quarter),
+      metric: Metric::Quarter,
+      ..SolverConfig::default()
+    };
+    let qtm = two_phase_with_config(&c, &tables0, &tables1, &config).unwrap();
+    assert!(algorithm_length(&qtm, Metric::Quarter) <= config.max_length);
+    assert_eq!(Cube::solved(), c.apply_algorithm(&qtm));
+
+    let tables0 = tables0.with_metric(Metric::Quarter);
+    let tables1 = tables1.with_metric(Metric::Quarter);
+    let solution = two_phase(&c, &tables0, &tables1, 40).unwrap();
+    assert_eq!(
+      Cube::solved(),
+      solution.g1.apply_algorithm(&solution.phase1)
+    );
+    assert_eq!(solution.g1, c.apply_algorithm(&solution.phase0));
+  }
+
+  #[test]
+  fn try_solve() {
+    let c = scramble();
+    let solution = try_solve_with_config(&c, &SolverConfig::default()).unwrap();
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution));
+
+    let mut invalid = c;
+    invalid.cp.swap(0, 1);
+    let err = try_solve_with_config(&invalid, &SolverConfig::default());
+    assert!(matches!(
+      err,
+      Err(Error::InvalidState(CubeStateErr::ErrParity))
+    ));
+
+    let config = SolverConfig {
+      stop: Some(Arc::new(AtomicBool::new(true))),
+      ..SolverConfig::default()
+    };
+    let err = try_solve_with_config(&c, &config);
+    assert!(matches!(err, Err(Error::NoSolution { max_length: 30 })));
+  }
+
+  proptest! {
+    #![proptest_config(proptest::test_runner::Config::with_cases(8))]
+
+    #[test]
+    fn solves_random_states(cube: Cube) {
+      let solution = super::solve(&cube);
+      prop_assert!(solution.len() <= 30);
+      prop_assert_eq!(Cube::solved(), cube.apply_algorithm(&solution));
+    }
+  }
+
+  #[cfg(feature = "parallel")]
+  #[test]
+  fn parallel() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let solution =
+      two_phase_parallel(&Cube::solved(), &tables0, &tables1, 30).unwrap();
+    assert!(solution.is_empty());
+
+    let c = scramble();
+    let solution = two_phase_parallel(&c, &tables0, &tables1, 30).unwrap();
+    assert!(solution.len() <= 30);
+    assert_eq!(Cube::solved(), c.apply_algorithm(&solution.moves()));
+    assert_eq!(c.apply_algorithm(&solution.phase0), solution.g1);
+  }
+
+  #[test]
+  fn solutions() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let solver = Solver::new(&tables0, &tables1);
+    let solutions: Vec<_> = solver.solutions(&Cube::solved()).collect();
+    assert_eq!(vec![Vec::<Move>::new()], solutions);
+
+    let c = scramble();
+    let first = two_phase(&c, &tables0, &tables1, 30).unwrap();
+    let solutions: Vec<_> = solver.solutions(&c).take(3).collect();
+    assert_eq!(first.moves(), solutions[0]);
+    for (i, solution) in solutions.iter().enumerate() {
+      assert_eq!(Cube::solved(), c.apply_algorithm(solution));
+      if i > 0 {
+        assert!(solution.len() < solutions[i - 1].len());
+      }
+    }
+  }
+
+  #[test]
+  fn resolve() {
+    let tables0 = Tables::get().phase0();
+    let tables1 = Tables::get().phase1();
+    let c = scramble();
+    let solution = two_phase(&c, &tables0, &tables1, 30).unwrap();
+
+    // Following the solution leaves the rest of it.
+    let moves = solution.moves();
+    for done in 0..=moves.len() {
+      let rest =
+        two_phase_resolve(&solution, &moves[..done], &tables0, &tables1, 30)
+          .unwrap();
+      assert_eq!(&moves[done..], &rest.moves()[..]);
+      let cube = c.apply_algorithm(&moves[..done]);
+      assert_eq!(Cube::solved(), cube.apply_algorithm(&rest.moves()));
+    }
+
+    // Any other moves are at worst undone.
+    let moves = [Move(Face::U, Turn::Clockwise), Move(Face::R, Turn::Half)];
+    let cube = c.apply_algorithm(&moves);
+    let rest =
+      two_phase_resolve(&solution, &moves, &tables0, &tables1, 30).unwrap();
+    assert!(rest.len() <= solution.len() + 2);
+    assert_eq!(Cube::solved(), cube.apply_algorithm(&rest.moves()));
+    assert_eq!(Cube::solved(), rest.g1.apply_algorithm(&rest.phase1));
+    assert!(
+      two_phase_resolve(&solution, &moves, &tables0, &tables1, 1).is_none()
+    );
+
+    // The rest of the solution is only kept if it is short enough, and
+    // otherwise the search does not find anything shorter for this cube.
+    let moves = solution.moves();
+    let done = &moves[..1];
+    let max_length = moves.len() - 1;
+    let rest =
+      two_phase_resolve(&solution, done, &tables0, &tables1, max_length)
+        .unwrap();
+    assert_eq!(&moves[1..], &rest.moves()[..]);
+    let max_length = max_length - 1;
+    let rest =
+      two_phase_resolve(&solution, done, &tables0, &tables1, max_length);
+    assert!(rest.is_none());
+    assert!(two_phase_resolve(&solution, &[], &tables0, &tables1, 1).is_none());
+  }
+}
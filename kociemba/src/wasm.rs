@@ -0,0 +1,14 @@
+use cube::{format_algorithm, Cube};
+use error::Error;
+use wasm_bindgen::prelude::*;
+
+/// Solve the cube given by a facelet string, as read by
+/// `Cube::from_facelet_str`, returning the solution in the standard notation.
+///
+/// The tables are built by the first call, which takes a few seconds. Throws
+/// an error if the facelets are not a valid cube.
+#[wasm_bindgen]
+pub fn solve(facelets: &str) -> Result<String, JsError> {
+  let cube = Cube::from_facelet_str(facelets).map_err(Error::from)?;
+  Ok(format_algorithm(&::two_phase::solve(&cube)))
+}
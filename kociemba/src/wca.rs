@@ -0,0 +1,122 @@
+use cube::{
+  format_algorithm, invert_algorithm, seeded_rng, Cube, Face, Move, Turn,
+};
+use rand::Rng;
+use std::fmt;
+use two_phase::{solve_with_config, SolverConfig};
+
+/// The number of scrambles of a round of most events.
+pub const SCRAMBLES_PER_ROUND: usize = 5;
+/// The number of extra scrambles of a round, for when an attempt has to be
+/// done again.
+pub const EXTRAS_PER_ROUND: usize = 2;
+
+/// The scrambles of a round.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScrambleSet {
+  /// The scrambles of the attempts.
+  pub scrambles: Vec<Vec<Move>>,
+  /// The extra scrambles.
+  pub extras: Vec<Vec<Move>>,
+}
+
+impl fmt::Display for ScrambleSet {
+  /// Format the scrambles one per line, numbered from 1, followed by the
+  /// extras, numbered from E1.
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    for (i, scramble) in self.scrambles.iter().enumerate() {
+      writeln!(f, "{}. {}", i + 1, format_algorithm(scramble))?;
+    }
+    for (i, scramble) in self.extras.iter().enumerate() {
+      writeln!(f, "E{}. {}", i + 1, format_algorithm(scramble))?;
+    }
+    Ok(())
+  }
+}
+
+// Whether `cube` is solved or one move away from it.
+fn is_within_one_move(cube: &Cube) -> bool {
+  *cube == Cube::solved()
+    || [Face::U, Face::R, Face::F, Face::D, Face::B, Face::L]
+      .iter()
+      .flat_map(|&f| Turn::ALL.iter().map(move |&t| Move(f, t)))
+      .any(|m| cube.apply_move(m) == Cube::solved())
+}
+
+/// A random-state scramble, as used in competitions: a random state, with
+/// every state equally likely, that is at least two moves from solved,
+/// scrambled with the inverse of a two-phase solution of at most 21 moves.
+pub fn random_state_scramble(rng: &mut impl Rng) -> Vec<Move> {
+  let config = SolverConfig {
+    max_length: 21,
+    ..SolverConfig::default()
+  };
+  loop {
+    let cube = Cube::random(rng);
+    if is_within_one_move(&cube) {
+      continue;
+    }
+    let solution = solve_with_config(&cube, &config)
+      .expect("every cube can be solved in 20 moves");
+    return invert_algorithm(&solution);
+  }
+}
+
+/// `num_scrambles` random-state scrambles and `num_extras` extra ones.
+pub fn scramble_set(
+  rng: &mut impl Rng,
+  num_scrambles: usize,
+  num_extras: usize,
+) -> ScrambleSet {
+  let scrambles = (0..num_scrambles)
+    .map(|_| random_state_scramble(rng))
+    .collect();
+  let extras = (0..num_extras)
+    .map(|_| random_state_scramble(rng))
+    .collect();
+  ScrambleSet { scrambles, extras }
+}
+
+/// The scrambles of a round, with `SCRAMBLES_PER_ROUND` scrambles and
+/// `EXTRAS_PER_ROUND` extras, which are the same for the same `seed` on
+/// every platform, so that a round can be audited from its seed.
+pub fn scramble_round(seed: u64) -> ScrambleSet {
+  let mut rng = seeded_rng(seed);
+  scramble_set(&mut rng, SCRAMBLES_PER_ROUND, EXTRAS_PER_ROUND)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use cube::parse_algorithm;
+
+  #[test]
+  fn within_one_move() {
+    assert!(is_within_one_move(&Cube::solved()));
+    let r = Cube::from_algorithm(&parse_algorithm("R'").unwrap());
+    assert!(is_within_one_move(&r));
+    let ru = Cube::from_algorithm(&parse_algorithm("R U").unwrap());
+    assert!(!is_within_one_move(&ru));
+  }
+
+  #[test]
+  fn round() {
+    let round = scramble_round(1573);
+    assert_eq!(SCRAMBLES_PER_ROUND, round.scrambles.len());
+    assert_eq!(EXTRAS_PER_ROUND, round.extras.len());
+    assert_eq!(round, scramble_round(1573));
+    assert_ne!(round, scramble_round(1574));
+    for scramble in round.scrambles.iter().chain(&round.extras) {
+      assert!(scramble.len() <= 21);
+      assert!(!is_within_one_move(&Cube::from_algorithm(scramble)));
+    }
+
+    let text = round.to_string();
+    let lines: Vec<&str> = text.lines().collect();
+    assert_eq!(SCRAMBLES_PER_ROUND + EXTRAS_PER_ROUND, lines.len());
+    assert!(lines[0].starts_with("1. "));
+    assert!(lines[SCRAMBLES_PER_ROUND].starts_with("E1. "));
+    let first = lines[0].trim_start_matches("1. ");
+    assert_eq!(round.scrambles[0], parse_algorithm(first).unwrap());
+  }
+}